@@ -3,17 +3,17 @@ use std::sync::Arc;
 
 use vgtk::lib::gio::{ActionExt, ApplicationFlags, File, FileExt, SimpleAction};
 use vgtk::lib::glib::Error;
-use vgtk::lib::gtk::prelude::*;
 use vgtk::lib::gtk::*;
-use vgtk::{ext::*, gtk, gtk_if, on_signal, Component, UpdateAction, VNode};
+use vgtk::{ext::*, gtk, gtk_if, Component, Persistent, UpdateAction, VNode};
 
+use serde_derive::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter};
 
 use crate::about::AboutDialog;
 use crate::items::{Item, Items};
 use crate::radio::Radio;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Display, EnumIter)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Display, EnumIter, Serialize, Deserialize)]
 pub enum Filter {
     All,
     Active,
@@ -26,14 +26,21 @@ impl Default for Filter {
     }
 }
 
-#[derive(Clone, Debug)]
+// The items and the filter survive a restart, but the file they came from
+// doesn't, so the restored items start out unsaved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Model {
     items: Arc<Items>,
     filter: Filter,
+    #[serde(skip)]
     file: Option<File>,
+    #[serde(skip)]
     clean: bool,
 }
 
+impl Persistent for Model {}
+
 impl Default for Model {
     fn default() -> Self {
         Model {
@@ -242,20 +249,19 @@ impl Component for Model {
                     .item("Save as...", "win.save-as"),
             )
             .section(menu().item("About...", "app.about"))
-            .section(menu().item("Quit", "app.quit"))
-            .build();
+            .section(menu().item("Quit", "app.quit"));
 
         gtk! {
             <Application::new_unwrap(Some("camp.lol.todomvc"), ApplicationFlags::empty())>
 
                 <SimpleAction::new("quit", None) Application::accels=["<Ctrl>q"].as_ref() enabled=true
-                        on activate=|a, _| Msg::Exit/>
+                        on activate=|_, _| Msg::Exit/>
                 <SimpleAction::new("about", None) enabled=true on activate=|_, _| Msg::MenuAbout/>
 
                 <ApplicationWindow default_width=800 default_height=480 border_width=20 on destroy=|_| Msg::Exit>
 
                     <SimpleAction::new("open", None) ApplicationWindow::accels=["<Ctrl>o"].as_ref()
-                                                     enabled=true on activate=|a, _| Msg::MenuOpen/>
+                                                     enabled=true on activate=|_, _| Msg::MenuOpen/>
                     <SimpleAction::new("save", None) ApplicationWindow::accels=["<Ctrl>s"].as_ref()
                                                      enabled=self.file.is_some() && !self.clean on activate=|_, _| Msg::MenuSave/>
                     <SimpleAction::new("save-as", None) ApplicationWindow::accels=["<Ctrl><Shift>s"].as_ref()
@@ -263,9 +269,7 @@ impl Component for Model {
 
                     <HeaderBar title=format!("TodoMVC - {}{}", title, clean) subtitle="wtf do we do now" show_close_button=true>
                         <MenuButton HeaderBar::pack_type=PackType::End @MenuButtonExt::direction=ArrowType::Down relief=ReliefStyle::None
-                                    image="open-menu-symbolic">
-                            <Menu::new_from_model(&main_menu)/>
-                        </MenuButton>
+                                    image="open-menu-symbolic" menu_model=main_menu />
                     </HeaderBar>
                     {
                         self.main_panel()
@@ -277,23 +281,13 @@ impl Component for Model {
 }
 
 async fn open() -> Result<Option<(File, Items)>, Error> {
-    let dialog = FileChooserNative::new(
-        Some("Open a todo list"),
-        vgtk::current_object()
-            .and_then(|w| w.downcast::<Window>().ok())
-            .as_ref(),
-        FileChooserAction::Open,
-        None,
-        None,
-    );
-    dialog.set_modal(true);
-    let filter = FileFilter::new();
-    filter.set_name(Some("Todo list files"));
-    filter.add_pattern("*.todo");
-    dialog.add_filter(&filter);
-    dialog.show();
-    if on_signal!(dialog, connect_response).await == Ok(ResponseType::Accept) {
-        let file = dialog.get_file().unwrap();
+    let paths = vgtk::FileChooser::open()
+        .title("Open a todo list")
+        .filter("Todo list files", &["*.todo"])
+        .run()
+        .await;
+    if let Some(path) = paths.first() {
+        let file = File::new_for_path(path);
         Items::read_from(&file)
             .await
             .map(|items| Some((file, items)))
@@ -307,21 +301,13 @@ async fn save(items: &Items, file: &File) -> Result<(), Error> {
 }
 
 async fn save_as(items: &Items) -> Result<Option<File>, Error> {
-    let dialog = FileChooserNative::new(
-        Some("Save your todo list"),
-        vgtk::current_window().as_ref(),
-        FileChooserAction::Save,
-        None,
-        None,
-    );
-    dialog.set_modal(true);
-    let filter = FileFilter::new();
-    filter.set_name(Some("Todo list files"));
-    filter.add_pattern("*.todo");
-    dialog.add_filter(&filter);
-    dialog.show();
-    if on_signal!(dialog, connect_response).await == Ok(ResponseType::Accept) {
-        let file = dialog.get_file().unwrap();
+    let paths = vgtk::FileChooser::save()
+        .title("Save your todo list")
+        .filter("Todo list files", &["*.todo"])
+        .run()
+        .await;
+    if let Some(path) = paths.first() {
+        let file = File::new_for_path(path);
         save(items, &file).await.map(|_| Some(file))
     } else {
         Ok(None)
@@ -48,7 +48,8 @@ impl Item {
     }
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Items {
     items: Vec<Item>,
 }
@@ -1,15 +1,13 @@
-#![recursion_limit = "4096"]
-
 mod about;
 mod app;
 mod items;
 mod radio;
 
-use vgtk::run;
+use vgtk::run_persistent;
 
 use app::Model;
 
 fn main() {
     pretty_env_logger::init();
-    std::process::exit(run::<Model>());
+    std::process::exit(run_persistent::<Model>());
 }
@@ -0,0 +1,209 @@
+use lalrpop_util::ParseError;
+use proc_macro2::{Delimiter, Group};
+
+use crate::context::{GtkElement, GtkFor, GtkIf};
+use crate::error::RsxParseError;
+use crate::lexer::{Keyword, Token, Tokens};
+use crate::parser::grammar::GtkElementsParser;
+
+type BlockResult<A> = Result<A, ParseError<usize, Token, RsxParseError>>;
+
+// A code block containing a control flow expression is only treated as
+// `gtk!` syntax if at least one of its bodies contains an element; otherwise
+// it's left alone as plain Rust code.
+fn contains_element(tokens: &Tokens) -> bool {
+    tokens.iter().any(|token| match token {
+        Token::Group(Delimiter::Brace, group) => {
+            let body: Tokens = group.stream().into();
+            matches!(
+                body.first(),
+                Some(Token::Punct1('<', _)) | Some(Token::Punct2('<', '@', _, _))
+            )
+        }
+        _ => false,
+    })
+}
+
+fn unexpected(
+    tokens: &Tokens,
+    index: usize,
+    expected: &str,
+) -> ParseError<usize, Token, RsxParseError> {
+    match tokens.get(index) {
+        Some(token) => ParseError::UnrecognizedToken {
+            token: (index, token.clone(), index + 1),
+            expected: vec![expected.to_string()],
+        },
+        None => ParseError::UnrecognizedEOF {
+            location: index,
+            expected: vec![expected.to_string()],
+        },
+    }
+}
+
+fn parse_elements(group: &Group) -> BlockResult<Vec<GtkElement>> {
+    let tokens: Tokens = group.stream().into();
+    GtkElementsParser::new().parse(tokens.lexer())
+}
+
+// Collect the branches of an `if` expression starting at `tokens[index]`,
+// returning the condition and body group of each branch along with the final
+// `else` body, if any.
+//
+// A branch's body is the last brace group before the next `else`, rather than
+// the first one after the `if`, as an `if let` pattern can contain braces too.
+fn collect_branches(
+    tokens: &Tokens,
+    mut index: usize,
+) -> BlockResult<(Vec<(Tokens, Group)>, Option<Group>)> {
+    let mut branches = Vec::new();
+    loop {
+        // `index` points at an `if` keyword.
+        index += 1;
+        let end = tokens[index..]
+            .iter()
+            .position(|token| matches!(token, Token::Keyword(Keyword::Else, _)))
+            .map_or(tokens.len(), |offset| index + offset);
+        if end == index {
+            return Err(unexpected(tokens, index, "expression"));
+        }
+        let body = match &tokens[end - 1] {
+            Token::Group(Delimiter::Brace, group) if end - 1 > index => group.clone(),
+            Token::Group(Delimiter::Brace, _) => {
+                return Err(unexpected(tokens, index, "expression"))
+            }
+            _ => return Err(unexpected(tokens, end, "BraceGroupToken")),
+        };
+        let condition = tokens[index..end - 1].iter().cloned().collect();
+        branches.push((condition, body));
+        index = end;
+        if index == tokens.len() {
+            return Ok((branches, None));
+        }
+        // `index` points at an `else` keyword.
+        index += 1;
+        match tokens.get(index) {
+            Some(Token::Keyword(Keyword::If, _)) => continue,
+            Some(Token::Group(Delimiter::Brace, group)) if index + 1 == tokens.len() => {
+                return Ok((branches, Some(group.clone())))
+            }
+            Some(Token::Group(Delimiter::Brace, _)) => {
+                return Err(unexpected(tokens, index + 1, "end of code block"))
+            }
+            _ => return Err(unexpected(tokens, index, "BraceGroupToken")),
+        }
+    }
+}
+
+fn parse_if(tokens: &Tokens) -> BlockResult<GtkElement> {
+    let (branches, otherwise) = collect_branches(tokens, 0)?;
+    let mut else_branch = match otherwise {
+        Some(body) => Some(parse_elements(&body)?),
+        None => None,
+    };
+    for (condition, body) in branches.into_iter().rev() {
+        let element = GtkElement::If(GtkIf {
+            condition,
+            then_branch: parse_elements(&body)?,
+            else_branch,
+        });
+        else_branch = Some(vec![element]);
+    }
+    Ok(else_branch.unwrap().pop().unwrap())
+}
+
+fn parse_for(tokens: &Tokens) -> BlockResult<GtkElement> {
+    // `tokens[0]` is the `for` keyword.
+    let in_index = match tokens
+        .iter()
+        .position(|token| matches!(token, Token::Keyword(Keyword::In, _)))
+    {
+        Some(index) if index > 1 => index,
+        Some(_) => return Err(unexpected(tokens, 1, "pattern")),
+        None => return Err(unexpected(tokens, tokens.len() - 1, "in")),
+    };
+    let body_index = tokens.len() - 1;
+    if body_index == in_index {
+        return Err(unexpected(tokens, tokens.len(), "expression"));
+    }
+    let body = match &tokens[body_index] {
+        Token::Group(Delimiter::Brace, group) if body_index > in_index + 1 => group,
+        Token::Group(Delimiter::Brace, _) => {
+            return Err(unexpected(tokens, body_index, "expression"))
+        }
+        _ => return Err(unexpected(tokens, tokens.len(), "BraceGroupToken")),
+    };
+    Ok(GtkElement::For(GtkFor {
+        pattern: tokens[1..in_index].iter().cloned().collect(),
+        iterator: tokens[in_index + 1..body_index].iter().cloned().collect(),
+        body: parse_elements(body)?,
+    }))
+}
+
+/// Parse a code block in child position, recognising control flow
+/// expressions whose bodies contain `gtk!` elements.
+pub fn parse_block(block: Group) -> BlockResult<GtkElement> {
+    let tokens: Tokens = block.stream().into();
+    if !contains_element(&tokens) {
+        return Ok(GtkElement::Block(block));
+    }
+    match tokens.first() {
+        Some(Token::Keyword(Keyword::If, _)) => parse_if(&tokens),
+        Some(Token::Keyword(Keyword::For, _)) => parse_for(&tokens),
+        _ => Ok(GtkElement::Block(block)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    fn block(stream: TokenStream) -> Group {
+        Group::new(Delimiter::Brace, stream)
+    }
+
+    #[test]
+    fn if_let_with_struct_pattern() {
+        let parsed = parse_block(block(quote! {
+            if let Item { done, .. } = item { <Label label="Done" /> } else { <Label /> }
+        }));
+        match parsed {
+            Ok(GtkElement::If(gtk_if)) => {
+                assert_eq!(
+                    gtk_if.condition.to_stream().to_string(),
+                    quote!(let Item { done, .. } = item).to_string()
+                );
+                assert_eq!(gtk_if.then_branch.len(), 1);
+                assert_eq!(gtk_if.else_branch.map(|branch| branch.len()), Some(1));
+            }
+            other => panic!("expected an if element, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn else_if_chain() {
+        let parsed = parse_block(block(quote! {
+            if a { <Label /> } else if let Some(Item { done }) = b { <Label /> }
+        }));
+        match parsed {
+            Ok(GtkElement::If(gtk_if)) => match gtk_if.else_branch.as_deref() {
+                Some([GtkElement::If(inner)]) => {
+                    assert_eq!(
+                        inner.condition.to_stream().to_string(),
+                        quote!(let Some(Item { done }) = b).to_string()
+                    );
+                    assert!(inner.else_branch.is_none());
+                }
+                other => panic!("expected a nested if element, got {:?}", other),
+            },
+            other => panic!("expected an if element, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn else_without_body() {
+        assert!(parse_block(block(quote! { if a { <Label /> } else })).is_err());
+    }
+}
@@ -16,6 +16,21 @@ pub struct GtkWidget {
 pub struct GtkComponent {
     pub name: Tokens,
     pub attributes: Vec<Attribute>,
+    pub children: Vec<GtkElement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GtkIf {
+    pub condition: Tokens,
+    pub then_branch: Vec<GtkElement>,
+    pub else_branch: Option<Vec<GtkElement>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GtkFor {
+    pub pattern: Tokens,
+    pub iterator: Tokens,
+    pub body: Vec<GtkElement>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +38,9 @@ pub enum GtkElement {
     Widget(GtkWidget),
     Component(GtkComponent),
     Block(Group),
+    If(GtkIf),
+    For(GtkFor),
+    Fragment(Vec<GtkElement>),
 }
 
 #[derive(Clone)]
@@ -35,10 +53,19 @@ pub enum Attribute {
     },
     Handler {
         name: Ident,
+        filter: Option<Group>,
         async_keyword: Option<Token>,
         args: Tokens,
         body: Tokens,
     },
+    Binding {
+        name: Ident,
+        value: Tokens,
+        handler: Tokens,
+    },
+    Spread {
+        value: Tokens,
+    },
 }
 
 fn stringify_attr_value(token: &Token) -> String {
@@ -70,10 +97,15 @@ impl Debug for Attribute {
             }
             Attribute::Handler {
                 name,
+                filter,
                 async_keyword,
                 args,
                 body,
             } => {
+                let filter = match filter {
+                    Some(filter) => filter.to_string(),
+                    None => String::new(),
+                };
                 let args: Vec<String> = args.iter().map(stringify_attr_value).collect();
                 let attrs: Vec<String> = body.iter().map(stringify_attr_value).collect();
                 let async_keyword = if async_keyword.is_some() {
@@ -83,13 +115,33 @@ impl Debug for Attribute {
                 };
                 write!(
                     f,
-                    "( {} = {}{} {} )",
+                    "( {}{} = {}{} {} )",
                     name.to_string(),
+                    filter,
                     async_keyword,
                     args.join(", "),
                     attrs.join(", ")
                 )
             }
+            Attribute::Binding {
+                name,
+                value,
+                handler,
+            } => {
+                let value: Vec<String> = value.iter().map(stringify_attr_value).collect();
+                let handler: Vec<String> = handler.iter().map(stringify_attr_value).collect();
+                write!(
+                    f,
+                    "( bind:{} = {} => {} )",
+                    name,
+                    value.join(", "),
+                    handler.join(", ")
+                )
+            }
+            Attribute::Spread { value } => {
+                let value: Vec<String> = value.iter().map(stringify_attr_value).collect();
+                write!(f, "( ..{} )", value.join(", "))
+            }
         }
     }
 }
@@ -114,6 +166,12 @@ impl PartialEq<(&str, &str)> for Attribute {
             Attribute::Handler { name, .. } => {
                 format!("on {}", name.to_string()) == other.0 // FIXME: only compares handler name
             }
+            Attribute::Binding { name, value, .. } => {
+                format!("bind:{}", name) == other.0 && stringify_attr_value(&value[0]) == other.1
+            }
+            Attribute::Spread { value } => {
+                other.0 == ".." && stringify_attr_value(&value[0]) == other.1
+            }
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::lexer::{to_stream, Token, Tokens};
 use lalrpop_util::ParseError::*;
-use proc_macro2::TokenStream;
+use proc_macro2::{Group, Ident, TokenStream};
 use quote::{quote, quote_spanned};
 
 pub type ParseError = lalrpop_util::ParseError<usize, Token, RsxParseError>;
@@ -9,6 +9,8 @@ pub type ParseError = lalrpop_util::ParseError<usize, Token, RsxParseError>;
 pub enum RsxParseError {
     TagMismatch { open: Tokens, close: Tokens },
     UnexpectedConstructor { name: Tokens, args: Token },
+    UnknownAttributePrefix { prefix: Ident },
+    EmptySpread { block: Group },
 }
 
 fn pprint_token(token: &str) -> &str {
@@ -16,6 +18,7 @@ fn pprint_token(token: &str) -> &str {
         "BraceGroupToken" => "code block",
         "ParenGroupToken" => "parenthesised block",
         "BracketGroupToken" => "array",
+        "SpreadToken" => "spread",
         "LiteralToken" => "literal",
         "IdentToken" => "identifier",
         a => a,
@@ -98,5 +101,20 @@ pub fn parse_error(input: &[Token], error: &ParseError) -> TokenStream {
                 compile_error! { #error_msg }
             }
         }
+        User {
+            error: RsxParseError::UnknownAttributePrefix { prefix },
+        } => {
+            let error_msg = format!("unknown attribute prefix `{}:`, expected `bind:`", prefix);
+            quote_spanned! { prefix.span() =>
+                compile_error! { #error_msg }
+            }
+        }
+        User {
+            error: RsxParseError::EmptySpread { block },
+        } => {
+            quote_spanned! { block.span() =>
+                compile_error! { "expected a bundle of properties to spread after `..`" }
+            }
+        }
     }
 }
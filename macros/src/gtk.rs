@@ -1,13 +1,70 @@
-use proc_macro2::{Group, Ident, Literal, TokenStream};
-use quote::{quote, quote_spanned};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+use quote::quote_spanned;
 
-use crate::context::{Attribute, GtkComponent, GtkElement, GtkWidget};
-use crate::lexer::{to_stream, Token};
+use crate::context::{Attribute, GtkComponent, GtkElement, GtkFor, GtkIf, GtkWidget};
+use crate::lexer::{to_stream, Token, Tokens};
+
+// The generated code's own variables resolve where the macro is defined, like
+// a `macro_rules!` macro's, so they can't capture or shadow the variables used
+// in the attribute values and handlers it's given.
+macro_rules! quote {
+    ($($tt:tt)*) => {
+        quote_spanned!(Span::mixed_site() => $($tt)*)
+    };
+}
 
 fn to_string_literal<S: ToString>(s: S) -> Literal {
     Literal::string(&s.to_string())
 }
 
+// A `key` attribute identifies a node among its siblings rather than setting
+// a property.
+fn is_key(child: bool, parent: &[Token], name: &Ident) -> bool {
+    !child && parent.is_empty() && name == "key"
+}
+
+fn expand_key(value: &[Token]) -> TokenStream {
+    let value = to_stream(value);
+    quote!(Some(std::string::ToString::to_string(&(#value))))
+}
+
+// A `ref` attribute hands the built object to a `WidgetRef`, or the built
+// component to a `ComponentRef`, rather than setting a property.
+fn is_ref(child: bool, parent: &[Token], name: &Ident) -> bool {
+    !child && parent.is_empty() && name == "ref"
+}
+
+fn ref_value(value: &[Token]) -> TokenStream {
+    // `ref={self.entry}` should refer to the field, not move out of it.
+    match value {
+        [Token::Group(Delimiter::Brace, group)] => group.stream(),
+        _ => to_stream(value),
+    }
+}
+
+fn expand_ref(value: &[Token]) -> TokenStream {
+    let value = ref_value(value);
+    quote!(Some((#value).setter()))
+}
+
+fn expand_component_ref(name: &TokenStream, value: &[Token]) -> TokenStream {
+    let value = ref_value(value);
+    quote!({
+        let component_ref: &vgtk::ComponentRef<#name> = &(#value);
+        Some(component_ref.setter())
+    })
+}
+
+// `Widget` already has a deprecated `style` property, so a `style` attribute
+// sets the inline CSS provided by `WidgetExtHelpers` instead.
+fn property_name(child: bool, parent: &[Token], name: &Ident) -> Ident {
+    if !child && parent.is_empty() && name == "style" {
+        Ident::new("inline_style", name.span())
+    } else {
+        name.clone()
+    }
+}
+
 fn count_attributes(attributes: &[Attribute]) -> (usize, usize, usize) {
     let mut props = 0;
     let mut child_props = 0;
@@ -22,6 +79,11 @@ fn count_attributes(attributes: &[Attribute]) -> (usize, usize, usize) {
                 }
             }
             Attribute::Handler { .. } => handlers += 1,
+            Attribute::Binding { .. } => {
+                props += 1;
+                handlers += 1;
+            }
+            Attribute::Spread { .. } => {}
         }
     }
     (props, child_props, handlers)
@@ -32,9 +94,110 @@ pub fn expand_gtk(gtk: &GtkElement) -> TokenStream {
         GtkElement::Widget(widget) => expand_widget(widget),
         GtkElement::Component(component) => expand_component(component),
         GtkElement::Block(_block) => panic!("blocks not allowed in this position"),
+        GtkElement::If(gtk_if) => expand_root_if(gtk_if),
+        GtkElement::For(GtkFor { pattern, .. }) => {
+            let span = pattern.first().map_or_else(Span::call_site, Token::span);
+            quote_spanned! {span =>
+                compile_error! { "a `for` loop can only add children to a parent widget" }
+            }
+        }
+        GtkElement::Fragment(fragment) => {
+            let children = expand_children(fragment);
+            quote!({
+                let mut children = std::vec::Vec::new();
+                #children
+                children
+            })
+        }
     }
 }
 
+// An `if` expression in place of the root element becomes an `if` expression
+// evaluating to the element of whichever branch is taken, so every branch
+// must hold exactly one element.
+fn expand_root_if(gtk_if: &GtkIf) -> TokenStream {
+    match (&gtk_if.then_branch[..], gtk_if.else_branch.as_deref()) {
+        ([then_branch], Some([else_branch])) => {
+            let condition = to_stream(&gtk_if.condition);
+            let then_branch = expand_gtk(then_branch);
+            let else_branch = expand_gtk(else_branch);
+            quote!(if #condition { #then_branch } else { #else_branch })
+        }
+        _ => {
+            let span = gtk_if
+                .condition
+                .first()
+                .map_or_else(Span::call_site, Token::span);
+            quote_spanned! {span =>
+                compile_error! {
+                    "an `if` in place of the root element needs an `else`, and exactly one element in each branch"
+                }
+            }
+        }
+    }
+}
+
+// Expand a list of child elements into statements which add them to a
+// `children` vector in scope.
+fn expand_children(children: &[GtkElement]) -> TokenStream {
+    let mut out = TokenStream::new();
+    for child in children {
+        out.extend(match child {
+            // Matching on the block rather than passing it straight to
+            // `extend()` keeps `unused_braces` from firing on it, while still
+            // keeping its temporaries alive until its children are added.
+            GtkElement::Block(block) => quote!(
+                match #block {
+                    block => children.extend(block),
+                }
+            ),
+            GtkElement::If(GtkIf {
+                condition,
+                then_branch,
+                else_branch,
+            }) => {
+                let condition = to_stream(condition);
+                let then_branch = expand_children(then_branch);
+                match else_branch {
+                    Some(else_branch) => {
+                        let else_branch = expand_children(else_branch);
+                        quote!(
+                            if #condition { #then_branch } else { #else_branch }
+                        )
+                    }
+                    None => quote!(
+                        if #condition { #then_branch }
+                    ),
+                }
+            }
+            GtkElement::For(GtkFor {
+                pattern,
+                iterator,
+                body,
+            }) => {
+                // The body's children get no implicit keys, as there's
+                // nothing to derive them from but the iteration's position,
+                // which is what an unkeyed child is matched by anyway.
+                let pattern = to_stream(pattern);
+                let iterator = to_stream(iterator);
+                let body = expand_children(body);
+                quote!(
+                    for #pattern in #iterator { #body }
+                )
+            }
+            // A fragment's children are added in its place.
+            GtkElement::Fragment(fragment) => expand_children(fragment),
+            _ => {
+                let child = expand_gtk(child);
+                quote!(
+                    children.push(#child);
+                )
+            }
+        });
+    }
+    out
+}
+
 pub fn expand_component(gtk: &GtkComponent) -> TokenStream {
     let name = to_stream(&gtk.name);
     let mut out = quote!(
@@ -50,7 +213,17 @@ pub fn expand_component(gtk: &GtkComponent) -> TokenStream {
                 name,
                 value,
             } => {
-                if *child {
+                if is_key(*child, parent, name) {
+                    let key = expand_key(value);
+                    quote!(
+                        vcomp.key = #key;
+                    )
+                } else if is_ref(*child, parent, name) {
+                    let component_ref = expand_component_ref(&to_stream(&gtk.name), value);
+                    quote!(
+                        vcomp.component_ref = #component_ref;
+                    )
+                } else if *child {
                     let prop = expand_property(None, *child, parent, name, value);
                     quote!(
                         vcomp.child_props.push(#prop);
@@ -70,10 +243,16 @@ pub fn expand_component(gtk: &GtkComponent) -> TokenStream {
             }
             Attribute::Handler {
                 name,
+                filter,
                 async_keyword,
                 args,
                 body,
             } => {
+                if let Some(filter) = filter {
+                    return quote_spanned! {filter.span() =>
+                        compile_error! { "component callbacks cannot take an argument" }
+                    };
+                }
                 if let Some(async_keyword) = async_keyword {
                     return quote_spanned! {async_keyword.span() =>
                         compile_error! { "component callbacks cannot be async" }
@@ -86,22 +265,38 @@ pub fn expand_component(gtk: &GtkComponent) -> TokenStream {
                     props.#name = PropTransform::transform(&vcomp, move #args #body);
                 )
             }
+            Attribute::Binding { name, .. } => {
+                quote_spanned! {name.span() =>
+                    compile_error! { "component properties cannot be bound" }
+                }
+            }
+            Attribute::Spread { value } => {
+                quote_spanned! {value[0].span() =>
+                    compile_error! { "component properties cannot be spread" }
+                }
+            }
         })
     }
+    if !gtk.children.is_empty() {
+        let children = expand_children(&gtk.children);
+        out.extend(quote!(
+            let mut children = std::vec::Vec::new();
+            #children
+            props.children = PropTransform::transform(&vcomp, children);
+        ));
+    }
     quote!({
         #out
+        let description = {
+            use vgtk::vnode::{DescribeDebug, DescribeOther, Description};
+            (&&Description(&props)).describe()
+        };
         vcomp.set_props::<#name>(props);
+        vcomp.props.set_description(description);
         VNode::Component(vcomp)
     })
 }
 
-fn is_block(gtk: &GtkElement) -> Option<&Group> {
-    match gtk {
-        GtkElement::Block(block) => Some(block),
-        _ => None,
-    }
-}
-
 pub fn expand_widget(gtk: &GtkWidget) -> TokenStream {
     let name = to_stream(&gtk.name);
     let (prop_count, child_prop_count, handler_count) = count_attributes(&gtk.attributes);
@@ -128,6 +323,34 @@ pub fn expand_widget(gtk: &GtkWidget) -> TokenStream {
             let constructor = None;
         ));
     }
+    let has_key = gtk.attributes.iter().any(|attribute| match attribute {
+        Attribute::Property {
+            child,
+            parent,
+            name,
+            ..
+        } => is_key(*child, parent, name),
+        _ => false,
+    });
+    if !has_key {
+        out.extend(quote!(
+            let key = None;
+        ));
+    }
+    let has_ref = gtk.attributes.iter().any(|attribute| match attribute {
+        Attribute::Property {
+            child,
+            parent,
+            name,
+            ..
+        } => is_ref(*child, parent, name),
+        _ => false,
+    });
+    if !has_ref {
+        out.extend(quote!(
+            let widget_ref = None;
+        ));
+    }
     for attribute in &gtk.attributes {
         out.extend(match attribute {
             Attribute::Property {
@@ -136,42 +359,72 @@ pub fn expand_widget(gtk: &GtkWidget) -> TokenStream {
                 name,
                 value,
             } => {
-                let prop = expand_property(Some(&gtk.name), *child, &parent, &name, &value);
-                if *child {
-                    quote!(
-                        child_props.push(#prop);
-                    )
-                } else {
-                    quote!(
-                        properties.push(#prop);
-                    )
+                if is_key(*child, parent, name) {
+                    let key = expand_key(value);
+                    out.extend(quote!(
+                        let key = #key;
+                    ));
+                    continue;
                 }
+                if is_ref(*child, parent, name) {
+                    let widget_ref = expand_ref(value);
+                    out.extend(quote!(
+                        let widget_ref = #widget_ref;
+                    ));
+                    continue;
+                }
+                expand_widget_property(&gtk.name, *child, parent, name, value)
             }
             Attribute::Handler {
                 name,
+                filter: None,
                 async_keyword,
                 args,
                 body,
-            } => expand_handler(&gtk.name, &name, async_keyword.as_ref(), &args, &body),
+            } => expand_handler(&gtk.name, name, None, async_keyword.as_ref(), args, body),
+            Attribute::Handler {
+                name,
+                filter: Some(binding),
+                async_keyword,
+                args,
+                body,
+            } if name == "key" => {
+                expand_key_handler(&gtk.name, binding, async_keyword.as_ref(), args, body)
+            }
+            Attribute::Handler {
+                name,
+                filter: Some(filter),
+                async_keyword,
+                args,
+                body,
+            } => match (expand_rate_limit(filter), async_keyword) {
+                (Err(error), _) => error,
+                (Ok(_), Some(async_keyword)) => {
+                    quote_spanned! {async_keyword.span() =>
+                        compile_error! { "debounced and throttled handlers cannot be async" }
+                    }
+                }
+                (Ok(rate_limit), None) => {
+                    expand_handler(&gtk.name, name, Some(rate_limit), None, args, body)
+                }
+            },
+            Attribute::Binding {
+                name,
+                value,
+                handler,
+            } => expand_binding(&gtk.name, name, value, handler),
+            Attribute::Spread { .. } => continue,
         });
     }
-    for child in &gtk.children {
-        if let Some(block) = is_block(child) {
-            out.extend(quote!(
-                children.extend(#block);
-            ));
-        } else {
-            let child = expand_gtk(child);
-            out.extend(quote!(
-                children.push(#child);
-            ));
-        }
-    }
+    out.extend(expand_spreads(&gtk.name, &gtk.attributes));
+    out.extend(expand_children(&gtk.children));
     quote!({
         #out
         VNode::Object(VObject {
             object_type,
+            key,
             constructor,
+            widget_ref,
             properties,
             child_props,
             handlers,
@@ -180,6 +433,94 @@ pub fn expand_widget(gtk: &GtkWidget) -> TokenStream {
     })
 }
 
+fn expand_widget_property(
+    widget: &[Token],
+    child: bool,
+    parent: &[Token],
+    name: &Ident,
+    value: &[Token],
+) -> TokenStream {
+    let name = property_name(child, parent, name);
+    let prop = expand_property(Some(widget), child, parent, &name, value);
+    if child {
+        quote!(
+            child_props.push(#prop);
+        )
+    } else {
+        quote!(
+            properties.push(#prop);
+        )
+    }
+}
+
+// Spread bundles only add the properties an element doesn't set itself, so
+// they're expanded after its other attributes, the last one first.
+fn expand_spreads(widget: &[Token], attributes: &[Attribute]) -> TokenStream {
+    let widget = to_stream(widget);
+    let mut out = TokenStream::new();
+    for attribute in attributes.iter().rev() {
+        if let Attribute::Spread { value } = attribute {
+            let at_value = value[0].span().resolved_at(Span::mixed_site());
+            let value = to_stream(value);
+            let spread = quote_spanned!(at_value => props.spread::<#widget>(&mut properties, &mut child_props));
+            out.extend(quote!(
+                let props = &(#value);
+                #spread;
+            ));
+        }
+    }
+    out
+}
+
+// The `props!` macro takes a single element without children, and bundles up
+// its properties.
+pub fn expand_props(gtk: &GtkElement) -> TokenStream {
+    let widget = match gtk {
+        GtkElement::Widget(widget)
+            if widget.constructor.is_empty() && widget.children.is_empty() =>
+        {
+            widget
+        }
+        _ => {
+            return quote!(compile_error! {
+                "props! takes a single element without a constructor or children, like `<Widget margin_start=6 />`"
+            })
+        }
+    };
+    let mut out = quote!(
+        use vgtk::vnode::VProperty;
+        use std::vec::Vec;
+        let mut properties = Vec::new();
+        let mut child_props = Vec::new();
+    );
+    for attribute in &widget.attributes {
+        out.extend(match attribute {
+            Attribute::Property {
+                child,
+                parent,
+                name,
+                value,
+            } if !is_key(*child, parent, name) && !is_ref(*child, parent, name) => {
+                expand_widget_property(&widget.name, *child, parent, name, value)
+            }
+            Attribute::Property { name, .. }
+            | Attribute::Handler { name, .. }
+            | Attribute::Binding { name, .. } => {
+                quote_spanned! {name.span() =>
+                    compile_error! { "props! can only bundle properties" }
+                }
+            }
+            Attribute::Spread { .. } => continue,
+        });
+    }
+    out.extend(expand_spreads(&widget.name, &widget.attributes));
+    let name = to_stream(&widget.name);
+    quote!({
+        #out
+        vgtk::Props::<#name>::new(properties, child_props)
+    })
+}
+
 pub fn expand_property(
     object_type: Option<&[Token]>,
     child_prop: bool,
@@ -203,7 +544,14 @@ pub fn expand_property(
     );
     let value_span = value[0].span();
     let value = to_stream(value);
-    let value = quote_spanned!(value_span => (#value).into_property_value());
+    let value = quote_spanned!(value_span => #value);
+    // The calls which convert and compare the value are located where it's
+    // written, so a value of the wrong type is reported there, but they still
+    // resolve variables like the rest of the generated code.
+    let at_value = value_span.resolved_at(Span::mixed_site());
+    let into_value = quote_spanned!(at_value => value.into_property_value());
+    let coerce = quote_spanned!(at_value => value.coerce());
+    let compare = |current| quote_spanned!(at_value => value.compare(#current));
     let prop_name = to_string_literal(name);
     let setter_prelude = if let Some(object_type) = object_type {
         let object_type = to_stream(object_type);
@@ -214,26 +562,44 @@ pub fn expand_property(
     } else {
         quote!()
     };
+    // A property whose value is unset is left alone when the object is built,
+    // and goes back to its default if it had been set before.
+    let gobject_name = to_string_literal(name.to_string().replace('_', "-"));
+    let reset = quote!(
+        if !force {
+            vgtk::properties::reset_property(object.upcast_ref(), #gobject_name);
+        }
+    );
     let setter_body = if !child_prop {
         if parent_type.is_empty() {
+            let compare = compare(quote_spanned!(at_value => object.#getter()));
             quote!(
-                if force || !value.compare(object.#getter()) {
-                    object.#setter(value.coerce());
+                if value.is_unset() {
+                    #reset
+                } else if force || !#compare {
+                    object.#setter(#coerce);
+                    vgtk::vnode::VProperty::changed();
                 }
             )
         } else {
+            let compare = compare(quote_spanned!(at_value => #parent_type::#getter(object)));
             quote!(
-                if force || !value.compare(#parent_type::#getter(object)) {
-                    #parent_type::#setter(object, value.coerce());
+                if value.is_unset() {
+                    #reset
+                } else if force || !#compare {
+                    #parent_type::#setter(object, #coerce);
+                    vgtk::vnode::VProperty::changed();
                 }
             )
         }
     } else {
+        let compare = compare(quote_spanned!(at_value => parent.#getter(object)));
         quote!(
             let parent: &#parent_type = parent.expect("child attribute without a reachable parent").downcast_ref()
                   .unwrap_or_else(|| panic!("downcast to {:?} failed on parent in property setter", #parent_type::static_type()));
-            if force || !value.compare(parent.#getter(object)) {
-                parent.#setter(object, value.coerce());
+            if !value.is_unset() && (force || !#compare) {
+                parent.#setter(object, #coerce);
+                vgtk::vnode::VProperty::changed();
             }
         )
     };
@@ -245,8 +611,14 @@ pub fn expand_property(
                 IntoPropertyValue, PropertyValue, PropertyValueCoerce, PropertyValueCompare,
             };
             let value = #value;
+            let description = {
+                use vgtk::vnode::{DescribeDebug, DescribeOther, Description};
+                (&&Description(&value)).describe()
+            };
+            let value = #into_value;
             VProperty {
                 name: #prop_name,
+                value: description,
                 set: std::boxed::Box::new(move |object: &vgtk::lib::glib::Object, parent: Option<&vgtk::lib::glib::Object>, force: bool| {
                     #setter_prelude
                     #setter_body
@@ -256,15 +628,102 @@ pub fn expand_property(
     )
 }
 
+// A binding sets a property like any other, and also connects to the
+// property's notify signal to send the handler's message when it changes.
+pub fn expand_binding(
+    object_type: &[Token],
+    name: &Ident,
+    value: &[Token],
+    handler: &[Token],
+) -> TokenStream {
+    let bind_value = Ident::new("bind_value", Span::mixed_site());
+    let bind_value_tokens: Tokens = Token::Ident(bind_value.clone()).into();
+    let prop = expand_property(Some(object_type), false, &[], name, &bind_value_tokens);
+    let object_type = to_stream(object_type);
+    let value = to_stream(value);
+    let handler = to_stream(handler);
+    let getter = Ident::new(&format!("get_{}", name), name.span());
+    let connect = Ident::new(&format!("connect_property_{}_notify", name), name.span());
+    let signal_name = to_string_literal(format!("notify::{}", name.to_string().replace('_', "-")));
+    let signal_id = to_string_literal(format!("{:?}", name.span()));
+    quote!({
+        let #bind_value = #value;
+        let bind_handler = vgtk::properties::bind_handler(&#bind_value, #handler);
+        properties.push(#prop);
+        handlers.push(VHandler {
+            name: #signal_name,
+            id: #signal_id,
+            set: std::boxed::Box::new(move |object: &vgtk::lib::glib::Object, scope: &Scope<_>| {
+                use vgtk::lib::glib::object::Cast;
+                use vgtk::properties::BindValue;
+                let object: &#object_type = object.downcast_ref()
+                      .unwrap_or_else(|| panic!("downcast to {:?} failed in binding setter", #object_type::static_type()));
+                let scope: Scope<_> = scope.clone();
+                let handler = bind_handler.clone();
+                object.#connect(move |object| {
+                    let value = BindValue::from_property_value(object.#getter());
+                    scope.send_message(handler(value));
+                })
+            })
+        });
+    })
+}
+
+// `on signal(debounce=300ms)=...` and `on signal(throttle=300ms)=...` pace
+// the handler's messages. The period is either a literal in `ms` or `s`, or
+// an expression which makes a `Duration`.
+fn expand_rate_limit(filter: &Group) -> Result<(Ident, TokenStream), TokenStream> {
+    let error = || {
+        quote_spanned! {filter.span() =>
+            compile_error! { "expected `debounce=...` or `throttle=...`, or `on key(...)`" }
+        }
+    };
+    let mut tokens = filter.stream().into_iter();
+    let kind = match tokens.next() {
+        Some(TokenTree::Ident(kind)) if kind == "debounce" || kind == "throttle" => kind,
+        _ => return Err(error()),
+    };
+    match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {}
+        _ => return Err(error()),
+    }
+    let period: Vec<TokenTree> = tokens.collect();
+    let period = match period.as_slice() {
+        [] => return Err(error()),
+        [TokenTree::Literal(literal)] => {
+            duration_literal(literal).unwrap_or_else(|| period.into_iter().collect())
+        }
+        _ => period.into_iter().collect(),
+    };
+    Ok((kind, period))
+}
+
+fn duration_literal(literal: &Literal) -> Option<TokenStream> {
+    let text = literal.to_string();
+    let (number, constructor) = if let Some(number) = text.strip_suffix("ms") {
+        (number, "from_millis")
+    } else if let Some(number) = text.strip_suffix('s') {
+        (number, "from_secs")
+    } else {
+        return None;
+    };
+    let number = Literal::u64_unsuffixed(number.parse().ok()?);
+    let constructor = Ident::new(constructor, literal.span());
+    Some(quote_spanned!(literal.span() => std::time::Duration::#constructor(#number)))
+}
+
 pub fn expand_handler(
     object_type: &[Token],
     name: &Ident,
+    rate_limit: Option<(Ident, TokenStream)>,
     async_keyword: Option<&Token>,
     args: &[Token],
     body: &[Token],
 ) -> TokenStream {
     let object_type = to_stream(object_type);
     let args_s = to_stream(args);
+    // The body goes in parentheses rather than a block of its own, so
+    // `unused_braces` doesn't fire on a body written in braces.
     let body_s = to_stream(body);
     let connect = Ident::new(&format!("connect_{}", name.to_string()), name.span());
     let signal_name = to_string_literal(name);
@@ -275,18 +734,31 @@ pub fn expand_handler(
             let scope = scope.clone();
             vgtk::lib::glib::MainContext::ref_thread_default().spawn_local(
                 async move {
-                    let msg = async move { #body_s }.await;
-                    scope.send_message(msg);
+                    let msg = async move { (#body_s) }.await;
+                    scope.send_messages(msg);
                 }
             )
         })
+    } else if rate_limit.is_some() {
+        quote!({
+            let msg = (#body_s);
+            rate_limit.send_result(msg)
+        })
     } else {
         quote!({
-            let msg = { #body_s };
-            scope.send_message(msg);
+            let msg = (#body_s);
+            scope.send_result(msg)
         })
     };
-    quote!(
+    let (period, rate_limit) = match rate_limit {
+        Some((kind, period)) => (
+            quote!(let period: std::time::Duration = #period;),
+            quote!(let rate_limit = vgtk::scope::RateLimit::#kind(&scope, period);),
+        ),
+        None => (quote!(), quote!()),
+    };
+    quote!({
+        #period
         handlers.push(VHandler {
             name: #signal_name,
             id: #signal_id,
@@ -295,8 +767,70 @@ pub fn expand_handler(
                 let object: &#object_type = object.downcast_ref()
                       .unwrap_or_else(|| panic!("downcast to {:?} failed in signal setter", #object_type::static_type()));
                 let scope: Scope<_> = scope.clone();
+                #rate_limit
                 object.#connect(move #args_s #inner_block)
             })
         });
-    )
+    })
+}
+
+// `on key(binding)=|| ...` handles presses of a single key, and keeps them
+// from other handlers, without the handler having to look at the event.
+fn expand_key_handler(
+    object_type: &[Token],
+    binding: &Group,
+    async_keyword: Option<&Token>,
+    args: &[Token],
+    body: &[Token],
+) -> TokenStream {
+    let object_type = to_stream(object_type);
+    let at_binding = binding.span().resolved_at(Span::mixed_site());
+    let binding = binding.stream();
+    let binding = quote_spanned!(at_binding => vgtk::KeyBinding::from(#binding));
+    // The handler's argument, if it has one, is the key event.
+    let event = match args {
+        [Token::Punct2('|', '|', _, _)] => quote!(_),
+        _ => to_stream(&args[1..args.len() - 1]),
+    };
+    let body = to_stream(body);
+    let location = args[0].span();
+    let signal_id = to_string_literal(format!("{:?}", location));
+    let send = if async_keyword.is_some() {
+        quote!(
+            let scope = scope.clone();
+            vgtk::lib::glib::MainContext::ref_thread_default().spawn_local(
+                async move {
+                    let msg = async move { (#body) }.await;
+                    scope.send_messages(msg);
+                }
+            );
+        )
+    } else {
+        quote!(
+            let msg = (#body);
+            scope.send_messages(msg);
+        )
+    };
+    quote!({
+        let binding = #binding;
+        handlers.push(VHandler {
+            name: "key_press_event",
+            id: #signal_id,
+            set: std::boxed::Box::new(move |object: &vgtk::lib::glib::Object, scope: &Scope<_>| {
+                use vgtk::lib::glib::object::Cast;
+                use vgtk::lib::gtk::WidgetExt;
+                let object: &#object_type = object.downcast_ref()
+                      .unwrap_or_else(|| panic!("downcast to {:?} failed in signal setter", #object_type::static_type()));
+                let scope: Scope<_> = scope.clone();
+                object.connect_key_press_event(move |_, event| {
+                    if !binding.matches(event) {
+                        return vgtk::lib::gtk::Inhibit(false);
+                    }
+                    let #event = event;
+                    #send
+                    vgtk::lib::gtk::Inhibit(true)
+                })
+            })
+        });
+    })
 }
@@ -11,6 +11,11 @@ pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
 #[derive(Debug, Clone)]
 pub enum Keyword {
     Async,
+    Else,
+    For,
+    If,
+    In,
+    Mut,
     On,
 }
 
@@ -18,6 +23,11 @@ fn keywordise(token: Token) -> Token {
     match token {
         Token::Ident(ident) => match ident.to_string().as_str() {
             "async" => Token::Keyword(Keyword::Async, ident),
+            "else" => Token::Keyword(Keyword::Else, ident),
+            "for" => Token::Keyword(Keyword::For, ident),
+            "if" => Token::Keyword(Keyword::If, ident),
+            "in" => Token::Keyword(Keyword::In, ident),
+            "mut" => Token::Keyword(Keyword::Mut, ident),
             "on" => Token::Keyword(Keyword::On, ident),
             _ => Token::Ident(ident),
         },
@@ -25,15 +35,55 @@ fn keywordise(token: Token) -> Token {
     }
 }
 
+/// Whether two joint punctuation characters make up an operator the parser
+/// knows about. Anything else is left as separate tokens, so that eg. `=|` in
+/// `on clicked=|_| ...` still parses as `=` followed by `|`.
+fn is_operator(first: char, second: char) -> bool {
+    matches!(
+        (first, second),
+        ('=', '=')
+            | ('!', '=')
+            | ('<', '=')
+            | ('>', '=')
+            | ('<', '<')
+            | ('>', '>')
+            | ('&', '&')
+            | ('|', '|')
+            | ('-', '>')
+            | (':', ':')
+            | ('=', '>')
+            | ('<', '/')
+            | ('/', '>')
+            | ('<', '@')
+    )
+}
+
+// A spread can't be told apart from a struct literal like `Foo {..base}` by
+// the grammar, so it gets a token of its own.
+fn is_spread(group: &Group) -> bool {
+    let mut tokens = group.stream().into_iter();
+    group.delimiter() == Delimiter::Brace
+        && match (tokens.next(), tokens.next()) {
+            (Some(TokenTree::Punct(first)), Some(TokenTree::Punct(second))) => {
+                first.as_char() == '.'
+                    && first.spacing() == Spacing::Joint
+                    && second.as_char() == '.'
+            }
+            _ => false,
+        }
+}
+
 #[derive(Clone, Debug)]
 pub enum Token {
     Ident(Ident),
     Literal(Literal),
     Punct1(char, Punct),
     Punct2(char, char, Punct, Punct),
-    Punct3(char, char, char, Punct, Punct, Punct),
     Group(Delimiter, Group),
     Keyword(Keyword, Ident),
+    /// A code block starting with `..`, which spreads a bundle of properties
+    /// over an element.
+    Spread(Group),
 }
 
 impl Token {
@@ -44,9 +94,9 @@ impl Token {
             Token::Punct1(_, punct) => punct.span(),
             // FIXME join these spans one day
             Token::Punct2(_, _, punct, _) => punct.span(),
-            Token::Punct3(_, _, _, punct, _, _) => punct.span(),
             Token::Group(_, group) => group.span(),
             Token::Keyword(_, ident) => ident.span(),
+            Token::Spread(group) => group.span(),
         }
     }
 
@@ -68,13 +118,9 @@ impl Display for Token {
                 punct1.fmt(f)?;
                 punct2.fmt(f)
             }
-            Token::Punct3(_, _, _, punct1, punct2, punct3) => {
-                punct1.fmt(f)?;
-                punct2.fmt(f)?;
-                punct3.fmt(f)
-            }
             Token::Group(_, group) => group.fmt(f),
             Token::Keyword(_, ident) => ident.fmt(f),
+            Token::Spread(group) => group.fmt(f),
         }
     }
 }
@@ -325,44 +371,19 @@ impl From<TokenStream> for Tokens {
             match tt {
                 TokenTree::Ident(ident) => vec.push(keywordise(ident.into())),
                 TokenTree::Literal(literal) => vec.push(literal.into()),
-                TokenTree::Punct(punct) => {
-                    if let Some(prev) = vec.pop() {
-                        let next = match prev {
-                            Token::Punct1(prev_chr, prev_punct) => {
-                                if prev_punct.spacing() == Spacing::Joint {
-                                    Token::Punct2(prev_chr, punct.as_char(), prev_punct, punct)
-                                } else {
-                                    vec.push(Token::Punct1(prev_chr, prev_punct));
-                                    punct.into()
-                                }
-                            }
-                            Token::Punct2(prev_chr_1, prev_chr_2, prev_p1, prev_p2) => {
-                                if prev_p2.spacing() == Spacing::Joint {
-                                    Token::Punct3(
-                                        prev_chr_1,
-                                        prev_chr_2,
-                                        punct.as_char(),
-                                        prev_p1,
-                                        prev_p2,
-                                        punct,
-                                    )
-                                } else {
-                                    vec.push(Token::Punct2(
-                                        prev_chr_1, prev_chr_2, prev_p1, prev_p2,
-                                    ));
-                                    punct.into()
-                                }
-                            }
-                            prev => {
-                                vec.push(prev);
-                                punct.into()
-                            }
-                        };
-                        vec.push(next);
-                    } else {
+                TokenTree::Punct(punct) => match vec.pop() {
+                    Some(Token::Punct1(prev_chr, prev_punct))
+                        if prev_punct.spacing() == Spacing::Joint
+                            && is_operator(prev_chr, punct.as_char()) =>
+                    {
+                        vec.push(Token::Punct2(prev_chr, punct.as_char(), prev_punct, punct))
+                    }
+                    prev => {
+                        vec.extend(prev);
                         vec.push(punct.into());
                     }
-                }
+                },
+                TokenTree::Group(group) if is_spread(&group) => vec.push(Token::Spread(group)),
                 TokenTree::Group(group) => vec.push(group.into()),
             }
         }
@@ -394,13 +415,9 @@ pub fn to_stream<'a, I: IntoIterator<Item = &'a Token>>(tokens: I) -> TokenStrea
                 TokenTree::Punct(p1.clone()),
                 TokenTree::Punct(p2.clone()),
             ]),
-            Token::Punct3(_, _, _, p1, p2, p3) => stream.extend(vec![
-                TokenTree::Punct(p1.clone()),
-                TokenTree::Punct(p2.clone()),
-                TokenTree::Punct(p3.clone()),
-            ]),
             Token::Group(_, group) => stream.extend(vec![TokenTree::Group(group.clone())]),
             Token::Keyword(_, ident) => stream.extend(vec![TokenTree::Ident(ident.clone())]),
+            Token::Spread(group) => stream.extend(vec![TokenTree::Group(group.clone())]),
         }
     }
     stream
@@ -5,15 +5,14 @@
 #[allow(rust_2018_idioms)]
 extern crate proc_macro;
 
-use proc_macro_hack::proc_macro_hack;
-
+mod block;
 mod context;
 mod error;
 mod gtk;
 mod lexer;
 mod parser;
 
-#[proc_macro_hack]
+#[proc_macro]
 pub fn gtk(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // let orig_stream = input.clone();
     // let input: proc_macro2::TokenStream = input.into();
@@ -39,3 +38,14 @@ pub fn gtk(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // ))
     // .expect("unable to write to macroexpand.log");
 }
+
+#[proc_macro]
+pub fn props(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let stream: lexer::Tokens = input.into();
+    let result = parser::grammar::GtkElementParser::new().parse(stream.lexer());
+    match result {
+        Err(err) => error::parse_error(&stream, &err),
+        Ok(element) => gtk::expand_props(&element),
+    }
+    .into()
+}
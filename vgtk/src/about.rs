@@ -0,0 +1,202 @@
+use gdk_pixbuf::Pixbuf;
+use gtk::prelude::GtkWindowExtManual;
+use gtk::{AboutDialog, AboutDialogExt, DialogExt, GtkWindowExt, License, WidgetExt, Window};
+
+use crate::component::current_window;
+
+/// Builds and shows an [`AboutDialog`][AboutDialog] for you.
+///
+/// Start one with the [`about!()`][about] macro, which fills in your
+/// program's name, version, authors, description, website and license from
+/// your `Cargo.toml`, then override or add to them with the builder methods,
+/// and [`show()`][show] it from your component's [`update`][update] method.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::UpdateAction;
+/// # #[derive(Clone, Debug)] enum Message { About }
+/// # #[derive(Default)] struct Comp;
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> vgtk::VNode<Comp> { unimplemented!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::About => {
+///             vgtk::about!()
+///                 .logo_icon_name("accessories-text-editor")
+///                 .copyright("© 2020 The Notes Authors")
+///                 .show();
+///             UpdateAction::None
+///         }
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [AboutDialog]: ../gtk/struct.AboutDialog.html
+/// [about]: macro.about.html
+/// [show]: #method.show
+/// [update]: trait.Component.html#method.update
+pub struct About {
+    dialog: AboutDialog,
+}
+
+impl About {
+    /// Construct an about dialog for the program `name` at `version`.
+    pub fn new(name: &str, version: &str) -> Self {
+        let dialog = AboutDialog::new();
+        dialog.set_program_name(name);
+        dialog.set_version(Some(version));
+        About { dialog }
+    }
+
+    /// Construct an about dialog from the package metadata Cargo provides,
+    /// leaving out whatever's empty.
+    #[doc(hidden)]
+    pub fn from_cargo(
+        name: &str,
+        version: &str,
+        authors: &str,
+        description: &str,
+        homepage: &str,
+        repository: &str,
+        license: &str,
+    ) -> Self {
+        let mut about = About::new(name, version);
+        let authors: Vec<&str> = authors
+            .split(':')
+            .filter(|author| !author.is_empty())
+            .collect();
+        if !authors.is_empty() {
+            about = about.authors(&authors);
+        }
+        if !description.is_empty() {
+            about = about.comments(description);
+        }
+        match (homepage, repository) {
+            ("", "") => {}
+            ("", website) | (website, _) => about = about.website(website),
+        }
+        match cargo_license(license) {
+            Some(license) => about.license_type(license),
+            None if !license.is_empty() => about.license(license),
+            None => about,
+        }
+    }
+
+    /// Set the list of authors.
+    pub fn authors(self, authors: &[&str]) -> Self {
+        self.dialog.set_authors(authors);
+        self
+    }
+
+    /// Set a short description of the program.
+    pub fn comments(self, comments: &str) -> Self {
+        self.dialog.set_comments(Some(comments));
+        self
+    }
+
+    /// Set the copyright notice.
+    pub fn copyright(self, copyright: &str) -> Self {
+        self.dialog.set_copyright(Some(copyright));
+        self
+    }
+
+    /// Set the URL of the program's website.
+    pub fn website(self, website: &str) -> Self {
+        self.dialog.set_website(Some(website));
+        self
+    }
+
+    /// Set the logo to an image.
+    pub fn logo(self, logo: &Pixbuf) -> Self {
+        self.dialog.set_logo(Some(logo));
+        self
+    }
+
+    /// Set the logo to a named icon from the icon theme.
+    pub fn logo_icon_name(self, icon_name: &str) -> Self {
+        self.dialog.set_logo_icon_name(Some(icon_name));
+        self
+    }
+
+    /// Set the license to one of the licenses GTK knows.
+    pub fn license_type(self, license: License) -> Self {
+        self.dialog.set_license_type(license);
+        self
+    }
+
+    /// Set the text of the license.
+    pub fn license(self, license: &str) -> Self {
+        self.dialog.set_license(Some(license));
+        self.dialog.set_wrap_license(true);
+        self
+    }
+
+    /// Set the window the dialog belongs to.
+    ///
+    /// If you don't set one, the [current window][current_window] will be used.
+    ///
+    /// [current_window]: fn.current_window.html
+    pub fn parent(self, parent: &Window) -> Self {
+        self.dialog.set_transient_for(Some(parent));
+        self
+    }
+
+    /// Show the dialog, which goes away again when the user closes it.
+    pub fn show(self) {
+        let dialog = self.dialog;
+        if dialog.get_transient_for().is_none() {
+            if let Some(parent) = current_window() {
+                dialog.set_transient_for(Some(&parent));
+            }
+        }
+        dialog.set_modal(true);
+        dialog.connect_response(|dialog, _| dialog.destroy());
+        dialog.present();
+    }
+}
+
+// The licenses GTK knows, by their SPDX names, which Cargo uses.
+fn cargo_license(license: &str) -> Option<License> {
+    match license {
+        "MIT" => Some(License::MitX11),
+        "BSD-2-Clause" | "BSD-3-Clause" => Some(License::Bsd),
+        "Artistic-2.0" => Some(License::Artistic),
+        "GPL-2.0-or-later" | "GPL-2.0+" => Some(License::Gpl20),
+        "GPL-2.0-only" | "GPL-2.0" => Some(License::Gpl20Only),
+        "GPL-3.0-or-later" | "GPL-3.0+" => Some(License::Gpl30),
+        "GPL-3.0-only" | "GPL-3.0" => Some(License::Gpl30Only),
+        "LGPL-2.1-or-later" | "LGPL-2.1+" => Some(License::Lgpl21),
+        "LGPL-2.1-only" | "LGPL-2.1" => Some(License::Lgpl21Only),
+        "LGPL-3.0-or-later" | "LGPL-3.0+" => Some(License::Lgpl30),
+        "LGPL-3.0-only" | "LGPL-3.0" => Some(License::Lgpl30Only),
+        _ => None,
+    }
+}
+
+/// Start an [`About`][About] dialog filled in from your `Cargo.toml`.
+///
+/// This takes the program's name, version, authors, description, website
+/// and license from the package metadata of the crate it's used in. If the
+/// package has no `homepage`, its `repository` is used as the website, and a
+/// license which GTK doesn't know, like `"MIT OR Apache-2.0"`, is shown as
+/// it's written.
+///
+/// See [`About`][About] for an example.
+///
+/// [About]: struct.About.html
+#[macro_export]
+macro_rules! about {
+    () => {
+        $crate::About::from_cargo(
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            env!("CARGO_PKG_AUTHORS"),
+            env!("CARGO_PKG_DESCRIPTION"),
+            env!("CARGO_PKG_HOMEPAGE"),
+            env!("CARGO_PKG_REPOSITORY"),
+            env!("CARGO_PKG_LICENSE"),
+        )
+    };
+}
@@ -0,0 +1,318 @@
+use gio::{ActionExt, ActionMap, ActionMapExt, SimpleAction, SimpleActionGroup};
+use glib::{Cast, Object, ObjectExt, Variant, VariantTy, VariantType};
+use gtk::{Application, ApplicationWindow, GtkApplicationExt, Widget, WidgetExt};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use colored::Colorize;
+use log::trace;
+
+use crate::component::Component;
+use crate::scope::Scope;
+
+type ActionHandler<Msg> = Rc<dyn Fn(Option<&Variant>) -> Msg>;
+
+/// A named action declared by a [`Component`][Component].
+///
+/// Return these from [`Component::actions()`][actions] to register them on your
+/// component's top level object. Activating an action, whether from a menu
+/// item, a keyboard shortcut or a widget's `action_name`, sends its message to
+/// your component's [`update`][update] method.
+///
+/// Actions with an accelerator are listed in the window shown by
+/// [`vgtk::show_shortcuts()`][show_shortcuts], under the
+/// [`title()`][title] and [`group()`][group] you give them.
+///
+/// Actions are registered with the prefix `app` if the component's top level
+/// object is an `Application`, `win` if it's an `ApplicationWindow`, and
+/// `component` for any other widget, in which case they're only available to
+/// that widget and its descendants.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, ActionDecl, Component, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)]
+/// enum Message {
+///     Save,
+/// }
+///
+/// # #[derive(Default)]
+/// # struct Editor { dirty: bool }
+/// # impl Component for Editor {
+/// #     type Message = Message; type Properties = ();
+/// fn actions(&self) -> Vec<ActionDecl<Message>> {
+///     vec![ActionDecl::new("save", Message::Save)
+///         .enabled(self.dirty)
+///         .accelerator("<Ctrl>s")
+///         .title("Save the document")
+///         .group("Documents")]
+/// }
+///
+/// fn view(&self) -> VNode<Self> {
+///     gtk! {
+///         <ApplicationWindow>
+///             <Button label="Save" action_name="win.save" />
+///         </ApplicationWindow>
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [Component]: trait.Component.html
+/// [actions]: trait.Component.html#method.actions
+/// [update]: trait.Component.html#method.update
+/// [show_shortcuts]: fn.show_shortcuts.html
+/// [title]: #method.title
+/// [group]: #method.group
+pub struct ActionDecl<Msg> {
+    name: String,
+    parameter_type: Option<VariantType>,
+    enabled: bool,
+    accelerators: Vec<String>,
+    title: Option<String>,
+    group: Option<String>,
+    activate: ActionHandler<Msg>,
+}
+
+impl<Msg: 'static> ActionDecl<Msg> {
+    /// Declare an action which sends `message` when it's activated.
+    pub fn new(name: &str, message: Msg) -> Self
+    where
+        Msg: Clone,
+    {
+        ActionDecl {
+            name: name.to_string(),
+            parameter_type: None,
+            enabled: true,
+            accelerators: Vec::new(),
+            title: None,
+            group: None,
+            activate: Rc::new(move |_| message.clone()),
+        }
+    }
+
+    /// Declare an action which takes a parameter of the given type, and
+    /// constructs its message from the parameter when it's activated.
+    pub fn with_parameter<F>(name: &str, parameter_type: &VariantTy, f: F) -> Self
+    where
+        F: Fn(&Variant) -> Msg + 'static,
+    {
+        ActionDecl {
+            name: name.to_string(),
+            parameter_type: Some(parameter_type.to_owned()),
+            enabled: true,
+            accelerators: Vec::new(),
+            title: None,
+            group: None,
+            activate: Rc::new(move |parameter| {
+                f(parameter.expect("action activated without a parameter"))
+            }),
+        }
+    }
+
+    /// Set whether the action can be activated. Actions are enabled by default.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Add a keyboard shortcut for the action, such as `"<Ctrl>s"`.
+    pub fn accelerator(mut self, accelerator: &str) -> Self {
+        self.accelerators.push(accelerator.to_string());
+        self
+    }
+
+    /// Set what the action is called in the shortcuts window. Without a
+    /// title, it's listed under its name.
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Set the heading the action is listed under in the shortcuts window,
+    /// along with the other actions in the same group.
+    pub fn group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+}
+
+impl<Msg> Debug for ActionDecl<Msg> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "ActionDecl[{}]", self.name)
+    }
+}
+
+/// A keyboard shortcut for an action, as listed in the shortcuts window.
+#[derive(Clone, Debug)]
+pub(crate) struct Shortcut {
+    pub(crate) action: String,
+    pub(crate) title: String,
+    pub(crate) group: Option<String>,
+    pub(crate) accelerators: Vec<String>,
+}
+
+thread_local! {
+    // Every action with an accelerator, in the order they were declared.
+    static SHORTCUTS: RefCell<Vec<Shortcut>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Get the keyboard shortcuts of every action which has any.
+pub(crate) fn shortcuts() -> Vec<Shortcut> {
+    SHORTCUTS.with(|shortcuts| shortcuts.borrow().clone())
+}
+
+fn set_shortcut(shortcut: Shortcut) {
+    SHORTCUTS.with(|shortcuts| {
+        let mut shortcuts = shortcuts.borrow_mut();
+        match shortcuts
+            .iter_mut()
+            .find(|existing| existing.action == shortcut.action)
+        {
+            Some(existing) if shortcut.accelerators.is_empty() => {
+                let action = existing.action.clone();
+                shortcuts.retain(|existing| existing.action != action);
+            }
+            Some(existing) => *existing = shortcut,
+            None if shortcut.accelerators.is_empty() => {}
+            None => shortcuts.push(shortcut),
+        }
+    });
+}
+
+struct RegisteredAction<Msg> {
+    action: SimpleAction,
+    accelerators: Vec<String>,
+    handler: Rc<RefCell<ActionHandler<Msg>>>,
+}
+
+/// The actions a component has registered on its top level object.
+pub(crate) struct ActionState<C: Component> {
+    prefix: &'static str,
+    map: ActionMap,
+    actions: HashMap<String, RegisteredAction<C::Message>>,
+}
+
+fn default_application() -> Option<Application> {
+    gio::Application::get_default().and_then(|app| app.downcast().ok())
+}
+
+impl<C: 'static + Component> ActionState<C> {
+    pub(crate) fn new(object: &Object) -> Self {
+        let (prefix, map): (_, ActionMap) = if let Some(app) = object.downcast_ref::<Application>()
+        {
+            ("app", app.clone().upcast())
+        } else if let Some(window) = object.downcast_ref::<ApplicationWindow>() {
+            ("win", window.clone().upcast())
+        } else if let Some(widget) = object.downcast_ref::<Widget>() {
+            let group = SimpleActionGroup::new();
+            widget.insert_action_group("component", Some(&group));
+            ("component", group.upcast())
+        } else {
+            panic!(
+                "components can only declare actions on Widgets or Applications, but {} was found.",
+                object.get_type()
+            );
+        };
+        ActionState {
+            prefix,
+            map,
+            actions: HashMap::new(),
+        }
+    }
+
+    fn set_accels(&self, name: &str, accelerators: &[String]) {
+        let name = format!("{}.{}", self.prefix, name);
+        trace!(
+            "{} {} -> {}",
+            "Action:".bright_black(),
+            name.bright_cyan().bold(),
+            format!("{:?}", accelerators).bright_green().bold()
+        );
+        if let Some(app) = default_application() {
+            let accels: Vec<&str> = accelerators.iter().map(String::as_str).collect();
+            app.set_accels_for_action(&name, &accels);
+        }
+    }
+
+    fn register(
+        &self,
+        decl: &ActionDecl<C::Message>,
+        scope: &Scope<C>,
+    ) -> RegisteredAction<C::Message> {
+        let action = SimpleAction::new(&decl.name, decl.parameter_type.as_deref());
+        let handler = Rc::new(RefCell::new(decl.activate.clone()));
+        let inner_handler = handler.clone();
+        let scope = scope.clone();
+        action.connect_activate(move |_, parameter| {
+            let handler = inner_handler.borrow().clone();
+            scope.send_message(handler(parameter));
+        });
+        self.map.add_action(&action);
+        RegisteredAction {
+            action,
+            accelerators: Vec::new(),
+            handler,
+        }
+    }
+
+    /// Bring the registered actions in line with a fresh set of declarations,
+    /// adding, updating and removing actions as needed.
+    pub(crate) fn update(&mut self, decls: Vec<ActionDecl<C::Message>>, scope: &Scope<C>) {
+        let mut old = std::mem::take(&mut self.actions);
+        for decl in decls {
+            let mut registered = match old.remove(&decl.name) {
+                Some(registered)
+                    if registered.action.get_parameter_type() == decl.parameter_type =>
+                {
+                    registered
+                }
+                Some(_) => {
+                    self.map.remove_action(&decl.name);
+                    self.register(&decl, scope)
+                }
+                None => self.register(&decl, scope),
+            };
+            *registered.handler.borrow_mut() = decl.activate;
+            registered.action.set_enabled(decl.enabled);
+            if registered.accelerators != decl.accelerators {
+                self.set_accels(&decl.name, &decl.accelerators);
+                registered.accelerators = decl.accelerators.clone();
+            }
+            let name = &decl.name;
+            set_shortcut(Shortcut {
+                action: format!("{}.{}", self.prefix, name),
+                title: decl.title.unwrap_or_else(|| name.clone()),
+                group: decl.group,
+                accelerators: decl.accelerators,
+            });
+            self.actions.insert(decl.name, registered);
+        }
+        for name in old.keys() {
+            self.unregister(name);
+        }
+    }
+
+    fn unregister(&self, name: &str) {
+        self.map.remove_action(name);
+        self.set_accels(name, &[]);
+        set_shortcut(Shortcut {
+            action: format!("{}.{}", self.prefix, name),
+            title: String::new(),
+            group: None,
+            accelerators: Vec::new(),
+        });
+    }
+
+    /// Remove every registered action.
+    pub(crate) fn clear(&mut self) {
+        for name in std::mem::take(&mut self.actions).keys() {
+            self.unregister(name);
+        }
+    }
+}
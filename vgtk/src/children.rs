@@ -0,0 +1,286 @@
+use glib::{Object, SignalHandlerId, Type};
+
+use std::any::{Any, TypeId};
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use crate::component::Component;
+use crate::scope::Scope;
+use crate::vdom::ComponentState;
+use crate::vnode::component::AnyProps;
+use crate::vnode::{VComponent, VHandler, VNode, VObject, VProperty};
+
+type PropertySetter = Rc<dyn Fn(&Object, Option<&Object>, bool)>;
+type BoundConstructor = Rc<dyn Fn(&AnyProps, Option<&Object>, &[VProperty]) -> ComponentState<()>>;
+
+/// Child nodes passed into a subcomponent from its parent's [`gtk!`][gtk!] macro.
+///
+/// Give your subcomponent's properties a field called `children` of this type,
+/// and anything you write between its opening and closing tags will be passed
+/// into it. Then use [`render()`][render] inside a code block in the
+/// subcomponent's view to place the children wherever you like.
+///
+/// The children's signal handlers still send their messages to the component
+/// whose view they were written in, not to the subcomponent that renders
+/// them, so you can write wrapper components without having to forward any
+/// messages.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Children, Component, VNode};
+/// # use vgtk::lib::gtk::*;
+/// #[derive(Clone, Debug, Default)]
+/// pub struct Card {
+///     pub title: String,
+///     pub children: Children,
+/// }
+///
+/// impl Component for Card {
+///     type Message = ();
+///     type Properties = Self;
+///
+///     fn create(props: Self) -> Self {
+///         props
+///     }
+///
+///     fn change(&mut self, props: Self) -> vgtk::UpdateAction<Self> {
+///         *self = props;
+///         vgtk::UpdateAction::Render
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         gtk! {
+///             <Frame label=self.title.clone()>
+///                 <Box orientation=Orientation::Vertical>
+///                     { self.children.render() }
+///                 </Box>
+///             </Frame>
+///         }
+///     }
+/// }
+///
+/// # #[derive(Clone, Debug)] enum Message { Save }
+/// # #[derive(Default)] struct Editor;
+/// # impl Component for Editor { type Message = Message; type Properties = ();
+/// fn view(&self) -> VNode<Editor> {
+///     gtk! {
+///         <@Card title="Settings">
+///             <Label label="Changes are saved when you close the window." />
+///             <Button label="Save now" on clicked=|_| Message::Save />
+///         </@Card>
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [gtk!]: macro.gtk.html
+/// [render]: #method.render
+#[derive(Clone, Default)]
+pub struct Children {
+    nodes: Rc<Vec<BoundNode>>,
+}
+
+impl Children {
+    pub(crate) fn new<Model: 'static + Component>(
+        nodes: Vec<VNode<Model>>,
+        scope: Scope<Model>,
+    ) -> Self {
+        Children {
+            nodes: Rc::new(nodes.into_iter().map(|node| bind(node, &scope)).collect()),
+        }
+    }
+
+    /// Test whether there are no children.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Get the number of children.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Render the children into the view of the component receiving them.
+    ///
+    /// The returned nodes can go straight into a code block in the
+    /// [`gtk!`][gtk!] macro.
+    ///
+    /// [gtk!]: macro.gtk.html
+    pub fn render<C: 'static + Component>(&self) -> Vec<VNode<C>> {
+        self.nodes.iter().map(render).collect()
+    }
+}
+
+impl PartialEq for Children {
+    /// Test whether two `Children` are equal.
+    ///
+    /// Children are considered equal only if they came from the same render
+    /// of the parent component.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes)
+    }
+}
+
+impl Debug for Children {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Children[{}]", self.nodes.len())
+    }
+}
+
+/// A child node with its signal handlers and subcomponents bound to the scope
+/// of the component which created it, so it no longer depends on its model type.
+enum BoundNode {
+    Object(BoundObject),
+    Component(BoundComponent),
+}
+
+struct BoundObject {
+    object_type: Type,
+    key: Option<String>,
+    constructor: Option<Rc<dyn Fn() -> Object>>,
+    widget_ref: Option<Rc<dyn Fn(Option<&Object>)>>,
+    properties: Vec<BoundProperty>,
+    child_props: Vec<BoundProperty>,
+    handlers: Vec<BoundHandler>,
+    children: Vec<BoundNode>,
+}
+
+struct BoundComponent {
+    model_type: TypeId,
+    name: &'static str,
+    key: Option<String>,
+    props: AnyProps,
+    constructor: BoundConstructor,
+    component_ref: Option<Rc<dyn Fn(Option<&dyn Any>)>>,
+    child_props: Vec<BoundProperty>,
+}
+
+struct BoundProperty {
+    name: &'static str,
+    value: Option<String>,
+    set: PropertySetter,
+}
+
+struct BoundHandler {
+    name: &'static str,
+    id: &'static str,
+    set: Rc<dyn Fn(&Object) -> SignalHandlerId>,
+}
+
+fn bind_properties(properties: Vec<VProperty>) -> Vec<BoundProperty> {
+    properties
+        .into_iter()
+        .map(|prop| BoundProperty {
+            name: prop.name,
+            value: prop.value,
+            set: Rc::from(prop.set),
+        })
+        .collect()
+}
+
+fn bind<Model: 'static + Component>(node: VNode<Model>, scope: &Scope<Model>) -> BoundNode {
+    match node {
+        // Children are rendered again along with the subcomponent anyway, so
+        // there's nothing to gain from keeping them lazy.
+        VNode::Lazy(lazy) => bind(lazy.into_node(), scope),
+        VNode::Object(object) => BoundNode::Object(BoundObject {
+            object_type: object.object_type,
+            key: object.key,
+            constructor: object.constructor.map(Rc::from),
+            widget_ref: object.widget_ref,
+            properties: bind_properties(object.properties),
+            child_props: bind_properties(object.child_props),
+            handlers: object
+                .handlers
+                .into_iter()
+                .map(|handler| {
+                    let scope = scope.clone();
+                    let set = handler.set;
+                    BoundHandler {
+                        name: handler.name,
+                        id: handler.id,
+                        set: Rc::new(move |object| set(object, &scope)),
+                    }
+                })
+                .collect(),
+            children: object
+                .children
+                .into_iter()
+                .map(|child| bind(child, scope))
+                .collect(),
+        }),
+        VNode::Component(comp) => {
+            let scope = scope.clone();
+            let constructor = comp.constructor;
+            BoundNode::Component(BoundComponent {
+                model_type: comp.model_type,
+                name: comp.name,
+                key: comp.key,
+                props: comp.props,
+                constructor: Rc::new(move |props, parent, child_props| {
+                    constructor(props, parent, child_props, &scope).retype()
+                }),
+                component_ref: comp.component_ref,
+                child_props: bind_properties(comp.child_props),
+            })
+        }
+    }
+}
+
+fn render_properties(properties: &[BoundProperty]) -> Vec<VProperty> {
+    properties
+        .iter()
+        .map(|prop| {
+            let set = prop.set.clone();
+            VProperty {
+                name: prop.name,
+                value: prop.value.clone(),
+                set: Box::new(move |object, parent, force| set(object, parent, force)),
+            }
+        })
+        .collect()
+}
+
+fn render<C: 'static + Component>(node: &BoundNode) -> VNode<C> {
+    match node {
+        BoundNode::Object(object) => VNode::Object(VObject {
+            object_type: object.object_type,
+            key: object.key.clone(),
+            constructor: object.constructor.clone().map(|constructor| {
+                let constructor: Box<dyn Fn() -> Object> = Box::new(move || constructor());
+                constructor
+            }),
+            widget_ref: object.widget_ref.clone(),
+            properties: render_properties(&object.properties),
+            child_props: render_properties(&object.child_props),
+            handlers: object
+                .handlers
+                .iter()
+                .map(|handler| {
+                    let set = handler.set.clone();
+                    VHandler {
+                        name: handler.name,
+                        id: handler.id,
+                        set: Box::new(move |object, _: &Scope<C>| set(object)),
+                    }
+                })
+                .collect(),
+            children: object.children.iter().map(render).collect(),
+        }),
+        BoundNode::Component(comp) => {
+            let constructor = comp.constructor.clone();
+            VNode::Component(VComponent::with_constructor(
+                comp.model_type,
+                comp.name,
+                comp.key.clone(),
+                comp.props.clone(),
+                Box::new(move |props, parent, child_props, _: &Scope<C>| {
+                    constructor(props, parent, child_props).retype()
+                }),
+                comp.component_ref.clone(),
+                render_properties(&comp.child_props),
+            ))
+        }
+    }
+}
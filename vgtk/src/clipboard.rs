@@ -0,0 +1,118 @@
+//! The clipboard.
+//!
+//! Copying is done straight from your component's [`update`][update] method
+//! with [`set_text()`][set_text] or [`set_image()`][set_image]. Pasting has
+//! to wait for whichever application owns the clipboard to send its contents,
+//! so [`text()`][text] and [`image()`][image] return a [`Future`][Future]
+//! instead, which you can turn into a message with
+//! [`UpdateAction::defer()`][defer].
+//!
+//! These all use the default clipboard, the one behind the usual copy and
+//! paste shortcuts, and GTK must have been initialised before they're called.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use vgtk::clipboard;
+//! # use vgtk::{Component, UpdateAction, VNode};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Copy,
+//!     Paste,
+//!     Pasted(Option<String>),
+//! }
+//!
+//! #[derive(Default)]
+//! struct Editor {
+//!     text: String,
+//! }
+//!
+//! impl Component for Editor {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         match message {
+//!             Message::Copy => {
+//!                 clipboard::set_text(&self.text);
+//!                 UpdateAction::None
+//!             }
+//!             Message::Paste => UpdateAction::defer(async {
+//!                 Message::Pasted(clipboard::text().await)
+//!             }),
+//!             Message::Pasted(Some(text)) => {
+//!                 self.text.push_str(&text);
+//!                 UpdateAction::Render
+//!             }
+//!             Message::Pasted(None) => UpdateAction::None,
+//!         }
+//!     }
+//! #   fn view(&self) -> VNode<Self> { todo!() }
+//! }
+//! ```
+//!
+//! [update]: ../trait.Component.html#method.update
+//! [set_text]: fn.set_text.html
+//! [set_image]: fn.set_image.html
+//! [text]: fn.text.html
+//! [image]: fn.image.html
+//! [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+//! [defer]: ../enum.UpdateAction.html#method.defer
+
+use gdk::{Atom, SELECTION_CLIPBOARD};
+use gdk_pixbuf::Pixbuf;
+use gtk::Clipboard;
+
+use futures::channel::oneshot;
+
+use std::future::Future;
+
+fn clipboard() -> Clipboard {
+    Clipboard::get(&SELECTION_CLIPBOARD)
+}
+
+/// Put `text` on the clipboard.
+pub fn set_text(text: &str) {
+    clipboard().set_text(text);
+}
+
+/// Put an image on the clipboard.
+pub fn set_image(image: &Pixbuf) {
+    clipboard().set_image(image);
+}
+
+/// Empty the clipboard, if it holds something your application put there.
+pub fn clear() {
+    clipboard().clear();
+}
+
+/// Ask for the text on the clipboard.
+///
+/// The returned [`Future`][Future] resolves to the text, or to `None` if the
+/// clipboard is empty or holds something which can't be turned into text.
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+pub fn text() -> impl Future<Output = Option<String>> {
+    let (notify, result) = oneshot::channel();
+    clipboard().request_text(move |_, text| {
+        let _ = notify.send(text.map(str::to_string));
+    });
+    async move { result.await.ok().flatten() }
+}
+
+/// Ask for the image on the clipboard.
+///
+/// The returned [`Future`][Future] resolves to the image, or to `None` if
+/// the clipboard doesn't hold an image.
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+pub fn image() -> impl Future<Output = Option<Pixbuf>> {
+    let (notify, result) = oneshot::channel();
+    // `Clipboard::request_image()` can't tell us when there isn't one, so ask
+    // for a PNG, which is what GTK offers images as, and decode it ourselves.
+    clipboard().request_contents(&Atom::intern("image/png"), move |_, data| {
+        let _ = notify.send(data.get_pixbuf());
+    });
+    async move { result.await.ok().flatten() }
+}
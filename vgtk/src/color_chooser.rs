@@ -0,0 +1,127 @@
+use gdk::RGBA;
+use gtk::{
+    ColorChooserDialog, ColorChooserExt, DialogExt, GtkWindowExt, ResponseType, WidgetExt, Window,
+};
+
+use std::future::Future;
+
+use crate::component::current_window;
+use crate::on_signal;
+
+/// Builds and runs a color chooser dialog for you.
+///
+/// [`run()`][run] returns a [`Future`][Future] which resolves to the color
+/// the user picked, or `None` if they cancelled, so you can use it from an
+/// [`UpdateAction::defer()`][defer] block and turn the result into a message.
+///
+/// If you'd rather have the user pick a color straight from your view, use a
+/// [`ColorButton`][ColorButton], which shows the same dialog when it's
+/// clicked. See [`ColorButtonExtHelpers`][ColorButtonExtHelpers].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ColorChooser, UpdateAction};
+/// # use vgtk::lib::gdk::RGBA;
+/// # #[derive(Clone, Debug)] enum Message { PickColor, Picked(Option<RGBA>) }
+/// # struct Comp { color: RGBA }
+/// # impl Default for Comp { fn default() -> Self { Comp { color: RGBA::black() } } }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> vgtk::VNode<Comp> { unimplemented!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::PickColor => {
+///             let color = ColorChooser::new()
+///                 .title("Highlight Color")
+///                 .rgba(&self.color)
+///                 .use_alpha(false)
+///                 .run();
+///             async move { Message::Picked(color.await) }.into()
+///         }
+///         Message::Picked(Some(color)) => {
+///             self.color = color;
+///             UpdateAction::Render
+///         }
+///         Message::Picked(None) => UpdateAction::None,
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [run]: #method.run
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [defer]: enum.UpdateAction.html#method.defer
+/// [ColorButton]: ../gtk/struct.ColorButton.html
+/// [ColorButtonExtHelpers]: ext/trait.ColorButtonExtHelpers.html
+pub struct ColorChooser {
+    dialog: ColorChooserDialog,
+}
+
+impl ColorChooser {
+    /// Construct a color chooser.
+    pub fn new() -> Self {
+        ColorChooser {
+            dialog: ColorChooserDialog::new(None, None::<&Window>),
+        }
+    }
+
+    /// Set the title of the color chooser.
+    pub fn title(self, title: &str) -> Self {
+        self.dialog.set_title(title);
+        self
+    }
+
+    /// Set the window the color chooser belongs to.
+    ///
+    /// If you don't set one, the [current window][current_window] will be used.
+    ///
+    /// [current_window]: fn.current_window.html
+    pub fn parent(self, parent: &Window) -> Self {
+        self.dialog.set_transient_for(Some(parent));
+        self
+    }
+
+    /// Set the color the color chooser starts out with.
+    pub fn rgba(self, color: &RGBA) -> Self {
+        self.dialog.set_rgba(color);
+        self
+    }
+
+    /// Allow the user to pick a color which isn't fully opaque.
+    pub fn use_alpha(self, use_alpha: bool) -> Self {
+        self.dialog.set_use_alpha(use_alpha);
+        self
+    }
+
+    /// Show the color chooser.
+    ///
+    /// The returned [`Future`][Future] resolves to the color the user picked,
+    /// or to `None` if they cancelled.
+    ///
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub fn run(self) -> impl Future<Output = Option<RGBA>> {
+        let dialog = self.dialog;
+        if dialog.get_transient_for().is_none() {
+            if let Some(parent) = current_window() {
+                dialog.set_transient_for(Some(&parent));
+            }
+        }
+        dialog.set_modal(true);
+        dialog.show();
+        async move {
+            let response = on_signal!(dialog, connect_response).await;
+            let color = match response {
+                Ok(ResponseType::Ok) => Some(dialog.get_rgba()),
+                _ => None,
+            };
+            dialog.destroy();
+            color
+        }
+    }
+}
+
+impl Default for ColorChooser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
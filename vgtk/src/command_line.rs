@@ -0,0 +1,190 @@
+use gio::prelude::*;
+use gio::ApplicationCommandLine;
+use glib::glib_sys;
+use glib::translate::{from_glib_full, ToGlibPtr};
+use glib::variant::FromVariant;
+use glib::{Char, OptionArg, OptionFlags, Variant};
+use gtk::Application;
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// A command line option your application accepts, as declared by
+/// [`Component::command_line_options()`][command_line_options].
+///
+/// Options are parsed by GIO before your application starts, which also
+/// takes care of `--help`, and their values are looked up by name on the
+/// [`CommandLine`][CommandLine] your component receives.
+///
+/// [command_line_options]: trait.Component.html#method.command_line_options
+/// [CommandLine]: struct.CommandLine.html
+#[derive(Clone, Debug)]
+pub struct CommandLineOption {
+    name: String,
+    short_name: Option<char>,
+    arg: OptionArg,
+    description: String,
+    arg_description: Option<String>,
+}
+
+impl CommandLineOption {
+    fn new(name: &str, arg: OptionArg, description: &str) -> Self {
+        CommandLineOption {
+            name: name.to_string(),
+            short_name: None,
+            arg,
+            description: description.to_string(),
+            arg_description: None,
+        }
+    }
+
+    /// Construct an option `--name` which takes no value.
+    pub fn flag(name: &str, description: &str) -> Self {
+        Self::new(name, OptionArg::None, description)
+    }
+
+    /// Construct an option `--name=VALUE` which takes a string.
+    pub fn string(name: &str, description: &str) -> Self {
+        Self::new(name, OptionArg::String, description)
+    }
+
+    /// Construct an option `--name=VALUE` which takes an integer.
+    pub fn int(name: &str, description: &str) -> Self {
+        Self::new(name, OptionArg::Int, description)
+    }
+
+    /// Construct an option `--name=VALUE` which takes a floating point number.
+    pub fn double(name: &str, description: &str) -> Self {
+        Self::new(name, OptionArg::Double, description)
+    }
+
+    /// Let the option also be given as `-c`.
+    pub fn short(mut self, short_name: char) -> Self {
+        self.short_name = Some(short_name);
+        self
+    }
+
+    /// Set the name of the option's value in the `--help` output, such as
+    /// `"FILE"`.
+    pub fn arg_description(mut self, arg_description: &str) -> Self {
+        self.arg_description = Some(arg_description.to_string());
+        self
+    }
+
+    pub(crate) fn add_to(&self, app: &Application) {
+        let short_name = Char::new(self.short_name.unwrap_or('\0'))
+            .expect("short option names must be a single byte");
+        app.add_main_option(
+            &self.name,
+            short_name,
+            OptionFlags::NONE,
+            self.arg,
+            &self.description,
+            self.arg_description.as_deref(),
+        );
+    }
+}
+
+/// An invocation of your application from the command line, as passed to
+/// [`Component::command_line()`][command_line].
+///
+/// This holds the values of the options you declared with
+/// [`Component::command_line_options()`][command_line_options] and the
+/// arguments left over after them. If the application was already running, it
+/// comes from the new process, which hands its command line to the running
+/// instance and exits, so the working directory may not be your own.
+///
+/// [command_line]: trait.Component.html#method.command_line
+/// [command_line_options]: trait.Component.html#method.command_line_options
+#[derive(Clone, Debug)]
+pub struct CommandLine {
+    options: Vec<(String, Variant)>,
+    arguments: Vec<OsString>,
+    cwd: Option<PathBuf>,
+    remote: bool,
+}
+
+impl CommandLine {
+    pub(crate) fn new(
+        command_line: &ApplicationCommandLine,
+        options: &[CommandLineOption],
+    ) -> Self {
+        // GIO sends the parsed options along in the platform data, which
+        // saves us binding `g_application_command_line_get_options_dict()`.
+        let parsed = command_line
+            .get_platform_data()
+            .and_then(|data| lookup(&data, "options"));
+        let options = match parsed {
+            Some(parsed) => options
+                .iter()
+                .filter_map(|option| {
+                    lookup(&parsed, &option.name).map(|value| (option.name.clone(), value))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        CommandLine {
+            options,
+            arguments: command_line.get_arguments().into_iter().skip(1).collect(),
+            cwd: command_line.get_cwd(),
+            remote: command_line.get_is_remote(),
+        }
+    }
+
+    fn get<A: FromVariant>(&self, name: &str) -> Option<A> {
+        self.options
+            .iter()
+            .find(|(option, _)| option == name)
+            .and_then(|(_, value)| value.get())
+    }
+
+    /// Test whether the flag `name` was given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.get(name).unwrap_or(false)
+    }
+
+    /// Get the value of the string option `name`, if it was given.
+    pub fn string(&self, name: &str) -> Option<String> {
+        self.get(name)
+    }
+
+    /// Get the value of the integer option `name`, if it was given.
+    pub fn int(&self, name: &str) -> Option<i32> {
+        self.get(name)
+    }
+
+    /// Get the value of the floating point option `name`, if it was given.
+    pub fn double(&self, name: &str) -> Option<f64> {
+        self.get(name)
+    }
+
+    /// Get the arguments which aren't options, not including the name of the
+    /// program.
+    pub fn arguments(&self) -> &[OsString] {
+        &self.arguments
+    }
+
+    /// Get the working directory of the process which was invoked, if it's
+    /// known.
+    pub fn cwd(&self) -> Option<&Path> {
+        self.cwd.as_deref()
+    }
+
+    /// Test whether the command line was passed on by another process,
+    /// rather than being the one which started this one.
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+}
+
+// glib-rs can't look up keys in a dictionary variant yet.
+fn lookup(dict: &Variant, key: &str) -> Option<Variant> {
+    #[allow(unsafe_code)]
+    unsafe {
+        from_glib_full(glib_sys::g_variant_lookup_value(
+            dict.to_glib_none().0,
+            key.to_glib_none().0,
+            std::ptr::null(),
+        ))
+    }
+}
@@ -1,23 +1,35 @@
 use futures::{
     channel::mpsc::{unbounded, UnboundedSender},
-    future::FutureExt,
-    stream::{select, Stream},
+    future::{abortable, AbortHandle, FutureExt},
+    stream::{select, select_all, Stream},
     task::{Context, Poll},
     StreamExt,
 };
-use glib::{Cast, MainContext, Object, ObjectExt, WeakRef};
+use gio::File;
+use glib::{Cast, MainContext, Object, ObjectExt, WeakRef, PRIORITY_HIGH_IDLE};
 use gtk::{Application, GtkApplicationExt, Widget, WidgetExt, Window};
 
-use std::any::TypeId;
+use std::cell::Cell;
 use std::fmt::{Debug, Error, Formatter};
 use std::future::Future;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::RwLock;
 
 use colored::Colorize;
 use log::{debug, trace};
 
+use crate::action::{ActionDecl, ActionState};
+use crate::command_line::{CommandLine, CommandLineOption};
+use crate::context::Consumer;
+use crate::css;
+#[cfg(feature = "debug")]
+use crate::inspector::Inspected;
+#[cfg(feature = "profile")]
+use crate::profile::Frame;
+use crate::progress::{Progress, ProgressReporter};
 use crate::scope::{AnyScope, Scope};
+use crate::subscription::Subscription;
 use crate::vdom::State;
 use crate::vnode::VNode;
 
@@ -80,6 +92,89 @@ impl<C: Component> UpdateAction<C> {
     pub fn defer(job: impl Future<Output = C::Message> + 'static) -> Self {
         UpdateAction::Defer(job.boxed_local())
     }
+
+    /// Construct a deferred action out of a task which reports its progress
+    /// as it goes.
+    ///
+    /// `job` is given a [`ProgressReporter`][ProgressReporter], which sends
+    /// the component the message `progress` makes out of each report. Keep
+    /// the latest [`Progress`][Progress] in your component's state, and give
+    /// it to the `progress` property of a [`ProgressBar`][ProgressBar] or a
+    /// [`LevelBar`][LevelBar] to show it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it's called from outside the component's own methods, like
+    /// [`Component::update()`][update].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{ext::*, gtk, timer, Component, Progress, UpdateAction, VNode};
+    /// # use vgtk::lib::gtk::*;
+    /// # use std::time::Duration;
+    /// #[derive(Clone, Debug)]
+    /// enum Message {
+    ///     Import,
+    ///     Progress(Progress),
+    ///     Imported,
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Importer {
+    ///     progress: Option<Progress>,
+    /// }
+    ///
+    /// impl Component for Importer {
+    ///     type Message = Message;
+    ///     type Properties = ();
+    ///
+    ///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+    ///         match message {
+    ///             Message::Import => {
+    ///                 self.progress = Some(Progress::Pulse);
+    ///                 return UpdateAction::defer_with_progress(Message::Progress, |progress| {
+    ///                     async move {
+    ///                         for step in 1..=10 {
+    ///                             timer::timeout(Duration::from_millis(200)).await;
+    ///                             progress.set_fraction(step as f64 / 10.0);
+    ///                         }
+    ///                         Message::Imported
+    ///                     }
+    ///                 });
+    ///             }
+    ///             Message::Progress(progress) => self.progress = Some(progress),
+    ///             Message::Imported => self.progress = None,
+    ///         }
+    ///         UpdateAction::Render
+    ///     }
+    ///
+    ///     fn view(&self) -> VNode<Self> {
+    ///         gtk! {
+    ///             <Box orientation=Orientation::Vertical>
+    ///                 <Button label="Import" sensitive=self.progress.is_none()
+    ///                         on clicked=|_| Message::Import />
+    ///                 <ProgressBar progress=self.progress.unwrap_or_default() />
+    ///             </Box>
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [ProgressReporter]: struct.ProgressReporter.html
+    /// [Progress]: enum.Progress.html
+    /// [ProgressBar]: ../gtk/struct.ProgressBar.html
+    /// [LevelBar]: ../gtk/struct.LevelBar.html
+    /// [update]: trait.Component.html#method.update
+    pub fn defer_with_progress<F, J>(progress: fn(Progress) -> C::Message, job: F) -> Self
+    where
+        C: 'static,
+        F: FnOnce(ProgressReporter<C>) -> J,
+        J: Future<Output = C::Message> + 'static,
+    {
+        let reporter = ProgressReporter::new(&Scope::current(), progress);
+        Self::defer(job(reporter))
+    }
 }
 
 impl<C, F> From<F> for UpdateAction<C>
@@ -92,6 +187,37 @@ where
     }
 }
 
+/// What to do when the user asks to quit, as decided by
+/// [`Component::on_quit()`](trait.Component.html#method.on_quit).
+pub enum QuitAction<C: Component> {
+    /// Go ahead and quit.
+    Quit,
+    /// Don't quit after all.
+    Cancel,
+    /// Don't quit yet, but run an async task, such as asking the user whether
+    /// to save their changes, and pass the message returned from the
+    /// [`Future`][Future] to [`Component::update()`][update].
+    ///
+    /// If that decides the application should quit after all, it can call
+    /// [`vgtk::quit()`][quit] again, once its state is such that
+    /// [`Component::on_quit()`][on_quit] will return `QuitAction::Quit`.
+    ///
+    /// [update]: trait.Component.html#method.update
+    /// [on_quit]: trait.Component.html#method.on_quit
+    /// [quit]: fn.quit.html
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    Defer(Pin<Box<dyn Future<Output = C::Message> + 'static>>),
+}
+
+impl<C: Component> QuitAction<C> {
+    /// Construct a deferred action given a [`Future`][Future].
+    ///
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub fn defer(job: impl Future<Output = C::Message> + 'static) -> Self {
+        QuitAction::Defer(job.boxed_local())
+    }
+}
+
 /// This is the trait your UI components should implement.
 ///
 /// You must always provide `Message` and `Properties` types, and the `view()` method.
@@ -169,8 +295,72 @@ pub trait Component: Default + Unpin {
         unimplemented!("add a Component::change() implementation")
     }
 
+    /// Decide whether a new `Component::Properties` object is worth passing on
+    /// to [`Component::change()`][change].
+    ///
+    /// A subcomponent receives its properties again every time its parent
+    /// renders, usually unchanged. If you return `false` here, the new
+    /// properties are dropped without calling [`change()`][change], and the
+    /// subcomponent doesn't have to render its view or patch its widgets.
+    /// Components generally keep their current properties around, so you can
+    /// compare the new ones against those.
+    ///
+    /// The default implementation always returns `true`.
+    ///
+    /// Note that [`Callback`][Callback]s are constructed anew on every render
+    /// of the parent and never compare equal, so leave them out of the
+    /// comparison. Skipping an update keeps the old callbacks, which still
+    /// send their messages to the parent as before.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{gtk, Callback, Component, UpdateAction, VNode};
+    /// # use vgtk::lib::gtk::*;
+    /// #[derive(Clone, Debug, Default)]
+    /// pub struct Row {
+    ///     pub label: String,
+    ///     pub on_click: Callback<()>,
+    /// }
+    ///
+    /// impl Component for Row {
+    ///     type Message = ();
+    ///     type Properties = Self;
+    ///
+    ///     fn create(props: Self) -> Self {
+    ///         props
+    ///     }
+    ///
+    ///     fn should_render(&self, props: &Self) -> bool {
+    ///         self.label != props.label
+    ///     }
+    ///
+    ///     fn change(&mut self, props: Self) -> UpdateAction<Self> {
+    ///         *self = props;
+    ///         UpdateAction::Render
+    ///     }
+    ///
+    ///     fn view(&self) -> VNode<Self> {
+    ///         gtk! {
+    ///             <Label label=self.label.clone() />
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [change]: #method.change
+    /// [Callback]: struct.Callback.html
+    fn should_render(&self, _props: &Self::Properties) -> bool {
+        true
+    }
+
     /// This method is called when the `Component` becomes visible to the user.
     ///
+    /// This applies to subcomponents as well as top level components: a
+    /// subcomponent is mounted once its widgets have been built and attached
+    /// to its parent, so this is a good place to start timers or connect to
+    /// external resources.
+    ///
     /// The default implementation does nothing. You can reimplement it if you
     /// need to be aware of when this happens.
     fn mounted(&mut self) {}
@@ -178,10 +368,311 @@ pub trait Component: Default + Unpin {
     /// This method is called just before the `Component` becomes hidden or is
     /// removed entirely.
     ///
+    /// The component's widgets still exist when this is called, and will be
+    /// destroyed immediately afterwards, so this is where you should release
+    /// anything you acquired in [`Component::mounted()`][mounted].
+    ///
     /// The default implementation does nothing. You can reimplement it if you
     /// need to be aware of when this happens.
+    ///
+    /// [mounted]: #method.mounted
     fn unmounted(&mut self) {}
 
+    /// Declare the long running sources of messages this `Component` wants to
+    /// listen to, such as timers, file monitors or channels.
+    ///
+    /// This is called once, right after [`Component::mounted()`][mounted]. The
+    /// runtime will pass every message produced by the returned
+    /// [`Subscription`][Subscription]s to [`Component::update()`][update], and
+    /// will drop them when the component unmounts.
+    ///
+    /// The default implementation returns no subscriptions.
+    ///
+    /// [mounted]: #method.mounted
+    /// [update]: #method.update
+    /// [Subscription]: struct.Subscription.html
+    fn subscriptions(&self) -> Vec<Subscription<Self::Message>> {
+        Vec::new()
+    }
+
+    /// Declare the named actions this `Component` provides.
+    ///
+    /// The actions are registered on the component's top level object when it
+    /// mounts, and brought up to date every time it re-renders, so you can use
+    /// the component's state to decide which actions exist and whether they're
+    /// enabled. Activating one of them sends its message to
+    /// [`Component::update()`][update]. See [`ActionDecl`][ActionDecl] for
+    /// details.
+    ///
+    /// The default implementation declares no actions.
+    ///
+    /// [update]: #method.update
+    /// [ActionDecl]: struct.ActionDecl.html
+    fn actions(&self) -> Vec<ActionDecl<Self::Message>> {
+        Vec::new()
+    }
+
+    /// Declare a stylesheet which only applies to this `Component`'s widgets.
+    ///
+    /// The first time a component of this type renders, the stylesheet is
+    /// loaded with every selector rewritten to only match the widgets in the
+    /// component's view, which are given a style class unique to the
+    /// component type. A rule like `label { color: red; }` therefore turns
+    /// the labels in this component red, but leaves those in any
+    /// subcomponents it renders, and every other label, alone.
+    ///
+    /// The class goes on the last part of each selector, so that part has to
+    /// match a widget from the view rather than one of the nodes GTK builds
+    /// inside a widget, like a scale's `trough` or a button's label.
+    ///
+    /// The default implementation declares no stylesheet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{gtk, Component, VNode};
+    /// # use vgtk::lib::gtk::*;
+    /// #[derive(Default)]
+    /// struct Warning;
+    ///
+    /// impl Component for Warning {
+    ///     type Message = ();
+    ///     type Properties = ();
+    ///
+    ///     fn style() -> &'static str {
+    ///         "label { color: red; font-weight: bold; }"
+    ///     }
+    ///
+    ///     fn view(&self) -> VNode<Self> {
+    ///         gtk! {
+    ///             <Box>
+    ///                 <Label label="Careful now" />
+    ///             </Box>
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn style() -> &'static str {
+        ""
+    }
+
+    /// Turn an activation of your application, other than the one which
+    /// started it, into a message.
+    ///
+    /// This is only called for a top level component. An application is
+    /// activated again when it's launched while it's already running, in
+    /// which case the new process tells the running instance to activate and
+    /// exits, so this is where you'd open a new document, for instance.
+    /// The application's active window is raised before the message is sent.
+    ///
+    /// The application is also activated before every call to
+    /// [`Component::command_line()`][command_line] and
+    /// [`Component::open()`][open], so you'll get this message along with
+    /// those when it's already running.
+    ///
+    /// The default implementation returns no message.
+    ///
+    /// [command_line]: #method.command_line
+    /// [open]: #method.open
+    fn activated() -> Option<Self::Message> {
+        None
+    }
+
+    /// Decide whether your application may quit.
+    ///
+    /// This is only called for a top level component, when the user closes
+    /// the application's last window or when [`vgtk::quit()`][quit] is called,
+    /// so you can keep the application running if there's unsaved work. If you
+    /// return `QuitAction::Quit`, the window is closed or the application quits
+    /// as requested. See [`QuitAction`][QuitAction] for the alternatives.
+    ///
+    /// The default implementation always lets the application quit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{gtk, Component, QuitAction, UpdateAction, VNode};
+    /// # use vgtk::lib::gtk::*;
+    /// #[derive(Clone, Debug)]
+    /// enum Message {
+    ///     Edited,
+    ///     ConfirmedQuit(bool),
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Editor {
+    ///     unsaved: bool,
+    /// }
+    ///
+    /// impl Component for Editor {
+    ///     type Message = Message;
+    ///     type Properties = ();
+    ///
+    ///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+    ///         match message {
+    ///             Message::Edited => self.unsaved = true,
+    ///             Message::ConfirmedQuit(true) => {
+    ///                 self.unsaved = false;
+    ///                 vgtk::quit();
+    ///             }
+    ///             Message::ConfirmedQuit(false) => {}
+    ///         }
+    ///         UpdateAction::None
+    ///     }
+    ///
+    ///     fn on_quit(&mut self) -> QuitAction<Self> {
+    ///         if !self.unsaved {
+    ///             return QuitAction::Quit;
+    ///         }
+    ///         QuitAction::defer(async {
+    ///             let response = vgtk::message_dialog(
+    ///                 vgtk::current_window().as_ref(),
+    ///                 DialogFlags::MODAL,
+    ///                 MessageType::Question,
+    ///                 ButtonsType::YesNo,
+    ///                 true,
+    ///                 "Quit without saving your changes?",
+    ///             )
+    ///             .await;
+    ///             Message::ConfirmedQuit(response == ResponseType::Yes)
+    ///         })
+    ///     }
+    /// #   fn view(&self) -> VNode<Self> { todo!() }
+    /// }
+    /// ```
+    ///
+    /// [quit]: fn.quit.html
+    /// [QuitAction]: enum.QuitAction.html
+    fn on_quit(&mut self) -> QuitAction<Self> {
+        QuitAction::Quit
+    }
+
+    /// Declare the command line options your application accepts.
+    ///
+    /// This is only called for a top level component, before the application
+    /// starts, and the options are parsed by GIO, which also adds them to the
+    /// output of `--help`. Their values are passed to
+    /// [`Component::command_line()`][command_line].
+    ///
+    /// The default implementation declares no options.
+    ///
+    /// [command_line]: #method.command_line
+    fn command_line_options() -> Vec<CommandLineOption> {
+        Vec::new()
+    }
+
+    /// Turn an invocation of your application from the command line into a
+    /// message.
+    ///
+    /// This is only called for a top level component whose
+    /// [`Application`][Application] has the `HANDLES_COMMAND_LINE` flag, once
+    /// for the command line which started it and once more whenever it's
+    /// invoked again while it's running, in which case the new process hands
+    /// its command line over to this one and exits. The application is
+    /// activated first, so the component has mounted by the time the message
+    /// arrives at [`Component::update()`][update].
+    ///
+    /// The default implementation returns no message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{ext::*, gtk, CommandLine, CommandLineOption, Component, VNode};
+    /// # use vgtk::lib::gio::ApplicationFlags;
+    /// # use vgtk::lib::gtk::*;
+    /// #[derive(Clone, Debug)]
+    /// enum Message {
+    ///     Open { path: std::path::PathBuf, line: Option<i32> },
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Editor;
+    ///
+    /// impl Component for Editor {
+    ///     type Message = Message;
+    ///     type Properties = ();
+    ///
+    ///     fn command_line_options() -> Vec<CommandLineOption> {
+    ///         vec![CommandLineOption::int("line", "Go to line LINE")
+    ///             .short('l')
+    ///             .arg_description("LINE")]
+    ///     }
+    ///
+    ///     fn command_line(command_line: &CommandLine) -> Option<Message> {
+    ///         let path = command_line.arguments().first()?;
+    ///         let path = match command_line.cwd() {
+    ///             Some(cwd) => cwd.join(path),
+    ///             None => path.into(),
+    ///         };
+    ///         Some(Message::Open { path, line: command_line.int("line") })
+    ///     }
+    ///
+    ///     fn view(&self) -> VNode<Self> {
+    ///         gtk! {
+    ///             <Application::new_unwrap(Some("com.example.editor"),
+    ///                                      ApplicationFlags::HANDLES_COMMAND_LINE)>
+    ///                 <Window />
+    ///             </Application>
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [Application]: ../gtk/struct.Application.html
+    /// [update]: #method.update
+    fn command_line(_command_line: &CommandLine) -> Option<Self::Message> {
+        None
+    }
+
+    /// Turn a request to open files into a message.
+    ///
+    /// This is only called for a top level component whose
+    /// [`Application`][Application] has the `HANDLES_OPEN` flag, when it's
+    /// started with files on its command line, as file managers do when you
+    /// open a file with it, or when another invocation passes files on to the
+    /// running instance. As with [`Component::command_line()`][command_line],
+    /// the application is activated first.
+    ///
+    /// The default implementation returns no message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{ext::*, gtk, Component, VNode};
+    /// # use vgtk::lib::gio::{ApplicationFlags, File};
+    /// # use vgtk::lib::gtk::*;
+    /// #[derive(Clone, Debug)]
+    /// enum Message {
+    ///     Open(Vec<File>),
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// struct Viewer;
+    ///
+    /// impl Component for Viewer {
+    ///     type Message = Message;
+    ///     type Properties = ();
+    ///
+    ///     fn open(files: &[File]) -> Option<Message> {
+    ///         Some(Message::Open(files.to_vec()))
+    ///     }
+    ///
+    ///     fn view(&self) -> VNode<Self> {
+    ///         gtk! {
+    ///             <Application::new_unwrap(Some("com.example.viewer"), ApplicationFlags::HANDLES_OPEN)>
+    ///                 <Window />
+    ///             </Application>
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [Application]: ../gtk/struct.Application.html
+    /// [command_line]: #method.command_line
+    fn open(_files: &[File]) -> Option<Self::Message> {
+        None
+    }
+
     /// Build a `VNode` tree to represent your UI.
     ///
     /// This is called whenever the `Component` needs to re-render, and its UI
@@ -207,6 +698,13 @@ pub(crate) enum ComponentMessage<C: Component> {
     Props(C::Properties),
     Mounted,
     Unmounted,
+    Render,
+    Flush,
+    /// Ask whether the application may quit, closing the window if given.
+    Quit(Option<Window>),
+    /// Replace the component's state, for the debugger's time travel.
+    #[cfg(feature = "debug")]
+    Restore(Rc<dyn Fn() -> C>),
 }
 
 impl<C: Component> Debug for ComponentMessage<C> {
@@ -224,6 +722,13 @@ impl<C: Component> Debug for ComponentMessage<C> {
             ComponentMessage::Props(_) => write!(f, "{}", "ComponentMessage::Props(...)".green()),
             ComponentMessage::Mounted => write!(f, "{}", "ComponentMessage::Mounted".green()),
             ComponentMessage::Unmounted => write!(f, "{}", "ComponentMessage::Unmounted".green()),
+            ComponentMessage::Render => write!(f, "{}", "ComponentMessage::Render".green()),
+            ComponentMessage::Flush => write!(f, "{}", "ComponentMessage::Flush".green()),
+            ComponentMessage::Quit(_) => write!(f, "{}", "ComponentMessage::Quit(...)".green()),
+            #[cfg(feature = "debug")]
+            ComponentMessage::Restore(_) => {
+                write!(f, "{}", "ComponentMessage::Restore(...)".green())
+            }
         }
     }
 }
@@ -235,6 +740,11 @@ impl<C: Component> Clone for ComponentMessage<C> {
             ComponentMessage::Props(props) => ComponentMessage::Props(props.clone()),
             ComponentMessage::Mounted => ComponentMessage::Mounted,
             ComponentMessage::Unmounted => ComponentMessage::Unmounted,
+            ComponentMessage::Render => ComponentMessage::Render,
+            ComponentMessage::Flush => ComponentMessage::Flush,
+            ComponentMessage::Quit(window) => ComponentMessage::Quit(window.clone()),
+            #[cfg(feature = "debug")]
+            ComponentMessage::Restore(restore) => ComponentMessage::Restore(restore.clone()),
         }
     }
 }
@@ -264,6 +774,16 @@ where
         props: C::Properties,
         parent: Option<&Object>,
         parent_scope: Option<&Scope<P>>,
+    ) -> Self {
+        Self::with_state(parent, parent_scope, move || C::create(props))
+    }
+
+    /// Like `new`, but construct the component's state with `create` rather
+    /// than from its properties.
+    pub(crate) fn with_state(
+        parent: Option<&Object>,
+        parent_scope: Option<&Scope<P>>,
+        create: impl FnOnce() -> C,
     ) -> Self {
         let (sys_send, sys_recv) = unbounded();
         let (user_send, user_recv) = unbounded();
@@ -281,9 +801,28 @@ where
             Some(ref p) => p.inherit(type_name, user_send),
             None => Scope::new(type_name, user_send),
         };
-        let state = C::create(props);
-        let initial_view = state.view();
-        let ui_state = State::build_root(&initial_view, parent, &scope);
+        let consumer = {
+            // If the component has already stopped, there's nothing left to render.
+            let sender = sys_send.clone();
+            Rc::new(Consumer::new(move || {
+                let _ = sender.unbounded_send(ComponentMessage::Render);
+            }))
+        };
+        #[cfg(feature = "debug")]
+        let inspected = Inspected::register::<C>();
+        let state = {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("create", component = type_name).entered();
+            with_current_scope(&scope, create)
+        };
+        let initial_view = render_view(&state, &scope, &consumer);
+        let ui_state = {
+            #[cfg(feature = "debug")]
+            let _building = inspected.building();
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("build", component = type_name).entered();
+            State::build_root(&initial_view, parent, &scope)
+        };
         PartialComponentTask {
             task: ComponentTask {
                 scope,
@@ -291,6 +830,16 @@ where
                 state,
                 ui_state: Some(ui_state),
                 channel,
+                subscriptions: None,
+                actions: None,
+                consumer,
+                sender: sys_send.clone(),
+                dirty: false,
+                render_scheduled: false,
+                on_unmount: None,
+                on_update: None,
+                #[cfg(feature = "debug")]
+                inspected,
             },
             view: initial_view,
             sender: sys_send,
@@ -303,11 +852,42 @@ where
         mut self,
     ) -> (UnboundedSender<ComponentMessage<C>>, ComponentTask<C, P>) {
         if let Some(ref mut ui_state) = self.task.ui_state {
+            #[cfg(feature = "debug")]
+            let _building = self.task.inspected.building();
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("build", component = self.task.scope.name()).entered();
             ui_state.build_children(&self.view, &self.task.scope);
         }
+        #[cfg(feature = "debug")]
+        self.task.inspected.rendered(&self.view);
         (self.sender, self.task)
     }
 
+    /// Call `f` with the component's state when it unmounts, before its
+    /// `unmounted` method is called.
+    pub(crate) fn on_unmount(mut self, f: impl FnOnce(&C) + 'static) -> Self {
+        self.task.on_unmount = Some(Box::new(f));
+        self
+    }
+
+    /// Call `f` with every message the component receives, along with its
+    /// state after it's been updated.
+    #[cfg(feature = "debug")]
+    pub(crate) fn on_update(mut self, f: impl Fn(&C::Message, &C) + 'static) -> Self {
+        self.task.on_update = Some(Box::new(f));
+        self
+    }
+
+    #[cfg(feature = "debug")]
+    pub(crate) fn state(&self) -> &C {
+        &self.task.state
+    }
+
+    #[cfg(feature = "debug")]
+    pub(crate) fn sender(&self) -> UnboundedSender<ComponentMessage<C>> {
+        self.sender.clone()
+    }
+
     pub(crate) fn object(&self) -> Object {
         self.task.ui_state.as_ref().unwrap().object().clone()
     }
@@ -327,6 +907,16 @@ where
     state: C,
     ui_state: Option<State<C>>,
     channel: Pin<Box<dyn Stream<Item = ComponentMessage<C>>>>,
+    subscriptions: Option<AbortHandle>,
+    actions: Option<ActionState<C>>,
+    consumer: Rc<Consumer>,
+    sender: UnboundedSender<ComponentMessage<C>>,
+    dirty: bool,
+    render_scheduled: bool,
+    on_unmount: Option<Box<dyn FnOnce(&C)>>,
+    on_update: Option<Box<dyn Fn(&C::Message, &C)>>,
+    #[cfg(feature = "debug")]
+    inspected: Inspected,
 }
 
 impl<C, P> ComponentTask<C, P>
@@ -343,14 +933,65 @@ where
     }
 
     fn run_job(&self, job: impl Future<Output = C::Message> + 'static) {
+        self.scope.spawn(job)
+    }
+
+    fn subscribe(&mut self) {
+        let subscriptions = self.state.subscriptions();
+        if subscriptions.is_empty() {
+            return;
+        }
         let scope = self.scope.clone();
-        MainContext::ref_thread_default().spawn_local(async move {
-            scope.send_message(job.await);
-        })
+        let mut stream = select_all(subscriptions.into_iter().map(Subscription::into_stream));
+        let (job, handle) = abortable(async move {
+            while let Some(message) = stream.next().await {
+                if scope.try_send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        MainContext::ref_thread_default().spawn_local(job.map(|_| ()));
+        self.subscriptions = Some(handle);
+    }
+
+    fn unsubscribe(&mut self) {
+        if let Some(handle) = self.subscriptions.take() {
+            handle.abort();
+        }
+    }
+
+    fn update_actions(&mut self) {
+        let decls = self.state.actions();
+        if decls.is_empty() && self.actions.is_none() {
+            return;
+        }
+        if let Some(ref ui_state) = self.ui_state {
+            self.actions
+                .get_or_insert_with(|| ActionState::new(ui_state.object()))
+                .update(decls, &self.scope);
+        }
+    }
+
+    // Render once the main loop has run out of more urgent work, just before
+    // GTK gets around to drawing, so a burst of messages only renders once.
+    fn schedule_render(&mut self) {
+        if self.render_scheduled {
+            return;
+        }
+        self.render_scheduled = true;
+        let sender = self.sender.clone();
+        MainContext::ref_thread_default().spawn_local_with_priority(
+            PRIORITY_HIGH_IDLE,
+            async move {
+                // If the component has already stopped, there's nothing left to render.
+                let _ = sender.unbounded_send(ComponentMessage::Flush);
+            },
+        );
     }
 
     pub(crate) fn process(&mut self, ctx: &mut Context<'_>) -> Poll<()> {
-        let mut render = false;
+        let mut render = std::mem::take(&mut self.dirty);
+        let mut flush = false;
         loop {
             let next = Stream::poll_next(self.channel.as_mut(), ctx);
             trace!(
@@ -360,57 +1001,156 @@ where
             );
             match next {
                 Poll::Ready(Some(msg)) => match msg {
-                    ComponentMessage::Update(msg) => match self.state.update(msg) {
-                        UpdateAction::Defer(job) => {
-                            self.run_job(job);
-                        }
-                        UpdateAction::Render => {
-                            render = true;
+                    ComponentMessage::Update(msg) => {
+                        #[cfg(feature = "debug")]
+                        self.inspected.event(format!("message {:?}", msg));
+                        #[cfg(feature = "tracing")]
+                        let _span = tracing::debug_span!(
+                            "update",
+                            component = self.scope.name(),
+                            message = ?msg
+                        )
+                        .entered();
+                        let recorded = self.on_update.as_ref().map(|_| msg.clone());
+                        match self.state.update(msg) {
+                            UpdateAction::Defer(job) => {
+                                self.run_job(job);
+                            }
+                            UpdateAction::Render => {
+                                render = true;
+                            }
+                            UpdateAction::None => {}
                         }
-                        UpdateAction::None => {}
-                    },
-                    ComponentMessage::Props(props) => match self.state.change(props) {
-                        UpdateAction::Defer(job) => {
-                            self.run_job(job);
+                        if let (Some(on_update), Some(msg)) = (&self.on_update, recorded) {
+                            on_update(&msg, &self.state);
                         }
-                        UpdateAction::Render => {
-                            render = true;
+                    }
+                    ComponentMessage::Props(props) if !self.state.should_render(&props) => {
+                        #[cfg(feature = "debug")]
+                        self.inspected.event("unchanged properties".to_string());
+                        #[cfg(feature = "tracing")]
+                        tracing::trace!(
+                            component = self.scope.name(),
+                            "skipping unchanged properties"
+                        );
+                        trace!(
+                            "{} {}",
+                            self.scope.name().bright_black(),
+                            "skipping unchanged properties".bright_black()
+                        );
+                    }
+                    ComponentMessage::Props(props) => {
+                        #[cfg(feature = "debug")]
+                        self.inspected.event("new properties".to_string());
+                        #[cfg(feature = "tracing")]
+                        let _span =
+                            tracing::debug_span!("change", component = self.scope.name()).entered();
+                        match self.state.change(props) {
+                            UpdateAction::Defer(job) => {
+                                self.run_job(job);
+                            }
+                            UpdateAction::Render => {
+                                render = true;
+                            }
+                            UpdateAction::None => {}
                         }
-                        UpdateAction::None => {}
-                    },
+                    }
                     ComponentMessage::Mounted => {
                         debug!(
                             "{} {}",
                             "Component mounted:".bright_blue(),
                             self.scope.name().magenta().bold()
                         );
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(component = self.scope.name(), "mounted");
                         self.state.mounted();
+                        self.subscribe();
+                        self.update_actions();
                     }
                     ComponentMessage::Unmounted => {
+                        self.unsubscribe();
+                        if let Some(mut actions) = self.actions.take() {
+                            actions.clear();
+                        }
+                        if let Some(on_unmount) = self.on_unmount.take() {
+                            on_unmount(&self.state);
+                        }
+                        self.state.unmounted();
                         if let Some(state) = self.ui_state.take() {
                             state.unmount();
                         }
-                        self.state.unmounted();
                         debug!(
                             "{} {}",
                             "Component unmounted:".bright_red(),
                             self.scope.name().magenta().bold()
                         );
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(component = self.scope.name(), "unmounted");
                         return Poll::Ready(());
                     }
+                    ComponentMessage::Render => {
+                        #[cfg(feature = "debug")]
+                        self.inspected.event("render requested".to_string());
+                        render = true;
+                    }
+                    ComponentMessage::Flush => {
+                        self.render_scheduled = false;
+                        flush = true;
+                    }
+                    ComponentMessage::Quit(window) => match self.state.on_quit() {
+                        QuitAction::Quit => match window {
+                            Some(window) => window.destroy(),
+                            None => crate::quit_now(),
+                        },
+                        QuitAction::Cancel => {}
+                        QuitAction::Defer(job) => {
+                            self.run_job(job);
+                        }
+                    },
+                    #[cfg(feature = "debug")]
+                    ComponentMessage::Restore(restore) => {
+                        self.inspected
+                            .event("state restored by the debugger".to_string());
+                        self.state = restore();
+                        render = true;
+                    }
                 },
+                Poll::Pending if render && !flush && batch_rendering() => {
+                    self.dirty = true;
+                    self.schedule_render();
+                    return Poll::Pending;
+                }
                 Poll::Pending if render => {
                     if let Some(ref mut ui_state) = self.ui_state {
                         // we patch
-                        let new_view = self.state.view();
+                        trace!(
+                            "{} {}",
+                            "Component rendering:".bright_black(),
+                            self.scope.name().magenta().bold()
+                        );
+                        #[cfg(feature = "profile")]
+                        let mut frame = Frame::start();
+                        let new_view = render_view(&self.state, &self.scope, &self.consumer);
+                        #[cfg(feature = "profile")]
+                        frame.viewed();
                         self.scope.mute();
+                        #[cfg(feature = "debug")]
+                        let _building = self.inspected.building();
+                        #[cfg(feature = "tracing")]
+                        let _span =
+                            tracing::debug_span!("patch", component = self.scope.name()).entered();
                         if !ui_state.patch(&new_view, None, &self.scope) {
                             unimplemented!(
                                 "{}: don't know how to propagate failed patch",
                                 self.scope.name()
                             );
                         }
+                        #[cfg(feature = "profile")]
+                        frame.finish(self.scope.name());
+                        #[cfg(feature = "debug")]
+                        self.inspected.rendered(&new_view);
                         self.scope.unmute();
+                        self.update_actions();
                         return Poll::Pending;
                     } else {
                         debug!(
@@ -438,6 +1178,16 @@ where
         self.ui_state.as_ref().map(|state| state.object().clone())
     }
 
+    pub(crate) fn scope(&self) -> Scope<C> {
+        self.scope.clone()
+    }
+
+    /// Get the component's ID in the inspector, if it's shown there.
+    #[cfg(feature = "debug")]
+    pub(crate) fn inspected_id(&self) -> Option<usize> {
+        self.inspected.id()
+    }
+
     pub(crate) fn current_parent_scope() -> Scope<C> {
         LOCAL_CONTEXT.with(|key| {
             let lock = key.read().unwrap();
@@ -445,8 +1195,24 @@ where
                 None => panic!("current task has no parent scope set!"),
                 Some(any_scope) => match any_scope.try_get::<C>() {
                     None => panic!(
-                        "unexpected type for current parent scope (expected {:?})",
-                        TypeId::of::<C::Properties>()
+                        "unexpected type for current parent scope (expected {})",
+                        std::any::type_name::<C>()
+                    ),
+                    Some(scope) => scope.clone(),
+                },
+            }
+        })
+    }
+
+    pub(crate) fn current_scope() -> Scope<C> {
+        LOCAL_CONTEXT.with(|key| {
+            let lock = key.read().unwrap();
+            match &lock.current_scope {
+                None => panic!("no component is currently running!"),
+                Some(any_scope) => match any_scope.try_get::<C>() {
+                    None => panic!(
+                        "unexpected type for current scope (expected {})",
+                        std::any::type_name::<C>()
                     ),
                     Some(scope) => scope.clone(),
                 },
@@ -509,9 +1275,39 @@ pub fn current_window() -> Option<Window> {
     })
 }
 
+thread_local! {
+    static BATCH_RENDERING: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Choose whether components wait for the main loop to go idle before
+/// rendering.
+///
+/// By default, a component which receives a message asking it to render
+/// doesn't do so straight away, but waits until the main loop has no more
+/// urgent work left, just before GTK draws the next frame. However many
+/// messages arrive in the meantime, for instance from a busy
+/// [`Subscription`][Subscription], the component only renders once.
+///
+/// Passing `false` makes components render as soon as they've processed the
+/// messages already waiting for them instead, which can make tests which
+/// step the main loop by hand easier to reason about.
+///
+/// This applies to every component on the current thread.
+///
+/// [Subscription]: struct.Subscription.html
+pub fn set_batch_rendering(enabled: bool) {
+    BATCH_RENDERING.with(|batching| batching.set(enabled))
+}
+
+fn batch_rendering() -> bool {
+    BATCH_RENDERING.with(Cell::get)
+}
+
 #[derive(Default)]
 struct LocalContext {
     parent_scope: Option<AnyScope>,
+    current_scope: Option<AnyScope>,
+    current_consumer: Option<Rc<Consumer>>,
     current_object: Option<WeakRef<Object>>,
 }
 
@@ -519,6 +1315,70 @@ thread_local! {
     static LOCAL_CONTEXT: RwLock<LocalContext> = RwLock::new(Default::default())
 }
 
+/// Call a function with a component's scope set as the current one.
+fn with_current_scope<C: 'static + Component, R>(scope: &Scope<C>, f: impl FnOnce() -> R) -> R {
+    let previous_scope = LOCAL_CONTEXT.with(|key| {
+        key.write()
+            .unwrap()
+            .current_scope
+            .replace(scope.clone().into())
+    });
+    let result = f();
+    LOCAL_CONTEXT.with(|key| key.write().unwrap().current_scope = previous_scope);
+    result
+}
+
+/// Call a component's view function, with its scope and context consumer set
+/// as the current ones for the duration of the call.
+fn render_view<C: 'static + Component>(
+    state: &C,
+    scope: &Scope<C>,
+    consumer: &Rc<Consumer>,
+) -> VNode<C> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("view", component = scope.name()).entered();
+    let mut view = call_view(state, scope, consumer);
+    css::scope_view(&mut view);
+    view
+}
+
+/// Call a component's view function for a component which isn't running,
+/// without loading its stylesheet.
+///
+/// Any messages sent to its scope while rendering are dropped.
+#[cfg(feature = "snapshot")]
+pub(crate) fn detached_view<C: 'static + Component>(state: &C) -> VNode<C> {
+    let (sender, _receiver) = unbounded();
+    let scope = Scope::new(std::any::type_name::<C>(), sender);
+    call_view(state, &scope, &Rc::new(Consumer::new(|| {})))
+}
+
+fn call_view<C: 'static + Component>(
+    state: &C,
+    scope: &Scope<C>,
+    consumer: &Rc<Consumer>,
+) -> VNode<C> {
+    let (previous_scope, previous_consumer) = LOCAL_CONTEXT.with(|key| {
+        let mut lock = key.write().unwrap();
+        (
+            lock.current_scope.replace(scope.clone().into()),
+            lock.current_consumer.replace(consumer.clone()),
+        )
+    });
+    let view = state.view();
+    LOCAL_CONTEXT.with(|key| {
+        let mut lock = key.write().unwrap();
+        lock.current_scope = previous_scope;
+        lock.current_consumer = previous_consumer;
+    });
+    view
+}
+
+/// Get the consumer of the component currently rendering its view, if any.
+pub(crate) fn current_consumer() -> Option<Rc<Consumer>> {
+    LOCAL_CONTEXT.with(|key| key.read().unwrap().current_consumer.clone())
+}
+
 impl<C, P> Future for ComponentTask<C, P>
 where
     C: 'static + Component,
@@ -530,6 +1390,8 @@ where
         LOCAL_CONTEXT.with(|key| {
             *key.write().unwrap() = LocalContext {
                 parent_scope: self.parent_scope.as_ref().map(|scope| scope.clone().into()),
+                current_scope: Some(self.scope.clone().into()),
+                current_consumer: None,
                 current_object: self
                     .ui_state
                     .as_ref()
@@ -0,0 +1,149 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use crate::component::Component;
+use crate::scope::Scope;
+
+/// A reference to a subcomponent built by the [`gtk!`][gtk!] macro.
+///
+/// A parent component usually talks to its subcomponents by changing their
+/// properties, but sometimes it just needs to tell one to do something, like
+/// scroll to an item or reset a form. Keep a `ComponentRef` in your component
+/// and pass it to the subcomponent's `ref` attribute, and you can then
+/// [`send()`][send] messages straight to the subcomponent's
+/// [`update`][update] method, for as long as it's mounted.
+///
+/// A `ComponentRef` is a shared handle, so cloning it gives you another
+/// reference to the same subcomponent.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, ComponentRef, UpdateAction, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] pub enum FormMessage { Reset }
+/// # #[derive(Clone, Debug, Default)] pub struct Form;
+/// # impl Component for Form { type Message = FormMessage; type Properties = ();
+/// #     fn view(&self) -> VNode<Self> { todo!() } }
+/// # #[derive(Clone, Debug)] enum Message { Clear }
+/// #[derive(Default)]
+/// struct Dialog {
+///     form: ComponentRef<Form>,
+/// }
+///
+/// impl Component for Dialog {
+///     type Message = Message;
+///     type Properties = ();
+///
+///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///         match message {
+///             Message::Clear => self.form.send(FormMessage::Reset),
+///         }
+///         UpdateAction::None
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         gtk! {
+///             <Box>
+///                 <@Form ref=self.form />
+///                 <Button label="Clear" on clicked=|_| Message::Clear />
+///             </Box>
+///         }
+///     }
+/// }
+/// ```
+///
+/// [gtk!]: macro.gtk.html
+/// [send]: #method.send
+/// [update]: trait.Component.html#method.update
+pub struct ComponentRef<C: Component> {
+    scope: Rc<RefCell<Option<Scope<C>>>>,
+}
+
+impl<C: 'static + Component> ComponentRef<C> {
+    /// Construct an empty `ComponentRef`.
+    pub fn new() -> Self {
+        ComponentRef {
+            scope: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Get the [`Scope`][Scope] of the subcomponent this `ComponentRef` refers
+    /// to, if it's currently mounted.
+    ///
+    /// [Scope]: struct.Scope.html
+    pub fn scope(&self) -> Option<Scope<C>> {
+        self.scope.borrow().clone()
+    }
+
+    /// Test whether the subcomponent this `ComponentRef` refers to is
+    /// currently mounted.
+    pub fn is_mounted(&self) -> bool {
+        self.scope.borrow().is_some()
+    }
+
+    /// Send a message to the subcomponent this `ComponentRef` refers to.
+    ///
+    /// If the subcomponent isn't mounted, the message is quietly dropped.
+    ///
+    /// As with [`Scope::try_send()`][try_send], never call this from inside a
+    /// signal handler; send a message to your own component and call it from
+    /// your [`update`][update] method instead.
+    ///
+    /// [try_send]: struct.Scope.html#method.try_send
+    /// [update]: trait.Component.html#method.update
+    pub fn send(&self, message: C::Message) {
+        if let Some(ref scope) = *self.scope.borrow() {
+            let _ = scope.try_send(message);
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn setter(&self) -> Rc<dyn Fn(Option<&dyn Any>)> {
+        let cell = self.scope.clone();
+        Rc::new(move |scope| {
+            *cell.borrow_mut() = scope.map(|scope| {
+                scope
+                    .downcast_ref::<Scope<C>>()
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "a ComponentRef<{}> can't refer to a different component",
+                            std::any::type_name::<C>()
+                        )
+                    })
+                    .clone()
+            })
+        })
+    }
+}
+
+impl<C: 'static + Component> Default for ComponentRef<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Component> Clone for ComponentRef<C> {
+    fn clone(&self) -> Self {
+        ComponentRef {
+            scope: self.scope.clone(),
+        }
+    }
+}
+
+impl<C: Component> PartialEq for ComponentRef<C> {
+    /// Test whether two `ComponentRef`s are equal.
+    ///
+    /// Two `ComponentRef`s are equal if one is a clone of the other.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.scope, &other.scope)
+    }
+}
+
+impl<C: Component> Debug for ComponentRef<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "ComponentRef({:?})", self.scope.borrow())
+    }
+}
@@ -0,0 +1,227 @@
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::{Rc, Weak};
+
+use crate::component::current_consumer;
+
+thread_local! {
+    static CONTEXTS: RefCell<HashMap<TypeId, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// A component which has read from a [`Context`][Context] while rendering its
+/// view, and can be asked to render again when the context changes.
+///
+/// [Context]: struct.Context.html
+pub(crate) struct Consumer {
+    rerender: Box<dyn Fn()>,
+}
+
+impl Consumer {
+    /// Construct a consumer from a function which asks the component to render
+    /// again.
+    pub(crate) fn new(rerender: impl Fn() + 'static) -> Self {
+        Consumer {
+            rerender: Box::new(rerender),
+        }
+    }
+}
+
+/// The components which have read some shared state while rendering.
+///
+/// Each component owns its consumer, and this only keeps weak references to
+/// them, so they're forgotten once the component goes away.
+#[derive(Default)]
+pub(crate) struct Consumers {
+    consumers: RefCell<Vec<Weak<Consumer>>>,
+}
+
+impl Consumers {
+    /// Add the component currently rendering its view, if any.
+    pub(crate) fn subscribe(&self) {
+        if let Some(consumer) = current_consumer() {
+            let consumer = Rc::downgrade(&consumer);
+            let mut consumers = self.consumers.borrow_mut();
+            if !consumers.iter().any(|known| known.ptr_eq(&consumer)) {
+                consumers.push(consumer);
+            }
+        }
+    }
+
+    /// Ask every component to render again.
+    pub(crate) fn notify(&self) {
+        // Consumers sign up again when they render, so any that have stopped
+        // reading the state are forgotten.
+        let consumers = std::mem::take(&mut *self.consumers.borrow_mut());
+        for consumer in consumers.iter().filter_map(Weak::upgrade) {
+            (consumer.rerender)();
+        }
+    }
+}
+
+struct ContextState<T> {
+    value: RefCell<T>,
+    consumers: Consumers,
+}
+
+/// A shared value which any component can read without having it passed down
+/// through every [`Properties`][Properties] object on the way.
+///
+/// There's one `Context` for each type of value. Create it once with
+/// [`Context::provide()`][provide], usually when your top level component is
+/// created, and look it up from anywhere else with
+/// [`Context::current()`][current].
+///
+/// When a component reads a context's value from its
+/// [`view`][view] method, it will automatically render again whenever the
+/// value is changed with [`set()`][set] or [`update()`][update], wherever the
+/// change comes from.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, Context, UpdateAction, VNode};
+/// # use vgtk::lib::gtk::*;
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Theme {
+///     Light,
+///     Dark,
+/// }
+///
+/// # #[derive(Clone, Debug)] enum Message { ToggleTheme }
+/// struct App {
+///     theme: Context<Theme>,
+/// }
+///
+/// impl Default for App {
+///     fn default() -> Self {
+///         App {
+///             theme: Context::provide(Theme::Light),
+///         }
+///     }
+/// }
+///
+/// # impl Component for App {
+/// #     type Message = Message; type Properties = ();
+/// #     fn view(&self) -> VNode<Self> { todo!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::ToggleTheme => self.theme.update(|theme| {
+///             *theme = if *theme == Theme::Light { Theme::Dark } else { Theme::Light }
+///         }),
+///     }
+///     UpdateAction::None
+/// }
+/// # }
+///
+/// // Somewhere deep inside the component tree:
+/// # #[derive(Default)] struct Badge;
+/// # impl Component for Badge { type Message = (); type Properties = ();
+/// fn view(&self) -> VNode<Self> {
+///     let dark = Context::<Theme>::current().get() == Theme::Dark;
+///     gtk! {
+///         <Label label={ if dark { "Dark" } else { "Light" } } />
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [Properties]: trait.Component.html#associatedtype.Properties
+/// [view]: trait.Component.html#tymethod.view
+/// [provide]: #method.provide
+/// [current]: #method.current
+/// [set]: #method.set
+/// [update]: #method.update
+pub struct Context<T> {
+    state: Rc<ContextState<T>>,
+}
+
+impl<T: 'static> Context<T> {
+    /// Provide a context with the given initial value, replacing any context
+    /// previously provided for the same type.
+    pub fn provide(value: T) -> Self {
+        let context = Context {
+            state: Rc::new(ContextState {
+                value: RefCell::new(value),
+                consumers: Consumers::default(),
+            }),
+        };
+        let stored: Rc<dyn Any> = Rc::new(context.clone());
+        CONTEXTS.with(|contexts| contexts.borrow_mut().insert(TypeId::of::<T>(), stored));
+        context
+    }
+
+    /// Look up the context provided for this type, if there is one.
+    pub fn try_current() -> Option<Self> {
+        CONTEXTS.with(|contexts| {
+            contexts
+                .borrow()
+                .get(&TypeId::of::<T>())
+                .and_then(|context| context.downcast_ref::<Self>())
+                .cloned()
+        })
+    }
+
+    /// Look up the context provided for this type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no context has been provided for this type.
+    pub fn current() -> Self {
+        Self::try_current().unwrap_or_else(|| {
+            panic!(
+                "no Context<{}> has been provided",
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    /// Call a function with a reference to the context's value.
+    ///
+    /// If you call this from a component's view, the component will render
+    /// again when the value changes.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.state.consumers.subscribe();
+        f(&self.state.value.borrow())
+    }
+
+    /// Get a copy of the context's value.
+    ///
+    /// If you call this from a component's view, the component will render
+    /// again when the value changes.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Replace the context's value, and re-render every component which has
+    /// read it.
+    pub fn set(&self, value: T) {
+        *self.state.value.borrow_mut() = value;
+        self.state.consumers.notify();
+    }
+
+    /// Modify the context's value in place, and re-render every component
+    /// which has read it.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.state.value.borrow_mut());
+        self.state.consumers.notify();
+    }
+}
+
+impl<T> Clone for Context<T> {
+    fn clone(&self) -> Self {
+        Context {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Context<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Context({:?})", self.state.value.borrow())
+    }
+}
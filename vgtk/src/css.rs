@@ -0,0 +1,533 @@
+//! Application stylesheets.
+//!
+//! Load a stylesheet with [`load()`][load], from a string of CSS or from a
+//! file, and it applies to every widget in your application. The
+//! [`Stylesheet`][Stylesheet] you get back can be reloaded with new CSS at any
+//! time, for instance when the user switches to a dark theme or when you're
+//! editing the file while the application is running, and GTK restyles
+//! everything straight away.
+//!
+//! Stylesheets apply to the default screen, so they can only be loaded once
+//! GTK has been initialised: load them in your top level component's
+//! [`create`][create] method, or after calling [`vgtk::start()`][start].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use vgtk::css::{self, Stylesheet};
+//! # use vgtk::{gtk, Component, UpdateAction, VNode};
+//! # use vgtk::lib::gtk::*;
+//!
+//! const LIGHT: &str = "label { color: black; }";
+//! const DARK: &str = "label { color: white; }";
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     SetDark(bool),
+//! }
+//!
+//! struct App {
+//!     stylesheet: Stylesheet,
+//! }
+//!
+//! impl Default for App {
+//!     fn default() -> Self {
+//!         App {
+//!             stylesheet: css::load(LIGHT).expect("invalid stylesheet"),
+//!         }
+//!     }
+//! }
+//!
+//! impl Component for App {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         match message {
+//!             Message::SetDark(dark) => {
+//!                 let css = if dark { DARK } else { LIGHT };
+//!                 self.stylesheet.reload(css).expect("invalid stylesheet");
+//!             }
+//!         }
+//!         UpdateAction::None
+//!     }
+//! #   fn view(&self) -> VNode<Self> { todo!() }
+//! }
+//! ```
+//!
+//! [load]: fn.load.html
+//! [Stylesheet]: struct.Stylesheet.html
+//! [create]: ../trait.Component.html#method.create
+//! [start]: ../fn.start.html
+
+use gdk::Screen;
+use glib::{Cast, Object, ObjectType, StaticType};
+use gtk::{CssProvider, CssProviderExt, StyleContext, StyleContextExt, Widget, WidgetExt};
+
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::component::Component;
+use crate::vnode::{VNode, VProperty};
+
+/// Where a stylesheet's CSS comes from.
+///
+/// Strings are CSS, and paths are files of CSS.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// A string of CSS.
+    Data(String),
+    /// The path to a CSS file.
+    Path(PathBuf),
+}
+
+impl From<&str> for Source {
+    fn from(css: &str) -> Self {
+        Source::Data(css.to_string())
+    }
+}
+
+impl From<String> for Source {
+    fn from(css: String) -> Self {
+        Source::Data(css)
+    }
+}
+
+impl From<&Path> for Source {
+    fn from(path: &Path) -> Self {
+        Source::Path(path.to_path_buf())
+    }
+}
+
+impl From<PathBuf> for Source {
+    fn from(path: PathBuf) -> Self {
+        Source::Path(path)
+    }
+}
+
+fn default_screen() -> Screen {
+    Screen::get_default().expect("stylesheets can't be loaded without a default screen")
+}
+
+/// A stylesheet applied to every widget in the application.
+///
+/// Construct one with [`load()`][load] or
+/// [`load_with_priority()`][load_with_priority]. Cloning a `Stylesheet` gives
+/// you another handle to the same stylesheet. It stays loaded until you call
+/// [`unload()`][unload], even if every handle to it is dropped.
+///
+/// [load]: fn.load.html
+/// [load_with_priority]: fn.load_with_priority.html
+/// [unload]: #method.unload
+#[derive(Clone, Debug)]
+pub struct Stylesheet {
+    provider: CssProvider,
+    priority: Rc<Cell<Option<u32>>>,
+}
+
+impl Stylesheet {
+    /// Replace the stylesheet's CSS with the CSS from `source`.
+    ///
+    /// If the new CSS can't be loaded, the stylesheet is left empty.
+    pub fn reload(&self, source: impl Into<Source>) -> Result<(), glib::Error> {
+        match source.into() {
+            Source::Data(css) => self.provider.load_from_data(css.as_bytes()),
+            Source::Path(path) => self.provider.load_from_path(&path.to_string_lossy()),
+        }
+    }
+
+    /// Get the stylesheet's priority, or `None` if it's been unloaded.
+    pub fn priority(&self) -> Option<u32> {
+        self.priority.get()
+    }
+
+    /// Change the stylesheet's priority.
+    ///
+    /// This loads the stylesheet again if it's been unloaded.
+    pub fn set_priority(&self, priority: u32) {
+        let screen = default_screen();
+        if self.priority.get().is_some() {
+            StyleContext::remove_provider_for_screen(&screen, &self.provider);
+        }
+        StyleContext::add_provider_for_screen(&screen, &self.provider, priority);
+        self.priority.set(Some(priority));
+    }
+
+    /// Stop applying the stylesheet.
+    pub fn unload(&self) {
+        if self.priority.take().is_some() {
+            StyleContext::remove_provider_for_screen(&default_screen(), &self.provider);
+        }
+    }
+}
+
+/// Load a stylesheet from `source` with application priority.
+///
+/// This is the same as calling [`load_with_priority()`][load_with_priority]
+/// with [`STYLE_PROVIDER_PRIORITY_APPLICATION`][priority], which overrides the
+/// theme but not the user's own stylesheet.
+///
+/// # Panics
+///
+/// Panics if GTK hasn't been initialised yet.
+///
+/// [load_with_priority]: fn.load_with_priority.html
+/// [priority]: ../../gtk/constant.STYLE_PROVIDER_PRIORITY_APPLICATION.html
+pub fn load(source: impl Into<Source>) -> Result<Stylesheet, glib::Error> {
+    load_with_priority(source, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION)
+}
+
+/// Load a stylesheet from `source` with the given priority.
+///
+/// When stylesheets disagree, the one with the highest priority wins. GTK's
+/// `STYLE_PROVIDER_PRIORITY_*` constants give the priorities of the theme,
+/// the user's settings and so on.
+///
+/// # Panics
+///
+/// Panics if GTK hasn't been initialised yet.
+pub fn load_with_priority(
+    source: impl Into<Source>,
+    priority: u32,
+) -> Result<Stylesheet, glib::Error> {
+    let stylesheet = Stylesheet {
+        provider: CssProvider::new(),
+        priority: Rc::new(Cell::new(None)),
+    };
+    stylesheet.reload(source)?;
+    stylesheet.set_priority(priority);
+    Ok(stylesheet)
+}
+
+thread_local! {
+    // Keyed by the address of the widget, and removed when it's destroyed.
+    static WIDGET_STYLES: RefCell<HashMap<usize, WidgetStyle>> = RefCell::new(HashMap::new());
+}
+
+// The `class` and `style` attributes a widget was last given.
+#[derive(Default)]
+struct WidgetStyle {
+    classes: String,
+    style: String,
+    provider: Option<CssProvider>,
+}
+
+fn style_key(widget: &Widget) -> usize {
+    widget.as_ptr() as usize
+}
+
+fn get_style<F, R>(widget: &Widget, f: F) -> R
+where
+    F: FnOnce(&WidgetStyle) -> R,
+    R: Default,
+{
+    WIDGET_STYLES.with(|styles| {
+        styles
+            .borrow()
+            .get(&style_key(widget))
+            .map(f)
+            .unwrap_or_default()
+    })
+}
+
+fn with_style<F, R>(widget: &Widget, f: F) -> R
+where
+    F: FnOnce(&mut WidgetStyle) -> R,
+{
+    WIDGET_STYLES.with(|styles| {
+        let key = style_key(widget);
+        let mut styles = styles.borrow_mut();
+        let style = styles.entry(key).or_insert_with(|| {
+            widget.connect_destroy(move |_| {
+                WIDGET_STYLES.with(|styles| styles.borrow_mut().remove(&key));
+            });
+            WidgetStyle::default()
+        });
+        f(style)
+    })
+}
+
+/// Get the style classes `widget` was last given with `set_classes`.
+pub(crate) fn classes(widget: &Widget) -> String {
+    get_style(widget, |style| style.classes.clone())
+}
+
+/// Give `widget` the whitespace separated style classes in `classes`,
+/// removing any classes it was given last time which aren't among them.
+///
+/// Classes added to the widget by anything else are left alone.
+pub(crate) fn set_classes(widget: &Widget, classes: &str) {
+    let context = widget.get_style_context();
+    with_style(widget, |style| {
+        let new: Vec<&str> = classes.split_whitespace().collect();
+        for class in style.classes.split_whitespace() {
+            if !new.contains(&class) {
+                context.remove_class(class);
+            }
+        }
+        for class in new {
+            context.add_class(class);
+        }
+        style.classes = classes.to_string();
+    })
+}
+
+/// Get the inline style `widget` was last given with `set_inline_style`.
+pub(crate) fn inline_style(widget: &Widget) -> String {
+    get_style(widget, |style| style.style.clone())
+}
+
+/// Apply the CSS declarations in `declarations` to `widget` alone.
+///
+/// # Panics
+///
+/// Panics if GTK can't parse the declarations.
+pub(crate) fn set_inline_style(widget: &Widget, declarations: &str) {
+    let context = widget.get_style_context();
+    with_style(widget, |style| {
+        let provider = style.provider.get_or_insert_with(|| {
+            let provider = CssProvider::new();
+            context.add_provider(&provider, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
+            provider
+        });
+        // A provider added to a widget's style context only applies to that
+        // widget, so the universal selector can't reach any further.
+        let css = format!("* {{ {} }}", declarations);
+        if let Err(error) = provider.load_from_data(css.as_bytes()) {
+            panic!("invalid inline style {:?}: {}", declarations, error);
+        }
+        style.style = declarations.to_string();
+    })
+}
+
+thread_local! {
+    static SCOPES: RefCell<HashMap<TypeId, String>> = RefCell::new(HashMap::new());
+}
+
+/// Give the widgets in a component's view the style class which scopes the
+/// component's stylesheet, loading the stylesheet if it hasn't been yet.
+pub(crate) fn scope_view<C: 'static + Component>(view: &mut VNode<C>) {
+    let css = C::style();
+    if css.trim().is_empty() {
+        return;
+    }
+    let class = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        let count = scopes.len();
+        scopes
+            .entry(TypeId::of::<C>())
+            .or_insert_with(|| {
+                let class = format!("vgtk-scope-{}", count);
+                if let Err(error) = load(scope_css(css, &class)) {
+                    panic!(
+                        "invalid stylesheet for {}: {}",
+                        std::any::type_name::<C>(),
+                        error
+                    );
+                }
+                class
+            })
+            .clone()
+    });
+    add_scope_class(view, &class);
+}
+
+/// Give the widgets in a lazily rendered part of a component's view the
+/// style class which scopes the component's stylesheet, if it has one.
+pub(crate) fn scope_lazy<C: 'static + Component>(node: &mut VNode<C>) {
+    let class = SCOPES.with(|scopes| scopes.borrow().get(&TypeId::of::<C>()).cloned());
+    if let Some(class) = class {
+        add_scope_class(node, &class);
+    }
+}
+
+// Every widget the component builds gets the class, but the widgets of its
+// subcomponents belong to their own views and are left alone. A lazy node
+// hasn't been rendered yet, so it's given the class when it is.
+fn add_scope_class<C: Component>(node: &mut VNode<C>, class: &str) {
+    if let VNode::Object(object) = node {
+        if object.object_type.is_a(&Widget::static_type()) {
+            let class = class.to_string();
+            object.properties.push(VProperty {
+                name: "vgtk-scope",
+                value: None,
+                set: Box::new(move |object: &Object, _, _| {
+                    if let Some(widget) = object.downcast_ref::<Widget>() {
+                        widget.get_style_context().add_class(&class);
+                    }
+                }),
+            });
+        }
+        for child in &mut object.children {
+            add_scope_class(child, class);
+        }
+    }
+}
+
+/// Rewrite every selector in `css` to only match widgets with the style class
+/// `class`.
+fn scope_css(css: &str, class: &str) -> String {
+    let css = strip_comments(css);
+    let mut out = String::new();
+    let mut rest = css.trim_start();
+    while !rest.is_empty() {
+        let open = rest.find('{');
+        if rest.starts_with('@') {
+            // At-rules are either terminated by a semicolon or followed by a
+            // block, and are copied as they are.
+            match (rest.find(';'), open) {
+                (Some(semicolon), open) if !matches!(open, Some(open) if open < semicolon) => {
+                    out.push_str(&rest[..=semicolon]);
+                    rest = &rest[semicolon + 1..];
+                }
+                (_, Some(open)) => {
+                    let end = block_end(rest, open);
+                    out.push_str(&rest[..end]);
+                    rest = &rest[end..];
+                }
+                _ => {
+                    out.push_str(rest);
+                    rest = "";
+                }
+            }
+        } else {
+            match open {
+                Some(open) => {
+                    let end = block_end(rest, open);
+                    let selectors: Vec<String> = rest[..open]
+                        .split(',')
+                        .map(|selector| selector.trim())
+                        .filter(|selector| !selector.is_empty())
+                        .map(|selector| scope_selector(selector, class))
+                        .collect();
+                    out.push_str(&selectors.join(", "));
+                    out.push(' ');
+                    out.push_str(&rest[open..end]);
+                    rest = &rest[end..];
+                }
+                None => {
+                    out.push_str(rest);
+                    rest = "";
+                }
+            }
+        }
+        out.push('\n');
+        rest = rest.trim_start();
+    }
+    out
+}
+
+// Add `class` to the last compound selector in `selector`, which is the one
+// matching the styled node, ahead of any pseudo-classes it has.
+fn scope_selector(selector: &str, class: &str) -> String {
+    let mut depth = 0;
+    let mut pseudo_class = None;
+    for (index, char) in selector.char_indices() {
+        match char {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            _ if depth > 0 => {}
+            ':' if pseudo_class.is_none() => pseudo_class = Some(index),
+            '>' | '+' | '~' => pseudo_class = None,
+            _ if char.is_whitespace() => pseudo_class = None,
+            _ => {}
+        }
+    }
+    let at = pseudo_class.unwrap_or(selector.len());
+    format!("{}.{}{}", &selector[..at], class, &selector[at..])
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+// Find the end of the block which opens at `open`, including any blocks
+// nested inside it.
+fn block_end(css: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (index, char) in css[open..].char_indices() {
+        match char {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    css.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scope_css;
+
+    #[test]
+    fn scopes_each_selector_in_a_list() {
+        assert_eq!(
+            scope_css("label, button { color: red; }", "scope"),
+            "label.scope, button.scope { color: red; }\n"
+        );
+    }
+
+    #[test]
+    fn scopes_the_last_compound_selector() {
+        assert_eq!(
+            scope_css("box > label, headerbar  entry { margin: 2px; }", "scope"),
+            "box > label.scope, headerbar  entry.scope { margin: 2px; }\n"
+        );
+    }
+
+    #[test]
+    fn scopes_ahead_of_pseudo_classes() {
+        assert_eq!(
+            scope_css(
+                "button:hover label, entry:not(:focus), row:nth-child(2n) { color: red; }",
+                "scope"
+            ),
+            "button:hover label.scope, entry.scope:not(:focus), row.scope:nth-child(2n) \
+             { color: red; }\n"
+        );
+    }
+
+    #[test]
+    fn copies_at_rules() {
+        assert_eq!(
+            scope_css(
+                "@define-color accent #3584e4;\n\
+                 @keyframes pulse { from { opacity: 1; } to { opacity: 0.5; } }\n\
+                 label { color: @accent; }",
+                "scope"
+            ),
+            "@define-color accent #3584e4;\n\
+             @keyframes pulse { from { opacity: 1; } to { opacity: 0.5; } }\n\
+             label.scope { color: @accent; }\n"
+        );
+    }
+
+    #[test]
+    fn strips_comments() {
+        assert_eq!(
+            scope_css(
+                "/* label { color: red; } */ entry /* , button */ { color: blue; }",
+                "scope"
+            ),
+            "entry.scope { color: blue; }\n"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Talking to D-Bus services.
+//!
+//! Most desktop integration, like media player controls over MPRIS, keeping
+//! the screensaver at bay or the XDG desktop portals, happens over D-Bus. The
+//! version of GIO `vgtk` is built on doesn't provide its D-Bus API, so this
+//! module uses [zbus] instead, which is re-exported as
+//! [`vgtk::lib::zbus`][lib::zbus].
+//!
+//! [`connection()`][connection] gets you a connection to the session or
+//! system bus, shared by everyone who asks for one, for making method calls,
+//! and [`signals()`][signals] turns the signals which match a rule into a
+//! [`Subscription`][Subscription], so they arrive at your component's
+//! [`update`][update] method as messages.
+//!
+//! To offer your own D-Bus interface, define it with zbus's `interface`
+//! attribute, which needs `zbus` in your own `Cargo.toml`, and give it your
+//! component's [`Scope`][Scope], so its methods can send the component
+//! messages. A `Scope` can be sent between threads, which is where zbus calls
+//! the methods from.
+//!
+//! This module is only available with the `dbus` feature enabled.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use vgtk::dbus::{self, Bus};
+//! use vgtk::{Scope, Subscription, UpdateAction};
+//! # use vgtk::{Component, VNode};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Raise,
+//!     Suspending(bool),
+//! }
+//!
+//! struct Remote {
+//!     scope: Scope<App>,
+//! }
+//!
+//! #[zbus::interface(name = "com.example.App")]
+//! impl Remote {
+//!     fn raise(&self) {
+//!         self.scope.send(Message::Raise);
+//!     }
+//! }
+//!
+//! #[derive(Default)]
+//! struct App;
+//!
+//! impl Component for App {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn mounted(&mut self) {
+//!         let remote = Remote { scope: Scope::current() };
+//!         vgtk::spawn_local(async move {
+//!             let result = async {
+//!                 let connection = dbus::connection(Bus::Session).await?;
+//!                 connection.object_server().at("/com/example/App", remote).await?;
+//!                 connection.request_name("com.example.App").await
+//!             };
+//!             if let Err(err) = result.await {
+//!                 eprintln!("unable to serve com.example.App: {}", err);
+//!             }
+//!         });
+//!     }
+//!
+//!     fn subscriptions(&self) -> Vec<Subscription<Message>> {
+//!         let rule = "type='signal',interface='org.freedesktop.login1.Manager',\
+//!                     member='PrepareForSleep'";
+//!         vec![dbus::signals(Bus::System, rule, |message| {
+//!             message.body().deserialize().ok().map(Message::Suspending)
+//!         })]
+//!     }
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         // ...
+//! #       UpdateAction::None
+//!     }
+//! #   fn view(&self) -> VNode<Self> { todo!() }
+//! }
+//! ```
+//!
+//! [zbus]: https://docs.rs/zbus/
+//! [lib::zbus]: ../lib/index.html
+//! [connection]: fn.connection.html
+//! [signals]: fn.signals.html
+//! [Subscription]: ../struct.Subscription.html
+//! [update]: ../trait.Component.html#method.update
+//! [Scope]: ../struct.Scope.html
+
+use futures::stream::{self, StreamExt};
+use log::warn;
+use zbus::{Connection, MessageStream, OwnedMatchRule};
+
+use std::convert::TryInto;
+use std::sync::Mutex;
+
+use crate::subscription::Subscription;
+
+/// Which message bus to connect to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bus {
+    /// The bus belonging to the user's login session, where desktop
+    /// applications and services live.
+    Session,
+    /// The bus for system services, like logind and NetworkManager.
+    System,
+}
+
+static SESSION: Mutex<Option<Connection>> = Mutex::new(None);
+static SYSTEM: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// Get a connection to `bus`.
+///
+/// The connection is made the first time it's asked for, and shared by
+/// everyone who asks for it after that.
+///
+/// This returns an error if the bus can't be reached.
+pub async fn connection(bus: Bus) -> zbus::Result<Connection> {
+    let cache = match bus {
+        Bus::Session => &SESSION,
+        Bus::System => &SYSTEM,
+    };
+    if let Some(connection) = cache.lock().unwrap().as_ref() {
+        return Ok(connection.clone());
+    }
+    let connection = match bus {
+        Bus::Session => Connection::session().await?,
+        Bus::System => Connection::system().await?,
+    };
+    // Someone else may have connected while we were waiting, in which case
+    // we'd rather everyone used theirs.
+    Ok(cache.lock().unwrap().get_or_insert(connection).clone())
+}
+
+/// Construct a subscription to the messages on `bus` which match `rule`.
+///
+/// The rule can be written as a D-Bus match rule string, like
+/// `"type='signal',interface='org.freedesktop.login1.Manager'"`, or built with
+/// zbus's `MatchRule::builder()`. The callback is called with each matching
+/// message, and can return `None` to ignore it.
+///
+/// If the bus can't be reached or it won't accept the rule, a warning is
+/// logged and the subscription never sends anything.
+pub fn signals<Msg, R, F>(bus: Bus, rule: R, f: F) -> Subscription<Msg>
+where
+    Msg: 'static,
+    R: TryInto<OwnedMatchRule> + 'static,
+    R::Error: Into<zbus::Error>,
+    F: Fn(&zbus::Message) -> Option<Msg> + 'static,
+{
+    let messages = stream::once(async move {
+        let rule = rule.try_into().map_err(Into::into)?;
+        let connection = connection(bus).await?;
+        MessageStream::for_match_rule(rule, &connection, None).await
+    })
+    .filter_map(|result: zbus::Result<MessageStream>| async move {
+        result
+            .map_err(|err| warn!("unable to subscribe to D-Bus messages: {}", err))
+            .ok()
+    })
+    .flatten()
+    .filter_map(move |message| {
+        let message = message.ok().and_then(|message| f(&message));
+        async move { message }
+    });
+    Subscription::new(messages)
+}
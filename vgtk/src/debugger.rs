@@ -0,0 +1,272 @@
+use futures::channel::mpsc::UnboundedSender;
+use glib::MainContext;
+use gtk::prelude::*;
+use gtk::{Box, Button, Label, ListBox, ListBoxRow, Orientation, Paned, ScrolledWindow, Window};
+
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::component::{
+    Component, ComponentMessage, ComponentTask, PartialComponentTask, UpdateAction,
+};
+use crate::ext::WindowExtHelpers;
+use crate::gtk;
+use crate::scope::Scope;
+use crate::vnode::VNode;
+
+/// The debugger's view of a recording, without the component's type.
+pub(crate) trait Timeline {
+    /// Describe every recorded message, starting with the initial state.
+    fn messages(&self) -> Vec<String>;
+    /// Get the index of the entry whose state the component is showing.
+    fn position(&self) -> usize;
+    /// Describe the state the component is showing.
+    fn state(&self) -> String;
+    /// Put the component back in the state it had after entry `index`.
+    fn jump(&self, index: usize);
+    /// Start over from the initial state and send every recorded message again.
+    fn replay(&self);
+    /// Tell the debugger whenever something is recorded.
+    fn listen(&self, scope: Scope<Debugger>);
+}
+
+struct Entry<C: Component> {
+    message: Option<C::Message>,
+    label: String,
+    state: C,
+}
+
+/// Every message a component has received, with a snapshot of its state after
+/// each one.
+struct Recording<C: Component> {
+    entries: RefCell<Vec<Entry<C>>>,
+    position: Cell<usize>,
+    sender: UnboundedSender<ComponentMessage<C>>,
+    listener: RefCell<Option<Scope<Debugger>>>,
+}
+
+impl<C: 'static + Component + Clone + Debug> Recording<C> {
+    fn record(&self, message: &C::Message, state: &C) {
+        let mut entries = self.entries.borrow_mut();
+        // A message received after travelling back in time starts a new
+        // timeline from there.
+        entries.truncate(self.position.get() + 1);
+        entries.push(Entry {
+            message: Some(message.clone()),
+            label: format!("{:?}", message),
+            state: state.clone(),
+        });
+        self.position.set(entries.len() - 1);
+        drop(entries);
+        self.notify();
+    }
+
+    fn restore(&self, index: usize) {
+        let state = self.entries.borrow()[index].state.clone();
+        self.position.set(index);
+        let _ = self
+            .sender
+            .unbounded_send(ComponentMessage::Restore(Rc::new(move || state.clone())));
+    }
+
+    fn notify(&self) {
+        if let Some(scope) = &*self.listener.borrow() {
+            scope.send(DebuggerMessage::Recorded);
+        }
+    }
+}
+
+impl<C: 'static + Component + Clone + Debug> Timeline for Recording<C> {
+    fn messages(&self) -> Vec<String> {
+        self.entries
+            .borrow()
+            .iter()
+            .map(|entry| entry.label.clone())
+            .collect()
+    }
+
+    fn position(&self) -> usize {
+        self.position.get()
+    }
+
+    fn state(&self) -> String {
+        format!("{:#?}", self.entries.borrow()[self.position.get()].state)
+    }
+
+    fn jump(&self, index: usize) {
+        if index < self.entries.borrow().len() {
+            self.restore(index);
+            self.notify();
+        }
+    }
+
+    fn replay(&self) {
+        let messages: Vec<C::Message> = self
+            .entries
+            .borrow_mut()
+            .drain(1..)
+            .filter_map(|entry| entry.message)
+            .collect();
+        self.restore(0);
+        for message in messages {
+            let _ = self
+                .sender
+                .unbounded_send(ComponentMessage::Update(message));
+        }
+        self.notify();
+    }
+
+    fn listen(&self, scope: Scope<Debugger>) {
+        *self.listener.borrow_mut() = Some(scope);
+    }
+}
+
+/// Record every message `task`'s component receives, and its state after
+/// each one, so the debugger can travel back and forth through them.
+pub(crate) fn record<C: 'static + Component + Clone + Debug>(
+    task: PartialComponentTask<C, ()>,
+) -> (PartialComponentTask<C, ()>, Rc<dyn Timeline>) {
+    let recording = Rc::new(Recording {
+        entries: RefCell::new(vec![Entry {
+            message: None,
+            label: "(initial state)".to_string(),
+            state: task.state().clone(),
+        }]),
+        position: Cell::new(0),
+        sender: task.sender(),
+        listener: RefCell::new(None),
+    });
+    let recorder = recording.clone();
+    let task = task.on_update(move |message, state| recorder.record(message, state));
+    (task, recording)
+}
+
+/// Open a debugger window for a recording.
+pub(crate) fn open(timeline: Rc<dyn Timeline>) {
+    let props = Debugger {
+        timeline: Some(timeline.clone()),
+    };
+    let (_, scope) = open_window::<Debugger>(props);
+    timeline.listen(scope);
+}
+
+/// Open a window component on its own, outside the application's component
+/// tree.
+pub(crate) fn open_window<C: 'static + Component>(props: C::Properties) -> (Window, Scope<C>) {
+    let (channel, task) = ComponentTask::<C, ()>::new(props, None, None);
+    let window: Window = task
+        .object()
+        .and_then(|object| object.downcast().ok())
+        .expect("a debugging tool's view must be a Window");
+    let scope = task.scope();
+    MainContext::ref_thread_default().spawn_local(task);
+    channel.unbounded_send(ComponentMessage::Mounted).unwrap();
+    window.connect_destroy(move |_| {
+        let _ = channel.unbounded_send(ComponentMessage::Unmounted);
+    });
+    window.present();
+    (window, scope)
+}
+
+/// A window listing the messages a component has received, which can put the
+/// component back in the state it had after any of them.
+#[derive(Clone, Default)]
+pub(crate) struct Debugger {
+    timeline: Option<Rc<dyn Timeline>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum DebuggerMessage {
+    Recorded,
+    Jump(usize),
+    Replay,
+}
+
+pub(crate) fn marker(current: bool) -> &'static str {
+    if current {
+        "▶"
+    } else {
+        " "
+    }
+}
+
+fn toolbar(position: usize, last: usize) -> VNode<Debugger> {
+    gtk! {
+        <Box spacing=6 Box::expand=false>
+            <Button label="Back" sensitive={ position > 0 }
+                    on clicked=|_| DebuggerMessage::Jump(position.saturating_sub(1)) />
+            <Button label="Forward" sensitive={ position < last }
+                    on clicked=|_| DebuggerMessage::Jump(position + 1) />
+            <Button label="Replay" sensitive={ last > 0 } on clicked=|_| DebuggerMessage::Replay />
+        </Box>
+    }
+}
+
+fn history(position: usize, messages: &[String]) -> VNode<Debugger> {
+    gtk! {
+        <ScrolledWindow property_height_request=200>
+            <ListBox on row_activated=|_, row| DebuggerMessage::Jump(row.get_index() as usize)>
+                {
+                    for (index, message) in messages.iter().enumerate() {
+                        <ListBoxRow>
+                            <Label label=format!("{} {}: {}", marker(index == position), index, message)
+                                   xalign=0.0 />
+                        </ListBoxRow>
+                    }
+                }
+            </ListBox>
+        </ScrolledWindow>
+    }
+}
+
+impl Debugger {
+    fn timeline(&self) -> &dyn Timeline {
+        &**self
+            .timeline
+            .as_ref()
+            .expect("the debugger has nothing to debug")
+    }
+}
+
+impl Component for Debugger {
+    type Message = DebuggerMessage;
+    type Properties = Self;
+
+    fn create(props: Self) -> Self {
+        props
+    }
+
+    fn change(&mut self, props: Self) -> UpdateAction<Self> {
+        *self = props;
+        UpdateAction::Render
+    }
+
+    fn update(&mut self, message: DebuggerMessage) -> UpdateAction<Self> {
+        match message {
+            DebuggerMessage::Recorded => {}
+            DebuggerMessage::Jump(index) => self.timeline().jump(index),
+            DebuggerMessage::Replay => self.timeline().replay(),
+        }
+        UpdateAction::Render
+    }
+
+    fn view(&self) -> VNode<Self> {
+        let timeline = self.timeline();
+        let position = timeline.position();
+        let messages = timeline.messages();
+        gtk! {
+            <Window title="vgtk debugger" default_width=480 default_height=640>
+                <Box orientation=Orientation::Vertical spacing=6>
+                    { toolbar(position, messages.len() - 1) }
+                    <Paned orientation=Orientation::Vertical Box::expand=true Box::fill=true>
+                        { history(position, &messages) }
+                        <ScrolledWindow>
+                            <Label label=timeline.state() selectable=true xalign=0.0 yalign=0.0 />
+                        </ScrolledWindow>
+                    </Paned>
+                </Box>
+            </Window>
+        }
+    }
+}
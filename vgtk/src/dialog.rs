@@ -0,0 +1,85 @@
+use gtk::ResponseType;
+
+use crate::component::Component;
+
+/// A [`Component`][Component] which runs as a modal dialog, producing a
+/// result once the user is done with it.
+///
+/// Its top level object must be a [`Dialog`][Dialog]. Run it with
+/// [`vgtk::run_component_dialog()`][run_component_dialog], which resolves to
+/// whatever [`output()`][output] makes of the component's state when the
+/// dialog responds, so a form can be filled in like any other component and
+/// its contents handed back to the component which opened it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, DialogComponent, UpdateAction, VNode};
+/// # use vgtk::lib::gtk::*;
+/// #[derive(Clone, Debug)]
+/// enum Message {
+///     Name(String),
+/// }
+///
+/// #[derive(Default)]
+/// struct Rename {
+///     name: String,
+/// }
+///
+/// impl Component for Rename {
+///     type Message = Message;
+///     type Properties = String;
+///
+///     fn create(name: String) -> Self {
+///         Rename { name }
+///     }
+///
+///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///         match message {
+///             Message::Name(name) => self.name = name,
+///         }
+///         UpdateAction::None
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         gtk! {
+///             <Dialog::new_with_buttons(
+///                 Some("Rename"),
+///                 None as Option<&Window>,
+///                 DialogFlags::MODAL,
+///                 &[("Cancel", ResponseType::Cancel), ("Rename", ResponseType::Ok)]
+///             )>
+///                 <Entry bind:text=self.name.clone() => Message::Name />
+///             </Dialog>
+///         }
+///     }
+/// }
+///
+/// impl DialogComponent for Rename {
+///     type Output = Option<String>;
+///
+///     fn output(&self, response: ResponseType) -> Option<String> {
+///         if response == ResponseType::Ok {
+///             Some(self.name.clone())
+///         } else {
+///             None
+///         }
+///     }
+/// }
+/// ```
+///
+/// [Component]: trait.Component.html
+/// [Dialog]: ../gtk/struct.Dialog.html
+/// [run_component_dialog]: fn.run_component_dialog.html
+/// [output]: #tymethod.output
+pub trait DialogComponent: Component {
+    /// The type of the dialog's result.
+    type Output: 'static;
+
+    /// Make the dialog's result from the component's state, once the dialog
+    /// has responded with `response`.
+    ///
+    /// If the dialog is destroyed without responding, `response` is
+    /// `ResponseType::None`.
+    fn output(&self, response: ResponseType) -> Self::Output;
+}
@@ -0,0 +1,205 @@
+use gdk::{DragAction, ModifierType};
+use glib::{ObjectExt, WeakRef};
+use gtk::prelude::WidgetExtManual;
+use gtk::{DestDefaults, TargetEntry, TargetFlags, Widget, WidgetExt};
+
+use std::cell::RefCell;
+
+/// The kinds of data a widget offers when it's dragged, or accepts when
+/// something is dropped on it, for the `drag_source` and `drag_dest`
+/// attributes provided by [`WidgetExtHelpers`][WidgetExtHelpers].
+///
+/// Targets are named by MIME type, like `"text/uri-list"`, or by a name of
+/// your own choosing for data which only makes sense to your application.
+/// Drags are copies unless you ask for other [`actions()`][actions].
+///
+/// A drag source sends its data through the `drag_data_get` signal, or as
+/// text given to its `drag_text` attribute, and a drag destination receives
+/// it through its `drag_data_received` signal, whose handler can turn it into
+/// a message like any other.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, DragTargets, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Move(usize, usize), Open(Vec<String>) }
+/// # #[derive(Default)] struct Comp { items: Vec<String> }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <ListBox drag_dest=DragTargets::uris()
+///              on drag_data_received=|_, _, _, _, data, _, _| {
+///                  Message::Open(data.get_uris().iter().map(|uri| uri.to_string()).collect())
+///              }>
+///         {
+///             self.items.iter().enumerate().map(|(index, item)| gtk! {
+///                 <EventBox drag_source=DragTargets::text() drag_text=index.to_string()
+///                           drag_dest=DragTargets::text()
+///                           on drag_data_received=|_, _, _, _, data, _, _| {
+///                               let from = data.get_text().and_then(|text| text.parse().ok());
+///                               from.map(|from| Message::Move(from, index))
+///                           }>
+///                     <Label label=item.clone() />
+///                 </EventBox>
+///             })
+///         }
+///     </ListBox>
+/// }
+/// # }}
+/// ```
+///
+/// [WidgetExtHelpers]: ext/trait.WidgetExtHelpers.html
+/// [actions]: #method.actions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DragTargets {
+    targets: Vec<String>,
+    actions: DragAction,
+}
+
+impl DragTargets {
+    /// Construct a target list from a list of target names.
+    pub fn new<S: Into<String>>(targets: impl IntoIterator<Item = S>) -> Self {
+        DragTargets {
+            targets: targets.into_iter().map(Into::into).collect(),
+            actions: DragAction::COPY,
+        }
+    }
+
+    /// Construct a target list for plain text, in all the forms GTK knows.
+    pub fn text() -> Self {
+        DragTargets::new(vec![
+            "UTF8_STRING",
+            "TEXT",
+            "COMPOUND_TEXT",
+            "STRING",
+            "text/plain;charset=utf-8",
+            "text/plain",
+        ])
+    }
+
+    /// Construct a target list for a list of URIs, which is how file
+    /// managers drag files.
+    pub fn uris() -> Self {
+        DragTargets::new(vec!["text/uri-list"])
+    }
+
+    /// Add the target `target` to the list.
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.targets.push(target.into());
+        self
+    }
+
+    /// Set the actions a drag can perform, `DragAction::COPY` by default.
+    pub fn actions(mut self, actions: DragAction) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    /// Test whether the list has no targets, in which case the widget isn't
+    /// a drag source or destination at all.
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    fn entries(&self) -> Vec<TargetEntry> {
+        self.targets
+            .iter()
+            .enumerate()
+            .map(|(info, target)| TargetEntry::new(target, TargetFlags::empty(), info as u32))
+            .collect()
+    }
+}
+
+impl Default for DragTargets {
+    fn default() -> Self {
+        DragTargets::new(Vec::<String>::new())
+    }
+}
+
+thread_local! {
+    static DRAG_STATES: RefCell<Vec<(WeakRef<Widget>, DragState)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+// The drag attributes a widget was last given.
+#[derive(Default)]
+struct DragState {
+    source: DragTargets,
+    dest: DragTargets,
+    text: Option<String>,
+}
+
+fn with_state<F, R>(widget: &Widget, f: F) -> R
+where
+    F: FnOnce(&mut DragState) -> R,
+{
+    DRAG_STATES.with(|states| {
+        let mut states = states.borrow_mut();
+        states.retain(|(dragged, _)| dragged.upgrade().is_some());
+        let index = match states
+            .iter()
+            .position(|(dragged, _)| dragged.upgrade().as_ref() == Some(widget))
+        {
+            Some(index) => index,
+            None => {
+                states.push((widget.downgrade(), DragState::default()));
+                states.len() - 1
+            }
+        };
+        f(&mut states[index].1)
+    })
+}
+
+pub(crate) fn source(widget: &Widget) -> DragTargets {
+    with_state(widget, |state| state.source.clone())
+}
+
+/// Make `widget` a drag source for `targets`, or stop it being one if there
+/// are none.
+pub(crate) fn set_source(widget: &Widget, targets: DragTargets) {
+    if targets.is_empty() {
+        widget.drag_source_unset();
+    } else {
+        widget.drag_source_set(
+            ModifierType::BUTTON1_MASK,
+            &targets.entries(),
+            targets.actions,
+        );
+    }
+    with_state(widget, |state| state.source = targets);
+}
+
+pub(crate) fn dest(widget: &Widget) -> DragTargets {
+    with_state(widget, |state| state.dest.clone())
+}
+
+/// Make `widget` a drag destination for `targets`, or stop it being one if
+/// there are none.
+pub(crate) fn set_dest(widget: &Widget, targets: DragTargets) {
+    if targets.is_empty() {
+        widget.drag_dest_unset();
+    } else {
+        widget.drag_dest_set(DestDefaults::ALL, &targets.entries(), targets.actions);
+    }
+    with_state(widget, |state| state.dest = targets);
+}
+
+pub(crate) fn text(widget: &Widget) -> String {
+    with_state(widget, |state| state.text.clone().unwrap_or_default())
+}
+
+/// Answer requests for `widget`'s drag data with `text`.
+pub(crate) fn set_text(widget: &Widget, text: &str) {
+    let connected = with_state(widget, |state| {
+        state.text.replace(text.to_string()).is_some()
+    });
+    if !connected {
+        widget.connect_drag_data_get(|widget, _, data, _, _| {
+            let text = with_state(widget, |state| state.text.clone());
+            if let Some(text) = text {
+                data.set_text(&text);
+            }
+        });
+    }
+}
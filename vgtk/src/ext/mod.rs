@@ -7,204 +7,1464 @@
 
 #![allow(missing_docs)]
 
+use gdk::{WindowExt, WindowState, RGBA};
 use gdk_pixbuf::Pixbuf;
 use gio::{Action, ActionExt, ApplicationFlags};
-use glib::{GString, IsA, Object, ObjectExt};
+use glib::{Cast, GString, IsA, Object, ObjectExt, SignalHandlerId};
 use gtk::{
-    Application, ApplicationWindowExt, BoxExt, GridExt, GtkApplicationExt, GtkWindowExt, ImageExt,
-    LabelExt, Widget, Window, WindowPosition, WindowType,
+    ActionableExt, Application, ApplicationWindowExt, BoxExt, CalendarExt, CellAreaExt, CellLayout,
+    CellLayoutExt, CellRenderer, ColorButtonExt, ColorChooserExt, ComboBoxExt, ComboBoxText,
+    ComboBoxTextExt, DrawingArea, FontButtonExt, FontChooserExt, GridExt, GtkApplicationExt,
+    GtkWindowExt, HeaderBarExt, ImageExt, InfoBar, Inhibit, LabelExt, LevelBar, ListBox,
+    OverlayExt, PanedExt, Popover, PopoverExt, ProgressBar, ResponseType, Scale, SearchBar,
+    SearchBarExt, Settings, SettingsExt, SpinButtonExt, Stack, StackSwitcher, StackSwitcherExt,
+    ToolbarExt, ToolbarStyle, TreeModelExt, Widget, WidgetExt, Window, WindowPosition, WindowType,
 };
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use colored::Colorize;
 use log::trace;
+#[cfg(feature = "webkit")]
+use webkit2gtk::{LoadEvent, WebViewExt};
+
+use crate::css;
+use crate::drag::{self, DragTargets};
+use crate::geometry;
+use crate::info_bar;
+use crate::list_model::{bound_model, ListBinding};
+use crate::painter::Painter;
+use crate::progress::{self, Progress};
+use crate::scale::{self, ValueFormat};
+use crate::search_bar;
+#[cfg(feature = "sourceview")]
+use crate::sourceview::{self, View};
+use crate::types::{CalendarDate, GridPosition};
+use crate::widget_ref::WidgetRef;
+
+/// Helper trait for [`Application`][Application].
+///
+/// `prefer_dark_theme` sets GTK's `gtk-application-prefer-dark-theme`
+/// setting, which switches themes with a dark variant, like the default
+/// Adwaita theme, to it. To follow changes to the theme made outside your
+/// application, subscribe to them with
+/// [`Subscription::dark_theme()`][dark_theme].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gio::ApplicationFlags;
+/// # use vgtk::lib::gtk::*;
+/// # fn view(dark: bool) -> VNode<()> {
+/// gtk! {
+///     <Application::new_unwrap(None, ApplicationFlags::empty()) prefer_dark_theme=dark>
+///         <Window />
+///     </Application>
+/// }
+/// # }
+/// ```
+///
+/// [Application]: ../../gtk/struct.Application.html
+/// [dark_theme]: ../struct.Subscription.html#method.dark_theme
+pub trait ApplicationHelpers: GtkApplicationExt {
+    /// Construct a new [`Application`][Application] and panic if it fails.
+    ///
+    /// This is like [`Application::new`][new], but returns an [`Application`][Application] instead of
+    /// an [`Option`][Option]`<`[`Application`][Application]`>`, so you can use it as a constructor in the [`gtk!`][gtk!]
+    /// macro.
+    ///
+    /// [gtk!]: ../macro.gtk.html
+    /// [Application]: ../../gtk/struct.Application.html
+    /// [new]: ../../gtk/struct.Application.html#method.new
+    /// [Option]: https://doc.rust-lang.org/std/option/enum.Option.html
+    fn new_unwrap(application_id: Option<&str>, flags: ApplicationFlags) -> Application {
+        Application::new(application_id, flags).expect("unable to create Application object")
+    }
+
+    fn get_child_accels<P: IsA<Action>>(&self, action: &P) -> Vec<GString> {
+        self.get_accels_for_action(&action_name("app", action.as_ref()))
+    }
+
+    fn set_child_accels<P: IsA<Action>>(&self, action: &P, accels: &[&str]) {
+        self.set_accels_for_action(&action_name("app", action.as_ref()), accels)
+    }
+
+    fn get_child_accelerator<P: IsA<Action>>(&self, action: &P) -> Option<GString> {
+        self.get_child_accels(action).into_iter().next()
+    }
+
+    fn set_child_accelerator<P: IsA<Action>>(&self, action: &P, accelerator: &str) {
+        self.set_child_accels(action, &[accelerator])
+    }
+
+    fn get_prefer_dark_theme(&self) -> bool {
+        Settings::get_default()
+            .map(|settings| settings.get_property_gtk_application_prefer_dark_theme())
+            .unwrap_or(false)
+    }
+
+    fn set_prefer_dark_theme(&self, prefer_dark_theme: bool) {
+        if let Some(settings) = Settings::get_default() {
+            settings.set_property_gtk_application_prefer_dark_theme(prefer_dark_theme);
+        }
+    }
+}
+
+impl<A> ApplicationHelpers for A where A: GtkApplicationExt {}
+
+fn action_name(prefix: &str, action: &Action) -> String {
+    format!(
+        "{}.{}",
+        prefix,
+        action.get_name().expect("Action has no name").as_str()
+    )
+}
+
+// An `ApplicationWindow` may not have been added to its `Application` yet when
+// its actions are being set up, in which case we have to wait until it has.
+fn set_window_accels<W>(window: &W, name: String, accels: Vec<String>)
+where
+    W: ApplicationWindowExt + GtkWindowExt + IsA<Window>,
+{
+    fn apply(app: &Application, name: &str, accels: &[String]) {
+        trace!(
+            "{} {} -> {}",
+            "Action:".bright_black(),
+            name.bright_cyan().bold(),
+            format!("{:?}", accels).bright_green().bold()
+        );
+        let accels: Vec<&str> = accels.iter().map(String::as_str).collect();
+        app.set_accels_for_action(name, &accels);
+    }
+
+    if let Some(app) = window.get_application() {
+        apply(&app, &name, &accels);
+    } else {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let id = Rc::new(Cell::new(None));
+        let inner_id = id.clone();
+        id.set(Some(window.connect_property_application_notify(
+            move |window: &W| {
+                if let Some(app) = window.get_application() {
+                    apply(&app, &name, &accels);
+                    window.disconnect(inner_id.replace(None).unwrap());
+                }
+            },
+        )));
+    }
+}
+
+/// Helper trait for [`ApplicationWindow`][ApplicationWindow].
+///
+/// [ApplicationWindow]: ../../gtk/struct.ApplicationWindow.html
+pub trait ApplicationWindowHelpers: ApplicationWindowExt + GtkWindowExt + IsA<Window> {
+    fn get_child_accels<P: IsA<Action>>(&self, action: &P) -> Vec<GString> {
+        match self.get_application() {
+            Some(app) => app.get_accels_for_action(&action_name("win", action.as_ref())),
+            // Not attached yet, so any accels we've set are still pending.
+            None => Vec::new(),
+        }
+    }
+
+    fn set_child_accels<P: IsA<Action>>(&self, action: &P, accels: &[&str]) {
+        let accels = accels.iter().map(|accel| accel.to_string()).collect();
+        set_window_accels(self, action_name("win", action.as_ref()), accels);
+    }
+
+    fn get_child_accelerator<P: IsA<Action>>(&self, action: &P) -> Option<GString> {
+        self.get_child_accels(action).into_iter().next()
+    }
+
+    fn set_child_accelerator<P: IsA<Action>>(&self, action: &P, accelerator: &str) {
+        set_window_accels(
+            self,
+            action_name("win", action.as_ref()),
+            vec![accelerator.to_string()],
+        );
+    }
+}
+
+impl<A> ApplicationWindowHelpers for A where A: ApplicationWindowExt + GtkWindowExt + IsA<Window> {}
+
+/// Helper trait for widgets implementing [`Actionable`][Actionable].
+///
+/// This provides an `accelerator` property, which registers a keyboard
+/// shortcut for the widget's `action_name` with the default
+/// [`Application`][Application]:
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// # fn build() -> VNode<()> {
+/// gtk! {
+///     <Button label="New" action_name="win.new" accelerator="<Ctrl>n" />
+/// }
+/// # }
+/// ```
+///
+/// The `action_name` must be set before the `accelerator`.
+///
+/// [Actionable]: ../../gtk/struct.Actionable.html
+/// [Application]: ../../gtk/struct.Application.html
+pub trait ActionableHelpers: ActionableExt {
+    fn get_accelerator(&self) -> Option<GString> {
+        let name = self.get_action_name()?;
+        default_application()?
+            .get_accels_for_action(&name)
+            .into_iter()
+            .next()
+    }
+
+    fn set_accelerator(&self, accelerator: &str) {
+        let name = self
+            .get_action_name()
+            .expect("accelerator set on a widget without an action_name");
+        default_application()
+            .expect("accelerator set without a running Application")
+            .set_accels_for_action(&name, &[accelerator]);
+    }
+}
+
+impl<A> ActionableHelpers for A where A: ActionableExt {}
+
+fn default_application() -> Option<Application> {
+    gio::Application::get_default().and_then(|app| app.downcast().ok())
+}
+
+/// Give a widget the keyboard focus, waiting until it's been mapped if it
+/// hasn't yet, as GTK won't focus a widget which isn't on screen.
+pub(crate) fn grab_focus_when_mapped<W: IsA<Widget>>(widget: &W) {
+    let widget = widget.as_ref();
+    if widget.get_mapped() {
+        widget.grab_focus();
+        return;
+    }
+    let handler = Rc::new(RefCell::new(None));
+    let inner_handler = handler.clone();
+    let id = widget.connect_map(move |widget| {
+        widget.grab_focus();
+        if let Some(id) = inner_handler.borrow_mut().take() {
+            widget.disconnect(id);
+        }
+    });
+    *handler.borrow_mut() = Some(id);
+}
+
+/// Helper trait for [`Widget`][Widget].
+///
+/// `has_focus=true` gives the widget the keyboard focus whenever it's
+/// rendered without it, while `autofocus=true` only focuses it when it's first
+/// built. Either way, if the widget isn't on screen yet, it's focused as soon
+/// as it's mapped.
+///
+/// `class` takes a list of CSS style classes separated by spaces. When it
+/// changes, the classes which are no longer listed are removed from the
+/// widget's [`StyleContext`][StyleContext] and the new ones are added. `style`
+/// takes CSS declarations, like `"margin: 4px;"`, and applies them to this
+/// widget alone.
+///
+/// `drag_source` and `drag_dest` take [`DragTargets`][DragTargets], making
+/// the widget a source or destination for drag and drop of those targets.
+/// `drag_text` gives the text a drag source sends when it's dropped
+/// somewhere, for sources which don't handle `drag_data_get` themselves.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # fn view(dangerous: bool) -> VNode<()> {
+/// gtk! {
+///     <Box style="padding: 6px;">
+///         <Entry autofocus=true />
+///         <Button label="Delete" class={ if dangerous { "destructive-action" } else { "" } } />
+///     </Box>
+/// }
+/// # }
+/// ```
+///
+/// [Widget]: ../../gtk/struct.Widget.html
+/// [StyleContext]: ../../gtk/struct.StyleContext.html
+/// [DragTargets]: ../struct.DragTargets.html
+pub trait WidgetExtHelpers: IsA<Widget> {
+    fn get_has_focus(&self) -> bool {
+        self.as_ref().is_focus()
+    }
+
+    fn set_has_focus(&self, has_focus: bool) {
+        if has_focus {
+            grab_focus_when_mapped(self);
+        }
+    }
+
+    fn get_autofocus(&self) -> bool {
+        // Pretend autofocus is always set, so it's only applied when the
+        // widget is built and never again when it's patched.
+        true
+    }
+
+    fn set_autofocus(&self, autofocus: bool) {
+        if autofocus {
+            grab_focus_when_mapped(self);
+        }
+    }
+
+    fn get_class(&self) -> String {
+        css::classes(self.as_ref())
+    }
+
+    fn set_class(&self, class: &str) {
+        css::set_classes(self.as_ref(), class);
+    }
+
+    // The `gtk!` macro maps the `style` attribute to this, as `Widget`
+    // already has a `style` property of its own.
+    fn get_inline_style(&self) -> String {
+        css::inline_style(self.as_ref())
+    }
+
+    fn set_inline_style(&self, style: &str) {
+        css::set_inline_style(self.as_ref(), style);
+    }
+
+    fn get_drag_source(&self) -> DragTargets {
+        drag::source(self.as_ref())
+    }
+
+    fn set_drag_source(&self, targets: DragTargets) {
+        drag::set_source(self.as_ref(), targets);
+    }
+
+    fn get_drag_dest(&self) -> DragTargets {
+        drag::dest(self.as_ref())
+    }
+
+    fn set_drag_dest(&self, targets: DragTargets) {
+        drag::set_dest(self.as_ref(), targets);
+    }
+
+    fn get_drag_text(&self) -> String {
+        drag::text(self.as_ref())
+    }
+
+    fn set_drag_text(&self, text: &str) {
+        drag::set_text(self.as_ref(), text);
+    }
+}
+
+impl<A> WidgetExtHelpers for A where A: IsA<Widget> {}
+
+/// Helper trait for [`Window`][Window].
+///
+/// `maximized`, `fullscreened` and `iconified` control whether the window is
+/// maximized, fullscreen or minimized. The user or the window manager can
+/// change these too, so to keep your component's state in step with them,
+/// bind them with `bind:`, which sends you a message whenever they change.
+///
+/// `remember_geometry` takes a name to save the window's size, position and
+/// maximized state under when it's closed, and restores them when a window
+/// with the same name is opened again, next time your application runs. Give
+/// each kind of window a different name. They're kept in `windows.ini` in a
+/// directory named after your program inside the user's data directory.
+/// Some window systems, like Wayland, don't let applications place their own
+/// windows, in which case only the size and maximized state are restored.
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Fullscreen(bool) }
+/// # #[derive(Default)] struct Notes { fullscreen: bool }
+/// # impl vgtk::Component for Notes { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Self> {
+/// gtk! {
+///     <Window title="Notes" default_width=640 default_height=480 remember_geometry="main-window"
+///             bind:fullscreened=self.fullscreen => Message::Fullscreen>
+///         <Label label="Hello" />
+///     </Window>
+/// }
+/// # }}
+/// ```
+///
+/// [Window]: ../../gtk/struct.Window.html
+pub trait WindowExtHelpers: GtkWindowExt {
+    fn get_default_height(&self) -> i32 {
+        self.get_property_default_height()
+    }
+
+    fn set_default_height(&self, default_height: i32) {
+        self.set_property_default_height(default_height)
+    }
+
+    fn get_default_width(&self) -> i32 {
+        self.get_property_default_width()
+    }
+
+    fn set_default_width(&self, default_width: i32) {
+        self.set_property_default_width(default_width)
+    }
+
+    fn get_has_toplevel_focus(&self) -> bool {
+        self.get_property_has_toplevel_focus()
+    }
+
+    fn get_is_active(&self) -> bool {
+        self.get_property_is_active()
+    }
+
+    fn get_is_maximized(&self) -> bool {
+        self.get_property_is_maximized()
+    }
+
+    fn get_type(&self) -> WindowType {
+        self.get_property_type()
+    }
+
+    fn get_window_position(&self) -> WindowPosition {
+        self.get_property_window_position()
+    }
+
+    fn set_window_position(&self, window_position: WindowPosition) {
+        self.set_property_window_position(window_position)
+    }
+
+    fn get_maximized(&self) -> bool {
+        self.get_property_is_maximized()
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        if maximized {
+            self.maximize();
+        } else {
+            self.unmaximize();
+        }
+    }
+
+    fn connect_property_maximized_notify<F: Fn(&Self) + 'static>(&self, f: F) -> SignalHandlerId {
+        self.connect_property_is_maximized_notify(f)
+    }
+
+    fn get_fullscreened(&self) -> bool
+    where
+        Self: WidgetExt + Sized,
+    {
+        has_window_state(self, WindowState::FULLSCREEN)
+    }
+
+    fn set_fullscreened(&self, fullscreened: bool) {
+        if fullscreened {
+            self.fullscreen();
+        } else {
+            self.unfullscreen();
+        }
+    }
+
+    fn connect_property_fullscreened_notify<F: Fn(&Self) + 'static>(&self, f: F) -> SignalHandlerId
+    where
+        Self: WidgetExt + Sized,
+    {
+        connect_window_state(self, WindowState::FULLSCREEN, f)
+    }
+
+    fn get_iconified(&self) -> bool
+    where
+        Self: WidgetExt + Sized,
+    {
+        has_window_state(self, WindowState::ICONIFIED)
+    }
+
+    fn set_iconified(&self, iconified: bool) {
+        if iconified {
+            self.iconify();
+        } else {
+            self.deiconify();
+        }
+    }
+
+    fn connect_property_iconified_notify<F: Fn(&Self) + 'static>(&self, f: F) -> SignalHandlerId
+    where
+        Self: WidgetExt + Sized,
+    {
+        connect_window_state(self, WindowState::ICONIFIED, f)
+    }
+
+    fn get_remember_geometry(&self) -> String
+    where
+        Self: IsA<Window>,
+    {
+        geometry::name(self.as_ref())
+    }
+
+    fn set_remember_geometry(&self, name: &str)
+    where
+        Self: IsA<Window>,
+    {
+        geometry::remember(self.as_ref(), name);
+    }
+}
+
+impl<A> WindowExtHelpers for A where A: GtkWindowExt {}
+
+// Windows only know whether they're fullscreen or iconified once they're on
+// screen, and until then they're neither.
+fn has_window_state<W: WidgetExt>(window: &W, state: WindowState) -> bool {
+    window
+        .get_window()
+        .map(|window| window.get_state().contains(state))
+        .unwrap_or(false)
+}
+
+fn connect_window_state<W, F>(window: &W, state: WindowState, f: F) -> SignalHandlerId
+where
+    W: WidgetExt,
+    F: Fn(&W) + 'static,
+{
+    window.connect_window_state_event(move |window, event| {
+        if event.get_changed_mask().contains(state) {
+            f(window);
+        }
+        Inhibit(false)
+    })
+}
+
+/// Helper trait for [`Box`][Box].
+///
+/// [Box]: ../../gtk/struct.Box.html
+pub trait BoxExtHelpers: BoxExt {
+    fn get_child_center_widget<P: IsA<Object>>(&self, _child: &P) -> bool {
+        // Always compare true, it's all taken care of in add_child().
+        true
+    }
+
+    fn set_child_center_widget<P: IsA<Object>>(&self, _child: &P, _center: bool) {
+        // This is handled by add_child() rules. The setter is a no-op.
+    }
+}
+
+impl<A> BoxExtHelpers for A where A: BoxExt {}
+
+/// Helper trait for [`HeaderBar`][HeaderBar].
+///
+/// Children of a `HeaderBar` are packed at its start by default. Use the
+/// `HeaderBar::pack_type` child property to pack them at the end instead, or
+/// set `HeaderBar::custom_title=true` on a child to use it in place of the
+/// header bar's title and subtitle. The custom title can be any widget with
+/// any children of its own.
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// # fn view() -> VNode<()> {
+/// gtk! {
+///     <HeaderBar show_close_button=true>
+///         <Button label="Open" />
+///         <Entry placeholder_text="Search" HeaderBar::custom_title=true />
+///         <MenuButton HeaderBar::pack_type=PackType::End />
+///     </HeaderBar>
+/// }
+/// # }
+/// ```
+///
+/// A [`StackSwitcher`][StackSwitcher] in the title is the usual way of
+/// switching between the pages of a window. Give it the
+/// [`WidgetRef`][WidgetRef] of the [`Stack`][Stack] in the window's body
+/// with its `stack_ref` property, provided by
+/// [`StackSwitcherExtHelpers`][StackSwitcherExtHelpers].
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, VNode, WidgetRef};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Default)] struct Comp { pages: WidgetRef<Stack> }
+/// # impl vgtk::Component for Comp { type Message = (); type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Window>
+///         <HeaderBar show_close_button=true>
+///             <StackSwitcher stack_ref=self.pages.clone() HeaderBar::custom_title=true />
+///         </HeaderBar>
+///         <Stack ref=self.pages>
+///             <Label label="Inbox" Stack::title="Inbox" />
+///             <Label label="Sent" Stack::title="Sent" />
+///         </Stack>
+///     </Window>
+/// }
+/// # }}
+/// ```
+///
+/// [StackSwitcher]: ../../gtk/struct.StackSwitcher.html
+/// [Stack]: ../../gtk/struct.Stack.html
+/// [WidgetRef]: ../struct.WidgetRef.html
+/// [StackSwitcherExtHelpers]: trait.StackSwitcherExtHelpers.html
+///
+/// [HeaderBar]: ../../gtk/struct.HeaderBar.html
+pub trait HeaderBarExtHelpers: HeaderBarExt {
+    fn get_child_custom_title<P: IsA<Object>>(&self, _child: &P) -> bool {
+        // Always compare true, it's all taken care of in add_child().
+        true
+    }
+
+    fn set_child_custom_title<P: IsA<Object>>(&self, _child: &P, _custom_title: bool) {
+        // This is handled by add_child() rules. The setter is a no-op.
+    }
+}
+
+impl<A> HeaderBarExtHelpers for A where A: HeaderBarExt {}
+
+/// Helper trait for [`Image`][Image].
+///
+/// [Image]: ../../gtk/struct.Image.html
+pub trait ImageExtHelpers: ImageExt {
+    fn set_pixbuf(&self, pixbuf: Option<Pixbuf>) {
+        self.set_from_pixbuf(pixbuf.as_ref());
+    }
+}
+
+impl<A> ImageExtHelpers for A where A: ImageExt {}
+
+/// Helper trait for [`Label`][Label].
+///
+/// [Label]: ../../gtk/struct.Label.html
+pub trait LabelExtHelpers: LabelExt {
+    fn get_markup(&self) -> Option<GString> {
+        self.get_label()
+    }
+}
+
+impl<A> LabelExtHelpers for A where A: LabelExt {}
+
+/// Helper trait for [`InfoBar`][InfoBar].
+///
+/// Children of an `InfoBar` go in its content area, except for those given a
+/// `InfoBar::response` child property, which go in its action area, where
+/// activating them makes the info bar emit its `response` signal with that
+/// [`ResponseType`][ResponseType]. Turn it into a message with
+/// `on response=`.
+///
+/// `revealed` shows and hides the info bar, sliding it in and out of view
+/// when GTK is new enough to animate it, which is 3.22 and up. With older
+/// versions, it simply shows and hides.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Retry, Dismiss }
+/// # #[derive(Default)] struct Comp { error: Option<String> }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <InfoBar message_type=MessageType::Error show_close_button=true
+///              revealed=self.error.is_some()
+///              on response=|_, response| {
+///                  if response == ResponseType::Ok {
+///                      Message::Retry
+///                  } else {
+///                      Message::Dismiss
+///                  }
+///              }>
+///         <Label label=self.error.clone().unwrap_or_default() />
+///         <Button label="Retry" InfoBar::response=ResponseType::Ok />
+///     </InfoBar>
+/// }
+/// # }}
+/// ```
+///
+/// [InfoBar]: ../../gtk/struct.InfoBar.html
+/// [ResponseType]: ../../gtk/enum.ResponseType.html
+pub trait InfoBarExtHelpers: IsA<InfoBar> {
+    fn get_revealed(&self) -> bool {
+        if self.as_ref().find_property("revealed").is_some() {
+            self.get_property("revealed")
+                .ok()
+                .and_then(|value| value.get_some().ok())
+                .unwrap_or(true)
+        } else {
+            self.as_ref().get_visible()
+        }
+    }
+
+    fn set_revealed(&self, revealed: bool) {
+        // The property is only there from GTK 3.22 onwards.
+        if self.as_ref().find_property("revealed").is_some() {
+            self.set_property("revealed", &revealed)
+                .expect("unable to set InfoBar::revealed");
+        } else {
+            self.as_ref().set_visible(revealed);
+        }
+    }
+
+    fn get_child_response<P: IsA<Object>>(&self, child: &P) -> ResponseType {
+        child
+            .dynamic_cast_ref::<Widget>()
+            .and_then(info_bar::response)
+            .unwrap_or(ResponseType::None)
+    }
+
+    fn set_child_response<P: IsA<Object>>(&self, child: &P, response: ResponseType) {
+        if let Some(widget) = child.dynamic_cast_ref::<Widget>() {
+            info_bar::set_response(self.as_ref(), widget, response);
+        }
+    }
+}
+
+impl<A> InfoBarExtHelpers for A where A: IsA<InfoBar> {}
+
+/// Helper trait for [`ListBox`][ListBox].
+///
+/// [ListBox]: ../../gtk/struct.ListBox.html
+pub trait ListBoxExtHelpers: IsA<ListBox> {
+    fn get_model(&self) -> Option<gio::ListModel> {
+        bound_model(self.upcast_ref())
+    }
+
+    fn set_model(&self, binding: ListBinding) {
+        binding.bind(self.upcast_ref());
+    }
+}
+
+impl<A> ListBoxExtHelpers for A where A: IsA<ListBox> {}
+
+/// Helper trait for [`Overlay`][Overlay].
+///
+/// The first child of an `Overlay` is its main widget, and any children after
+/// it float on top of it, in the order they're declared. Place them with
+/// their `halign`, `valign` and margin properties, and set
+/// `Overlay::pass_through=true` on the ones which shouldn't catch the mouse,
+/// so clicks go through to the main widget underneath.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Add }
+/// # #[derive(Default)] struct Comp { status: String }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Overlay>
+///         <ScrolledWindow>
+///             <ListBox />
+///         </ScrolledWindow>
+///         <Label label=self.status.clone() halign=Align::Center valign=Align::Start
+///                Overlay::pass_through=true />
+///         <Button label="Add" halign=Align::End valign=Align::End
+///                 margin_end=12 margin_bottom=12 on clicked=|_| Message::Add />
+///     </Overlay>
+/// }
+/// # }}
+/// ```
+///
+/// [Overlay]: ../../gtk/struct.Overlay.html
+pub trait OverlayExtHelpers: OverlayExt {
+    fn get_child_pass_through<P: IsA<Widget>>(&self, child: &P) -> bool {
+        self.get_overlay_pass_through(child)
+    }
+
+    fn set_child_pass_through<P: IsA<Widget>>(&self, child: &P, pass_through: bool) {
+        self.set_overlay_pass_through(child, pass_through);
+    }
+}
+
+impl<A> OverlayExtHelpers for A where A: OverlayExt {}
+
+/// Helper trait for [`Paned`][Paned].
+///
+/// A `Paned` takes two children, the first of which goes on the left or at
+/// the top. Whether each of them grows when the paned is resized, and
+/// whether it can be made smaller than it wants to be, is set with the
+/// `Paned::resize` and `Paned::shrink` child properties.
+///
+/// To remember where the user has dragged the divider, bind its `position`
+/// property to your component's state, or turn the changes into messages
+/// with `on position_changed`, which is given the new position.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Split(i32) }
+/// # #[derive(Default)] struct Comp { split: i32 }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Paned orientation=Orientation::Horizontal position=self.split
+///            on position_changed=|_, position| Message::Split(position)>
+///         <ListBox Paned::resize=false Paned::shrink=false />
+///         <TextView Paned::resize=true />
+///     </Paned>
+/// }
+/// # }}
+/// ```
+///
+/// [Paned]: ../../gtk/struct.Paned.html
+pub trait PanedExtHelpers: PanedExt {
+    fn connect_position_changed<F: Fn(&Self, i32) + 'static>(&self, f: F) -> SignalHandlerId {
+        self.connect_property_position_notify(move |paned| f(paned, paned.get_position()))
+    }
+}
+
+impl<A> PanedExtHelpers for A where A: PanedExt {}
+
+/// Helper trait for [`Popover`][Popover].
+///
+/// A popover declared inside another widget points at that widget, but
+/// `relative_to_ref` can point it at any widget you hold a
+/// [`WidgetRef`][WidgetRef] to instead, even one which hasn't been built yet.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode, WidgetRef};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Close }
+/// # #[derive(Default)] struct Comp { open: bool, entry: WidgetRef<Entry> }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Box>
+///         <Entry ref=self.entry />
+///         <Popover relative_to_ref=self.entry.clone() visible=self.open
+///                  on closed=|_| Message::Close>
+///             <Label label="That name is taken." />
+///         </Popover>
+///     </Box>
+/// }
+/// # }}
+/// ```
+///
+/// [Popover]: ../../gtk/struct.Popover.html
+/// [WidgetRef]: ../struct.WidgetRef.html
+pub trait PopoverExtHelpers: IsA<Popover> {
+    fn get_relative_to_ref(&self) -> Option<Widget> {
+        self.as_ref().get_relative_to()
+    }
+
+    fn set_relative_to_ref<W: IsA<Widget>>(&self, widget: &WidgetRef<W>) {
+        let popover = self.as_ref().downgrade();
+        widget.when_mounted(move |widget| {
+            if let Some(popover) = popover.upgrade() {
+                popover.set_relative_to(Some(widget));
+            }
+        });
+    }
+}
+
+impl<A> PopoverExtHelpers for A where A: IsA<Popover> {}
+
+/// Helper trait for [`ProgressBar`][ProgressBar].
+///
+/// `progress` takes a [`Progress`][Progress], which sets the bar's fraction,
+/// or has it pulse until it's given a fraction again when the task doesn't
+/// know how far along it is. See
+/// [`UpdateAction::defer_with_progress()`][defer_with_progress] for how to
+/// have a task report its progress.
+///
+/// [ProgressBar]: ../../gtk/struct.ProgressBar.html
+/// [Progress]: ../enum.Progress.html
+/// [defer_with_progress]: ../enum.UpdateAction.html#method.defer_with_progress
+pub trait ProgressBarExtHelpers: IsA<ProgressBar> {
+    fn get_progress(&self) -> Progress {
+        progress::progress_bar_progress(self.as_ref())
+    }
+
+    fn set_progress(&self, value: Progress) {
+        progress::set_progress_bar_progress(self.as_ref(), value);
+    }
+}
+
+impl<A> ProgressBarExtHelpers for A where A: IsA<ProgressBar> {}
+
+/// Helper trait for [`LevelBar`][LevelBar].
+///
+/// `progress` takes a [`Progress`][Progress], which sets the bar's value to
+/// the same fraction of the way from its `min_value` to its `max_value`, so
+/// set those first. A level bar can't pulse, so `Progress::Pulse` shows no
+/// progress.
+///
+/// [LevelBar]: ../../gtk/struct.LevelBar.html
+/// [Progress]: ../enum.Progress.html
+pub trait LevelBarExtHelpers: IsA<LevelBar> {
+    fn get_progress(&self) -> Progress {
+        progress::level_bar_progress(self.as_ref())
+    }
+
+    fn set_progress(&self, value: Progress) {
+        progress::set_level_bar_progress(self.as_ref(), value);
+    }
+}
+
+impl<A> LevelBarExtHelpers for A where A: IsA<LevelBar> {}
+
+/// Helper trait for [`Scale`][Scale].
+///
+/// `marks` takes a list of values and the labels to put next to them, which
+/// are Pango markup, and replaces the scale's marks with them. An empty label
+/// gives you a mark without one. Marks go below a horizontal scale and to the
+/// right of a vertical one, so set `orientation` before `marks`.
+///
+/// `value_format` takes a [`ValueFormat`][ValueFormat], which turns the value
+/// the scale displays into text, like a percentage or a zoom level.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, ValueFormat, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Volume(f64) }
+/// # #[derive(Default)] struct Comp { volume: f64 }
+/// fn percent(_: &(), value: f64) -> String {
+///     format!("{:.0}%", value * 100.0)
+/// }
+///
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Scale orientation=Orientation::Horizontal
+///            adjustment=Adjustment::new(0.0, 0.0, 1.0, 0.05, 0.1, 0.0)
+///            marks=[(0.0, "Mute"), (1.0, "Max")]
+///            value_format=ValueFormat::new((), percent)
+///            value=self.volume
+///            on value_changed=|scale| Message::Volume(scale.get_value()) />
+/// }
+/// # }}
+/// ```
+///
+/// [Scale]: ../../gtk/struct.Scale.html
+/// [ValueFormat]: ../struct.ValueFormat.html
+pub trait ScaleExtHelpers: IsA<Scale> {
+    fn get_marks(&self) -> Vec<(f64, String)> {
+        scale::marks(self.as_ref())
+    }
+
+    fn set_marks(&self, marks: &[(f64, &str)]) {
+        scale::set_marks(self.as_ref(), marks);
+    }
+
+    fn get_value_format(&self) -> Option<ValueFormat> {
+        scale::value_format(self.as_ref())
+    }
+
+    fn set_value_format(&self, format: ValueFormat) {
+        scale::set_value_format(self.as_ref(), format);
+    }
+}
+
+impl<A> ScaleExtHelpers for A where A: IsA<Scale> {}
+
+/// Helper trait for [`sourceview::View`][View].
+///
+/// `language` and `scheme` take the GtkSourceView IDs of the language to
+/// highlight the view's text as and the style scheme to highlight it with,
+/// like `"rust"` and `"classic"`. An empty ID means none.
+///
+/// `text` replaces all of the text in the view's buffer, and
+/// `text_changed` is emitted with the new text whenever the user edits it.
+/// Keep the text your component passes to `text` up to date from
+/// `text_changed`, or the next render will undo the user's edits. Replacing
+/// the text through `text` doesn't emit `text_changed`.
+///
+/// Only available with the `sourceview` feature enabled. See the
+/// [`sourceview`][sourceview] module for an example.
+///
+/// [View]: ../sourceview/struct.View.html
+/// [sourceview]: ../sourceview/index.html
+#[cfg(feature = "sourceview")]
+pub trait SourceViewExtHelpers: IsA<View> {
+    fn get_language(&self) -> String {
+        sourceview::language(self.as_ref())
+    }
+
+    fn set_language(&self, id: &str) {
+        sourceview::set_language(self.as_ref(), id);
+    }
+
+    fn get_scheme(&self) -> String {
+        sourceview::scheme(self.as_ref())
+    }
+
+    fn set_scheme(&self, id: &str) {
+        sourceview::set_scheme(self.as_ref(), id);
+    }
+
+    fn get_text(&self) -> String {
+        sourceview::text(self.as_ref())
+    }
+
+    fn set_text(&self, text: &str) {
+        sourceview::set_text(self.as_ref(), text);
+    }
+
+    fn connect_text_changed<F: Fn(&Self, String) + 'static>(&self, f: F) -> SignalHandlerId {
+        sourceview::connect_text_changed(self, f)
+    }
+}
 
-use crate::types::GridPosition;
+#[cfg(feature = "sourceview")]
+impl<A> SourceViewExtHelpers for A where A: IsA<View> {}
 
-/// Helper trait for [`Application`][Application].
+/// Helper trait for WebKit2GTK's [`WebView`][WebView].
 ///
-/// [Application]: ../../gtk/struct.Application.html
-pub trait ApplicationHelpers: GtkApplicationExt {
-    /// Construct a new [`Application`][Application] and panic if it fails.
-    ///
-    /// This is like [`Application::new`][new], but returns an [`Application`][Application] instead of
-    /// an [`Option`][Option]`<`[`Application`][Application]`>`, so you can use it as a constructor in the [`gtk!`][gtk!]
-    /// macro.
-    ///
-    /// [gtk!]: ../macro.gtk.html
-    /// [Application]: ../../gtk/struct.Application.html
-    /// [new]: ../../gtk/struct.Application.html#method.new
-    /// [Option]: https://doc.rust-lang.org/std/option/enum.Option.html
-    fn new_unwrap(application_id: Option<&str>, flags: ApplicationFlags) -> Application {
-        Application::new(application_id, flags).expect("unable to create Application object")
+/// `uri` loads the page at the given URI, which WebKit2GTK itself only does
+/// through [`load_uri()`][load_uri]. `navigated` is emitted with the new URI
+/// whenever the view starts showing a different page, whether your component
+/// or the user navigated to it, and `title_changed` with the page's title
+/// whenever it changes. Keep the URI your component passes to `uri` up to date
+/// from `navigated`, or the next render will take the user back to the old
+/// page. For more detail, use the view's own `load_changed` signal.
+///
+/// Only available with the `webkit` feature enabled, which re-exports the
+/// [`webkit2gtk`][webkit2gtk] crate as `vgtk::lib::webkit2gtk`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// use vgtk::lib::webkit2gtk::{WebView, WebViewExt};
+/// # #[derive(Clone, Debug)] enum Message { Navigated(String), Title(String) }
+/// # #[derive(Default)] struct Browser { uri: String }
+/// # impl vgtk::Component for Browser { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Browser> {
+/// gtk! {
+///     <WebView uri=self.uri.clone()
+///              on navigated=|_, uri| Message::Navigated(uri)
+///              on title_changed=|_, title| Message::Title(title) />
+/// }
+/// # }}
+/// ```
+///
+/// [WebView]: ../../webkit2gtk/struct.WebView.html
+/// [load_uri]: ../../webkit2gtk/trait.WebViewExt.html#tymethod.load_uri
+/// [webkit2gtk]: https://crates.io/crates/webkit2gtk
+#[cfg(feature = "webkit")]
+pub trait WebViewExtHelpers: WebViewExt {
+    fn set_uri(&self, uri: &str) {
+        self.load_uri(uri);
     }
 
-    fn get_child_accels<P: IsA<Action>>(&self, action: &P) -> Vec<GString> {
-        self.get_accels_for_action(&format!(
-            "app.{}",
-            action
-                .as_ref()
-                .get_name()
-                .expect("Action has no name")
-                .as_str()
-        ))
+    fn connect_navigated<F: Fn(&Self, String) + 'static>(&self, f: F) -> SignalHandlerId {
+        self.connect_load_changed(move |view, event| {
+            if let (LoadEvent::Committed, Some(uri)) = (event, view.get_uri()) {
+                f(view, uri.to_string());
+            }
+        })
     }
 
-    fn set_child_accels<P: IsA<Action>>(&self, action: &P, accels: &[&str]) {
-        self.set_accels_for_action(
-            &format!(
-                "app.{}",
-                action
-                    .as_ref()
-                    .get_name()
-                    .expect("Action has no name")
-                    .as_str()
-            ),
-            accels,
-        )
+    fn connect_title_changed<F: Fn(&Self, String) + 'static>(&self, f: F) -> SignalHandlerId {
+        self.connect_property_title_notify(move |view| {
+            f(view, view.get_title().map(Into::into).unwrap_or_default());
+        })
     }
 }
 
-impl<A> ApplicationHelpers for A where A: GtkApplicationExt {}
+#[cfg(feature = "webkit")]
+impl<A> WebViewExtHelpers for A where A: WebViewExt {}
 
-/// Helper trait for [`ApplicationWindow`][ApplicationWindow].
+/// Helper trait for [`SpinButton`][SpinButton].
 ///
-/// [ApplicationWindow]: ../../gtk/struct.ApplicationWindow.html
-pub trait ApplicationWindowHelpers: ApplicationWindowExt + GtkWindowExt + IsA<Window> {
-    fn get_child_accels<P: IsA<Action>>(&self, action: &P) -> Vec<GString> {
-        let app = self
-            .get_application()
-            .expect("ApplicationWindow has no Application!");
-        app.get_accels_for_action(&format!(
-            "win.{}",
-            action
-                .as_ref()
-                .get_name()
-                .expect("Action has no name")
-                .as_str()
-        ))
-    }
-
-    fn set_child_accels<P: IsA<Action>>(&self, action: &P, accels: &'static [&str]) {
-        let name = format!(
-            "win.{}",
-            action
-                .as_ref()
-                .get_name()
-                .expect("Action has no name")
-                .as_str()
-        );
-        if let Some(app) = self.get_application() {
-            app.set_accels_for_action(&name, accels);
-        } else {
-            use std::cell::Cell;
-            use std::rc::Rc;
-
-            let id = Rc::new(Cell::new(None));
-            let inner_id = id.clone();
-            id.set(Some(self.connect_property_application_notify(
-                move |window: &Self| {
-                    if let Some(app) = window.get_application() {
-                        trace!(
-                            "{} {} -> {}",
-                            "Action:".bright_black(),
-                            name.bright_cyan().bold(),
-                            format!("{:?}", accels).bright_green().bold()
-                        );
-                        app.set_accels_for_action(&name, accels);
-                        window.disconnect(inner_id.replace(None).unwrap());
-                    }
-                },
-            )));
-        }
+/// A `SpinButton` keeps its value, limits and step size in an
+/// [`Adjustment`][Adjustment]. The `min`, `max` and `step` attributes set
+/// them on the spin button's own adjustment, so you don't have to build one,
+/// and changes to them are applied like any other attribute's. Pressing Page
+/// Up or Page Down moves the value by ten steps.
+///
+/// The value is clamped to the limits, so set `value` after `min` and `max`.
+/// Bind it to your component's state to receive the new value whenever the
+/// user changes it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Copies(f64) }
+/// # #[derive(Default)] struct Comp { copies: f64 }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <SpinButton min=1.0 max=99.0 step=1.0 bind:value=self.copies => Message::Copies />
+/// }
+/// # }}
+/// ```
+///
+/// [SpinButton]: ../../gtk/struct.SpinButton.html
+/// [Adjustment]: ../../gtk/struct.Adjustment.html
+pub trait SpinButtonExtHelpers: SpinButtonExt {
+    fn get_min(&self) -> f64 {
+        self.get_range().0
+    }
+
+    fn set_min(&self, min: f64) {
+        let (_, max) = self.get_range();
+        self.set_range(min, max.max(min));
+    }
+
+    fn get_max(&self) -> f64 {
+        self.get_range().1
+    }
+
+    fn set_max(&self, max: f64) {
+        let (min, _) = self.get_range();
+        self.set_range(min.min(max), max);
+    }
+
+    fn get_step(&self) -> f64 {
+        self.get_increments().0
+    }
+
+    fn set_step(&self, step: f64) {
+        self.set_increments(step, step * 10.0);
     }
 }
 
-impl<A> ApplicationWindowHelpers for A where A: ApplicationWindowExt + GtkWindowExt + IsA<Window> {}
+impl<A> SpinButtonExtHelpers for A where A: SpinButtonExt {}
 
-/// Helper trait for [`Window`][Window].
+/// Helper trait for [`StackSwitcher`][StackSwitcher].
 ///
-/// [Window]: ../../gtk/struct.Window.html
-pub trait WindowExtHelpers: GtkWindowExt {
-    fn get_default_height(&self) -> i32 {
-        self.get_property_default_height()
+/// `stack_ref` points the switcher at the [`Stack`][Stack] you hold a
+/// [`WidgetRef`][WidgetRef] to, even one which hasn't been built yet, so the
+/// switcher can go somewhere else in your view, like the custom title of a
+/// [`HeaderBar`][HeaderBar].
+///
+/// [StackSwitcher]: ../../gtk/struct.StackSwitcher.html
+/// [Stack]: ../../gtk/struct.Stack.html
+/// [WidgetRef]: ../struct.WidgetRef.html
+/// [HeaderBar]: trait.HeaderBarExtHelpers.html
+pub trait StackSwitcherExtHelpers: IsA<StackSwitcher> {
+    fn get_stack_ref(&self) -> Option<Widget> {
+        self.as_ref().get_stack().map(Cast::upcast)
     }
 
-    fn set_default_height(&self, default_height: i32) {
-        self.set_property_default_height(default_height)
+    fn set_stack_ref(&self, stack: &WidgetRef<Stack>) {
+        let switcher = self.as_ref().downgrade();
+        stack.when_mounted(move |stack| {
+            if let Some(switcher) = switcher.upgrade() {
+                switcher.set_stack(Some(stack));
+            }
+        });
     }
+}
 
-    fn get_default_width(&self) -> i32 {
-        self.get_property_default_width()
+impl<A> StackSwitcherExtHelpers for A where A: IsA<StackSwitcher> {}
+
+/// Helper trait for [`SearchBar`][SearchBar].
+///
+/// `search_mode_enabled` shows or hides the bar, and can be bound to your
+/// component's state so it hears about the bar being closed with the Escape
+/// key or its close button. Setting `capture_key_events` has the bar listen
+/// to key presses anywhere in its window, so it opens and starts searching as
+/// soon as the user starts typing, unless they're typing into some other text
+/// field. A [`SearchEntry`][SearchEntry] directly inside the bar is the one
+/// the keys go to.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Searching(bool), Search(String) }
+/// # #[derive(Default)] struct Comp { searching: bool }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Box orientation=Orientation::Vertical>
+///         <SearchBar capture_key_events=true
+///                    bind:search_mode_enabled=self.searching => Message::Searching>
+///             <SearchEntry on search_changed=|entry| {
+///                 let text = entry.get_text().map(|text| text.to_string());
+///                 Message::Search(text.unwrap_or_default())
+///             } />
+///         </SearchBar>
+///         <ListBox Box::expand=true />
+///     </Box>
+/// }
+/// # }}
+/// ```
+///
+/// [SearchBar]: ../../gtk/struct.SearchBar.html
+/// [SearchEntry]: ../../gtk/struct.SearchEntry.html
+pub trait SearchBarExtHelpers: IsA<SearchBar> {
+    fn get_search_mode_enabled(&self) -> bool {
+        self.as_ref().get_search_mode()
     }
 
-    fn set_default_width(&self, default_width: i32) {
-        self.set_property_default_width(default_width)
+    fn set_search_mode_enabled(&self, search_mode_enabled: bool) {
+        self.as_ref().set_search_mode(search_mode_enabled);
     }
 
-    fn get_has_toplevel_focus(&self) -> bool {
-        self.get_property_has_toplevel_focus()
+    fn get_capture_key_events(&self) -> bool {
+        search_bar::captures_key_events(self.as_ref())
     }
 
-    fn get_is_active(&self) -> bool {
-        self.get_property_is_active()
+    fn set_capture_key_events(&self, capture_key_events: bool) {
+        search_bar::set_capture_key_events(self.as_ref(), capture_key_events);
     }
+}
 
-    fn get_is_maximized(&self) -> bool {
-        self.get_property_is_maximized()
+impl<A> SearchBarExtHelpers for A where A: IsA<SearchBar> {}
+
+/// Helper trait for [`Toolbar`][Toolbar].
+///
+/// The children of a `Toolbar` are [`ToolItem`][ToolItem]s, like
+/// [`ToolButton`][ToolButton], [`ToggleToolButton`][ToggleToolButton] and
+/// [`SeparatorToolItem`][SeparatorToolItem]. Any other widget can go in a
+/// toolbar inside a plain `ToolItem`. `Toolbar::expand` and
+/// `Toolbar::homogeneous` set how each item shares the space, and
+/// `toolbar_style` picks whether the items show icons, labels or both.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Open, Save, Search(String) }
+/// # #[derive(Default)] struct Comp;
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Toolbar toolbar_style=ToolbarStyle::Icons>
+///         <ToolButton icon_name="document-open" label="Open" on clicked=|_| Message::Open />
+///         <ToolButton icon_name="document-save" label="Save" on clicked=|_| Message::Save />
+///         <SeparatorToolItem draw=false Toolbar::expand=true />
+///         <ToolItem>
+///             <SearchEntry on search_changed=|entry| {
+///                 let text = entry.get_text().map(|text| text.to_string());
+///                 Message::Search(text.unwrap_or_default())
+///             } />
+///         </ToolItem>
+///     </Toolbar>
+/// }
+/// # }}
+/// ```
+///
+/// [Toolbar]: ../../gtk/struct.Toolbar.html
+/// [ToolItem]: ../../gtk/struct.ToolItem.html
+/// [ToolButton]: ../../gtk/struct.ToolButton.html
+/// [ToggleToolButton]: ../../gtk/struct.ToggleToolButton.html
+/// [SeparatorToolItem]: ../../gtk/struct.SeparatorToolItem.html
+pub trait ToolbarExtHelpers: ToolbarExt {
+    fn get_toolbar_style(&self) -> ToolbarStyle {
+        self.get_property_toolbar_style()
     }
 
-    fn get_type(&self) -> WindowType {
-        self.get_property_type()
+    fn set_toolbar_style(&self, toolbar_style: ToolbarStyle) {
+        self.set_style(toolbar_style);
     }
 
-    fn get_window_position(&self) -> WindowPosition {
-        self.get_property_window_position()
+    fn get_child_expand<P: IsA<Widget>>(&self, child: &P) -> bool {
+        self.get_item_expand(child)
     }
 
-    fn set_window_position(&self, window_position: WindowPosition) {
-        self.set_property_window_position(window_position)
+    fn set_child_expand<P: IsA<Widget>>(&self, child: &P, expand: bool) {
+        self.set_item_expand(child, expand);
+    }
+
+    fn get_child_homogeneous<P: IsA<Widget>>(&self, child: &P) -> bool {
+        self.get_item_homogeneous(child)
+    }
+
+    fn set_child_homogeneous<P: IsA<Widget>>(&self, child: &P, homogeneous: bool) {
+        self.set_item_homogeneous(child, homogeneous);
     }
 }
 
-impl<A> WindowExtHelpers for A where A: GtkWindowExt {}
+impl<A> ToolbarExtHelpers for A where A: ToolbarExt {}
 
-/// Helper trait for [`Box`][Box].
+/// Helper trait for [`Calendar`][Calendar].
 ///
-/// [Box]: ../../gtk/struct.Box.html
-pub trait BoxExtHelpers: BoxExt {
-    fn get_child_center_widget(&self, _child: &Object) -> bool {
-        // Always compare true, it's all taken care of in add_child().
-        true
+/// `year`, `month` and `day` select a date a part at a time, so you can keep
+/// them in your component's state separately, and `selected_date` selects a
+/// whole [`CalendarDate`][CalendarDate] at once. Months go from 1 to 12, not
+/// from 0 like GTK's, and a `day` of 0 means no day is selected.
+///
+/// `marked_days` marks the days of the displayed month which are in the
+/// list, and unmarks the others. A calendar keeps its marks when the user
+/// moves to another month, so update them when it emits `month_changed`.
+/// Keep the list in ascending order to have it compare equal to the marks
+/// the calendar reports.
+///
+/// `on date_selected` is given the newly selected date whenever the user
+/// selects a day.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # use vgtk::types::CalendarDate;
+/// # #[derive(Clone, Debug)] enum Message { Select(CalendarDate), Browse(i32, u32) }
+/// # #[derive(Default)] struct Comp { date: CalendarDate, booked: Vec<u32> }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <Calendar year=self.date.year month=self.date.month day=self.date.day
+///               marked_days=self.booked.clone()
+///               on date_selected=|_, date| Message::Select(date)
+///               on month_changed=|calendar| {
+///                   Message::Browse(calendar.get_year(), calendar.get_month())
+///               } />
+/// }
+/// # }}
+/// ```
+///
+/// [Calendar]: ../../gtk/struct.Calendar.html
+/// [CalendarDate]: ../types/struct.CalendarDate.html
+pub trait CalendarExtHelpers: CalendarExt {
+    fn get_year(&self) -> i32 {
+        self.get_property_year()
     }
 
-    fn set_child_center_widget(&self, _child: &Object, _center: bool) {
-        // This is handled by add_child() rules. The setter is a no-op.
+    fn set_year(&self, year: i32) {
+        self.set_property_year(year);
+    }
+
+    fn get_month(&self) -> u32 {
+        self.get_property_month() as u32 + 1
+    }
+
+    fn set_month(&self, month: u32) {
+        self.set_property_month(month.saturating_sub(1) as i32);
+    }
+
+    fn get_day(&self) -> u32 {
+        self.get_property_day() as u32
+    }
+
+    fn set_day(&self, day: u32) {
+        self.set_property_day(day as i32);
+    }
+
+    fn get_selected_date(&self) -> CalendarDate {
+        let (year, month, day) = self.get_date();
+        CalendarDate::new(year as i32, month + 1, day)
+    }
+
+    fn set_selected_date(&self, date: CalendarDate) {
+        self.select_month(date.month.saturating_sub(1), date.year as u32);
+        self.select_day(date.day);
+    }
+
+    fn get_marked_days(&self) -> Vec<u32> {
+        (1..=31)
+            .filter(|day| self.get_day_is_marked(*day))
+            .collect()
+    }
+
+    fn set_marked_days(&self, days: &[u32]) {
+        self.clear_marks();
+        for day in days {
+            self.mark_day(*day);
+        }
+    }
+
+    fn connect_date_selected<F: Fn(&Self, CalendarDate) + 'static>(&self, f: F) -> SignalHandlerId {
+        self.connect_day_selected(move |calendar| f(calendar, calendar.get_selected_date()))
     }
 }
 
-impl<A> BoxExtHelpers for A where A: BoxExt {}
+impl<A> CalendarExtHelpers for A where A: CalendarExt {}
 
-/// Helper trait for [`Image`][Image].
+/// Helper trait for [`ColorButton`][ColorButton].
 ///
-/// [Image]: ../../gtk/struct.Image.html
-pub trait ImageExtHelpers: ImageExt {
-    fn set_pixbuf(&self, pixbuf: Option<Pixbuf>) {
-        self.set_from_pixbuf(pixbuf.as_ref());
+/// The `rgba` property sets the color the button shows, and `on color_chosen`
+/// is given the color the user picked whenever they pick one, so you can keep
+/// it in your component's state. To ask for a color from your update function
+/// instead, use a [`ColorChooser`][ColorChooser].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # use vgtk::lib::gdk::RGBA;
+/// # #[derive(Clone, Debug)] enum Message { Highlight(RGBA) }
+/// # struct Comp { highlight: RGBA }
+/// # impl Default for Comp { fn default() -> Self { Comp { highlight: RGBA::black() } } }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <ColorButton rgba=self.highlight use_alpha=false
+///                  on color_chosen=|_, color| Message::Highlight(color) />
+/// }
+/// # }}
+/// ```
+///
+/// [ColorButton]: ../../gtk/struct.ColorButton.html
+/// [ColorChooser]: ../struct.ColorChooser.html
+pub trait ColorButtonExtHelpers: ColorButtonExt + ColorChooserExt {
+    fn connect_color_chosen<F: Fn(&Self, RGBA) + 'static>(&self, f: F) -> SignalHandlerId {
+        self.connect_color_set(move |button| f(button, button.get_rgba()))
     }
 }
 
-impl<A> ImageExtHelpers for A where A: ImageExt {}
+impl<A> ColorButtonExtHelpers for A where A: ColorButtonExt + ColorChooserExt {}
 
-/// Helper trait for [`Label`][Label].
+/// Helper trait for [`FontButton`][FontButton].
 ///
-/// [Label]: ../../gtk/struct.Label.html
-pub trait LabelExtHelpers: LabelExt {
-    fn get_markup(&self) -> Option<GString> {
-        self.get_label()
+/// The `font` property sets the font the button shows from a font name like
+/// `"Sans Bold 12"`, and `on font_chosen` is given the name of the font the
+/// user picked whenever they pick one, so you can keep it in your component's
+/// state. Pango's [`FontDescription`][FontDescription] isn't `Send`, so it
+/// can't go in a message, but you can turn a name into one with
+/// `FontDescription::from_string()`, and give that to the `font_desc`
+/// property instead.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ext::*, gtk, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Font(String) }
+/// # #[derive(Default)] struct Comp { font: String }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <FontButton font=self.font.clone() on font_chosen=|_, font| Message::Font(font) />
+/// }
+/// # }}
+/// ```
+///
+/// [FontButton]: ../../gtk/struct.FontButton.html
+/// [FontDescription]: ../../pango/struct.FontDescription.html
+pub trait FontButtonExtHelpers: FontButtonExt + FontChooserExt {
+    fn connect_font_chosen<F: Fn(&Self, String) + 'static>(&self, f: F) -> SignalHandlerId {
+        self.connect_font_set(move |button| {
+            if let Some(font) = button.get_font() {
+                f(button, font.to_string());
+            }
+        })
     }
 }
 
-impl<A> LabelExtHelpers for A where A: LabelExt {}
+impl<A> FontButtonExtHelpers for A where A: FontButtonExt + FontChooserExt {}
+
+/// Helper trait for [`DrawingArea`][DrawingArea].
+///
+/// The `painter` property takes a [`Painter`][Painter], which draws the
+/// area's contents from a snapshot of your component's state.
+///
+/// [DrawingArea]: ../../gtk/struct.DrawingArea.html
+/// [Painter]: ../struct.Painter.html
+pub trait DrawingAreaExtHelpers: IsA<DrawingArea> {
+    fn get_painter(&self) -> Option<Painter> {
+        Painter::attached(self.as_ref())
+    }
+
+    fn set_painter(&self, painter: Painter) {
+        painter.attach(self.as_ref());
+    }
+}
+
+impl<A> DrawingAreaExtHelpers for A where A: IsA<DrawingArea> {}
 
 /// Helper trait for [`Grid`][Grid] layout.
 ///
@@ -237,7 +1497,6 @@ impl<A> LabelExtHelpers for A where A: LabelExt {}
 /// ```
 ///
 /// ```rust,no_run
-/// # #![recursion_limit="256"]
 /// # use vgtk::{gtk, VNode};
 /// # use vgtk::ext::*;
 /// # use vgtk::lib::gtk::*;
@@ -283,8 +1542,15 @@ impl<A> LabelExtHelpers for A where A: LabelExt {}
 /// # }
 /// ```
 ///
+/// Each child is placed with the `Grid::left`, `Grid::top`, `Grid::width` and
+/// `Grid::height` child properties, or with all four at once using
+/// `Grid::position`, which takes a [`GridPosition`][GridPosition]. Children
+/// without them are put in the top left cell. `Grid::left_attach` and
+/// `Grid::top_attach` are accepted too, matching the names of GTK's own child
+/// properties.
 ///
 /// [Grid]: ../../gtk/struct.Grid.html
+/// [GridPosition]: ../types/struct.GridPosition.html
 pub trait GridExtHelpers: GridExt {
     fn set_child_position<P: IsA<Widget>>(&self, child: &P, position: GridPosition);
     fn get_child_position<P: IsA<Widget>>(&self, child: &P) -> GridPosition;
@@ -295,6 +1561,12 @@ pub trait GridExtHelpers: GridExt {
     fn set_child_top<P: IsA<Widget>>(&self, child: &P, top: i32);
     fn get_child_top<P: IsA<Widget>>(&self, child: &P) -> i32;
 
+    fn set_child_left_attach<P: IsA<Widget>>(&self, child: &P, left: i32);
+    fn get_child_left_attach<P: IsA<Widget>>(&self, child: &P) -> i32;
+
+    fn set_child_top_attach<P: IsA<Widget>>(&self, child: &P, top: i32);
+    fn get_child_top_attach<P: IsA<Widget>>(&self, child: &P) -> i32;
+
     fn set_child_width<P: IsA<Widget>>(&self, child: &P, width: i32);
     fn get_child_width<P: IsA<Widget>>(&self, child: &P) -> i32;
 
@@ -338,6 +1610,22 @@ where
         self.get_cell_top_attach(child)
     }
 
+    fn set_child_left_attach<P: IsA<Widget>>(&self, child: &P, left: i32) {
+        self.set_cell_left_attach(child, left);
+    }
+
+    fn get_child_left_attach<P: IsA<Widget>>(&self, child: &P) -> i32 {
+        self.get_cell_left_attach(child)
+    }
+
+    fn set_child_top_attach<P: IsA<Widget>>(&self, child: &P, top: i32) {
+        self.set_cell_top_attach(child, top);
+    }
+
+    fn get_child_top_attach<P: IsA<Widget>>(&self, child: &P) -> i32 {
+        self.get_cell_top_attach(child)
+    }
+
     fn set_child_width<P: IsA<Widget>>(&self, child: &P, width: i32) {
         self.set_cell_width(child, width);
     }
@@ -354,3 +1642,163 @@ where
         self.get_cell_height(child)
     }
 }
+
+fn cell_renderer<P: IsA<Object>>(child: &P) -> &CellRenderer {
+    child.as_ref().downcast_ref().unwrap_or_else(|| {
+        panic!(
+            "children of a CellLayout must be CellRenderers, but {} was found.",
+            child.as_ref().get_type()
+        )
+    })
+}
+
+fn get_attribute_column<C: IsA<CellLayout>, P: IsA<Object>>(
+    layout: &C,
+    child: &P,
+    attribute: &str,
+) -> i32 {
+    match layout.get_area() {
+        Some(area) => area.attribute_get_column(cell_renderer(child), attribute),
+        None => -1,
+    }
+}
+
+fn set_attribute_column<C: IsA<CellLayout>, P: IsA<Object>>(
+    layout: &C,
+    child: &P,
+    attribute: &str,
+    model_column: i32,
+) {
+    let area = layout.get_area().expect("CellLayout has no CellArea");
+    let renderer = cell_renderer(child);
+    if area.attribute_get_column(renderer, attribute) >= 0 {
+        area.attribute_disconnect(renderer, attribute);
+    }
+    area.attribute_connect(renderer, attribute, model_column);
+}
+
+/// Helper trait for [`CellLayout`][CellLayout]s, such as
+/// [`TreeViewColumn`][TreeViewColumn] and [`ComboBox`][ComboBox].
+///
+/// Cell renderers inside a cell layout take their values from the columns of
+/// a model. Map a renderer's property to a model column with a child
+/// attribute of the same name, such as `TreeViewColumn::text=0` to show the
+/// first column as text.
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// # fn view() -> VNode<()> {
+/// gtk! {
+///     <TreeViewColumn title="File">
+///         <CellRendererPixbuf TreeViewColumn::icon_name=0 />
+///         <CellRendererText TreeViewColumn::text=1 />
+///     </TreeViewColumn>
+/// }
+/// # }
+/// ```
+///
+/// [CellLayout]: ../../gtk/struct.CellLayout.html
+/// [TreeViewColumn]: ../../gtk/struct.TreeViewColumn.html
+/// [ComboBox]: ../../gtk/struct.ComboBox.html
+pub trait CellLayoutExtHelpers: IsA<CellLayout> {
+    fn get_child_text<P: IsA<Object>>(&self, child: &P) -> i32 {
+        get_attribute_column(self, child, "text")
+    }
+
+    fn set_child_text<P: IsA<Object>>(&self, child: &P, column: i32) {
+        set_attribute_column(self, child, "text", column);
+    }
+
+    fn get_child_markup<P: IsA<Object>>(&self, child: &P) -> i32 {
+        get_attribute_column(self, child, "markup")
+    }
+
+    fn set_child_markup<P: IsA<Object>>(&self, child: &P, column: i32) {
+        set_attribute_column(self, child, "markup", column);
+    }
+
+    fn get_child_active<P: IsA<Object>>(&self, child: &P) -> i32 {
+        get_attribute_column(self, child, "active")
+    }
+
+    fn set_child_active<P: IsA<Object>>(&self, child: &P, column: i32) {
+        set_attribute_column(self, child, "active", column);
+    }
+
+    fn get_child_icon_name<P: IsA<Object>>(&self, child: &P) -> i32 {
+        get_attribute_column(self, child, "icon-name")
+    }
+
+    fn set_child_icon_name<P: IsA<Object>>(&self, child: &P, column: i32) {
+        set_attribute_column(self, child, "icon-name", column);
+    }
+
+    fn get_child_value<P: IsA<Object>>(&self, child: &P) -> i32 {
+        get_attribute_column(self, child, "value")
+    }
+
+    fn set_child_value<P: IsA<Object>>(&self, child: &P, column: i32) {
+        set_attribute_column(self, child, "value", column);
+    }
+}
+
+impl<A> CellLayoutExtHelpers for A where A: IsA<CellLayout> {}
+
+/// Helper trait for [`ComboBoxText`][ComboBoxText].
+///
+/// The `items` property replaces the combo box's items with a list of
+/// strings, each of which is also used as its item's ID, so the selection
+/// can be read and set through the `active_id` property. Set `items` before
+/// `active` or `active_id`, so there's something to select.
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Pick(String) }
+/// # #[derive(Default)] struct Comp { colour: String }
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Comp> {
+/// gtk! {
+///     <ComboBoxText items=vec!["Red", "Green", "Blue"]
+///                   bind:active_id=self.colour.clone() => Message::Pick />
+/// }
+/// # }}
+/// ```
+///
+/// [ComboBoxText]: ../../gtk/struct.ComboBoxText.html
+pub trait ComboBoxTextExtHelpers: IsA<ComboBoxText> {
+    fn get_items(&self) -> Vec<String> {
+        let mut items = Vec::new();
+        if let Some(model) = self.as_ref().get_model() {
+            if let Some(iter) = model.get_iter_first() {
+                loop {
+                    if let Ok(Some(item)) = model.get_value(&iter, 0).get::<String>() {
+                        items.push(item);
+                    }
+                    if !model.iter_next(&iter) {
+                        break;
+                    }
+                }
+            }
+        }
+        items
+    }
+
+    fn set_items<S: AsRef<str>>(&self, items: Vec<S>) {
+        let combo = self.as_ref();
+        // Hold on to the selection, in case it's still among the new items.
+        let active = combo.get_active_id();
+        combo.remove_all();
+        for item in &items {
+            combo.append(Some(item.as_ref()), item.as_ref());
+        }
+        if let Some(active) = active {
+            combo.set_active_id(Some(&active));
+        }
+    }
+}
+
+impl<A> ComboBoxTextExtHelpers for A where A: IsA<ComboBoxText> {}
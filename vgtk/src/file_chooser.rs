@@ -0,0 +1,159 @@
+use gtk::{
+    FileChooserAction, FileChooserExt, FileChooserNative, FileChooserNativeExt, FileFilter,
+    NativeDialogExt, ResponseType, Window,
+};
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use crate::component::current_window;
+use crate::on_signal;
+
+/// Builds and runs a native file chooser for you.
+///
+/// This uses a [`FileChooserNative`][FileChooserNative], so it will show the
+/// platform's own file chooser, or go through the desktop portal when running
+/// in a sandbox.
+///
+/// [`run()`][run] returns a [`Future`][Future] which resolves to the paths the
+/// user picked, or an empty list if they cancelled, so you can use it from an
+/// [`UpdateAction::defer()`][defer] block and turn the result into a message.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::path::PathBuf;
+/// # use vgtk::{FileChooser, UpdateAction};
+/// # #[derive(Clone, Debug)] enum Message { Open, Opened(Vec<PathBuf>) }
+/// # #[derive(Default)] struct Comp;
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> vgtk::VNode<Comp> { unimplemented!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::Open => {
+///             let paths = FileChooser::open()
+///                 .title("Open Images")
+///                 .filter("Images", &["*.png", "*.jpg"])
+///                 .select_multiple(true)
+///                 .run();
+///             async move { Message::Opened(paths.await) }.into()
+///         }
+///         Message::Opened(paths) => UpdateAction::Render,
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [FileChooserNative]: ../gtk/struct.FileChooserNative.html
+/// [run]: #method.run
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [defer]: enum.UpdateAction.html#method.defer
+pub struct FileChooser {
+    dialog: FileChooserNative,
+}
+
+impl FileChooser {
+    /// Construct a file chooser for the given [`FileChooserAction`][FileChooserAction].
+    ///
+    /// [FileChooserAction]: ../gtk/enum.FileChooserAction.html
+    pub fn new(action: FileChooserAction) -> Self {
+        FileChooser {
+            dialog: FileChooserNative::new(None, None::<&Window>, action, None, None),
+        }
+    }
+
+    /// Construct a file chooser for opening existing files.
+    pub fn open() -> Self {
+        Self::new(FileChooserAction::Open)
+    }
+
+    /// Construct a file chooser for saving a file, which will ask the user
+    /// for confirmation before choosing an existing file.
+    pub fn save() -> Self {
+        let chooser = Self::new(FileChooserAction::Save);
+        chooser.dialog.set_do_overwrite_confirmation(true);
+        chooser
+    }
+
+    /// Construct a file chooser for picking a folder.
+    pub fn select_folder() -> Self {
+        Self::new(FileChooserAction::SelectFolder)
+    }
+
+    /// Set the title of the file chooser.
+    pub fn title(self, title: &str) -> Self {
+        self.dialog.set_title(title);
+        self
+    }
+
+    /// Set the label of the accept button.
+    pub fn accept_label(self, label: &str) -> Self {
+        self.dialog.set_accept_label(Some(label));
+        self
+    }
+
+    /// Set the window the file chooser belongs to.
+    ///
+    /// If you don't set one, the [current window][current_window] will be used.
+    ///
+    /// [current_window]: fn.current_window.html
+    pub fn parent(self, parent: &Window) -> Self {
+        self.dialog.set_transient_for(Some(parent));
+        self
+    }
+
+    /// Allow the user to pick more than one file.
+    pub fn select_multiple(self, select_multiple: bool) -> Self {
+        self.dialog.set_select_multiple(select_multiple);
+        self
+    }
+
+    /// Add a named filter matching the given glob patterns, such as `"*.txt"`.
+    pub fn filter(self, name: &str, patterns: &[&str]) -> Self {
+        let filter = FileFilter::new();
+        filter.set_name(Some(name));
+        for pattern in patterns {
+            filter.add_pattern(pattern);
+        }
+        self.dialog.add_filter(&filter);
+        self
+    }
+
+    /// Set the folder the file chooser starts out in.
+    pub fn current_folder(self, folder: impl AsRef<Path>) -> Self {
+        self.dialog.set_current_folder(folder);
+        self
+    }
+
+    /// Set the suggested file name when saving.
+    pub fn current_name(self, name: &str) -> Self {
+        self.dialog.set_current_name(name);
+        self
+    }
+
+    /// Show the file chooser.
+    ///
+    /// The returned [`Future`][Future] resolves to the paths the user picked,
+    /// or to an empty list if they cancelled.
+    ///
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub fn run(self) -> impl Future<Output = Vec<PathBuf>> {
+        let dialog = self.dialog;
+        if dialog.get_transient_for().is_none() {
+            if let Some(parent) = current_window() {
+                dialog.set_transient_for(Some(&parent));
+            }
+        }
+        dialog.set_modal(true);
+        dialog.show();
+        async move {
+            let response = on_signal!(dialog, connect_response).await;
+            let paths = match response {
+                Ok(ResponseType::Accept) => dialog.get_filenames(),
+                _ => Vec::new(),
+            };
+            dialog.destroy();
+            paths
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use colored::Colorize;
+use gdk::WindowState;
+use glib::{KeyFile, KeyFileFlags, ObjectExt, WeakRef};
+use gtk::{GtkWindowExt, Inhibit, WidgetExt, Window};
+use log::{debug, error};
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+thread_local! {
+    static REMEMBERED: RefCell<Vec<(WeakRef<Window>, Rc<RefCell<Geometry>>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Debug)]
+struct Geometry {
+    name: String,
+    size: Option<(i32, i32)>,
+    position: Option<(i32, i32)>,
+    maximized: bool,
+}
+
+// The saved geometry of every window goes in one key file, in a group named
+// after the window, next to the state saved by `vgtk::run_persistent()`.
+fn geometry_path() -> PathBuf {
+    let program = glib::get_prgname().unwrap_or_else(|| "vgtk".to_string());
+    glib::get_user_data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(program)
+        .join("windows.ini")
+}
+
+fn load() -> KeyFile {
+    let file = KeyFile::new();
+    // A missing or broken file is as good as an empty one.
+    let _ = file.load_from_file(geometry_path(), KeyFileFlags::KEEP_COMMENTS);
+    file
+}
+
+impl Geometry {
+    fn restore(name: &str) -> Self {
+        let file = load();
+        let pair = |first, second| match (
+            file.get_integer(name, first),
+            file.get_integer(name, second),
+        ) {
+            (Ok(first), Ok(second)) => Some((first, second)),
+            _ => None,
+        };
+        Geometry {
+            name: name.to_string(),
+            size: pair("width", "height"),
+            position: pair("x", "y"),
+            maximized: file.get_boolean(name, "maximized").unwrap_or(false),
+        }
+    }
+
+    fn apply(&self, window: &Window) {
+        if let Some((width, height)) = self.size {
+            window.resize(width, height);
+        }
+        if let Some((x, y)) = self.position {
+            window.move_(x, y);
+        }
+        if self.maximized {
+            window.maximize();
+        }
+    }
+
+    fn save(&self) {
+        let path = geometry_path();
+        let file = load();
+        if let Some((width, height)) = self.size {
+            file.set_integer(&self.name, "width", width);
+            file.set_integer(&self.name, "height", height);
+        }
+        if let Some((x, y)) = self.position {
+            file.set_integer(&self.name, "x", x);
+            file.set_integer(&self.name, "y", y);
+        }
+        file.set_boolean(&self.name, "maximized", self.maximized);
+        let result = match path.parent() {
+            Some(dir) => std::fs::create_dir_all(dir).map_err(|err| err.to_string()),
+            None => Ok(()),
+        }
+        .and_then(|()| file.save_to_file(&path).map_err(|err| err.to_string()));
+        match result {
+            Ok(()) => debug!(
+                "{} {} {}",
+                "Saved geometry of".bright_blue(),
+                self.name.magenta().bold(),
+                path.display()
+            ),
+            Err(err) => error!(
+                "unable to save window geometry to {}: {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+}
+
+fn remembered(window: &Window) -> Option<Rc<RefCell<Geometry>>> {
+    REMEMBERED.with(|remembered| {
+        let mut remembered = remembered.borrow_mut();
+        remembered.retain(|(window, _)| window.upgrade().is_some());
+        remembered
+            .iter()
+            .find(|(remembering, _)| remembering.upgrade().as_ref() == Some(window))
+            .map(|(_, geometry)| geometry.clone())
+    })
+}
+
+/// Get the name `window` remembers its geometry under, or an empty string
+/// if it doesn't.
+pub(crate) fn name(window: &Window) -> String {
+    remembered(window)
+        .map(|geometry| geometry.borrow().name.clone())
+        .unwrap_or_default()
+}
+
+/// Have `window` remember its geometry under `name`, restoring whatever was
+/// saved under it last time.
+pub(crate) fn remember(window: &Window, name: &str) {
+    if let Some(geometry) = remembered(window) {
+        // The window's already keeping track of its geometry, which it'll
+        // save under the new name from now on.
+        geometry.borrow_mut().name = name.to_string();
+        return;
+    }
+    let geometry = Geometry::restore(name);
+    geometry.apply(window);
+    let geometry = Rc::new(RefCell::new(geometry));
+    REMEMBERED.with(|remembered| {
+        remembered
+            .borrow_mut()
+            .push((window.downgrade(), geometry.clone()))
+    });
+    // A maximized window's size and position aren't the ones it goes back to
+    // when it's unmaximized, which are the ones we want to keep.
+    let configured = geometry.clone();
+    window.connect_configure_event(move |window, _| {
+        let mut geometry = configured.borrow_mut();
+        if !geometry.maximized {
+            geometry.size = Some(window.get_size());
+            geometry.position = Some(window.get_position());
+        }
+        false
+    });
+    let state_changed = geometry.clone();
+    window.connect_window_state_event(move |_, event| {
+        state_changed.borrow_mut().maximized = event
+            .get_new_window_state()
+            .contains(WindowState::MAXIMIZED);
+        Inhibit(false)
+    });
+    // Windows are unmapped when they're closed, and when they're destroyed
+    // on the way out.
+    window.connect_unmap(move |_| geometry.borrow().save());
+}
@@ -0,0 +1,60 @@
+use glib::{Cast, ObjectExt, WeakRef};
+use gtk::{
+    Box as GtkBox, BoxExt, Container, ContainerExt, InfoBar, InfoBarExt, ResponseType, Widget,
+    WidgetExt,
+};
+
+use std::cell::RefCell;
+
+// GTK doesn't tell us which response an action widget was added with, so we
+// keep track of it ourselves.
+thread_local! {
+    static RESPONSES: RefCell<Vec<(WeakRef<Widget>, ResponseType)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Get the response `widget` was added to an info bar's action area with, if
+/// it's in one.
+pub(crate) fn response(widget: &Widget) -> Option<ResponseType> {
+    RESPONSES.with(|responses| {
+        let mut responses = responses.borrow_mut();
+        responses.retain(|(widget, _)| widget.upgrade().is_some());
+        responses
+            .iter()
+            .find(|(responding, _)| responding.upgrade().as_ref() == Some(widget))
+            .map(|(_, response)| *response)
+    })
+}
+
+/// Move `widget` into the action area of `bar`, where activating it makes the
+/// info bar emit `response`.
+///
+/// A widget which is already in the action area keeps its place in it.
+pub(crate) fn set_response(bar: &InfoBar, widget: &Widget, response: ResponseType) {
+    let action_area = bar
+        .get_action_area()
+        .and_then(|area| area.downcast::<GtkBox>().ok());
+    let position = action_area
+        .as_ref()
+        .and_then(|area| area.get_children().iter().position(|child| child == widget));
+    if let Some(parent) = widget
+        .get_parent()
+        .and_then(|parent| parent.downcast::<Container>().ok())
+    {
+        parent.remove(widget);
+    }
+    bar.add_action_widget(widget, response);
+    if let (Some(area), Some(position)) = (action_area, position) {
+        area.reorder_child(widget, position as i32);
+    }
+    RESPONSES.with(|responses| {
+        let mut responses = responses.borrow_mut();
+        responses.retain(|(responding, _)| {
+            responding
+                .upgrade()
+                .map(|responding| &responding != widget)
+                .unwrap_or(false)
+        });
+        responses.push((widget.downgrade(), response));
+    });
+}
@@ -0,0 +1,365 @@
+//! A window showing your application's live component tree.
+//!
+//! [`open()`][open] opens a window listing every component your application
+//! has mounted, each nested under the component whose view built it.
+//! Selecting a component shows its current properties, the last few things
+//! that happened to it, like the messages it received and the renders they
+//! caused, and an outline of the view it rendered last. This is the place to
+//! start when you're trying to work out why a subtree keeps rendering, or why
+//! it doesn't.
+//!
+//! Properties are only shown for subcomponents whose properties implement
+//! `Debug`.
+//!
+//! This is only available with the `debug` feature enabled.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, Component, UpdateAction, VNode};
+//! # use vgtk::lib::gtk::*;
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Inspect,
+//! }
+//!
+//! #[derive(Default)]
+//! struct App;
+//!
+//! impl Component for App {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         match message {
+//!             Message::Inspect => vgtk::inspector::open(),
+//!         }
+//!         UpdateAction::None
+//!     }
+//!
+//!     fn view(&self) -> VNode<Self> {
+//!         gtk! {
+//!             <Window>
+//!                 <Button label="Inspect" on clicked=|_| Message::Inspect />
+//!             </Window>
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [open]: fn.open.html
+
+use gtk::prelude::*;
+use gtk::{Label, ListBox, ListBoxRow, Orientation, Paned, ScrolledWindow, Window};
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+
+use crate::component::{Component, UpdateAction};
+use crate::debugger::{marker, open_window, Debugger};
+use crate::ext::WindowExtHelpers;
+use crate::gtk;
+use crate::scope::Scope;
+use crate::vnode::describe::outline;
+use crate::vnode::VNode;
+
+/// The number of events to remember for each component.
+const EVENTS: usize = 20;
+
+struct Entry {
+    name: &'static str,
+    parent: Option<usize>,
+    props: Option<Rc<str>>,
+    events: VecDeque<String>,
+    renders: usize,
+    view: String,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: usize,
+    components: BTreeMap<usize, Entry>,
+    building: Vec<usize>,
+    window: Option<(Window, Scope<Inspector>)>,
+    notified: bool,
+}
+
+impl Registry {
+    /// Tell the inspector window something has changed, unless it's already
+    /// been told and hasn't caught up yet.
+    fn changed(&mut self) {
+        if let Some((_, scope)) = &self.window {
+            if !self.notified {
+                self.notified = true;
+                scope.send(InspectorMessage::Changed);
+            }
+        }
+    }
+
+    /// List the components depth first, with their depth in the tree.
+    fn tree(&self) -> Vec<(usize, usize)> {
+        let mut children: BTreeMap<Option<usize>, Vec<usize>> = BTreeMap::new();
+        for (id, entry) in &self.components {
+            // A component whose parent has gone away is shown as a root.
+            let parent = entry
+                .parent
+                .filter(|parent| self.components.contains_key(parent));
+            children.entry(parent).or_default().push(*id);
+        }
+        let mut tree = Vec::new();
+        let mut stack: Vec<(usize, usize)> = children
+            .get(&None)
+            .map(|roots| roots.iter().rev().map(|id| (0, *id)).collect())
+            .unwrap_or_default();
+        while let Some((depth, id)) = stack.pop() {
+            tree.push((depth, id));
+            if let Some(ids) = children.get(&Some(id)) {
+                stack.extend(ids.iter().rev().map(|id| (depth + 1, *id)));
+            }
+        }
+        tree
+    }
+
+    fn details(&self, id: usize) -> Option<String> {
+        let entry = self.components.get(&id)?;
+        let mut out = format!("{}\nRendered {} times\n\n", entry.name, entry.renders);
+        out.push_str("Properties:\n");
+        match (&entry.props, entry.parent) {
+            (Some(props), _) => out.push_str(props),
+            (None, None) => out.push_str("(top level component)"),
+            (None, Some(_)) => out.push_str("(the properties don't implement Debug)"),
+        }
+        out.push_str("\n\nRecent events:\n");
+        for event in &entry.events {
+            out.push_str(event);
+            out.push('\n');
+        }
+        out.push_str("\nLast rendered view:\n");
+        out.push_str(&entry.view);
+        Some(out)
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+fn with_entry(id: usize, f: impl FnOnce(&mut Entry)) {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if let Some(entry) = registry.components.get_mut(&id) {
+            f(entry);
+            registry.changed();
+        }
+    })
+}
+
+/// A component's place in the inspector's component tree, which it leaves
+/// when this is dropped.
+pub(crate) struct Inspected {
+    id: Option<usize>,
+}
+
+impl Inspected {
+    /// Add a component to the tree, under the component which is currently
+    /// building its view, if any.
+    ///
+    /// The debugging tools' own windows are left out.
+    pub(crate) fn register<C: 'static + Component>() -> Self {
+        let type_id = TypeId::of::<C>();
+        if type_id == TypeId::of::<Inspector>() || type_id == TypeId::of::<Debugger>() {
+            return Inspected { id: None };
+        }
+        let name = std::any::type_name::<C>();
+        REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            let id = registry.next_id;
+            registry.next_id += 1;
+            let parent = registry.building.last().copied();
+            registry.components.insert(
+                id,
+                Entry {
+                    name,
+                    parent,
+                    props: None,
+                    events: VecDeque::new(),
+                    renders: 0,
+                    view: String::new(),
+                },
+            );
+            registry.changed();
+            Inspected { id: Some(id) }
+        })
+    }
+
+    pub(crate) fn id(&self) -> Option<usize> {
+        self.id
+    }
+
+    /// Make the component the parent of any subcomponents built until the
+    /// returned guard is dropped.
+    pub(crate) fn building(&self) -> Building {
+        if let Some(id) = self.id {
+            REGISTRY.with(|registry| registry.borrow_mut().building.push(id));
+        }
+        Building { id: self.id }
+    }
+
+    /// Remember something that happened to the component.
+    pub(crate) fn event(&self, event: String) {
+        if let Some(id) = self.id {
+            with_entry(id, |entry| {
+                if entry.events.len() == EVENTS {
+                    entry.events.pop_front();
+                }
+                entry.events.push_back(event);
+            });
+        }
+    }
+
+    /// Remember the view the component has just rendered.
+    pub(crate) fn rendered<C: 'static + Component>(&self, view: &VNode<C>) {
+        if let Some(id) = self.id {
+            let outline = outline(view, false);
+            self.event("rendered".to_string());
+            with_entry(id, |entry| {
+                entry.renders += 1;
+                entry.view = outline;
+            });
+        }
+    }
+}
+
+impl Drop for Inspected {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            REGISTRY.with(|registry| {
+                let mut registry = registry.borrow_mut();
+                registry.components.remove(&id);
+                registry.changed();
+            });
+        }
+    }
+}
+
+/// A guard which stops a component from being the parent of new
+/// subcomponents when it's dropped.
+pub(crate) struct Building {
+    id: Option<usize>,
+}
+
+impl Drop for Building {
+    fn drop(&mut self) {
+        if self.id.is_some() {
+            REGISTRY.with(|registry| registry.borrow_mut().building.pop());
+        }
+    }
+}
+
+/// Remember the properties a subcomponent was last given.
+pub(crate) fn set_props(id: Option<usize>, props: Option<Rc<str>>) {
+    if let Some(id) = id {
+        with_entry(id, |entry| entry.props = props);
+    }
+}
+
+/// Open the inspector window, or bring it to the front if it's already open.
+pub fn open() {
+    let open = REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .window
+            .as_ref()
+            .map(|(window, _)| window.clone())
+    });
+    if let Some(window) = open {
+        window.present();
+        return;
+    }
+    let (window, scope) = open_window::<Inspector>(());
+    window.connect_destroy(|_| {
+        REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            registry.window = None;
+            registry.notified = false;
+        })
+    });
+    REGISTRY.with(|registry| registry.borrow_mut().window = Some((window, scope)));
+}
+
+/// A window listing the live components, with the details of the selected
+/// one.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Inspector {
+    selected: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum InspectorMessage {
+    Changed,
+    Select(usize),
+}
+
+fn components(tree: &[(usize, String)], selected: Option<usize>) -> VNode<Inspector> {
+    gtk! {
+        <ScrolledWindow property_width_request=280>
+            <ListBox on row_activated=|_, row| InspectorMessage::Select(row.get_index() as usize)>
+                {
+                    for (id, label) in tree.iter() {
+                        <ListBoxRow>
+                            <Label label=format!("{} {}", marker(Some(*id) == selected), label)
+                                   xalign=0.0 />
+                        </ListBoxRow>
+                    }
+                }
+            </ListBox>
+        </ScrolledWindow>
+    }
+}
+
+impl Component for Inspector {
+    type Message = InspectorMessage;
+    type Properties = ();
+
+    fn update(&mut self, message: InspectorMessage) -> UpdateAction<Self> {
+        REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            match message {
+                InspectorMessage::Changed => registry.notified = false,
+                InspectorMessage::Select(row) => {
+                    self.selected = registry.tree().get(row).map(|(_, id)| *id)
+                }
+            }
+        });
+        UpdateAction::Render
+    }
+
+    fn view(&self) -> VNode<Self> {
+        let (tree, details) = REGISTRY.with(|registry| {
+            let registry = registry.borrow();
+            let tree: Vec<(usize, String)> = registry
+                .tree()
+                .into_iter()
+                .map(|(depth, id)| {
+                    let name = registry.components[&id].name;
+                    (id, format!("{}{}", "    ".repeat(depth), name))
+                })
+                .collect();
+            let details = self.selected.and_then(|id| registry.details(id));
+            (tree, details)
+        });
+        let details = details.unwrap_or_else(|| "Select a component to inspect it.".to_string());
+        gtk! {
+            <Window title="vgtk inspector" default_width=800 default_height=600>
+                <Paned orientation=Orientation::Horizontal>
+                    { components(&tree, self.selected) }
+                    <ScrolledWindow>
+                        <Label label=details selectable=true xalign=0.0 yalign=0.0 />
+                    </ScrolledWindow>
+                </Paned>
+            </Window>
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use gdk::{EventKey, ModifierType};
+
+/// A key, along with the modifiers which must be held down with it, for an
+/// `on key(...)` handler in the [`gtk!`][gtk!] macro.
+///
+/// A `KeyBinding` can be made from a key constant in
+/// [`gdk::enums::key`][key], which matches the key without any modifiers, or
+/// from an accelerator string like `"<Ctrl>s"`, as understood by
+/// [`gtk::accelerator_parse()`][accelerator_parse].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, VNode};
+/// # use vgtk::lib::gdk::enums::key;
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Cancel, Save }
+/// # #[derive(Default)] struct Editor;
+/// # impl Component for Editor { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Editor> {
+/// gtk! {
+///     <TextView on key(key::Escape)=|| Message::Cancel on key("<Ctrl>s")=|| Message::Save />
+/// }
+/// # }}
+/// ```
+///
+/// [gtk!]: macro.gtk.html
+/// [key]: ../gdk/enums/key/index.html
+/// [accelerator_parse]: ../gtk/fn.accelerator_parse.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    key: u32,
+    modifiers: ModifierType,
+}
+
+impl KeyBinding {
+    /// Make a binding for `key` pressed along with `modifiers`.
+    pub fn new(key: u32, modifiers: ModifierType) -> Self {
+        KeyBinding {
+            key: gdk::keyval_to_lower(key),
+            modifiers,
+        }
+    }
+
+    /// Test whether a key press event is for this binding.
+    ///
+    /// Modifiers which don't usually take part in shortcuts, like Caps Lock,
+    /// are ignored, and so is the case of letter keys.
+    pub fn matches(&self, event: &EventKey) -> bool {
+        let modifiers = event.get_state() & gtk::accelerator_get_default_mod_mask();
+        gdk::keyval_to_lower(event.get_keyval()) == self.key && modifiers == self.modifiers
+    }
+}
+
+impl From<u32> for KeyBinding {
+    fn from(key: u32) -> Self {
+        KeyBinding::new(key, ModifierType::empty())
+    }
+}
+
+impl From<&str> for KeyBinding {
+    /// Parse an accelerator string like `"<Ctrl>s"`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if GTK can't parse the accelerator.
+    fn from(accelerator: &str) -> Self {
+        match gtk::accelerator_parse(accelerator) {
+            (0, _) => panic!("invalid accelerator {:?}", accelerator),
+            (key, modifiers) => KeyBinding::new(key, modifiers),
+        }
+    }
+}
@@ -159,6 +159,61 @@
 //! This will cause a `Message::ButtonWasClicked` message to be sent to your component's
 //! [`update`][Component::update] function when the user clicks the button.
 //!
+//! Many signals carry more arguments than just the object emitting them, and your handler receives
+//! all of them, with the same types the `connect_*` method passes to its callback. For instance,
+//! [`ListBox`][ListBox]'s [`row_activated`][ListBox::connect_row_activated] signal passes the list
+//! box along with the [`ListBoxRow`][ListBoxRow] which was activated. The arguments can be bound
+//! with any simple pattern: a name, `_`, a `mut` binding or a tuple pattern.
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, Component};
+//! # use vgtk::lib::gtk::{ListBox, ListBoxExt, ListBoxRowExt};
+//! # #[derive(Clone, Debug)] enum Message { RowActivated(i32) }
+//! # #[derive(Default)] struct Comp;
+//! # impl Component for Comp { type Message = Message; type Properties = (); fn view(&self) -> VNode<Self> {
+//! gtk! {
+//!     <ListBox on row_activated=|_, row| Message::RowActivated(row.get_index()) />
+//! }
+//! # }}
+//! ```
+//!
+//! Some signals, such as [`Window`][Window]'s [`delete_event`][Widget::connect_delete_event] or any
+//! widget's `key_press_event`, expect their handlers to return an [`Inhibit`][Inhibit] value, which
+//! decides whether the event is stopped there or passed on to the default handler. For these, your
+//! handler returns a tuple of the [`Inhibit`][Inhibit] value and the message. Because GTK needs the
+//! answer straight away, these handlers can't be `async`. This lets a window veto its own closing, for
+//! instance:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, Component};
+//! # use vgtk::lib::gtk::{Inhibit, Window, WidgetExt};
+//! # #[derive(Clone, Debug)] enum Message { AskToClose }
+//! # #[derive(Default)] struct Comp;
+//! # impl Component for Comp { type Message = Message; type Properties = (); fn view(&self) -> VNode<Self> {
+//! gtk! {
+//!     <Window on delete_event=|_, _| (Inhibit(true), Message::AskToClose) />
+//! }
+//! # }}
+//! ```
+//!
+//! For keyboard shortcuts on a particular widget, `on key(...)` saves you from looking at the key
+//! press event yourself. It takes a key constant from [`gdk::enums::key`][gdk::enums::key] or an
+//! accelerator string like `"<Ctrl>s"`, and its handler, which can take the event as an argument or
+//! no arguments at all, is only called for that key. Key presses it handles aren't passed on:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, Component};
+//! # use vgtk::lib::gdk::enums::key;
+//! # use vgtk::lib::gtk::Entry;
+//! # #[derive(Clone, Debug)] enum Message { Cancel, Save }
+//! # #[derive(Default)] struct Comp;
+//! # impl Component for Comp { type Message = Message; type Properties = (); fn view(&self) -> VNode<Self> {
+//! gtk! {
+//!     <Entry on key(key::Escape)=|| Message::Cancel on key("<Ctrl>s")=|| Message::Save />
+//! }
+//! # }}
+//! ```
+//!
 //! Signal handlers can also be declared as `async`, which will cause the framework to wrap the handler
 //! in an `async {}` block and `await` the
 //! message result before passing it on to your update function. For instance, this very contrived
@@ -184,6 +239,51 @@
 //! # }}
 //! ```
 //!
+//! A handler doesn't have to send a message every time it's called. If it returns an
+//! [`Option`][Option] of a message instead, returning `None` will simply do nothing, so you don't need a
+//! `Message::Noop` variant just to ignore a signal. This works for `async` handlers, and for
+//! [`Callback`][Callback]s passed to subcomponents, too. Likewise, a handler can return a `Vec` of
+//! messages, or use the [`msgs!`][msgs] macro, to send several messages in order, and the component
+//! will still only render once. See [`IntoMessages`][IntoMessages] for the types a handler can return.
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, Component};
+//! # use vgtk::lib::gtk::{Entry, EntryExt};
+//! # #[derive(Clone, Debug)] enum Message { Submit(String) }
+//! # #[derive(Default)] struct Comp;
+//! # impl Component for Comp { type Message = Message; type Properties = (); fn view(&self) -> VNode<Self> {
+//! gtk! {
+//!     <Entry on activate=|entry| {
+//!         match entry.get_text() {
+//!             Some(text) if !text.is_empty() => Some(Message::Submit(text.to_string())),
+//!             _ => None,
+//!         }
+//!     } />
+//! }
+//! # }}
+//! ```
+//!
+//! Some signals fire far more often than you'd want to update your component, like an entry's
+//! `changed` signal while the user is typing. Giving a handler a `debounce` period only sends the
+//! message from the last time it was called, once the signal has been quiet for that long, while a
+//! `throttle` period sends the first message straight away and then at most one every period. The
+//! period is written like `300ms` or `2s`, or can be any expression which makes a
+//! [`Duration`][Duration]:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, Component};
+//! # use vgtk::lib::gtk::{EditableSignals, EntryExt, SearchEntry};
+//! # #[derive(Clone, Debug)] enum Message { Search(String) }
+//! # #[derive(Default)] struct Comp;
+//! # impl Component for Comp { type Message = Message; type Properties = (); fn view(&self) -> VNode<Self> {
+//! gtk! {
+//!     <SearchEntry on changed(debounce=300ms)=|entry| {
+//!         Message::Search(entry.get_text().map(|text| text.to_string()).unwrap_or_default())
+//!     } />
+//! }
+//! # }}
+//! ```
+//!
 //! ## The `gtk!` Syntax
 //!
 //! The syntax for the [`gtk!`][vgtk::gtk!] macro is similar to [JSX], but with a number of necessary
@@ -204,6 +304,11 @@
 //! # }
 //! ```
 //!
+//! Attribute values are converted to the type the setter expects where that's safe, so you can
+//! give a `u32` property like `border_width` an `i32` or a `usize`, a `f64` property like
+//! `fraction` an integer, and a string property a [`String`][String], a `&str` or a `GString`.
+//! Integers which turn out not to fit the property cause a panic when the view is rendered.
+//!
 //! A GTK container is represented by an open/close element tag, with child tags representing its
 //! children.
 //!
@@ -255,48 +360,742 @@
 //! # }
 //! ```
 //!
-//! The final addition to the attribute syntax pertains to when you need to qualify an
-//! ambiguous method name. For instance, a [`MenuButton`][MenuButton] implements both
-//! [`WidgetExt`][WidgetExt] and [`MenuButtonExt`][MenuButtonExt], both of which contains
-//! a `set_direction` method. In order to let the compiler know which one you mean, you
-//! can qualify it with an `@` and the type name, like this:
+//! The same goes for the pages of a [`Notebook`][Notebook], whose tab labels are set with
+//! `Notebook::tab_label`. Pages are inserted in the order they're declared, and if you give them
+//! keys, adding, removing or reordering pages won't rebuild the others or change the page the user
+//! is looking at:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Label, LabelExt, Notebook, NotebookExt};
+//! # fn view(show_advanced: bool) -> VNode<()> {
+//! gtk! {
+//!     <Notebook>
+//!         <Label key="general" label="General settings" Notebook::tab_label="General" />
+//!         { if show_advanced {
+//!             <Label key="advanced" label="Advanced settings" Notebook::tab_label="Advanced" />
+//!         } }
+//!         <Label key="about" label="About this app" Notebook::tab_label="About" />
+//!     </Notebook>
+//! }
+//! # }
+//! ```
+//!
+//! Likewise, the pages of a [`Stack`][Stack] are named with `Stack::name` and given a title for a
+//! [`StackSwitcher`][StackSwitcher] with `Stack::title`, and setting the stack's
+//! `visible_child_name` property from your component's state switches between them:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Label, LabelExt, Stack, StackExt};
+//! # fn view(page: &str) -> VNode<()> {
+//! gtk! {
+//!     <Stack visible_child_name=page>
+//!         <Label label="Welcome!" Stack::name="welcome" Stack::title="Welcome" />
+//!         <Label label="Settings go here." Stack::name="settings" Stack::title="Settings" />
+//!     </Stack>
+//! }
+//! # }
+//! ```
+//!
+//! The pages of an [`Assistant`][Assistant] are its children too, with `Assistant::title`,
+//! `Assistant::page_type` and `Assistant::complete` child properties. The assistant won't move
+//! on from a page until it's complete, so that's the one to set from your component's state as
+//! the user fills the page in. Its `apply`, `cancel`, `close` and `prepare` signals tell you what
+//! the user did:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Name(String), Apply, Cancel }
+//! # #[derive(Default)] struct Comp { name: String }
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <Assistant on apply=|_| Message::Apply on cancel=|_| Message::Cancel
+//!                on close=|_| Message::Cancel>
+//!         <Label label="Let's get you set up." Assistant::title="Welcome"
+//!                Assistant::page_type=AssistantPageType::Intro Assistant::complete=true />
+//!         <Entry bind:text=self.name.clone() => Message::Name Assistant::title="Your Name"
+//!                Assistant::complete=!self.name.is_empty() />
+//!         <Label label="All set!" Assistant::title="Done"
+//!                Assistant::page_type=AssistantPageType::Confirm Assistant::complete=true />
+//!     </Assistant>
+//! }
+//! # }}
+//! ```
+//!
+//! The final addition to the attribute syntax pertains to when you need to qualify an
+//! ambiguous method name. For instance, a [`MenuButton`][MenuButton] implements both
+//! [`WidgetExt`][WidgetExt] and [`MenuButtonExt`][MenuButtonExt], both of which contains
+//! a `set_direction` method. In order to let the compiler know which one you mean, you
+//! can qualify it with an `@` and the type name, like this:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{MenuButton, MenuButtonExt, WidgetExt, ArrowType, TextDirection};
+//! # fn view1() -> VNode<()> { gtk!{
+//! <MenuButton @MenuButtonExt::direction=ArrowType::Down />
+//! # }} fn view2() -> VNode<()> { gtk! {
+//! <MenuButton @WidgetExt::direction=TextDirection::Ltr />
+//! # }}
+//! ```
+//!
+//! An attribute which isn't a property of the widget is reported by the compiler as a missing
+//! setter method, pointing at the attribute, along with any similarly named methods it knows of.
+//!
+//! ### Bindings
+//!
+//! Input widgets often need to both reflect a piece of your component's state and report
+//! changes to it. Rather than setting a property and connecting a separate handler to its
+//! change signal, you can bind the property using the `bind:` prefix, followed by the
+//! property's value and, after a `=>`, a function turning the property's new value into a
+//! message:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, Component};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Rename(String), Toggle(bool) }
+//! # #[derive(Default)] struct Comp { name: String, enabled: bool }
+//! # impl Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <Box>
+//!         <Entry bind:text=self.name.clone() => Message::Rename />
+//!         <Switch bind:active=self.enabled => |active| Message::Toggle(active) />
+//!     </Box>
+//! }
+//! # }}
+//! ```
+//!
+//! The message is sent whenever the property's `notify` signal fires because of the user,
+//! but not when the property is updated by a render, so the widget and your component's
+//! state can't end up fighting each other. The bound value should be an owned value, and
+//! [`String`][String] properties will receive their new value as a [`String`][String].
+//!
+//! ### Optional Attributes
+//!
+//! An attribute can be given an [`Option`][Option] of the value its setter expects, to set the
+//! property only some of the time. `Some` sets the property as usual, while `None` leaves it
+//! alone when the widget is built, and puts it back to its default value if an earlier render
+//! had set it, so you don't need to write out the whole element twice in a conditional:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Label, LabelExt, WidgetExt};
+//! # fn view(status: Option<String>, width: Option<i32>) -> VNode<()> {
+//! gtk! {
+//!     <Label label=status.as_deref() width_chars=width tooltip_text=status.as_deref() />
+//! }
+//! # }
+//! ```
+//!
+//! A property whose setter takes an `Option` itself, like `tooltip_text`, is simply set to
+//! `None`, which has the same effect. Helper properties from [`vgtk::ext`][ext] which aren't
+//! GObject properties keep their last value when they become `None`.
+//!
+//! ### Spreading Properties
+//!
+//! When many elements share a set of attributes, like their margins or sizing, you can bundle
+//! them up with the [`props!`][props!] macro, which takes a single element without children, and
+//! spread the bundle over any element of the same type, or of a type descended from it, with
+//! `{..bundle}`:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, props, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # fn view() -> VNode<()> {
+//! let padded = props! { <Widget margin_start=12 margin_end=12 margin_top=6 /> };
+//! gtk! {
+//!     <Box orientation=Orientation::Vertical>
+//!         <Label label="Name" {..padded.clone()} />
+//!         <Entry {..padded} margin_top=0 />
+//!     </Box>
+//! }
+//! # }
+//! ```
+//!
+//! Attributes written on the element itself take precedence over those in a bundle, wherever
+//! the spread appears, and of two bundles setting the same property, the later one wins. A
+//! bundle is a [`Props`][Props] value, so helper functions can return one too.
+//!
+//! ### Interpolation
+//!
+//! The `gtk!` macro's parser tries to be smart about recognising Rust expressions as attribute
+//! values, but it's not perfect. If the parser chokes on some particularly complicated Rust
+//! expression, you can always wrap an attribute's value in a `{}` block, as per [JSX].
+//!
+//! This curly bracket syntax is also used to dynamically insert child widgets into a tree.
+//! You can insert a code block in place of a child widget, which should return an iterator
+//! of widgets that will be appended by the macro when rendering the virtual tree.
+//!
+//! For instance, to dynamically generate a series of buttons, you can do this:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Button, ButtonExt, Box, BoxExt, Orientation};
+//! # fn view() -> VNode<()> {
+//! gtk! {
+//!     <Box>
+//!         {
+//!             (1..=5).map(|counter| {
+//!                 gtk! { <Button label=format!("Button #{}", counter) /> }
+//!             })
+//!         }
+//!     </Box>
+//! }
+//! # }
+//! ```
+//!
+//! ### Conditionals
+//!
+//! A code block in place of a child widget may also contain an `if` expression whose
+//! branches are written in `gtk!` syntax rather than Rust. Each branch can contain any
+//! number of children, including none, and you can use `else if` and `if let` the way you
+//! would in normal Rust code:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Button, ButtonExt, Box, Label, LabelExt};
+//! # fn view(done: bool, error: Option<String>) -> VNode<()> {
+//! gtk! {
+//!     <Box>
+//!         {
+//!             if done {
+//!                 <Label label="All done!" />
+//!             } else {
+//!                 <Button label="Finish" />
+//!             }
+//!         }
+//!         {
+//!             if let Some(error) = &error {
+//!                 <Label label=error.clone() />
+//!             }
+//!         }
+//!     </Box>
+//! }
+//! # }
+//! ```
+//!
+//! A code block is only treated this way if at least one of its branches starts with an
+//! element; otherwise it's left alone as a Rust expression returning an iterator of widgets.
+//!
+//! An `if` expression can also take the place of the root element, as long as it has an `else`
+//! and each of its branches holds exactly one element, which becomes the view when that branch is
+//! taken:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Label, LabelExt, Spinner, SpinnerExt};
+//! # fn view(loading: bool) -> VNode<()> {
+//! gtk! {
+//!     {
+//!         if loading {
+//!             <Spinner property_active=true />
+//!         } else {
+//!             <Label label="Ready" />
+//!         }
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Widgets added or removed this way pop in and out of view. To have them slide or fade
+//! instead, put them in a [`Revealer`][Revealer] whose `reveal_child` property follows the same
+//! condition, and pick an animation with its `transition_type` and `transition_duration`
+//! properties. When the revealer's child is removed while it's hiding it, the child is kept
+//! around until it's out of view, so it can be taken away at the same time as the revealer is
+//! told to hide:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # fn view(error: Option<String>) -> VNode<()> {
+//! gtk! {
+//!     <Revealer reveal_child=error.is_some() transition_type=RevealerTransitionType::SlideDown
+//!               transition_duration=250>
+//!         {
+//!             if let Some(error) = &error {
+//!                 <Label label=error.clone() />
+//!             }
+//!         }
+//!     </Revealer>
+//! }
+//! # }
+//! ```
+//!
+//! ### Loops
+//!
+//! Likewise, a code block can contain a `for` loop with a body in `gtk!` syntax, which
+//! inserts the body's children once for every iteration. This is equivalent to the
+//! iterator example above:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Button, ButtonExt, Box, BoxExt, Orientation};
+//! # fn view() -> VNode<()> {
+//! gtk! {
+//!     <Box>
+//!         {
+//!             for counter in 1..=5 {
+//!                 <Button label=format!("Button #{}", counter) />
+//!             }
+//!         }
+//!     </Box>
+//! }
+//! # }
+//! ```
+//!
+//! Loops and conditionals can be nested inside each other as you'd expect.
+//!
+//! A loop doesn't give the children it generates keys of its own, as the macro can't tell what
+//! identifies an item, so they're matched up by their position like any other children. If the
+//! items can be reordered or removed, write a `key` attribute on the root element of the loop's
+//! body, as described under [Keys](#keys) below.
+//!
+//! ### Fragments
+//!
+//! To return several sibling widgets from a helper function without wrapping them in a `Box`
+//! of their own, put them in an empty tag, `<>...</>`. This makes the `gtk!` macro return a
+//! `Vec<VNode>` instead of a single [`VNode`][VNode], which you can insert with a code block
+//! wherever the widgets belong:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Box, Entry, Label, LabelExt, WidgetExt};
+//! fn field(label: &str) -> Vec<VNode<()>> {
+//!     gtk! {
+//!         <>
+//!             <Label label=label />
+//!             <Entry hexpand=true />
+//!         </>
+//!     }
+//! }
+//!
+//! # fn view() -> VNode<()> {
+//! gtk! {
+//!     <Box>
+//!         { field("Name") }
+//!         { field("Email") }
+//!     </Box>
+//! }
+//! # }
+//! ```
+//!
+//! A fragment can also be written straight into a list of children, or into the body of a
+//! conditional or a loop, where its children are added in its place.
+//!
+//! ### Keys
+//!
+//! By default, the differ matches up child widgets by their position inside their parent,
+//! so removing the first of a list of rows means every row after it gets patched to look
+//! like its successor, and the last one is destroyed. For rows with state of their own, like
+//! the text in an [`Entry`][Entry] or which widget has focus, that's not what you want.
+//!
+//! If you give every child of a container a `key` attribute, the differ will match them up
+//! by key instead. Children whose keys are still present are patched and moved into their
+//! new positions rather than rebuilt, children whose keys have gone away are removed, and
+//! children with new keys are constructed. A key can be any value that implements
+//! [`Display`][Display], and must be unique among its siblings.
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::{Entry, EntryExt, ListBox, ListBoxRow};
+//! # struct Item { id: usize, text: String }
+//! # fn view(items: &[Item]) -> VNode<()> {
+//! gtk! {
+//!     <ListBox>
+//!         {
+//!             for item in items {
+//!                 <ListBoxRow key=item.id>
+//!                     <Entry text=item.text.clone() />
+//!                 </ListBoxRow>
+//!             }
+//!         }
+//!     </ListBox>
+//! }
+//! # }
+//! ```
+//!
+//! Keys work the same way on subcomponents. Keyed matching is only used when all the children
+//! of a container have keys; otherwise, the differ falls back to matching by position.
+//!
+//! For long lists, even patching every row on every render adds up. A node built with
+//! [`VNode::lazy()`][VNode::lazy] from a value and a render function is only rendered and
+//! patched when the value differs from last time, and left alone otherwise. Use
+//! [`VNode::with_key()`][VNode::with_key] to give it a key.
+//!
+//! For very long lists, you can leave the rows out of your view altogether: keep the items in a
+//! [`ListModel`][ListModel], and give it to a [`ListBox`][ListBox] with
+//! `<ListBox model=self.items.bind(render_row) />`. GTK then builds a row with `render_row`
+//! whenever an item is added to the model, and destroys it when the item is removed.
+//!
+//! If even building a row for every item is too much, the built in [`VirtualList`][VirtualList]
+//! subcomponent only builds the rows which are currently scrolled into view, rendering each one
+//! by its index with a closure you give it.
+//!
+//! ### Refs
+//!
+//! If you need to get at a widget directly, for instance to give it the keyboard focus from
+//! your [`update`][Component::update] method, you can give it a `ref` attribute pointing at a
+//! [`WidgetRef`][WidgetRef] kept in your component. The `WidgetRef` refers to the widget once
+//! it's been built, and is emptied again when the widget is unmounted.
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, WidgetRef};
+//! # use vgtk::lib::gtk::{Box, Entry};
+//! # fn view(entry: &WidgetRef<Entry>) -> VNode<()> {
+//! gtk! {
+//!     <Box>
+//!         <Entry ref=entry />
+//!     </Box>
+//! }
+//! # }
+//! ```
+//!
+//! The most common reason to do this is to move the keyboard focus, which you can do by calling
+//! [`WidgetRef::grab_focus()`][WidgetRef::grab_focus]. This works even if the widget hasn't been
+//! built yet, in which case it's focused as soon as it appears. If you'd rather declare where the
+//! focus should go, the [`WidgetExtHelpers`][WidgetExtHelpers] trait adds an `autofocus`
+//! attribute, which focuses a widget when it's first built, and a `has_focus` attribute, which
+//! gives a widget the focus whenever it's rendered with `has_focus=true`.
+//!
+//! For the occasional widget you haven't given a `ref`, the [`vgtk::query`][vgtk::query] module
+//! searches the widgets inside a window or any other widget by type and attributes, as in
+//! `find::<Button>().with_label("Clear completed").first(&window)`.
+//!
+//! ### Styling
+//!
+//! Every widget accepts a `class` attribute, a list of CSS style classes separated by spaces,
+//! and a `style` attribute, which applies CSS declarations to that widget alone. Both are
+//! provided by [`WidgetExtHelpers`][WidgetExtHelpers]. When a widget is rendered with different
+//! classes, only the classes it no longer has are removed, so classes added by GTK itself are
+//! left alone.
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::ext::*;
+//! # use vgtk::lib::gtk::*;
+//! # fn view() -> VNode<()> {
+//! gtk! {
+//!     <Box spacing=6 style="margin: 12px;">
+//!         <Button label="Cancel" />
+//!         <Button label="Delete" class="suggested-action destructive-action" />
+//!     </Box>
+//! }
+//! # }
+//! ```
+//!
+//! Rules for the classes you use belong in a stylesheet, which you can load for the whole
+//! application with [`css::load()`][css::load], and swap for another while the application is
+//! running. A component can also declare its own stylesheet with
+//! [`Component::style()`][Component::style], whose rules only apply to that component's own
+//! widgets.
+//!
+//! To offer a dark theme, give your `Application` the `prefer_dark_theme` attribute from
+//! [`ApplicationHelpers`][ApplicationHelpers], and follow changes made to the theme outside your
+//! application with [`Subscription::dark_theme()`][Subscription::dark_theme].
+//!
+//! ### Menus
+//!
+//! Widget based menus are built like any other widget tree: a [`MenuBar`][MenuBar] or a
+//! [`Menu`][Menu] takes [`MenuItem`][MenuItem]s as children, and a [`MenuItem`][MenuItem]
+//! takes a single [`Menu`][Menu] child as its submenu. A menu item can dispatch a message
+//! through its `activate` signal, or trigger an action through its `action_name` property.
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Open }
+//! # #[derive(Default)] struct Comp;
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <MenuBar>
+//!         <MenuItem label="File">
+//!             <Menu>
+//!                 <MenuItem label="Open..." on activate=|_| Message::Open />
+//!                 <MenuItem label="Quit" action_name="app.quit" />
+//!             </Menu>
+//!         </MenuItem>
+//!     </MenuBar>
+//! }
+//! # }}
+//! ```
+//!
+//! A [`Menu`][Menu] declared inside any other widget becomes its context menu, which pops up
+//! when the widget is right clicked, or when it has the focus and the menu key is pressed. It
+//! doesn't count as one of the widget's children, so it can be given to a [`ListBoxRow`][ListBoxRow]
+//! along with the row's contents. Clicks only reach widgets with a window of their own, or
+//! inside one, so wrap widgets like labels in an [`EventBox`][EventBox].
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Rename(usize), Delete(usize) }
+//! # #[derive(Default)] struct Comp { items: Vec<String> }
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <ListBox>
+//!         {
+//!             self.items.iter().enumerate().map(|(index, item)| gtk! {
+//!                 <ListBoxRow>
+//!                     <Menu>
+//!                         <MenuItem label="Rename" on activate=|_| Message::Rename(index) />
+//!                         <MenuItem label="Delete" on activate=|_| Message::Delete(index) />
+//!                     </Menu>
+//!                     <EventBox>
+//!                         <Label label=item.clone() />
+//!                     </EventBox>
+//!                 </ListBoxRow>
+//!             })
+//!         }
+//!     </ListBox>
+//! }
+//! # }}
+//! ```
+//!
+//! [`Application`][Application]'s `menubar` and `app_menu` properties, as well as
+//! [`MenuButton`][MenuButton]'s `menu_model`, take a [`gio::Menu`][gio::Menu] instead, which
+//! you can construct using the [`menu()`][menu] builder. Its items refer to actions by name,
+//! and you declare those actions as [`SimpleAction`][SimpleAction] children of your
+//! [`Application`][Application] (for `app.*` names) or [`ApplicationWindow`][ApplicationWindow]
+//! (for `win.*` names), using their `activate` signal to dispatch messages. Menu models are
+//! compared by content, so it's fine to build a fresh one on every render.
+//!
+//! Alternatively, a component can declare its actions in code by implementing
+//! [`Component::actions()`][Component::actions], which returns a list of
+//! [`ActionDecl`][ActionDecl]s mapping action names to messages. These are registered on the
+//! component's top level object and kept up to date as it re-renders.
+//!
+//! To give an action a keyboard shortcut, set the `Application::accelerator` or
+//! `ApplicationWindow::accelerator` child property on it, as appropriate, to an accelerator
+//! string like `"<Ctrl>q"`. Widgets with an `action_name` also accept an `accelerator`
+//! attribute, provided by [`ActionableHelpers`][ActionableHelpers].
+//!
+//! ```rust,no_run
+//! # use vgtk::{ext::*, gtk, menu, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # use vgtk::lib::gio::{ActionExt, ApplicationFlags, SimpleAction};
+//! # #[derive(Clone, Debug)] enum Message { Quit }
+//! # #[derive(Default)] struct Comp;
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! let main_menu = menu()
+//!     .sub("File", menu().item("Quit", "app.quit"))
+//!     .build();
+//! gtk! {
+//!     <Application::new_unwrap(None, ApplicationFlags::empty()) menubar=main_menu>
+//!         <SimpleAction::new("quit", None) Application::accelerator="<Ctrl>q"
+//!                                          enabled=true on activate=|_, _| Message::Quit />
+//!         <ApplicationWindow show_menubar=true />
+//!     </Application>
+//! }
+//! # }}
+//! ```
+//!
+//! A [`Popover`][Popover], or a [`PopoverMenu`][PopoverMenu], declared inside another widget
+//! points at that widget, and pops up when its `visible` property is set, so you can keep
+//! whether it's open in your component's state. Popovers close themselves when the user clicks
+//! elsewhere, so update your state from their `closed` signal. To point one at a widget
+//! elsewhere in your view, give it that widget's [`WidgetRef`][WidgetRef] with its
+//! `relative_to_ref` property, provided by [`PopoverExtHelpers`][PopoverExtHelpers].
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Open, Close }
+//! # #[derive(Default)] struct Comp { open: bool }
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <Button label="Details" on clicked=|_| Message::Open>
+//!         <Popover visible=self.open on closed=|_| Message::Close>
+//!             <Label label="More than you wanted to know." />
+//!         </Popover>
+//!     </Button>
+//! }
+//! # }}
+//! ```
+//!
+//! A [`MenuButton`][MenuButton] pops up either the [`Popover`][Popover] or the [`Menu`][Menu]
+//! declared inside it, or the menu model given to its `menu_model` property, which takes the
+//! [`menu()`][menu] builder as it is.
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, menu, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message {}
+//! # #[derive(Default)] struct Comp;
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <HeaderBar title="Notes" show_close_button=true>
+//!         <MenuButton HeaderBar::pack_type=PackType::End image="open-menu-symbolic"
+//!                     menu_model=menu().item("About", "app.about").item("Quit", "app.quit") />
+//!         <MenuButton HeaderBar::pack_type=PackType::End image="emblem-system-symbolic">
+//!             <Popover>
+//!                 <CheckButton label="Spell check" action_name="win.spell-check" />
+//!             </Popover>
+//!         </MenuButton>
+//!     </HeaderBar>
+//! }
+//! # }}
+//! ```
+//!
+//! ### Tree Views
+//!
+//! A [`TreeView`][TreeView] takes [`TreeViewColumn`][TreeViewColumn]s as children, and each column
+//! takes one or more cell renderers, such as [`CellRendererText`][CellRendererText]. The rows
+//! themselves come from the tree view's `model`: implement [`TreeRow`][TreeRow] for your row
+//! type, keep a [`ListRows`][ListRows] (or a [`TreeRows`][TreeRows], for nested rows) in your
+//! component, and give the tree view the result of calling its `sync()` method with your rows.
+//! Each renderer is told which column of the model to display through a child attribute named
+//! after the renderer property it sets, like `TreeViewColumn::text=0`.
+//!
+//! ```rust,no_run
+//! # use vgtk::{ext::*, gtk, ListRows, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Open(usize) }
+//! # #[derive(Default)] struct Comp { names: Vec<String>, rows: ListRows<String> }
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <TreeView model=self.rows.sync(&self.names)
+//!               on row_activated=|_, path, _| Message::Open(path.get_indices()[0] as usize)>
+//!         <TreeViewColumn title="Name">
+//!             <CellRendererText TreeViewColumn::text=0 />
+//!         </TreeViewColumn>
+//!     </TreeView>
+//! }
+//! # }}
+//! ```
+//!
+//! A [`ComboBox`][ComboBox] takes a `model` and cell renderers the same way a column does.
+//! For the common case of a list of strings, a [`ComboBoxText`][ComboBoxText] accepts them
+//! through its `items` property, provided by [`ComboBoxTextExtHelpers`][ComboBoxTextExtHelpers],
+//! and uses each string as its item's ID as well, so you can bind the selection to your
+//! component's state with `bind:active_id`.
+//!
+//! ```rust,no_run
+//! # use vgtk::{ext::*, gtk, ListRows, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # use vgtk::lib::gtk::prelude::ComboBoxExtManual;
+//! # #[derive(Clone, Debug)] enum Message { Size(String), Font(Option<u32>) }
+//! # #[derive(Default)] struct Comp { size: String, fonts: Vec<String>, rows: ListRows<String>, font: Option<u32> }
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <Box>
+//!         <ComboBoxText items=vec!["Small", "Medium", "Large"]
+//!                       bind:active_id=self.size.clone() => Message::Size />
+//!         <ComboBox model=self.rows.sync(&self.fonts) bind:active=self.font => Message::Font>
+//!             <CellRendererText ComboBox::text=0 />
+//!         </ComboBox>
+//!     </Box>
+//! }
+//! # }}
+//! ```
+//!
+//! ### Drawing
+//!
+//! Custom graphics, like charts, go in a [`DrawingArea`][DrawingArea], whose contents are drawn
+//! by a [`Painter`][Painter]: a plain function which receives a value taken from your component's
+//! state, the drawing area and a cairo context to draw with. Give the drawing area its painter
+//! through the `painter` property with `Painter::new(self.points.clone(), draw_points)`, and it's
+//! redrawn whenever your component renders with a different value.
+//!
+//! ### Gestures
+//!
+//! Gestures, like [`GestureMultiPress`][GestureMultiPress] and [`GestureDrag`][GestureDrag], are
+//! declared as children of the widget whose events they handle, and report through their
+//! signals like any other object. They don't count as children of their widget, so they can be
+//! given to a [`Button`][Button] or an [`EventBox`][EventBox] along with its usual child. GTK 3
+//! has no controller for pointer motion, so use the widget's `motion_notify_event` signal for
+//! that instead.
 //!
 //! ```rust,no_run
 //! # use vgtk::{gtk, VNode};
-//! # use vgtk::lib::gtk::{MenuButton, MenuButtonExt, WidgetExt, ArrowType, TextDirection};
-//! # fn view1() -> VNode<()> { gtk!{
-//! <MenuButton @MenuButtonExt::direction=ArrowType::Down />
-//! # }} fn view2() -> VNode<()> { gtk! {
-//! <MenuButton @WidgetExt::direction=TextDirection::Ltr />
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Menu(f64, f64), Move(f64, f64) }
+//! # #[derive(Default)] struct Comp;
+//! # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! gtk! {
+//!     <EventBox>
+//!         <GestureMultiPress button=3 on pressed=|_, _, x, y| Message::Menu(x, y) />
+//!         <GestureDrag on drag_update=|_, x, y| Message::Move(x, y) />
+//!         <Label label="Drag me" />
+//!     </EventBox>
+//! }
 //! # }}
 //! ```
 //!
-//! ### Interpolation
+//! ### Drag And Drop
 //!
-//! The `gtk!` macro's parser tries to be smart about recognising Rust expressions as attribute
-//! values, but it's not perfect. If the parser chokes on some particularly complicated Rust
-//! expression, you can always wrap an attribute's value in a `{}` block, as per [JSX].
+//! Any widget with a window of its own can be made a drag source or a drop destination through
+//! its `drag_source` and `drag_dest` attributes, which take the [`DragTargets`][DragTargets] it
+//! offers or accepts, provided by [`WidgetExtHelpers`][WidgetExtHelpers]. Dropped data arrives
+//! through the destination's `drag_data_received` signal, so it reaches your component as a
+//! message from that signal's handler.
 //!
-//! This curly bracket syntax is also used to dynamically insert child widgets into a tree.
-//! You can insert a code block in place of a child widget, which should return an iterator
-//! of widgets that will be appended by the macro when rendering the virtual tree.
+//! ### Other Widget Libraries
 //!
-//! For instance, to dynamically generate a series of buttons, you can do this:
+//! The `gtk!` macro isn't limited to the widgets in [`gtk`][gtk]: any widget type built on the
+//! same version of gtk-rs works, as long as its extension traits are in scope. For a code editor,
+//! enable the `sourceview` feature, which adds GtkSourceView's [`View`][sourceview::View] in
+//! [`vgtk::sourceview`][sourceview]. [`SourceViewExtHelpers`][SourceViewExtHelpers] gives it
+//! `language`, `scheme` and `text` attributes, and a `text_changed` signal which sends your
+//! component the user's edits.
+//!
+//! For an embedded browser, enable the `webkit` feature, which re-exports the
+//! [`webkit2gtk`][webkit2gtk] crate as `vgtk::lib::webkit2gtk`. WebKit2GTK's `WebView` only
+//! navigates through `load_uri()`, so [`WebViewExtHelpers`][WebViewExtHelpers] turns that into a
+//! `uri` attribute, and adds `navigated` and `title_changed` signals for sending your component
+//! the URI of each new page and its title. Keep your component's idea of the current URI up to
+//! date from `navigated`, or the next render will navigate back to the old one. The view's own
+//! signals, like `load_changed`, work as usual:
 //!
 //! ```rust,no_run
-//! # use vgtk::{gtk, VNode};
-//! # use vgtk::lib::gtk::{Button, ButtonExt, Box, BoxExt, Orientation};
-//! # fn view() -> VNode<()> {
+//! # use vgtk::{ext::*, gtk, Component, VNode};
+//! use vgtk::lib::webkit2gtk::{LoadEvent, WebView, WebViewExt};
+//! # #[derive(Clone, Debug)] enum Message { Loading(bool), Navigated(String), Title(String) }
+//! # #[derive(Default)] struct Browser { uri: String }
+//! # impl Component for Browser { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Browser> {
 //! gtk! {
-//!     <Box>
-//!         {
-//!             (1..=5).map(|counter| {
-//!                 gtk! { <Button label=format!("Button #{}", counter) /> }
-//!             })
-//!         }
-//!     </Box>
+//!     <WebView uri=self.uri.clone()
+//!              on load_changed=|_, event| Message::Loading(event != LoadEvent::Finished)
+//!              on navigated=|_, uri| Message::Navigated(uri)
+//!              on title_changed=|_, title| Message::Title(title) />
 //! }
-//! # }
+//! # }}
+//! ```
+//!
+//! Any other property without a setter can be given one with a helper trait of your own, the same
+//! way [`vgtk::ext`][ext] does for GTK's widgets.
+//!
+//! ### Without The Macro
+//!
+//! If you'd rather not use the `gtk!` syntax, or your view is generated from data, like a form
+//! built from a schema, you can build the same tree in plain Rust, starting with
+//! [`VNode::widget()`][VNode::widget]. The [`VObjectBuilder`][VObjectBuilder] it returns takes
+//! properties by their GObject names and signal handlers by their signal names, so mistakes
+//! show up as panics when the widget is built, rather than as compile errors:
+//!
+//! ```rust,no_run
+//! # use vgtk::{VNode, Component};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug)] enum Message { Save }
+//! # #[derive(Default)] struct Comp;
+//! # impl Component for Comp { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Comp> {
+//! VNode::widget::<Button>()
+//!     .prop("label", "Save")
+//!     .on("clicked", |_| Message::Save)
+//!     .build()
+//! # }}
 //! ```
 //!
 //! ## Subcomponents
@@ -410,6 +1209,128 @@
 //! parent component it lives within inside its type signature. It'll just work, with nary a
 //! profunctor in sight.
 //!
+//! Communication in the other direction usually happens through properties, but if a parent needs
+//! to tell a subcomponent to do something, like reset a form, it can give the subcomponent a `ref`
+//! attribute pointing at a [`ComponentRef`][ComponentRef], and use that to send messages directly
+//! to the subcomponent's [`update`][Component::update] function.
+//!
+//! ### Rendering
+//!
+//! Every component instance, including each subcomponent, runs as its own task with its own
+//! message queue, and only ever renders its own [`view`][Component::view]. When a message
+//! arrives, only the component which received it is updated, re-rendered and diffed; its parent
+//! and siblings aren't touched. Subcomponents of a component which does re-render receive their
+//! new properties through [`change`][Component::change], and render again only if it asks them
+//! to, or not at all if [`should_render`][Component::should_render] turns the properties down.
+//! Changes travel back up the tree only when a subcomponent sends its parent a message through a
+//! [`Callback`][Callback].
+//!
+//! A component doesn't render the moment it's asked to, either, but waits until the main loop has
+//! nothing more urgent to do, so a burst of messages results in a single render just before GTK
+//! draws the next frame. Tests which would rather have components render straight away can turn
+//! this off with [`set_batch_rendering()`][set_batch_rendering].
+//!
+//! ### Children
+//!
+//! A subcomponent can also take child nodes, written between an opening and a closing tag
+//! just like the children of a GTK object. To accept them, give its properties a field called
+//! `children` of type [`Children`][Children], and place them in its own view by calling
+//! [`Children::render()`][Children::render] from a code block. This makes it easy to write
+//! wrapper components, such as a titled frame:
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, VNode, Component, Children};
+//! # use vgtk::lib::gtk::*;
+//! # #[derive(Clone, Debug, Default)]
+//! # pub struct Card { pub title: String, pub children: Children }
+//! # impl Component for Card {
+//! #     type Message = ();
+//! #     type Properties = Self;
+//! fn view(&self) -> VNode<Self> {
+//!     gtk! {
+//!         <Frame label=self.title.clone()>
+//!             <Box orientation=Orientation::Vertical>
+//!                 { self.children.render() }
+//!             </Box>
+//!         </Frame>
+//!     }
+//! }
+//! # }
+//! # #[derive(Clone, Debug)] enum ParentMessage { ButtonClicked }
+//! # #[derive(Default)] struct Parent;
+//! # impl Component for Parent { type Message = ParentMessage; type Properties = ();
+//! # fn view(&self) -> VNode<Self> { gtk! {
+//! <@Card title="Actions">
+//!     <Button label="Click me!" on clicked=|_| ParentMessage::ButtonClicked />
+//! </@Card>
+//! # }}}
+//! ```
+//!
+//! Signal handlers on the children still belong to the component whose view they were written
+//! in, so the button above sends its message to `Parent`, not to `Card`.
+//!
+//! ### Context
+//!
+//! Some state, like a colour theme or the logged in user, is needed all over an application, and
+//! passing it down through the properties of every component in between gets tedious. Instead,
+//! you can put it in a [`Context`][Context] with [`Context::provide()`][Context::provide], and
+//! look it up from any component with [`Context::current()`][Context::current]. Components which
+//! read a context from their [`view`][Component::view] method are rendered again whenever its
+//! value changes.
+//!
+//! Applications which outgrow this can keep their state in a [`Store`][Store] from the
+//! [`vgtk::store`][vgtk::store] module instead, which only changes in response to actions
+//! dispatched to it, and can log, undo or persist those actions using middleware. State which
+//! belongs to a single component can be made undoable by wrapping it in an
+//! [`Undoable`][undo::Undoable] from the [`vgtk::undo`][vgtk::undo] module.
+//!
+//! ### Workers
+//!
+//! Everything a component does happens on the GTK main loop, so anything slow, like searching
+//! through a large number of files, will freeze your UI while it runs. Put that kind of work in a
+//! [`Worker`][Worker] instead, which runs on its own thread. Start it with
+//! [`Bridge::spawn()`][Bridge::spawn], keep the [`Bridge`][Bridge] in your component, and use it
+//! to send the worker inputs and to receive its outputs as messages through a
+//! [`Subscription`][Subscription].
+//!
+//! ### Settings
+//!
+//! Preferences which should survive a restart belong in GSettings. A
+//! [`SettingsBinding`][settings::SettingsBinding] from the [`vgtk::settings`][vgtk::settings]
+//! module ties keys of a settings schema to your component's messages: it sends you a message
+//! with a key's value through a subscription whenever the key changes, and writes a key whenever
+//! you hand it a message which sets it.
+//!
+//! Alternatively, with the `persist` feature enabled, your top level component can implement
+//! [`Persistent`][Persistent] and be started with [`vgtk::run_persistent()`][run_persistent], which
+//! saves its whole state with serde when the application exits and restores it when it starts.
+//!
+//! ### Running In The Background
+//!
+//! An application normally exits when its last window closes. One which should keep running, like
+//! a chat client waiting for messages, can call [`hold()`][Application::hold] on the default
+//! [`Application`][Application] and hide its window from a [`delete_event`][Widget::connect_delete_event]
+//! handler instead of letting it be destroyed. Launching it again activates the running instance,
+//! which raises its window and sends the message from [`Component::activated()`][activated], and
+//! [`vgtk::notify()`][notify] can let the user know something happened while it was hidden.
+//!
+//! With the `tray` feature enabled, [`vgtk::tray::StatusIcon`][vgtk::tray::StatusIcon] gives it a
+//! status icon in the system tray, which sends a message when it's clicked and can have a menu of
+//! actions. It's shown through the StatusNotifierItem D-Bus protocol, so it only appears on
+//! desktops which display those, and on GNOME only with its AppIndicator extension installed.
+//!
+//! ## Testing
+//!
+//! The [`vgtk::testing`][vgtk::testing] module lets your tests build a component on its own and
+//! interact with its widgets the way a user would, clicking buttons, typing into entries and
+//! activating widgets found with CSS-like selectors such as `"GtkButton#save"`, or with queries
+//! from [`vgtk::query`][vgtk::query]. These go through the same GTK signals as a real user's
+//! actions, so the test covers your signal handlers, your `update` method and your view together.
+//!
+//! To check a view without building any widgets at all, the `snapshot` feature adds
+//! [`vgtk::snapshot`][vgtk::snapshot], which writes out a component's view as text, with property
+//! values but without signal handlers, for comparing against a saved snapshot.
+//!
 //! ## Logging
 //!
 //! `vgtk` uses the [`log`][log] crate for debug output. You'll need to provide your own logger for this;
@@ -421,6 +1342,24 @@
 //! in your component's interactions. At log level `trace`, you'll also get a lot of `vgtk` internal
 //! information that's likely only useful if you're debugging the framework.
 //!
+//! With the `debug` feature enabled, you can go one step further and start your application with
+//! [`vgtk::run_with_debugger()`][run_with_debugger] instead of [`vgtk::run()`][run]. This opens a
+//! window next to your application listing every message your top level component has received,
+//! and lets you step back and forth through them, putting the component back in the state it had
+//! after each one, or replay them all from the start. To see what the rest of your components are
+//! doing, [`vgtk::inspector::open()`][inspector::open] opens a window showing the live component
+//! tree, with each component's properties, the messages it received and the view it last rendered.
+//!
+//! If it's performance you're after, the `profile` feature measures how long each render spends in
+//! your view and patching the widgets, and how many changes it makes. Renders are logged at level
+//! `debug`, and the totals for each component are available from [`vgtk::profile`][vgtk::profile].
+//!
+//! If your application already uses [`tracing`][tracing], the `tracing` feature makes `vgtk` emit
+//! a span for every `create`, `change`, `update` and `view` call and every patch of the widgets,
+//! each tagged with the component's name, and events when components mount and unmount. Your
+//! existing subscriber then gets a structured record of which messages caused which renders, and
+//! how long each one took.
+//!
 //! ## Work In Progress
 //!
 //! While this framework is currently sufficiently usable that we can implement [TodoMVC] in it, there
@@ -449,21 +1388,99 @@
 //! [TodoMVC]: http://todomvc.com/
 //! [log]: https://crates.io/crates/log
 //! [pretty_env_logger]: https://crates.io/crates/pretty_env_logger
+//! [tracing]: https://crates.io/crates/tracing
 //! [vgtk::gtk!]: macro.gtk.html
 //! [vgtk::ext]: ext/index.html
 //! [Component]: trait.Component.html
 //! [Component::view]: trait.Component.html#tymethod.view
 //! [Component::update]: trait.Component.html#method.update
+//! [Component::actions]: trait.Component.html#method.actions
 //! [Component::create]: trait.Component.html#method.create
 //! [Component::change]: trait.Component.html#method.change
+//! [Component::should_render]: trait.Component.html#method.should_render
+//! [set_batch_rendering]: fn.set_batch_rendering.html
 //! [Component::Message]: trait.Component.html#associatedtype.Message
 //! [Component::Properties]: trait.Component.html#associatedtype.Properties
 //! [Callback]: struct.Callback.html
+//! [ComponentRef]: struct.ComponentRef.html
+//! [Children]: struct.Children.html
+//! [Children::render]: struct.Children.html#method.render
+//! [Context]: struct.Context.html
+//! [Store]: store/struct.Store.html
+//! [vgtk::store]: store/index.html
+//! [undo::Undoable]: undo/struct.Undoable.html
+//! [vgtk::undo]: undo/index.html
+//! [settings::SettingsBinding]: settings/struct.SettingsBinding.html
+//! [vgtk::settings]: settings/index.html
+//! [Persistent]: trait.Persistent.html
+//! [run_persistent]: fn.run_persistent.html
+//! [run_with_debugger]: fn.run_with_debugger.html
+//! [inspector::open]: inspector/fn.open.html
+//! [vgtk::profile]: profile/index.html
+//! [vgtk::query]: query/index.html
+//! [vgtk::snapshot]: snapshot/index.html
+//! [vgtk::testing]: testing/index.html
+//! [run]: fn.run.html
+//! [Context::provide]: struct.Context.html#method.provide
+//! [Context::current]: struct.Context.html#method.current
+//! [VNode]: enum.VNode.html
+//! [VNode::lazy]: enum.VNode.html#method.lazy
+//! [VNode::with_key]: enum.VNode.html#method.with_key
+//! [ListModel]: struct.ListModel.html
+//! [ListRows]: struct.ListRows.html
+//! [TreeRows]: struct.TreeRows.html
+//! [TreeRow]: trait.TreeRow.html
+//! [TreeView]: ../gtk/struct.TreeView.html
+//! [TreeViewColumn]: ../gtk/struct.TreeViewColumn.html
+//! [CellRendererText]: ../gtk/struct.CellRendererText.html
+//! [ComboBox]: ../gtk/struct.ComboBox.html
+//! [ComboBoxText]: ../gtk/struct.ComboBoxText.html
+//! [ComboBoxTextExtHelpers]: ext/trait.ComboBoxTextExtHelpers.html
+//! [DrawingArea]: ../gtk/struct.DrawingArea.html
+//! [Painter]: struct.Painter.html
+//! [GestureMultiPress]: ../gtk/struct.GestureMultiPress.html
+//! [GestureDrag]: ../gtk/struct.GestureDrag.html
+//! [EventBox]: ../gtk/struct.EventBox.html
+//! [DragTargets]: struct.DragTargets.html
+//! [gtk]: ../gtk/index.html
+//! [sourceview]: sourceview/index.html
+//! [sourceview::View]: sourceview/struct.View.html
+//! [SourceViewExtHelpers]: ext/trait.SourceViewExtHelpers.html
+//! [webkit2gtk]: https://crates.io/crates/webkit2gtk
+//! [WebViewExtHelpers]: ext/trait.WebViewExtHelpers.html
+//! [VNode::widget]: enum.VNode.html#method.widget
+//! [VObjectBuilder]: struct.VObjectBuilder.html
+//! [ext]: ext/index.html
+//! [gdk::enums::key]: ../gdk/enums/key/index.html
+//! [Props]: struct.Props.html
+//! [props!]: macro.props.html
+//! [css::load]: css/fn.load.html
+//! [Component::style]: trait.Component.html#method.style
+//! [ApplicationHelpers]: ext/trait.ApplicationHelpers.html
+//! [Subscription::dark_theme]: struct.Subscription.html#method.dark_theme
+//! [VirtualList]: struct.VirtualList.html
+//! [WidgetRef]: struct.WidgetRef.html
+//! [Subscription]: struct.Subscription.html
+//! [Worker]: trait.Worker.html
+//! [Bridge]: struct.Bridge.html
+//! [Bridge::spawn]: struct.Bridge.html#method.spawn
+//! [WidgetRef::grab_focus]: struct.WidgetRef.html#method.grab_focus
+//! [WidgetExtHelpers]: ext/trait.WidgetExtHelpers.html
+//! [IntoMessages]: trait.IntoMessages.html
+//! [msgs]: macro.msgs.html
 //! [UpdateAction]: enum.UpdateAction.html
 //! [UpdateAction::None]: enum.UpdateAction.html#variant.None
 //! [UpdateAction::Render]: enum.UpdateAction.html#variant.Render
 //! [UpdateAction::Defer]: enum.UpdateAction.html#variant.Defer
+//! [ActionDecl]: struct.ActionDecl.html
+//! [ActionableHelpers]: ext/trait.ActionableHelpers.html
 //! [Application]: ../gtk/struct.Application.html
+//! [Application::hold]: ../gio/trait.ApplicationExt.html#tymethod.hold
+//! [vgtk::tray::StatusIcon]: tray/struct.StatusIcon.html
+//! [activated]: trait.Component.html#method.activated
+//! [notify]: fn.notify.html
+//! [ApplicationWindow]: ../gtk/struct.ApplicationWindow.html
+//! [Assistant]: ../gtk/struct.Assistant.html
 //! [Buildable]: ../gtk/struct.Buildable.html
 //! [Button]: ../gtk/struct.Button.html
 //! [Button::connect_clicked]: ../gtk/trait.ButtonExt.html#tymethod.connect_clicked
@@ -471,49 +1488,140 @@
 //! [Box]: ../gtk/struct.Box.html
 //! [Box::new]: ../gtk/struct.Box.html#method.new
 //! [Container]: ../gtk/struct.Container.html
+//! [Display]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+//! [String]: https://doc.rust-lang.org/std/string/struct.String.html
+//! [Option]: https://doc.rust-lang.org/std/option/enum.Option.html
+//! [Entry]: ../gtk/struct.Entry.html
+//! [ListBox]: ../gtk/struct.ListBox.html
+//! [ListBox::connect_row_activated]: ../gtk/trait.ListBoxExt.html#tymethod.connect_row_activated
+//! [ListBoxRow]: ../gtk/struct.ListBoxRow.html
+//! [Popover]: ../gtk/struct.Popover.html
+//! [PopoverMenu]: ../gtk/struct.PopoverMenu.html
+//! [PopoverExtHelpers]: ext/trait.PopoverExtHelpers.html
+//! [gio::Menu]: ../gio/struct.Menu.html
+//! [menu]: fn.menu.html
+//! [Menu]: ../gtk/struct.Menu.html
+//! [MenuBar]: ../gtk/struct.MenuBar.html
 //! [MenuButton]: ../gtk/struct.MenuButton.html
+//! [MenuItem]: ../gtk/struct.MenuItem.html
+//! [Notebook]: ../gtk/struct.Notebook.html
+//! [Revealer]: ../gtk/struct.Revealer.html
+//! [Stack]: ../gtk/struct.Stack.html
+//! [StackSwitcher]: ../gtk/struct.StackSwitcher.html
+//! [SimpleAction]: ../gio/struct.SimpleAction.html
 //! [MenuButtonExt]: ../gtk/trait.MenuButtonExt.html
 //! [WidgetExt]: ../gtk/trait.WidgetExt.html
 //! [Window]: ../gtk/struct.Window.html
+//! [Widget::connect_delete_event]: ../gtk/trait.WidgetExt.html#tymethod.connect_delete_event
+//! [Inhibit]: ../gtk/struct.Inhibit.html
 //! [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+//! [Duration]: https://doc.rust-lang.org/std/time/struct.Duration.html
 
 #![forbid(rust_2018_idioms)]
 #![deny(nonstandard_style, unsafe_code)]
 #![warn(unreachable_pub, missing_docs)]
 #![allow(clippy::needless_doctest_main)]
 
+mod about;
+mod action;
 mod callback;
+mod children;
+pub mod clipboard;
+mod color_chooser;
+mod command_line;
 mod component;
+mod component_ref;
+mod context;
+pub mod css;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+#[cfg(feature = "debug")]
+mod debugger;
+mod dialog;
+mod drag;
 pub mod ext;
+mod file_chooser;
+mod geometry;
+mod info_bar;
+#[cfg(feature = "debug")]
+pub mod inspector;
+mod key_binding;
+mod list_model;
 mod menu_builder;
+mod notification;
+mod painter;
+#[cfg(feature = "persist")]
+mod persist;
+#[cfg(feature = "portal")]
+pub mod portal;
+mod print;
+#[cfg(feature = "profile")]
+pub mod profile;
+mod progress;
 #[doc(hidden)]
 pub mod properties;
+pub mod query;
+mod scale;
 #[doc(hidden)]
 pub mod scope;
+mod search_bar;
+pub mod settings;
+mod shortcuts;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+#[cfg(feature = "sourceview")]
+pub mod sourceview;
+pub mod store;
+mod subscription;
+pub mod testing;
+pub mod timer;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "tray")]
+pub mod tray;
+mod tree_model;
 pub mod types;
+pub mod undo;
 mod vdom;
+mod virtual_list;
 #[doc(hidden)]
 pub mod vnode;
+mod widget_ref;
+mod worker;
 
-use proc_macro_hack::proc_macro_hack;
+// The `gtk!` macro refers to this crate by name, so let it do so from inside
+// the crate too.
+extern crate self as vgtk;
 
 /// Generate a virtual component tree.
 ///
 /// See the [top level documentation][toplevel] for a description of its syntax.
 ///
 /// [toplevel]: index.html
-#[proc_macro_hack(support_nested)]
 pub use vgtk_macros::gtk;
 
+/// Make a bundle of [`Props`][Props] to spread over elements in the [`gtk!`][gtk!] macro.
+///
+/// It takes a single element without children, whose attributes make up the bundle, and which
+/// may itself spread other bundles. Handlers, bindings, keys and refs aren't allowed.
+///
+/// See [`Props`][Props] for an example.
+///
+/// [Props]: struct.Props.html
+/// [gtk!]: macro.gtk.html
+pub use vgtk_macros::props;
+
 use gio::prelude::*;
 use gio::Cancellable;
 use glib::MainContext;
 use gtk::prelude::*;
 use gtk::{
-    Application, ButtonsType, Dialog, DialogFlags, MessageDialog, MessageType, ResponseType, Window,
+    Application, ButtonsType, Dialog, DialogFlags, Inhibit, MessageDialog, MessageType,
+    ResponseType, Window,
 };
 
 use futures::channel::oneshot::{self, Canceled};
+use std::cell::RefCell;
 use std::future::Future;
 
 use colored::Colorize;
@@ -521,22 +1629,55 @@ use log::debug;
 
 use crate::component::{ComponentMessage, ComponentTask, PartialComponentTask};
 
+pub use crate::about::About;
+pub use crate::action::ActionDecl;
 pub use crate::callback::Callback;
-pub use crate::component::{current_object, current_window, Component, UpdateAction};
+pub use crate::children::Children;
+pub use crate::color_chooser::ColorChooser;
+pub use crate::command_line::{CommandLine, CommandLineOption};
+pub use crate::component::{
+    current_object, current_window, set_batch_rendering, Component, QuitAction, UpdateAction,
+};
+pub use crate::component_ref::ComponentRef;
+pub use crate::context::Context;
+pub use crate::dialog::DialogComponent;
+pub use crate::drag::DragTargets;
+pub use crate::file_chooser::FileChooser;
+pub use crate::key_binding::KeyBinding;
+pub use crate::list_model::{ListBinding, ListModel};
 pub use crate::menu_builder::{menu, MenuBuilder};
-pub use crate::scope::Scope;
-pub use crate::vnode::{VNode, VNodeIterator};
+pub use crate::notification::{notify, withdraw_notification, NotificationBuilder};
+pub use crate::painter::Painter;
+#[cfg(feature = "persist")]
+pub use crate::persist::Persistent;
+pub use crate::print::Print;
+pub use crate::progress::{Progress, ProgressReporter};
+pub use crate::scale::ValueFormat;
+pub use crate::scope::{IntoMessages, Scope};
+pub use crate::shortcuts::{shortcuts_window, show_shortcuts};
+pub use crate::subscription::Subscription;
+pub use crate::tree_model::{ListRows, TreeNode, TreeRow, TreeRows};
+pub use crate::virtual_list::{RowRenderer, VirtualList, VirtualListMessage};
+pub use crate::vnode::{Props, VNode, VNodeIterator, VObjectBuilder};
+pub use crate::widget_ref::WidgetRef;
+pub use crate::worker::{Bridge, Worker, WorkerLink};
 
 /// Re-exports of GTK and its associated libraries.
 ///
 /// It is recommended that you use these rather than pulling them in as
 /// dependencies of your own project, to avoid versioning conflicts.
 pub mod lib {
+    pub use ::cairo;
     pub use ::gdk;
     pub use ::gdk_pixbuf;
     pub use ::gio;
     pub use ::glib;
     pub use ::gtk;
+    pub use ::pango;
+    #[cfg(feature = "webkit")]
+    pub use ::webkit2gtk;
+    #[cfg(feature = "dbus")]
+    pub use ::zbus;
 }
 
 /// Run an [`Application`][Application] component until termination.
@@ -610,7 +1751,16 @@ pub fn run<C: 'static + Component>() -> i32 {
 /// [Scope]: struct.Scope.html
 pub fn start<C: 'static + Component>() -> (Application, Scope<C>) {
     gtk::init().expect("GTK failed to initialise");
-    let partial_task = PartialComponentTask::<C, ()>::new(Default::default(), None, None);
+    start_task(PartialComponentTask::<C, ()>::new(
+        Default::default(),
+        None,
+        None,
+    ))
+}
+
+fn start_task<C: 'static + Component>(
+    partial_task: PartialComponentTask<C, ()>,
+) -> (Application, Scope<C>) {
     let app: Application = partial_task.object().downcast().unwrap_or_else(|_| {
         panic!(
             "The top level object must be an Application, but {} was found.",
@@ -618,29 +1768,162 @@ pub fn start<C: 'static + Component>() -> (Application, Scope<C>) {
         )
     });
     app.set_default();
+    let options = C::command_line_options();
+    for option in &options {
+        option.add_to(&app);
+    }
     app.register(None as Option<&Cancellable>)
         .expect("unable to register Application");
 
     let scope = partial_task.scope();
+    let command_line_scope = scope.clone();
+    let open_scope = scope.clone();
+    let activate_scope = scope.clone();
     let const_app = app.clone();
 
     let constructor = once(move |_| {
         let (channel, task) = partial_task.finalise();
         MainContext::ref_thread_default().spawn_local(task);
         channel.unbounded_send(ComponentMessage::Mounted).unwrap();
+        let quit_channel = channel.clone();
+        set_quit_request(Some(Box::new(move |window| {
+            let _ = quit_channel.unbounded_send(ComponentMessage::Quit(window));
+        })));
         const_app.connect_shutdown(move |_| {
+            set_quit_request(None);
             channel.unbounded_send(ComponentMessage::Unmounted).unwrap();
         });
     });
 
-    app.connect_activate(move |_| {
-        debug!("{}", "Application has activated.".bright_blue());
-        constructor(());
+    // Closing the last window quits the application, so the top level
+    // component gets to decide whether it may close.
+    app.connect_window_added(|app, window| {
+        let app = app.downgrade();
+        window.connect_delete_event(move |window, _| {
+            let last = app
+                .upgrade()
+                .map(|app| app.get_windows().len() == 1)
+                .unwrap_or(false);
+            Inhibit(last && request_quit(Some(window.clone())))
+        });
+    });
+
+    let activated = std::cell::Cell::new(false);
+    app.connect_activate(move |app| {
+        if !activated.replace(true) {
+            debug!("{}", "Application has activated.".bright_blue());
+            constructor(());
+            return;
+        }
+        debug!("{}", "Application has been activated again.".bright_blue());
+        if let Some(window) = app.get_active_window() {
+            window.present();
+        }
+        if let Some(message) = C::activated() {
+            activate_scope.send(message);
+        }
+    });
+
+    app.connect_command_line(move |app, command_line| {
+        let command_line = CommandLine::new(command_line, &options);
+        debug!(
+            "{} {:?}",
+            "Application has received a command line:".bright_blue(),
+            command_line.arguments()
+        );
+        app.activate();
+        if let Some(message) = C::command_line(&command_line) {
+            command_line_scope.send(message);
+        }
+        0
+    });
+
+    app.connect_open(move |app, files, _| {
+        debug!(
+            "{} {:?}",
+            "Application has been asked to open:".bright_blue(),
+            files.iter().map(|file| file.get_uri()).collect::<Vec<_>>()
+        );
+        app.activate();
+        if let Some(message) = C::open(files) {
+            open_scope.send(message);
+        }
     });
 
     (app, scope)
 }
 
+/// Run an [`Application`][Application] component until termination, saving its
+/// state when it exits and restoring it when it starts.
+///
+/// This works just like [`vgtk::run()`][run], except the component is
+/// constructed from the state it was in when the application last exited, if
+/// it was saved, rather than with [`Component::create()`][create]. See
+/// [`Persistent`][Persistent] for details.
+///
+/// This is only available with the `persist` feature enabled.
+///
+/// [Application]: ../gtk/struct.Application.html
+/// [run]: fn.run.html
+/// [create]: trait.Component.html#method.create
+/// [Persistent]: trait.Persistent.html
+#[cfg(feature = "persist")]
+pub fn run_persistent<C: 'static + Persistent>() -> i32 {
+    gtk::init().expect("GTK failed to initialise");
+    let partial_task = PartialComponentTask::<C, ()>::with_state(None, None, || {
+        persist::restore().unwrap_or_else(|| C::create(Default::default()))
+    })
+    .on_unmount(persist::save::<C>);
+    let (app, _) = start_task(partial_task);
+    let args: Vec<String> = std::env::args().collect();
+    let result = app.run(&args);
+    // The component only unmounts, and saves its state, once the main loop
+    // gets to process the message sent when the application shut down.
+    let context = MainContext::default();
+    while context.pending() {
+        context.iteration(false);
+    }
+    result
+}
+
+/// Run an [`Application`][Application] component until termination, with a
+/// debugger window alongside it.
+///
+/// This works just like [`vgtk::run()`][run], except that every message your
+/// top level component receives is recorded along with a snapshot of its state
+/// afterwards, and listed in the debugger window. Selecting a message, or
+/// stepping back and forth with the window's buttons, puts the component back
+/// in the state it had after that message, and sending a new message from
+/// there forgets the messages which came after it. Replaying sends every
+/// recorded message again, starting from the initial state, which is useful
+/// for checking that a bug in your view or your update method has been fixed.
+///
+/// Only the top level component is recorded, and travelling in time only
+/// changes its own state, not the state of its subcomponents.
+///
+/// This is only available with the `debug` feature enabled.
+///
+/// [Application]: ../gtk/struct.Application.html
+/// [run]: fn.run.html
+#[cfg(feature = "debug")]
+pub fn run_with_debugger<C: 'static + Component + Clone + std::fmt::Debug>() -> i32 {
+    gtk::init().expect("GTK failed to initialise");
+    let (partial_task, timeline) = debugger::record(PartialComponentTask::<C, ()>::new(
+        Default::default(),
+        None,
+        None,
+    ));
+    let (app, _) = start_task(partial_task);
+    let opened = std::cell::Cell::new(false);
+    app.connect_activate(move |_| {
+        if !opened.replace(true) {
+            debugger::open(timeline.clone());
+        }
+    });
+    let args: Vec<String> = std::env::args().collect();
+    app.run(&args)
+}
+
 /// Launch a [`Dialog`][Dialog] component as a modal dialog.
 ///
 /// The parent window will be blocked until it resolves.
@@ -680,6 +1963,161 @@ pub fn run_dialog<C: 'static + Component>(
     result
 }
 
+/// Launch a [`DialogComponent`][DialogComponent] as a modal dialog, constructed
+/// from `props`.
+///
+/// The parent window will be blocked until it resolves.
+///
+/// It returns a [`Future`][Future] which resolves to the component's
+/// [`output()`][output] once the dialog responds, or is destroyed, after which
+/// the component is unmounted. If `parent` is `None`, the
+/// [current window][current_window] is used.
+///
+/// If the component doesn't have a [`Dialog`][Dialog] (or something which implements [`Dialog`][Dialog])
+/// as its top level object, this function will panic.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{Component, DialogComponent, UpdateAction, VNode};
+/// # use vgtk::lib::gtk::ResponseType;
+/// # #[derive(Default)] struct Rename;
+/// # impl Component for Rename { type Message = (); type Properties = String;
+/// # fn view(&self) -> VNode<Self> { todo!() } }
+/// # impl DialogComponent for Rename { type Output = Option<String>;
+/// # fn output(&self, _: ResponseType) -> Option<String> { todo!() } }
+/// # #[derive(Clone, Debug)] enum Message { Rename, Renamed(Option<String>) }
+/// # #[derive(Default)] struct Files { name: String }
+/// # impl Component for Files { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Self> { todo!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::Rename => {
+///             let name = vgtk::run_component_dialog::<Rename>(None, self.name.clone());
+///             async move { Message::Renamed(name.await) }.into()
+///         }
+///         Message::Renamed(Some(name)) => {
+///             self.name = name;
+///             UpdateAction::Render
+///         }
+///         Message::Renamed(None) => UpdateAction::None,
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [DialogComponent]: trait.DialogComponent.html
+/// [Dialog]: ../gtk/struct.Dialog.html
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [output]: trait.DialogComponent.html#tymethod.output
+/// [current_window]: fn.current_window.html
+pub fn run_component_dialog<C: 'static + DialogComponent>(
+    parent: Option<&Window>,
+    props: C::Properties,
+) -> impl Future<Output = C::Output> {
+    let response = std::rc::Rc::new(std::cell::Cell::new(ResponseType::None));
+    let (notify, result) = oneshot::channel();
+    let responded = response.clone();
+    let (channel, task) = PartialComponentTask::<C, ()>::new(props, None, None)
+        .on_unmount(move |state: &C| {
+            let _ = notify.send(state.output(responded.get()));
+        })
+        .finalise();
+    let dialog: Dialog = task
+        .object()
+        .unwrap()
+        .downcast()
+        .expect("Dialog must be a gtk::Dialog");
+    if let Some(parent) = parent.cloned().or_else(current_window) {
+        dialog.set_transient_for(Some(&parent));
+    }
+    dialog.set_modal(true);
+    MainContext::ref_thread_default().spawn_local(task);
+    channel.unbounded_send(ComponentMessage::Mounted).unwrap();
+    let unmount = channel.clone();
+    dialog.connect_response(move |_, answer| {
+        response.set(answer);
+        // Only the first response counts, as the component's gone after it.
+        let _ = unmount.unbounded_send(ComponentMessage::Unmounted);
+    });
+    dialog.connect_destroy(move |_| {
+        let _ = channel.unbounded_send(ComponentMessage::Unmounted);
+    });
+    dialog.present();
+    async move {
+        result
+            .await
+            .expect("dialog component went away without unmounting")
+    }
+}
+
+/// Open a new top level window, hosting a component of its own.
+///
+/// The component is constructed from `props`, and its top level object must
+/// be a [`Window`][Window], which is added to the running
+/// [`Application`][Application] and shown. The application keeps running
+/// for as long as any of its windows are open, and the component is
+/// unmounted when its window is closed, which it can do itself through
+/// [`vgtk::current_window()`][current_window].
+///
+/// The [`Scope`][Scope] it returns lets you send the new component messages.
+/// Windows which don't need a component of their own can simply be declared
+/// as further children of your [`Application`][Application] instead.
+///
+/// If the component doesn't have a [`Window`][Window] as its top level
+/// object, this function will panic.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{Component, UpdateAction, VNode};
+/// # #[derive(Clone, Debug, Default)] struct Document;
+/// # #[derive(Clone, Debug, Default, PartialEq)] struct DocumentProps { path: String }
+/// # impl Component for Document { type Message = (); type Properties = DocumentProps;
+/// # fn view(&self) -> VNode<Self> { todo!() } }
+/// # #[derive(Clone, Debug)] enum Message { Open(String) }
+/// # #[derive(Default)] struct App;
+/// # impl Component for App { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Self> { todo!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::Open(path) => {
+///             vgtk::open_window::<Document>(DocumentProps { path });
+///             UpdateAction::None
+///         }
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [Window]: ../gtk/struct.Window.html
+/// [Application]: ../gtk/struct.Application.html
+/// [current_window]: fn.current_window.html
+/// [Scope]: struct.Scope.html
+pub fn open_window<C: 'static + Component>(props: C::Properties) -> Scope<C> {
+    let (channel, task) = ComponentTask::<C, ()>::new(props, None, None);
+    let object = task.object().unwrap();
+    let window: Window = object.clone().downcast().unwrap_or_else(|_| {
+        panic!(
+            "The top level object of a window component must be a Window, but {} was found.",
+            object.get_type()
+        )
+    });
+    let app = gio::Application::get_default().and_then(|app| app.downcast::<Application>().ok());
+    if let Some(app) = app {
+        app.add_window(&window);
+    }
+    let scope = task.scope();
+    MainContext::ref_thread_default().spawn_local(task);
+    channel.unbounded_send(ComponentMessage::Mounted).unwrap();
+    window.connect_destroy(move |_| {
+        // The component may already have gone away with the application.
+        let _ = channel.unbounded_send(ComponentMessage::Unmounted);
+    });
+    window.present();
+    scope
+}
+
 /// Turn an `FnOnce(A)` into an `Fn(A)` that will panic if you call it twice.
 fn once<A, F: FnOnce(A)>(f: F) -> impl Fn(A) {
     use std::cell::Cell;
@@ -695,21 +2133,101 @@ fn once<A, F: FnOnce(A)>(f: F) -> impl Fn(A) {
     }
 }
 
+/// Run a [`Future`][Future] on the GTK main loop, from any thread.
+///
+/// The future must be `Send`, as it's handed over to the main thread. It's
+/// polled there along with everything else GTK does, so it shouldn't block,
+/// and it can do anything the main thread can, like sending a message to a
+/// component through its [`Scope`][Scope].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{Component, Scope, VNode};
+/// # #[derive(Clone, Debug)] enum Message { Loaded(String) }
+/// # #[derive(Default)] struct Comp;
+/// # impl Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Self> { todo!() }
+/// fn mounted(&mut self) {
+///     let scope = Scope::<Self>::current();
+///     std::thread::spawn(move || {
+///         let text = std::fs::read_to_string("notes.txt").unwrap_or_default();
+///         vgtk::spawn(async move { scope.send(Message::Loaded(text)) });
+///     });
+/// }
+/// # }
+/// ```
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [Scope]: struct.Scope.html
+pub fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    MainContext::default().spawn(future);
+}
+
+/// Run a [`Future`][Future] on the GTK main loop, from the main thread.
+///
+/// Unlike [`vgtk::spawn()`][spawn], the future doesn't have to be `Send`, so it
+/// can hold on to GTK objects. To turn the result of a future into a message
+/// for a component, use [`Scope::spawn()`][Scope::spawn] instead.
+///
+/// # Panics
+///
+/// Panics if it's called from a thread other than the one running the GTK
+/// main loop.
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [spawn]: fn.spawn.html
+/// [Scope::spawn]: struct.Scope.html#method.spawn
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) {
+    MainContext::ref_thread_default().spawn_local(future);
+}
+
 /// Tell the running [`Application`][Application] to quit.
 ///
 /// This calls [`Application::quit()`][Application::quit] on the current default
 /// [`Application`][Application]. It will cause the [`vgtk::run()`][run] in
 /// charge of that [`Application`][Application] to terminate.
 ///
+/// If the application was started with [`vgtk::run()`][run], its top level
+/// component is asked first, through [`Component::on_quit()`][on_quit], and
+/// the application only quits if it agrees.
+///
 /// [Application]: ../gtk/struct.Application.html
 /// [Application::quit]: ../gio/trait.ApplicationExt.html#tymethod.quit
 /// [run]: fn.run.html
+/// [on_quit]: trait.Component.html#method.on_quit
 pub fn quit() {
+    if !request_quit(None) {
+        quit_now();
+    }
+}
+
+pub(crate) fn quit_now() {
     gio::Application::get_default()
         .expect("no default Application!")
         .quit();
 }
 
+thread_local! {
+    static QUIT_REQUEST: RefCell<Option<Box<dyn Fn(Option<Window>)>>> = const { RefCell::new(None) };
+}
+
+fn set_quit_request(request: Option<Box<dyn Fn(Option<Window>)>>) {
+    QUIT_REQUEST.with(|quit_request| *quit_request.borrow_mut() = request);
+}
+
+/// Ask the running top level component whether the application may quit,
+/// closing `window` if it may. Returns `false` if there's nobody to ask.
+fn request_quit(window: Option<Window>) -> bool {
+    QUIT_REQUEST.with(|quit_request| match &*quit_request.borrow() {
+        Some(request) => {
+            request(window);
+            true
+        }
+        None => false,
+    })
+}
+
 /// Connect a GLib signal to a [`Future`][Future].
 ///
 /// This macro takes a GLib object and the name of a method to connect it to a
@@ -802,6 +2320,42 @@ macro_rules! stream_signal {
     }};
 }
 
+/// Collect several messages for a signal handler to send.
+///
+/// Each argument can be anything a signal handler could return on its own: a
+/// message, an `Option` of a message, or a `Vec` of messages. They're collected,
+/// in order, into a single `Vec`, which the handler can return to send them
+/// all. The component only renders once they've all been processed.
+///
+/// See [`IntoMessages`][IntoMessages] for the details.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, msgs, VNode, Component};
+/// # use vgtk::lib::gtk::{Button, ButtonExt};
+/// # #[derive(Clone, Debug)] enum Message { Save, Close }
+/// # #[derive(Default)] struct Comp { dirty: bool }
+/// # impl Component for Comp { type Message = Message; type Properties = (); fn view(&self) -> VNode<Self> {
+/// let dirty = self.dirty;
+/// gtk! {
+///     <Button label="Close" on clicked=|_| {
+///         msgs![if dirty { Some(Message::Save) } else { None }, Message::Close]
+///     } />
+/// }
+/// # }}
+/// ```
+///
+/// [IntoMessages]: trait.IntoMessages.html
+#[macro_export]
+macro_rules! msgs {
+    ($($message:expr),* $(,)?) => {{
+        let mut messages = ::std::vec::Vec::new();
+        $($crate::scope::extend_messages(&mut messages, $message);)*
+        messages
+    }};
+}
+
 /// Open a simple [`MessageDialog`][MessageDialog].
 ///
 /// The arguments are passed directly to [`MessageDialog::new()`][new].
@@ -845,14 +2399,77 @@ where
     S: AsRef<str>,
 {
     let dialog = MessageDialog::new(parent, flags, message_type, buttons, message.as_ref());
-    dialog.set_modal(true);
     if is_markup {
         dialog.set_markup(message.as_ref());
     }
-    dialog.show();
-    let response = on_signal!(dialog, connect_response).await;
-    dialog.destroy();
-    response.unwrap()
+    present_dialog(&dialog).await
+}
+
+/// Run a [`Dialog`][Dialog] as a modal dialog.
+///
+/// This presents the dialog, attaching it to the [current window][current_window] if it
+/// doesn't already have a parent, and returns a [`Future`][Future] which resolves to the
+/// [`ResponseType`][ResponseType] the user responds with, destroying the dialog once they
+/// have. If the dialog is destroyed without a response, it resolves to
+/// [`ResponseType::None`][ResponseType].
+///
+/// This lets you write confirmation flows linearly inside an
+/// [`UpdateAction::defer()`][defer] block, rather than connecting to the dialog's signals
+/// by hand.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Delete, Deleted, Cancelled }
+/// # #[derive(Default)] struct Comp;
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> vgtk::VNode<Comp> { unimplemented!() }
+/// fn update(&mut self, message: Message) -> vgtk::UpdateAction<Self> {
+///     match message {
+///         Message::Delete => {
+///             let dialog = MessageDialog::new(
+///                 None::<&Window>,
+///                 DialogFlags::MODAL,
+///                 MessageType::Question,
+///                 ButtonsType::OkCancel,
+///                 "Really delete?",
+///             );
+///             let response = vgtk::present_dialog(&dialog);
+///             async move {
+///                 match response.await {
+///                     ResponseType::Ok => Message::Deleted,
+///                     _ => Message::Cancelled,
+///                 }
+///             }
+///             .into()
+///         }
+///         Message::Deleted => vgtk::UpdateAction::Render,
+///         Message::Cancelled => vgtk::UpdateAction::None,
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [Dialog]: ../gtk/struct.Dialog.html
+/// [ResponseType]: ../gtk/enum.ResponseType.html
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [current_window]: fn.current_window.html
+/// [defer]: enum.UpdateAction.html#method.defer
+pub fn present_dialog<D: IsA<Dialog>>(dialog: &D) -> impl Future<Output = ResponseType> {
+    let dialog: Dialog = dialog.as_ref().clone();
+    if dialog.get_transient_for().is_none() {
+        if let Some(parent) = current_window() {
+            dialog.set_transient_for(Some(&parent));
+        }
+    }
+    dialog.set_modal(true);
+    dialog.present();
+    async move {
+        let response = on_signal!(dialog, connect_response).await;
+        dialog.destroy();
+        response.unwrap_or(ResponseType::None)
+    }
 }
 
 /// Generate a virtual component tree only if a condition is true.
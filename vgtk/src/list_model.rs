@@ -0,0 +1,337 @@
+use gio::{ListModelExt, ListStore, ListStoreExt};
+use glib::{Cast, Object, ObjectExt, StaticType, WeakRef};
+use gtk::{ListBox, ListBoxExt, Widget, WidgetExt};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use crate::component::Component;
+use crate::scope::Scope;
+use crate::vdom::State;
+use crate::vnode::VNode;
+
+thread_local! {
+    static BOUND_MODELS: RefCell<Vec<(WeakRef<ListBox>, gio::ListModel)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Get the model currently bound to a `ListBox` through a `ListBinding`.
+pub(crate) fn bound_model(list_box: &ListBox) -> Option<gio::ListModel> {
+    BOUND_MODELS.with(|models| {
+        let mut models = models.borrow_mut();
+        models.retain(|(bound, _)| bound.upgrade().is_some());
+        models
+            .iter()
+            .find(|(bound, _)| bound.upgrade().as_ref() == Some(list_box))
+            .map(|(_, model)| model.clone())
+    })
+}
+
+/// A list of items displayed in a [`ListBox`][ListBox] through GTK's own
+/// model binding, rather than by rendering every row in your view.
+///
+/// Rendering a list with thousands of rows in the [`gtk!`][gtk!] macro means
+/// building and diffing thousands of nodes every time your component renders.
+/// Keep the items in a `ListModel` instead, and bind it to a
+/// [`ListBox`][ListBox] with its `model` property and
+/// [`bind()`][bind]. GTK then asks for a row only when an item is
+/// added, and destroys it when the item is removed, so changing one item
+/// only touches one row, and the rest of your view never has to know about
+/// the list at all.
+///
+/// A `ListModel` is a shared handle, so cloning it gives you another handle to
+/// the same list.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, ListModel, UpdateAction, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Add, Remove(usize) }
+/// fn render_row(name: &String) -> VNode<Names> {
+///     gtk! {
+///         <Label label=name.clone() xalign=0.0 />
+///     }
+/// }
+///
+/// #[derive(Default)]
+/// struct Names {
+///     names: ListModel<String>,
+/// }
+///
+/// impl Component for Names {
+///     type Message = Message;
+///     type Properties = ();
+///
+///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///         match message {
+///             Message::Add => self.names.push(format!("Name #{}", self.names.len())),
+///             Message::Remove(index) => {
+///                 self.names.remove(index);
+///             }
+///         }
+///         UpdateAction::None
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         gtk! {
+///             <ScrolledWindow>
+///                 <ListBox model=self.names.bind(render_row)
+///                          on row_activated=|_, row| Message::Remove(row.get_index() as usize) />
+///             </ScrolledWindow>
+///         }
+///     }
+/// }
+/// ```
+///
+/// [ListBox]: ../gtk/struct.ListBox.html
+/// [gtk!]: macro.gtk.html
+/// [bind]: #method.bind
+pub struct ListModel<T> {
+    store: ListStore,
+    items: Rc<RefCell<HashMap<Object, T>>>,
+}
+
+impl<T: 'static> ListModel<T> {
+    /// Construct an empty `ListModel`.
+    pub fn new() -> Self {
+        ListModel {
+            store: ListStore::new(Object::static_type()),
+            items: Default::default(),
+        }
+    }
+
+    // Each item is represented in the store by a placeholder object, which
+    // the row renderer uses to look the item up again.
+    fn placeholder(&self, item: T) -> Object {
+        let object = Object::new(Object::static_type(), &[])
+            .expect("unable to construct a ListModel placeholder");
+        self.items.borrow_mut().insert(object.clone(), item);
+        object
+    }
+
+    fn object(&self, index: usize) -> Object {
+        self.store
+            .get_object(index as u32)
+            .unwrap_or_else(|| panic!("index {} is out of bounds for a ListModel", index))
+    }
+
+    /// Get the number of items in the list.
+    pub fn len(&self) -> usize {
+        self.store.get_n_items() as usize
+    }
+
+    /// Test whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get a copy of the item at `index`, if there is one.
+    pub fn get(&self, index: usize) -> Option<T>
+    where
+        T: Clone,
+    {
+        let object = self.store.get_object(index as u32)?;
+        self.items.borrow().get(&object).cloned()
+    }
+
+    /// Add an item to the end of the list.
+    pub fn push(&self, item: T) {
+        let object = self.placeholder(item);
+        self.store.append(&object);
+    }
+
+    /// Insert an item at `index`, moving everything after it along.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length of the list.
+    pub fn insert(&self, index: usize, item: T) {
+        if index > self.len() {
+            panic!("index {} is out of bounds for a ListModel", index);
+        }
+        let object = self.placeholder(item);
+        self.store.insert(index as u32, &object);
+    }
+
+    /// Remove and return the item at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&self, index: usize) -> T {
+        let object = self.object(index);
+        self.store.remove(index as u32);
+        self.items
+            .borrow_mut()
+            .remove(&object)
+            .expect("ListModel item has gone missing")
+    }
+
+    /// Replace the item at `index`, rendering its row again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, item: T) {
+        let old = self.object(index);
+        let object = self.placeholder(item);
+        self.store.splice(index as u32, 1, &[object]);
+        self.items.borrow_mut().remove(&old);
+    }
+
+    /// Remove every item from the list.
+    pub fn clear(&self) {
+        self.store.remove_all();
+        self.items.borrow_mut().clear();
+    }
+
+    /// Bring the list up to date with `items`.
+    ///
+    /// Items at the start and end of the list which haven't changed are left
+    /// alone, and only the rows in between are replaced, so this is a cheap
+    /// way to keep a `ListModel` in step with a list you keep elsewhere.
+    pub fn update(&self, items: impl IntoIterator<Item = T>)
+    where
+        T: PartialEq,
+    {
+        let mut items: Vec<T> = items.into_iter().collect();
+        let len = self.len();
+        let (prefix, suffix) = {
+            let current = self.items.borrow();
+            let item_at = |index: usize| current.get(&self.object(index));
+            let prefix = (0..len.min(items.len()))
+                .take_while(|&index| item_at(index) == Some(&items[index]))
+                .count();
+            let suffix = (0..(len - prefix).min(items.len() - prefix))
+                .take_while(|&offset| {
+                    item_at(len - 1 - offset) == Some(&items[items.len() - 1 - offset])
+                })
+                .count();
+            (prefix, suffix)
+        };
+        let removed: Vec<Object> = (prefix..len - suffix)
+            .map(|index| self.object(index))
+            .collect();
+        items.truncate(items.len() - suffix);
+        let added: Vec<Object> = items
+            .drain(prefix..)
+            .map(|item| self.placeholder(item))
+            .collect();
+        self.store
+            .splice(prefix as u32, removed.len() as u32, &added);
+        let mut current = self.items.borrow_mut();
+        for object in removed {
+            current.remove(&object);
+        }
+    }
+
+    /// Construct a binding for a [`ListBox`][ListBox]'s `model` property,
+    /// which renders each item into a row with `render`.
+    ///
+    /// Rows are built like any other part of your view, and their signal
+    /// handlers send messages to your component. Each row is rendered once,
+    /// when its item is added, so `render` can only depend on the item.
+    ///
+    /// Call this from your component's [`view`][view] method. A `ListBox`
+    /// stays bound to the same `ListModel` until it's given a different one,
+    /// and it shouldn't have any children of its own in the meantime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it's called from outside the view of a component of type `C`.
+    ///
+    /// [ListBox]: ../gtk/struct.ListBox.html
+    /// [view]: trait.Component.html#tymethod.view
+    pub fn bind<C: 'static + Component>(&self, render: fn(&T) -> VNode<C>) -> ListBinding {
+        let scope = Scope::<C>::current();
+        let items = self.items.clone();
+        ListBinding {
+            model: self.store.clone().upcast(),
+            create: Rc::new(move |object| {
+                let node = render(
+                    items
+                        .borrow()
+                        .get(object)
+                        .expect("ListModel item has gone missing"),
+                );
+                let state = State::build(&node, None, &scope);
+                let widget = state.widget().cloned().unwrap_or_else(|| {
+                    panic!(
+                        "ListModel rows must be Widgets, but {} was found",
+                        state.object().get_type()
+                    )
+                });
+                // GTK destroys the row when its item is removed, which is our
+                // cue to unmount it.
+                let state = RefCell::new(Some(state));
+                widget.connect_destroy(move |_| {
+                    if let Some(state) = state.borrow_mut().take() {
+                        state.unmount();
+                    }
+                });
+                widget
+            }),
+        }
+    }
+}
+
+impl<T: 'static> Default for ListModel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ListModel<T> {
+    fn clone(&self) -> Self {
+        ListModel {
+            store: self.store.clone(),
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T> Debug for ListModel<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "ListModel[{}]", self.items.borrow().len())
+    }
+}
+
+/// A [`ListModel`][ListModel] bound to a row renderer, ready to be given to a
+/// [`ListBox`][ListBox]'s `model` property.
+///
+/// Construct one with [`ListModel::bind()`][bind].
+///
+/// [ListModel]: struct.ListModel.html
+/// [ListBox]: ../gtk/struct.ListBox.html
+/// [bind]: struct.ListModel.html#method.bind
+#[derive(Clone)]
+pub struct ListBinding {
+    pub(crate) model: gio::ListModel,
+    create: Rc<dyn Fn(&Object) -> Widget>,
+}
+
+impl ListBinding {
+    /// Bind the model to a `ListBox`, replacing any model bound before.
+    pub(crate) fn bind(&self, list_box: &ListBox) {
+        let create = self.create.clone();
+        list_box.bind_model(Some(&self.model), move |object| create(object));
+        BOUND_MODELS.with(|models| {
+            let mut models = models.borrow_mut();
+            models.retain(|(bound, _)| match bound.upgrade() {
+                Some(bound) => &bound != list_box,
+                None => false,
+            });
+            models.push((list_box.downgrade(), self.model.clone()));
+        });
+    }
+}
+
+impl Debug for ListBinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "ListBinding[{}]", self.model.get_n_items())
+    }
+}
@@ -1,6 +1,6 @@
 use gio::{Menu, MenuItem};
 
-/// Makes a [`gtk::Menu`][Menu] for you.
+/// Makes a [`gio::Menu`][Menu] for you.
 ///
 /// # Examples
 ///
@@ -19,7 +19,12 @@ use gio::{Menu, MenuItem};
 ///     ).build();
 /// ```
 ///
-/// [Menu]: https://gtk-rs.org/docs/gtk/struct.Menu.html
+/// The resulting menu model can be passed to properties like `menubar` on an
+/// `Application` or `menu_model` on a `MenuButton` inside the `gtk!` macro,
+/// and so can the builder itself, without calling [`build()`][build].
+///
+/// [Menu]: https://gtk-rs.org/docs/gio/struct.Menu.html
+/// [build]: #method.build
 pub struct MenuBuilder {
     menu: Menu,
 }
@@ -0,0 +1,129 @@
+use gio::prelude::*;
+use gio::{Notification, NotificationPriority, ThemedIcon};
+
+/// Sends a desktop notification for you.
+///
+/// Start one with [`vgtk::notify()`][notify], add whatever else it needs, and
+/// [`send()`][send] it. Notifications stay around after your window has been
+/// closed, so they're a good way of letting the user know that something
+/// they were waiting for, like a download, has finished.
+///
+/// Clicking a notification or one of its buttons activates an action, named
+/// like `"app.open"`, which you declare with
+/// [`Component::actions()`][actions] on your top level component like any
+/// other, so the click ends up at its [`update`][update] method as a message.
+/// Only `app` actions can be used, as the notification doesn't belong to any
+/// of your windows.
+///
+/// Notifications are sent through the default [`Application`][Application],
+/// which must have an application ID.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::UpdateAction;
+/// # #[derive(Clone, Debug)] enum Message { Downloaded(String) }
+/// # #[derive(Default)] struct Comp;
+/// # impl vgtk::Component for Comp { type Message = Message; type Properties = ();
+/// # fn view(&self) -> vgtk::VNode<Comp> { unimplemented!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::Downloaded(name) => {
+///             vgtk::notify("Download finished")
+///                 .body(&format!("{} has been downloaded.", name))
+///                 .icon_name("folder-download")
+///                 .default_action("app.show-downloads")
+///                 .button("Open", "app.open-download")
+///                 .id("download")
+///                 .send();
+///             UpdateAction::None
+///         }
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [notify]: fn.notify.html
+/// [send]: #method.send
+/// [actions]: trait.Component.html#method.actions
+/// [update]: trait.Component.html#method.update
+/// [Application]: ../gtk/struct.Application.html
+pub struct NotificationBuilder {
+    notification: Notification,
+    id: Option<String>,
+}
+
+/// Construct a [`NotificationBuilder`][NotificationBuilder] for a notification
+/// titled `title`.
+///
+/// [NotificationBuilder]: struct.NotificationBuilder.html
+pub fn notify(title: &str) -> NotificationBuilder {
+    NotificationBuilder {
+        notification: Notification::new(title),
+        id: None,
+    }
+}
+
+/// Take back the notification sent with the ID `id`, if it's still showing.
+pub fn withdraw_notification(id: &str) {
+    application().withdraw_notification(id);
+}
+
+fn application() -> gio::Application {
+    gio::Application::get_default().expect("no default Application!")
+}
+
+impl NotificationBuilder {
+    /// Set the text of the notification, below its title.
+    pub fn body(self, body: &str) -> Self {
+        self.notification.set_body(Some(body));
+        self
+    }
+
+    /// Set the notification's icon to a named icon from the icon theme.
+    pub fn icon_name(self, icon_name: &str) -> Self {
+        self.notification.set_icon(&ThemedIcon::new(icon_name));
+        self
+    }
+
+    /// Set how urgently the notification should be brought to the user's
+    /// attention.
+    pub fn priority(self, priority: NotificationPriority) -> Self {
+        self.notification.set_priority(priority);
+        self
+    }
+
+    /// Set the action activated when the notification itself is clicked.
+    ///
+    /// Without one, clicking it just activates your application.
+    pub fn default_action(self, action: &str) -> Self {
+        self.notification.set_default_action(action);
+        self
+    }
+
+    /// Add a button to the notification, which activates `action`.
+    pub fn button(self, label: &str, action: &str) -> Self {
+        self.notification.add_button(label, action);
+        self
+    }
+
+    /// Give the notification an ID, so that it replaces any notification
+    /// you sent earlier with the same ID, and can be taken back with
+    /// [`vgtk::withdraw_notification()`][withdraw_notification].
+    ///
+    /// [withdraw_notification]: fn.withdraw_notification.html
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Send the notification.
+    pub fn send(self) {
+        application().send_notification(self.id.as_deref(), &self.notification);
+    }
+
+    /// Finalise the `NotificationBuilder` and get your `Notification`.
+    pub fn build(self) -> Notification {
+        self.notification
+    }
+}
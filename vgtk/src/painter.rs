@@ -0,0 +1,145 @@
+use cairo::Context;
+use glib::{ObjectExt, WeakRef};
+use gtk::{DrawingArea, Inhibit, WidgetExt};
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+thread_local! {
+    static PAINTERS: RefCell<Vec<(WeakRef<DrawingArea>, Rc<RefCell<Painter>>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+struct PainterState<T> {
+    state: T,
+    paint: fn(&T, &DrawingArea, &Context),
+}
+
+fn same_state<T: 'static + PartialEq>(left: &dyn Any, right: &dyn Any) -> bool {
+    match (
+        left.downcast_ref::<PainterState<T>>(),
+        right.downcast_ref::<PainterState<T>>(),
+    ) {
+        (Some(left), Some(right)) => {
+            left.paint as usize == right.paint as usize && left.state == right.state
+        }
+        _ => false,
+    }
+}
+
+fn paint_state<T: 'static>(state: &dyn Any, area: &DrawingArea, context: &Context) {
+    let state = state
+        .downcast_ref::<PainterState<T>>()
+        .expect("painter holds state of the wrong type");
+    (state.paint)(&state.state, area, context)
+}
+
+/// A function which draws the contents of a [`DrawingArea`][DrawingArea],
+/// along with the state it draws from.
+///
+/// Give a `DrawingArea` a painter through its `painter` property, provided by
+/// [`DrawingAreaExtHelpers`][DrawingAreaExtHelpers]. Whenever GTK needs the
+/// area drawn, the painter's function is called with the state, the area and
+/// a cairo [`Context`][Context] to draw with.
+///
+/// The state is a snapshot of whatever your view passed in, usually a clone of
+/// part of your component's state, so the function can only read it. When the
+/// component renders again with a painter whose state has changed, the area
+/// is redrawn; if the state is the same, nothing happens.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Painter, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::cairo::Context;
+/// # use vgtk::lib::gtk::*;
+/// fn draw_bars(values: &Vec<f64>, area: &DrawingArea, cr: &Context) {
+///     let width = f64::from(area.get_allocated_width()) / values.len() as f64;
+///     let height = f64::from(area.get_allocated_height());
+///     for (index, value) in values.iter().enumerate() {
+///         cr.rectangle(index as f64 * width, height * (1.0 - value), width - 1.0, height * value);
+///     }
+///     cr.fill();
+/// }
+///
+/// # fn view(values: &Vec<f64>) -> VNode<()> {
+/// gtk! {
+///     <DrawingArea painter=Painter::new(values.clone(), draw_bars) />
+/// }
+/// # }
+/// ```
+///
+/// [DrawingArea]: ../gtk/struct.DrawingArea.html
+/// [DrawingAreaExtHelpers]: ext/trait.DrawingAreaExtHelpers.html
+/// [Context]: ../cairo/struct.Context.html
+#[derive(Clone)]
+pub struct Painter {
+    state: Rc<dyn Any>,
+    same: fn(&dyn Any, &dyn Any) -> bool,
+    paint: fn(&dyn Any, &DrawingArea, &Context),
+}
+
+impl Painter {
+    /// Construct a painter which draws `state` with `paint`.
+    pub fn new<T: 'static + PartialEq>(state: T, paint: fn(&T, &DrawingArea, &Context)) -> Self {
+        Painter {
+            state: Rc::new(PainterState { state, paint }),
+            same: same_state::<T>,
+            paint: paint_state::<T>,
+        }
+    }
+
+    /// Test whether two painters would draw the same thing.
+    pub(crate) fn is_same(&self, other: &Painter) -> bool {
+        (self.same)(&*self.state, &*other.state)
+    }
+
+    /// Give `area` this painter, redrawing it.
+    pub(crate) fn attach(self, area: &DrawingArea) {
+        let existing = PAINTERS.with(|painters| {
+            let mut painters = painters.borrow_mut();
+            painters.retain(|(area, _)| area.upgrade().is_some());
+            painters
+                .iter()
+                .find(|(painted, _)| painted.upgrade().as_ref() == Some(area))
+                .map(|(_, painter)| painter.clone())
+        });
+        match existing {
+            Some(painter) => *painter.borrow_mut() = self,
+            None => {
+                let painter = Rc::new(RefCell::new(self));
+                PAINTERS.with(|painters| {
+                    painters
+                        .borrow_mut()
+                        .push((area.downgrade(), painter.clone()))
+                });
+                area.connect_draw(move |area, context| {
+                    let painter = painter.borrow();
+                    (painter.paint)(&*painter.state, area, context);
+                    Inhibit(false)
+                });
+            }
+        }
+        area.queue_draw();
+    }
+
+    /// Get the painter `area` was last given.
+    pub(crate) fn attached(area: &DrawingArea) -> Option<Painter> {
+        PAINTERS.with(|painters| {
+            painters
+                .borrow()
+                .iter()
+                .find(|(painted, _)| painted.upgrade().as_ref() == Some(area))
+                .map(|(_, painter)| painter.borrow().clone())
+        })
+    }
+}
+
+impl Debug for Painter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Painter()")
+    }
+}
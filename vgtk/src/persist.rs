@@ -0,0 +1,119 @@
+use log::{debug, error, warn};
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use colored::Colorize;
+
+use crate::component::Component;
+
+/// A top level [`Component`][Component] whose state is saved when the
+/// application exits, and restored when it starts again.
+///
+/// Implement this for your top level component, along with serde's
+/// `Serialize` and `Deserialize`, and start your application with
+/// [`vgtk::run_persistent()`][run_persistent] instead of
+/// [`vgtk::run()`][run]. Fields which can't or shouldn't be saved, like open
+/// files, can be left out with `#[serde(skip)]`, and are restored to their
+/// default values.
+///
+/// This is only available with the `persist` feature enabled.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, Persistent, VNode};
+/// # use serde_derive::{Deserialize, Serialize};
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct Notes {
+///     notes: Vec<String>,
+///     #[serde(skip)]
+///     editing: Option<usize>,
+/// }
+///
+/// impl Persistent for Notes {}
+/// #
+/// # impl Component for Notes {
+/// #     type Message = ();
+/// #     type Properties = ();
+/// #     fn view(&self) -> VNode<Self> { todo!() }
+/// # }
+///
+/// fn main() {
+///     std::process::exit(vgtk::run_persistent::<Notes>());
+/// }
+/// ```
+///
+/// [Component]: trait.Component.html
+/// [run_persistent]: fn.run_persistent.html
+/// [run]: fn.run.html
+pub trait Persistent: Component + Serialize + DeserializeOwned {
+    /// Get the path of the file the component's state is saved to.
+    ///
+    /// The default is `state.json` in a directory named after your program
+    /// inside the user's data directory, following the XDG base directory
+    /// specification, usually `~/.local/share/<program>/state.json`.
+    fn state_path() -> PathBuf {
+        let program = glib::get_prgname().unwrap_or_else(|| "vgtk".to_string());
+        glib::get_user_data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(program)
+            .join("state.json")
+    }
+}
+
+/// Load the state saved for a component, if there is any.
+///
+/// State which can't be read is logged and ignored, so the application can
+/// still start with its default state.
+pub(crate) fn restore<C: Persistent>() -> Option<C> {
+    let path = C::state_path();
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == ErrorKind::NotFound => return None,
+        Err(err) => {
+            warn!(
+                "unable to read saved state from {}: {}",
+                path.display(),
+                err
+            );
+            return None;
+        }
+    };
+    match serde_json::from_slice(&data) {
+        Ok(state) => {
+            debug!(
+                "{} {}",
+                "Restored state from".bright_blue(),
+                path.display().to_string().magenta().bold()
+            );
+            Some(state)
+        }
+        Err(err) => {
+            warn!("unable to parse saved state in {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+/// Save a component's state, logging any failure to do so.
+pub(crate) fn save<C: Persistent>(state: &C) {
+    let path = C::state_path();
+    let result = serde_json::to_vec_pretty(state)
+        .map_err(|err| err.to_string())
+        .and_then(|data| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            }
+            std::fs::write(&path, data).map_err(|err| err.to_string())
+        });
+    match result {
+        Ok(()) => debug!(
+            "{} {}",
+            "Saved state to".bright_blue(),
+            path.display().to_string().magenta().bold()
+        ),
+        Err(err) => error!("unable to save state to {}: {}", path.display(), err),
+    }
+}
@@ -0,0 +1,342 @@
+//! Asking the desktop portals for files and screenshots.
+//!
+//! An application running in a sandbox, like Flatpak, can't look around the
+//! user's files or their screen. Instead, it asks the [XDG desktop
+//! portals][portals] over D-Bus, which show the user a dialog outside the
+//! sandbox and hand back only what the user picked.
+//!
+//! [`OpenFile`][OpenFile], [`SaveFile`][SaveFile] and
+//! [`screenshot()`][screenshot] each return a [`Future`][Future] which
+//! resolves once the user's done, so you can use them from an
+//! [`UpdateAction::defer()`][defer] block and turn the result into a
+//! message. They resolve to an error if the portal can't be reached, which
+//! is usually the case outside a desktop session.
+//!
+//! Portal dialogs aren't attached to any of your windows, as that needs a
+//! window handle which GDK doesn't give out here.
+//!
+//! This module is only available with the `portal` feature enabled, which
+//! also enables the `dbus` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use std::path::PathBuf;
+//! use vgtk::portal::OpenFile;
+//! use vgtk::UpdateAction;
+//! # use vgtk::{Component, VNode};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Open,
+//!     Opened(Vec<PathBuf>),
+//! }
+//!
+//! # #[derive(Default)] struct Viewer;
+//! # impl Component for Viewer { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Self> { todo!() }
+//! fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!     match message {
+//!         Message::Open => {
+//!             let paths = OpenFile::new("Open Images")
+//!                 .filter("Images", &["*.png", "*.jpg"])
+//!                 .select_multiple(true)
+//!                 .run();
+//!             UpdateAction::defer(async move {
+//!                 Message::Opened(paths.await.unwrap_or_default())
+//!             })
+//!         }
+//!         Message::Opened(paths) => UpdateAction::Render,
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! [portals]: https://flatpak.github.io/xdg-desktop-portal/
+//! [OpenFile]: struct.OpenFile.html
+//! [SaveFile]: struct.SaveFile.html
+//! [screenshot]: fn.screenshot.html
+//! [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+//! [defer]: ../enum.UpdateAction.html#method.defer
+
+use futures::stream::StreamExt;
+use gio::{File, FileExt};
+use serde::Serialize;
+use zbus::message::Type as MessageType;
+use zbus::zvariant::{DynamicType, OwnedValue, Value};
+use zbus::{MatchRule, MessageStream};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::dbus::{connection, Bus};
+
+type Options = HashMap<&'static str, Value<'static>>;
+type Results = HashMap<String, OwnedValue>;
+type Filter = (String, Vec<(u32, String)>);
+
+/// Builds and runs a portal file chooser for opening files.
+///
+/// See the [module documentation][portal] for an example.
+///
+/// [portal]: index.html
+#[derive(Debug)]
+pub struct OpenFile {
+    title: String,
+    options: Options,
+    filters: Vec<Filter>,
+}
+
+impl OpenFile {
+    /// Construct a file chooser with the given title.
+    pub fn new(title: &str) -> Self {
+        OpenFile {
+            title: title.to_string(),
+            options: Options::new(),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Set the label of the accept button.
+    pub fn accept_label(mut self, label: &str) -> Self {
+        self.options
+            .insert("accept_label", label.to_string().into());
+        self
+    }
+
+    /// Allow the user to pick more than one file.
+    pub fn select_multiple(mut self, select_multiple: bool) -> Self {
+        self.options.insert("multiple", select_multiple.into());
+        self
+    }
+
+    /// Have the user pick folders instead of files.
+    pub fn select_folder(mut self, select_folder: bool) -> Self {
+        self.options.insert("directory", select_folder.into());
+        self
+    }
+
+    /// Add a named filter matching the given glob patterns, such as `"*.txt"`.
+    pub fn filter(mut self, name: &str, patterns: &[&str]) -> Self {
+        self.filters.push(filter(name, patterns));
+        self
+    }
+
+    /// Set the folder the file chooser starts out in.
+    pub fn current_folder(mut self, folder: impl AsRef<Path>) -> Self {
+        self.options
+            .insert("current_folder", path_bytes(folder.as_ref()));
+        self
+    }
+
+    /// Show the file chooser.
+    ///
+    /// The returned [`Future`][Future] resolves to the paths the user picked,
+    /// or to an empty list if they cancelled.
+    ///
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub fn run(self) -> impl Future<Output = zbus::Result<Vec<PathBuf>>> {
+        let OpenFile {
+            title,
+            mut options,
+            filters,
+        } = self;
+        if !filters.is_empty() {
+            options.insert("filters", filters.into());
+        }
+        async move {
+            let results = request(
+                "org.freedesktop.portal.FileChooser",
+                "OpenFile",
+                |options| ("", title, options),
+                options,
+            )
+            .await?;
+            Ok(results.map(chosen_paths).unwrap_or_default())
+        }
+    }
+}
+
+/// Builds and runs a portal file chooser for saving a file.
+///
+/// The portal asks the user for confirmation before choosing an existing
+/// file.
+#[derive(Debug)]
+pub struct SaveFile {
+    title: String,
+    options: Options,
+    filters: Vec<Filter>,
+}
+
+impl SaveFile {
+    /// Construct a file chooser with the given title.
+    pub fn new(title: &str) -> Self {
+        SaveFile {
+            title: title.to_string(),
+            options: Options::new(),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Set the label of the accept button.
+    pub fn accept_label(mut self, label: &str) -> Self {
+        self.options
+            .insert("accept_label", label.to_string().into());
+        self
+    }
+
+    /// Add a named filter matching the given glob patterns, such as `"*.txt"`.
+    pub fn filter(mut self, name: &str, patterns: &[&str]) -> Self {
+        self.filters.push(filter(name, patterns));
+        self
+    }
+
+    /// Set the folder the file chooser starts out in.
+    pub fn current_folder(mut self, folder: impl AsRef<Path>) -> Self {
+        self.options
+            .insert("current_folder", path_bytes(folder.as_ref()));
+        self
+    }
+
+    /// Set the suggested file name.
+    pub fn current_name(mut self, name: &str) -> Self {
+        self.options.insert("current_name", name.to_string().into());
+        self
+    }
+
+    /// Show the file chooser.
+    ///
+    /// The returned [`Future`][Future] resolves to the path the user picked,
+    /// or to `None` if they cancelled.
+    ///
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub fn run(self) -> impl Future<Output = zbus::Result<Option<PathBuf>>> {
+        let SaveFile {
+            title,
+            mut options,
+            filters,
+        } = self;
+        if !filters.is_empty() {
+            options.insert("filters", filters.into());
+        }
+        async move {
+            let results = request(
+                "org.freedesktop.portal.FileChooser",
+                "SaveFile",
+                |options| ("", title, options),
+                options,
+            )
+            .await?;
+            Ok(results.and_then(|results| chosen_paths(results).into_iter().next()))
+        }
+    }
+}
+
+/// Ask the portal for a screenshot.
+///
+/// If `interactive` is `true`, the user gets to pick what to take a
+/// screenshot of first. The returned [`Future`][Future] resolves to the path
+/// of the image file the portal saved it in, or to `None` if the user
+/// cancelled.
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+pub fn screenshot(interactive: bool) -> impl Future<Output = zbus::Result<Option<PathBuf>>> {
+    let mut options = Options::new();
+    options.insert("interactive", interactive.into());
+    async move {
+        let results = request(
+            "org.freedesktop.portal.Screenshot",
+            "Screenshot",
+            |options| ("", options),
+            options,
+        )
+        .await?;
+        Ok(results.and_then(|results| {
+            let uri = String::try_from(results.get("uri")?.try_clone().ok()?).ok()?;
+            File::new_for_uri(&uri).get_path()
+        }))
+    }
+}
+
+fn filter(name: &str, patterns: &[&str]) -> Filter {
+    // Each pattern is tagged 0 for a glob, as opposed to 1 for a MIME type.
+    let patterns = patterns
+        .iter()
+        .map(|pattern| (0, pattern.to_string()))
+        .collect();
+    (name.to_string(), patterns)
+}
+
+// Paths go over D-Bus as nul terminated byte strings, as they needn't be
+// valid UTF-8.
+fn path_bytes(path: &Path) -> Value<'static> {
+    let mut bytes = path.as_os_str().as_bytes().to_vec();
+    bytes.push(0);
+    bytes.into()
+}
+
+fn chosen_paths(results: Results) -> Vec<PathBuf> {
+    results
+        .get("uris")
+        .and_then(|uris| uris.try_clone().ok())
+        .and_then(|uris| Vec::<String>::try_from(uris).ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|uri| File::new_for_uri(uri).get_path())
+        .collect()
+}
+
+static NEXT_TOKEN: AtomicUsize = AtomicUsize::new(0);
+
+// Make a portal request and wait for its response, which arrives as a signal
+// on a request object whose path we can work out ahead of time from the
+// `handle_token` we pass along. We need to be listening for it before making
+// the call, or we might miss it. Resolves to `None` if the user cancelled.
+async fn request<B, F>(
+    interface: &'static str,
+    method: &'static str,
+    body: F,
+    mut options: Options,
+) -> zbus::Result<Option<Results>>
+where
+    B: Serialize + DynamicType,
+    F: FnOnce(Options) -> B,
+{
+    let connection = connection(Bus::Session).await?;
+    let token = format!("vgtk{}", NEXT_TOKEN.fetch_add(1, Ordering::Relaxed));
+    let sender = connection
+        .unique_name()
+        .map(|name| name.trim_start_matches(':').replace('.', "_"))
+        .unwrap_or_default();
+    let path = format!(
+        "/org/freedesktop/portal/desktop/request/{}/{}",
+        sender, token
+    );
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .interface("org.freedesktop.portal.Request")?
+        .member("Response")?
+        .path(path)?
+        .build();
+    let mut responses = MessageStream::for_match_rule(rule, &connection, None).await?;
+    options.insert("handle_token", token.into());
+    connection
+        .call_method(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            Some(interface),
+            method,
+            &body(options),
+        )
+        .await?;
+    let response = match responses.next().await {
+        Some(response) => response?,
+        None => return Ok(None),
+    };
+    let (code, results): (u32, Results) = response.body().deserialize()?;
+    Ok(if code == 0 { Some(results) } else { None })
+}
@@ -0,0 +1,186 @@
+use cairo::Context;
+use futures::channel::oneshot;
+use glib::ObjectExt;
+use gtk::{
+    PrintContext, PrintOperation, PrintOperationAction, PrintOperationExt, PrintOperationResult,
+    PrintStatus, Window,
+};
+
+use std::cell::RefCell;
+use std::future::Future;
+
+use crate::component::current_window;
+
+/// Builds and runs a [`PrintOperation`][PrintOperation] for you.
+///
+/// You tell it how many pages there are, either up front with
+/// [`pages()`][pages] or, if that depends on the paper size, by working it
+/// out in a [`paginate()`][paginate] callback, and give it a
+/// [`draw()`][draw] callback which draws each page with cairo. Then
+/// [`run()`][run] it, which shows the print dialog and returns a
+/// [`Future`][Future] which resolves once the document's been handed to the
+/// printer, so you can use it from an [`UpdateAction::defer()`][defer] block
+/// and turn the result into a message.
+///
+/// To keep the user up to date while a long document is printing, use
+/// [`status()`][status] to have the callback send your component a message
+/// through its [`Scope`][Scope] whenever the status of the print job
+/// changes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{Print, Scope, UpdateAction};
+/// # use vgtk::lib::gtk::{PrintOperationResult, PrintStatus};
+/// # #[derive(Clone, Debug)] enum Message { Print, Status(PrintStatus), Printed(bool) }
+/// # #[derive(Default)] struct Report { lines: Vec<String> }
+/// # impl vgtk::Component for Report { type Message = Message; type Properties = ();
+/// # fn view(&self) -> vgtk::VNode<Report> { unimplemented!() }
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::Print => {
+///             let lines = self.lines.clone();
+///             let scope = Scope::<Self>::current();
+///             let result = Print::new("Report")
+///                 .pages(1)
+///                 .draw(move |cr, _context, _page| {
+///                     for (index, line) in lines.iter().enumerate() {
+///                         cr.move_to(0.0, 20.0 * (index + 1) as f64);
+///                         cr.show_text(line);
+///                     }
+///                 })
+///                 .status(move |status| scope.send(Message::Status(status)))
+///                 .run();
+///             UpdateAction::defer(async move {
+///                 Message::Printed(result.await == Ok(PrintOperationResult::Apply))
+///             })
+///         }
+///         // ...
+/// #       _ => UpdateAction::None,
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [PrintOperation]: ../gtk/struct.PrintOperation.html
+/// [pages]: #method.pages
+/// [paginate]: #method.paginate
+/// [draw]: #method.draw
+/// [run]: #method.run
+/// [status]: #method.status
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [defer]: enum.UpdateAction.html#method.defer
+/// [Scope]: struct.Scope.html
+pub struct Print {
+    operation: PrintOperation,
+    parent: Option<Window>,
+}
+
+impl Print {
+    /// Construct a print operation for a document called `job_name`.
+    pub fn new(job_name: &str) -> Self {
+        let operation = PrintOperation::new();
+        operation.set_job_name(job_name);
+        Print {
+            operation,
+            parent: None,
+        }
+    }
+
+    /// Set the number of pages in the document.
+    pub fn pages(self, pages: i32) -> Self {
+        self.operation.set_n_pages(pages);
+        self
+    }
+
+    /// Work out the number of pages in the document once the user has picked
+    /// a printer and paper size.
+    ///
+    /// The callback is given the [`PrintContext`][PrintContext], whose
+    /// `get_width()` and `get_height()` tell you how much room there is on
+    /// each page, and returns the number of pages.
+    ///
+    /// [PrintContext]: ../gtk/struct.PrintContext.html
+    pub fn paginate<F>(self, f: F) -> Self
+    where
+        F: Fn(&PrintContext) -> i32 + 'static,
+    {
+        self.operation
+            .connect_begin_print(move |operation, context| operation.set_n_pages(f(context)));
+        self
+    }
+
+    /// Set the function which draws each page.
+    ///
+    /// The callback is given a cairo [`Context`][Context] to draw the page
+    /// with, the [`PrintContext`][PrintContext], and the number of the page,
+    /// starting from 0.
+    ///
+    /// [Context]: ../cairo/struct.Context.html
+    /// [PrintContext]: ../gtk/struct.PrintContext.html
+    pub fn draw<F>(self, f: F) -> Self
+    where
+        F: Fn(&Context, &PrintContext, i32) + 'static,
+    {
+        self.operation.connect_draw_page(move |_, context, page| {
+            f(&context.get_cairo_context().unwrap(), context, page)
+        });
+        self
+    }
+
+    /// Call `f` whenever the status of the print job changes.
+    pub fn status<F>(self, f: F) -> Self
+    where
+        F: Fn(PrintStatus) + 'static,
+    {
+        self.operation
+            .connect_status_changed(move |operation| f(operation.get_status()));
+        self
+    }
+
+    /// Set the window the print dialog belongs to.
+    ///
+    /// If you don't set one, the [current window][current_window] will be used.
+    ///
+    /// [current_window]: fn.current_window.html
+    pub fn parent(mut self, parent: &Window) -> Self {
+        self.parent = Some(parent.clone());
+        self
+    }
+
+    /// Show the print dialog and print the document.
+    ///
+    /// The returned [`Future`][Future] resolves to
+    /// `Ok(PrintOperationResult::Apply)` once the document has been printed,
+    /// to `Ok(PrintOperationResult::Cancel)` if the user cancelled, or to an
+    /// error if printing failed.
+    ///
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub fn run(self) -> impl Future<Output = Result<PrintOperationResult, glib::Error>> {
+        let operation = self.operation;
+        let parent = self.parent.or_else(current_window);
+        operation.set_allow_async(true);
+        let (notify, done) = oneshot::channel();
+        let notify = RefCell::new(Some(notify));
+        let handler = operation.connect_done(move |_, result| {
+            if let Some(notify) = notify.borrow_mut().take() {
+                let _ = notify.send(result);
+            }
+        });
+        let result = operation.run(PrintOperationAction::PrintDialog, parent.as_ref());
+        async move {
+            let result = match result {
+                Ok(PrintOperationResult::InProgress) => match done.await {
+                    Ok(PrintOperationResult::Error) => {
+                        operation.get_error().map(|()| PrintOperationResult::Error)
+                    }
+                    Ok(result) => Ok(result),
+                    Err(_) => Ok(PrintOperationResult::Cancel),
+                },
+                result => result,
+            };
+            operation.disconnect(handler);
+            result
+        }
+    }
+}
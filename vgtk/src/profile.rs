@@ -0,0 +1,189 @@
+//! Timings and patch counts for component renders.
+//!
+//! Every time a component renders again after it's been built, `vgtk` measures
+//! how long its [`view`][view] method took, how long it took to diff the new
+//! view against the existing widgets and patch them, and how many changes the
+//! patch made. Each render is logged at the `debug` level, and the totals for
+//! each component can be read with [`stats()`][stats], so you can measure how
+//! a change to a big view affects its performance.
+//!
+//! This is only available with the `profile` feature enabled.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, Component, VNode};
+//! # #[derive(Default)] struct App;
+//! # impl Component for App { type Message = (); type Properties = ();
+//! #     fn view(&self) -> VNode<Self> { todo!() } }
+//! fn main() {
+//!     let status = vgtk::run::<App>();
+//!     for (component, stats) in vgtk::profile::stats() {
+//!         println!(
+//!             "{}: {} renders, {:?} in view(), {:?} patching, {} changes",
+//!             component,
+//!             stats.renders,
+//!             stats.total.view,
+//!             stats.total.patch,
+//!             stats.total.changes()
+//!         );
+//!     }
+//!     std::process::exit(status);
+//! }
+//! ```
+//!
+//! [view]: ../trait.Component.html#tymethod.view
+//! [stats]: fn.stats.html
+
+use colored::Colorize;
+use log::debug;
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+use std::time::{Duration, Instant};
+
+/// What happened during one or more renders of a component.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// The time spent in the component's `view` method.
+    pub view: Duration,
+    /// The time spent diffing the view against the existing widgets and
+    /// patching them, including building any new subcomponents.
+    pub patch: Duration,
+    /// The number of widgets and other objects built.
+    pub built: usize,
+    /// The number of widgets and other objects removed.
+    pub removed: usize,
+    /// The number of properties set to a new value.
+    pub properties: usize,
+    /// The number of signal handlers connected or disconnected.
+    pub handlers: usize,
+}
+
+impl RenderStats {
+    /// Get the total number of changes made to the widgets.
+    pub fn changes(&self) -> usize {
+        self.built + self.removed + self.properties + self.handlers
+    }
+
+    fn since(&self, earlier: &Self) -> Self {
+        RenderStats {
+            view: Duration::default(),
+            patch: Duration::default(),
+            built: self.built - earlier.built,
+            removed: self.removed - earlier.removed,
+            properties: self.properties - earlier.properties,
+            handlers: self.handlers - earlier.handlers,
+        }
+    }
+}
+
+impl AddAssign for RenderStats {
+    fn add_assign(&mut self, other: Self) {
+        self.view += other.view;
+        self.patch += other.patch;
+        self.built += other.built;
+        self.removed += other.removed;
+        self.properties += other.properties;
+        self.handlers += other.handlers;
+    }
+}
+
+/// The renders of every component of one type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComponentStats {
+    /// The number of times the components rendered.
+    pub renders: usize,
+    /// The totals for every render.
+    pub total: RenderStats,
+    /// The most recent render.
+    pub last: RenderStats,
+    /// The render which spent the longest time in `view` and patching.
+    pub slowest: RenderStats,
+}
+
+thread_local! {
+    static COUNTS: Cell<RenderStats> = Cell::new(RenderStats::default());
+    static STATS: RefCell<BTreeMap<&'static str, ComponentStats>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Get the statistics for every component type which has rendered, by name.
+///
+/// Only renders on the current thread, which should be the GTK main thread,
+/// are counted.
+pub fn stats() -> Vec<(&'static str, ComponentStats)> {
+    STATS.with(|stats| {
+        stats
+            .borrow()
+            .iter()
+            .map(|(name, stats)| (*name, *stats))
+            .collect()
+    })
+}
+
+/// Forget the statistics gathered so far.
+pub fn reset() {
+    STATS.with(|stats| stats.borrow_mut().clear());
+}
+
+/// Count a change to the widgets.
+pub(crate) fn count(f: impl FnOnce(&mut RenderStats)) {
+    COUNTS.with(|counts| {
+        let mut current = counts.get();
+        f(&mut current);
+        counts.set(current);
+    })
+}
+
+/// A render in progress.
+pub(crate) struct Frame {
+    started: Instant,
+    view: Duration,
+    counts: RenderStats,
+}
+
+impl Frame {
+    /// Start measuring a render, before the component's view is called.
+    pub(crate) fn start() -> Self {
+        Frame {
+            started: Instant::now(),
+            view: Duration::default(),
+            counts: COUNTS.with(Cell::get),
+        }
+    }
+
+    /// Mark the end of the component's view, and the start of the patch.
+    pub(crate) fn viewed(&mut self) {
+        self.view = self.started.elapsed();
+    }
+
+    /// Finish measuring a render, and add it to the component's statistics.
+    pub(crate) fn finish(self, component: &'static str) {
+        let mut render = COUNTS.with(Cell::get).since(&self.counts);
+        render.view = self.view;
+        render.patch = self.started.elapsed() - self.view;
+        debug!(
+            "{} {} {}",
+            "Component rendered:".bright_blue(),
+            component.magenta().bold(),
+            format!(
+                "view {:?}, patch {:?}, {} changes",
+                render.view,
+                render.patch,
+                render.changes()
+            )
+            .bright_black()
+        );
+        STATS.with(|stats| {
+            let mut stats = stats.borrow_mut();
+            let stats = stats.entry(component).or_default();
+            stats.renders += 1;
+            stats.total += render;
+            stats.last = render;
+            if render.view + render.patch > stats.slowest.view + stats.slowest.patch {
+                stats.slowest = render;
+            }
+        });
+    }
+}
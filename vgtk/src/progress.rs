@@ -0,0 +1,189 @@
+use glib::{Continue, ObjectExt, SourceId, WeakRef};
+use gtk::{LevelBar, LevelBarExt, ProgressBar, ProgressBarExt};
+
+use std::cell::RefCell;
+use std::fmt::{Debug, Error, Formatter};
+
+use crate::component::Component;
+use crate::scope::Scope;
+
+// How often a progress bar in pulse mode moves its block along, in
+// milliseconds.
+const PULSE_INTERVAL: u32 = 100;
+
+thread_local! {
+    static PULSING: RefCell<Vec<(WeakRef<ProgressBar>, SourceId)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// How far along a task is.
+///
+/// Give it to the `progress` property of a [`ProgressBar`][ProgressBar] or a
+/// [`LevelBar`][LevelBar], which are provided by
+/// [`ProgressBarExtHelpers`][ProgressBarExtHelpers] and
+/// [`LevelBarExtHelpers`][LevelBarExtHelpers], and have a running task
+/// report it with a [`ProgressReporter`][ProgressReporter].
+///
+/// [ProgressBar]: ../gtk/struct.ProgressBar.html
+/// [LevelBar]: ../gtk/struct.LevelBar.html
+/// [ProgressBarExtHelpers]: ext/trait.ProgressBarExtHelpers.html
+/// [LevelBarExtHelpers]: ext/trait.LevelBarExtHelpers.html
+/// [ProgressReporter]: struct.ProgressReporter.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Progress {
+    /// The task is this far along, from `0.0` to `1.0`.
+    Fraction(f64),
+    /// The task is busy, but it doesn't know how far along it is.
+    ///
+    /// A [`ProgressBar`][ProgressBar] pulses to show this. A
+    /// [`LevelBar`][LevelBar] can't, so it shows no progress instead.
+    ///
+    /// [ProgressBar]: ../gtk/struct.ProgressBar.html
+    /// [LevelBar]: ../gtk/struct.LevelBar.html
+    Pulse,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Progress::Fraction(0.0)
+    }
+}
+
+/// Reports the progress of a running task to a component as messages.
+///
+/// Construct one for a task with
+/// [`UpdateAction::defer_with_progress()`][defer_with_progress], or with
+/// [`ProgressReporter::new()`][new] if you're running it some other way. It's
+/// cheap to clone, and it's `Send`, so you can hand it to a task running on
+/// another thread.
+///
+/// [defer_with_progress]: enum.UpdateAction.html#method.defer_with_progress
+/// [new]: #method.new
+pub struct ProgressReporter<C: Component> {
+    scope: Scope<C>,
+    message: fn(Progress) -> C::Message,
+}
+
+impl<C: 'static + Component> ProgressReporter<C> {
+    /// Construct a progress reporter which sends the component `scope` belongs
+    /// to the message `message` makes out of each report.
+    pub fn new(scope: &Scope<C>, message: fn(Progress) -> C::Message) -> Self {
+        ProgressReporter {
+            scope: scope.clone(),
+            message,
+        }
+    }
+
+    /// Report how far along the task is.
+    pub fn report(&self, progress: Progress) {
+        // The component may have unmounted while the task was running, which
+        // shouldn't stop the task from finishing.
+        let _ = self.scope.try_send((self.message)(progress));
+    }
+
+    /// Report that the task is `fraction` of the way done, from `0.0` to
+    /// `1.0`.
+    pub fn set_fraction(&self, fraction: f64) {
+        self.report(Progress::Fraction(fraction.clamp(0.0, 1.0)));
+    }
+
+    /// Report that the task is busy without knowing how far along it is.
+    pub fn pulse(&self) {
+        self.report(Progress::Pulse);
+    }
+}
+
+impl<C: Component> Clone for ProgressReporter<C> {
+    fn clone(&self) -> Self {
+        ProgressReporter {
+            scope: self.scope.clone(),
+            message: self.message,
+        }
+    }
+}
+
+impl<C: Component> Debug for ProgressReporter<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "ProgressReporter({:?})", self.scope)
+    }
+}
+
+fn is_pulsing(bar: &ProgressBar) -> bool {
+    PULSING.with(|pulsing| {
+        let mut pulsing = pulsing.borrow_mut();
+        pulsing.retain(|(bar, _)| bar.upgrade().is_some());
+        pulsing
+            .iter()
+            .any(|(pulsed, _)| pulsed.upgrade().as_ref() == Some(bar))
+    })
+}
+
+fn stop_pulsing(bar: &ProgressBar) {
+    let timer = PULSING.with(|pulsing| {
+        let mut pulsing = pulsing.borrow_mut();
+        pulsing
+            .iter()
+            .position(|(pulsed, _)| pulsed.upgrade().as_ref() == Some(bar))
+            .map(|index| pulsing.remove(index).1)
+    });
+    if let Some(timer) = timer {
+        glib::source_remove(timer);
+    }
+}
+
+/// Get the progress `bar` is showing.
+pub(crate) fn progress_bar_progress(bar: &ProgressBar) -> Progress {
+    if is_pulsing(bar) {
+        Progress::Pulse
+    } else {
+        Progress::Fraction(bar.get_fraction())
+    }
+}
+
+/// Have `bar` show `progress`, pulsing until it's given a fraction if it's
+/// `Progress::Pulse`.
+pub(crate) fn set_progress_bar_progress(bar: &ProgressBar, progress: Progress) {
+    match progress {
+        Progress::Fraction(fraction) => {
+            stop_pulsing(bar);
+            bar.set_fraction(fraction);
+        }
+        Progress::Pulse => {
+            if is_pulsing(bar) {
+                return;
+            }
+            bar.pulse();
+            let pulsed = bar.downgrade();
+            let timer = glib::timeout_add_local(PULSE_INTERVAL, move || match pulsed.upgrade() {
+                Some(bar) => {
+                    bar.pulse();
+                    Continue(true)
+                }
+                // The timer goes away with the bar.
+                None => Continue(false),
+            });
+            PULSING.with(|pulsing| pulsing.borrow_mut().push((bar.downgrade(), timer)));
+        }
+    }
+}
+
+/// Get the progress `bar` is showing.
+pub(crate) fn level_bar_progress(bar: &LevelBar) -> Progress {
+    let (min, max) = (bar.get_min_value(), bar.get_max_value());
+    if max > min {
+        Progress::Fraction((bar.get_value() - min) / (max - min))
+    } else {
+        Progress::Fraction(0.0)
+    }
+}
+
+/// Have `bar` show `progress` as a value between its minimum and maximum
+/// values.
+pub(crate) fn set_level_bar_progress(bar: &LevelBar, progress: Progress) {
+    let fraction = match progress {
+        Progress::Fraction(fraction) => fraction,
+        Progress::Pulse => 0.0,
+    };
+    let (min, max) = (bar.get_min_value(), bar.get_max_value());
+    bar.set_value(min + fraction * (max - min));
+}
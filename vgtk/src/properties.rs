@@ -1,9 +1,21 @@
 //! Property conversion traits.
 
+use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
-use glib::{Cast, GString};
-use gtk::{IconSize, Image, ImageExt, Widget};
+use gio::{ListModel, Menu, MenuAttributeIterExt, MenuLinkIterExt, MenuModel, MenuModelExt};
+use glib::{Cast, GString, IsA, Object, ObjectExt, ParamFlags, Type, Value, Variant};
+use gtk::{IconSize, Image, ImageExt, TreeModel, Widget};
+use pango::FontDescription;
+
+use crate::list_model::ListBinding;
+use crate::menu_builder::MenuBuilder;
+use crate::painter::Painter;
+use crate::scale::ValueFormat;
+use crate::vnode::VProperty;
+use crate::widget_ref::WidgetRef;
 
 pub struct PropertyValue<'a, A, Get, Set>
 where
@@ -31,14 +43,32 @@ where
     pub fn coerce(&'a self) -> Set {
         A::property_coerce(&self.value)
     }
+
+    pub fn is_unset(&self) -> bool {
+        A::property_is_unset(&self.value)
+    }
 }
 
+#[diagnostic::on_unimplemented(
+    message = "a value of type `{Self}` can't be used for a property of type `{A}`",
+    label = "this value has the wrong type for this property"
+)]
 pub trait PropertyValueCompare<'a, A> {
     fn property_compare(left: A, right: &Self) -> bool;
 }
 
+#[diagnostic::on_unimplemented(
+    message = "a value of type `{Self}` can't be passed to a property setter taking `{A}`",
+    label = "this value has the wrong type for this property"
+)]
 pub trait PropertyValueCoerce<'a, A> {
     fn property_coerce(value: &'a Self) -> A;
+
+    /// Test whether `value` leaves the property unset, in which case it's
+    /// never coerced.
+    fn property_is_unset(_value: &Self) -> bool {
+        false
+    }
 }
 
 impl<'a, A> PropertyValueCompare<'a, A> for A
@@ -95,6 +125,32 @@ where
     }
 }
 
+// An `Option` given to a property whose setter takes the value inside it sets
+// the property when it's `Some`, and leaves it unset when it's `None`.
+impl<'a, A> PropertyValueCompare<'a, A> for Option<A>
+where
+    A: PartialEq + 'a,
+{
+    fn property_compare(left: A, right: &Option<A>) -> bool {
+        right.as_ref() == Some(&left)
+    }
+}
+
+impl<'a, A> PropertyValueCoerce<'a, A> for Option<A>
+where
+    A: Clone + 'a,
+{
+    fn property_coerce(value: &'a Option<A>) -> A {
+        value
+            .clone()
+            .expect("tried to coerce an unset property value")
+    }
+
+    fn property_is_unset(value: &Option<A>) -> bool {
+        value.is_none()
+    }
+}
+
 impl<'a, A> PropertyValueCoerce<'a, Option<&'a A>> for Option<A> {
     fn property_coerce(value: &'a Option<A>) -> Option<&'a A> {
         value.as_ref()
@@ -145,6 +201,36 @@ impl<'a> PropertyValueCoerce<'a, Option<GString>> for String {
     }
 }
 
+impl<'a> PropertyValueCompare<'a, Option<GString>> for Option<String> {
+    fn property_compare(left: Option<GString>, right: &Option<String>) -> bool {
+        left.as_ref().map(GString::as_str) == right.as_ref().map(String::as_str)
+    }
+}
+
+impl<'a> PropertyValueCoerce<'a, Option<&'a str>> for Option<String> {
+    fn property_coerce(value: &'a Option<String>) -> Option<&'a str> {
+        value.as_ref().map(String::as_str)
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, GString> for Option<String> {
+    fn property_compare(left: GString, right: &Option<String>) -> bool {
+        right.as_deref() == Some(left.as_str())
+    }
+}
+
+impl<'a> PropertyValueCoerce<'a, &'a str> for Option<String> {
+    fn property_coerce(value: &'a Option<String>) -> &'a str {
+        value
+            .as_deref()
+            .expect("tried to coerce an unset property value")
+    }
+
+    fn property_is_unset(value: &Option<String>) -> bool {
+        value.is_none()
+    }
+}
+
 impl<'a> PropertyValueCompare<'a, Option<Widget>> for Image {
     fn property_compare(left: Option<Widget>, right: &Image) -> bool {
         if let Some(left) = left {
@@ -163,12 +249,256 @@ impl<'a> PropertyValueCoerce<'a, Option<&'a Image>> for Image {
     }
 }
 
+fn menu_attributes(model: &MenuModel, index: i32) -> Vec<(GString, Variant)> {
+    let mut attributes = Vec::new();
+    if let Some(iter) = model.iterate_item_attributes(index) {
+        while let Some(attribute) = iter.get_next() {
+            attributes.push(attribute);
+        }
+    }
+    attributes.sort_by(|left, right| left.0.cmp(&right.0));
+    attributes
+}
+
+fn menu_links(model: &MenuModel, index: i32) -> Vec<(GString, MenuModel)> {
+    let mut links = Vec::new();
+    if let Some(iter) = model.iterate_item_links(index) {
+        while let Some(link) = iter.get_next() {
+            links.push(link);
+        }
+    }
+    links.sort_by(|left, right| left.0.cmp(&right.0));
+    links
+}
+
+// Menu models are compared by content rather than identity, because a view
+// function will usually construct a fresh `Menu` every time it's called.
+fn menu_model_eq(left: &MenuModel, right: &MenuModel) -> bool {
+    if left == right {
+        return true;
+    }
+    let count = left.get_n_items();
+    if count != right.get_n_items() {
+        return false;
+    }
+    (0..count).all(|index| {
+        let (left_links, right_links) = (menu_links(left, index), menu_links(right, index));
+        menu_attributes(left, index) == menu_attributes(right, index)
+            && left_links.len() == right_links.len()
+            && left_links
+                .iter()
+                .zip(right_links.iter())
+                .all(|(left, right)| left.0 == right.0 && menu_model_eq(&left.1, &right.1))
+    })
+}
+
+impl<'a> PropertyValueCompare<'a, Option<MenuModel>> for MenuModel {
+    fn property_compare(left: Option<MenuModel>, right: &MenuModel) -> bool {
+        if let Some(left) = left {
+            menu_model_eq(&left, right)
+        } else {
+            false
+        }
+    }
+}
+
+impl<'a> PropertyValueCoerce<'a, Option<&'a MenuModel>> for MenuModel {
+    fn property_coerce(value: &'a MenuModel) -> Option<&'a MenuModel> {
+        Some(value)
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, Option<MenuModel>> for Menu {
+    fn property_compare(left: Option<MenuModel>, right: &Menu) -> bool {
+        MenuModel::property_compare(left, right.upcast_ref())
+    }
+}
+
+impl<'a> PropertyValueCoerce<'a, Option<&'a Menu>> for Menu {
+    fn property_coerce(value: &'a Menu) -> Option<&'a Menu> {
+        Some(value)
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, Option<MenuModel>> for Option<Menu> {
+    fn property_compare(left: Option<MenuModel>, right: &Option<Menu>) -> bool {
+        match (left, right) {
+            (Some(left), Some(right)) => menu_model_eq(&left, right.upcast_ref()),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, Option<ListModel>> for ListBinding {
+    fn property_compare(left: Option<ListModel>, right: &ListBinding) -> bool {
+        left.as_ref() == Some(&right.model)
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, Option<TreeModel>> for TreeModel {
+    fn property_compare(left: Option<TreeModel>, right: &TreeModel) -> bool {
+        left.as_ref() == Some(right)
+    }
+}
+
+impl<'a> PropertyValueCoerce<'a, Option<&'a TreeModel>> for TreeModel {
+    fn property_coerce(value: &'a TreeModel) -> Option<&'a TreeModel> {
+        Some(value)
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, Option<Painter>> for Painter {
+    fn property_compare(left: Option<Painter>, right: &Painter) -> bool {
+        match left {
+            Some(left) => left.is_same(right),
+            None => false,
+        }
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, Option<FontDescription>> for FontDescription {
+    fn property_compare(left: Option<FontDescription>, right: &FontDescription) -> bool {
+        left.as_ref() == Some(right)
+    }
+}
+
+impl<'a, W: IsA<Widget>> PropertyValueCompare<'a, Option<Widget>> for WidgetRef<W> {
+    fn property_compare(left: Option<Widget>, right: &WidgetRef<W>) -> bool {
+        left == right.get().map(Cast::upcast)
+    }
+}
+
 impl PropertyValueCompare<'_, Vec<GString>> for &'_ [&'_ str] {
     fn property_compare(left: Vec<GString>, right: &&[&str]) -> bool {
         left == *right
     }
 }
 
+impl PropertyValueCompare<'_, Vec<String>> for Vec<&'_ str> {
+    fn property_compare(left: Vec<String>, right: &Vec<&str>) -> bool {
+        left == *right
+    }
+}
+
+impl<'a> PropertyValueCoerce<'a, &'a [u32]> for Vec<u32> {
+    fn property_coerce(value: &'a Vec<u32>) -> &'a [u32] {
+        value
+    }
+}
+
+impl PropertyValueCompare<'_, Vec<(f64, String)>> for &'_ [(f64, &'_ str)] {
+    fn property_compare(left: Vec<(f64, String)>, right: &&[(f64, &str)]) -> bool {
+        left.len() == right.len()
+            && left.iter().zip(right.iter()).all(
+                |((left_value, left_label), (right_value, right_label))| {
+                    left_value == right_value && left_label == right_label
+                },
+            )
+    }
+}
+
+impl<const N: usize> PropertyValueCompare<'_, Vec<(f64, String)>> for [(f64, &'_ str); N] {
+    fn property_compare(left: Vec<(f64, String)>, right: &[(f64, &str); N]) -> bool {
+        <&[(f64, &str)]>::property_compare(left, &right.as_ref())
+    }
+}
+
+impl<'a, 'b: 'a, const N: usize> PropertyValueCoerce<'a, &'a [(f64, &'a str)]>
+    for [(f64, &'b str); N]
+{
+    fn property_coerce(value: &'a [(f64, &'b str); N]) -> &'a [(f64, &'a str)] {
+        value
+    }
+}
+
+impl<'a> PropertyValueCompare<'a, Option<ValueFormat>> for ValueFormat {
+    fn property_compare(left: Option<ValueFormat>, right: &ValueFormat) -> bool {
+        match left {
+            Some(left) => left.is_same(right),
+            None => false,
+        }
+    }
+}
+
+/// Conversion from the value a property getter returns into the type of a
+/// bound value, for the `bind:` attribute syntax.
+pub trait BindValue<Get> {
+    fn from_property_value(value: Get) -> Self;
+}
+
+impl<A> BindValue<A> for A {
+    fn from_property_value(value: A) -> A {
+        value
+    }
+}
+
+impl BindValue<GString> for String {
+    fn from_property_value(value: GString) -> String {
+        value.to_string()
+    }
+}
+
+impl BindValue<Option<GString>> for String {
+    fn from_property_value(value: Option<GString>) -> String {
+        value.map(|value| value.to_string()).unwrap_or_default()
+    }
+}
+
+/// Wrap the handler of a `bind:` attribute, pinning its argument type to the
+/// type of the bound value.
+pub fn bind_handler<A, Msg, F>(_value: &A, handler: F) -> Rc<dyn Fn(A) -> Msg>
+where
+    F: Fn(A) -> Msg + 'static,
+{
+    Rc::new(handler)
+}
+
+/// Reset the property `name` of `object` to its default value, when the value
+/// it was given has become unset.
+///
+/// Properties which aren't GObject properties, like the ones vgtk's helper
+/// traits add, have no default to go back to, so they keep their last value.
+pub fn reset_property(object: &Object, name: &str) {
+    let default = match object.find_property(name) {
+        Some(pspec)
+            if pspec.get_flags().contains(ParamFlags::WRITABLE)
+                && !pspec.get_flags().contains(ParamFlags::CONSTRUCT_ONLY) =>
+        {
+            match pspec.get_default_value() {
+                Some(default) => default,
+                None => return,
+            }
+        }
+        _ => return,
+    };
+    if !matches!(object.get_property(name), Ok(current) if same_value(&current, &default)) {
+        object.set_property(name, &default).unwrap_or_else(|error| {
+            panic!(
+                "can't reset property {:?} on {}: {}",
+                name,
+                object.get_type(),
+                error
+            )
+        });
+        VProperty::changed();
+    }
+}
+
+// GValues can't be compared as such, but glib can describe their contents,
+// which tells apart any two values of the same type except floating point
+// numbers, whose descriptions are rounded.
+pub(crate) fn same_value(left: &Value, right: &Value) -> bool {
+    if left.type_() != right.type_() {
+        return false;
+    }
+    match left.type_() {
+        Type::F32 => left.get::<f32>().ok() == right.get::<f32>().ok(),
+        Type::F64 => left.get::<f64>().ok() == right.get::<f64>().ok(),
+        _ => format!("{:?}", left) == format!("{:?}", right),
+    }
+}
+
 pub trait IntoPropertyValue<'a, A, Get, Set>
 where
     A: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set> + 'a,
@@ -204,6 +534,15 @@ where
     }
 }
 
+impl<'a, Get, Set> IntoPropertyValue<'a, Option<String>, Get, Set> for Option<&'_ str>
+where
+    Option<String>: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set>,
+{
+    fn into_property_value(self) -> PropertyValue<'a, Option<String>, Get, Set> {
+        PropertyValue::new(self.map(str::to_string))
+    }
+}
+
 impl<'a, Get, Set> IntoPropertyValue<'a, String, Get, Set> for &'_ str
 where
     String: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set>,
@@ -213,6 +552,32 @@ where
     }
 }
 
+impl<'a, Get, Set> IntoPropertyValue<'a, Menu, Get, Set> for MenuBuilder
+where
+    Menu: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set>,
+{
+    fn into_property_value(self) -> PropertyValue<'a, Menu, Get, Set> {
+        PropertyValue::new(self.build())
+    }
+}
+
+// Other kinds of string are turned into a `String` for properties which take
+// one, or a `&str`.
+macro_rules! string_property_value {
+    ($($from:ty),*) => {$(
+        impl<'a, Get, Set> IntoPropertyValue<'a, String, Get, Set> for $from
+        where
+            String: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set>,
+        {
+            fn into_property_value(self) -> PropertyValue<'a, String, Get, Set> {
+                PropertyValue::new(self.to_string())
+            }
+        }
+    )*};
+}
+
+string_property_value!(GString, Cow<'_, str>, Box<str>, Rc<str>, char);
+
 impl<'a, Get, Set> IntoPropertyValue<'a, Image, Get, Set> for (&'_ str, IconSize)
 where
     Image: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set>,
@@ -231,3 +596,72 @@ where
         PropertyValue::new(Image::new_from_icon_name(Some(self), IconSize::Button))
     }
 }
+
+// Numbers are converted to the type a property expects when that can't lose
+// anything, and integers which might not fit are checked as they're given, so
+// `border_width=self.padding` works without a cast whatever the type of
+// `padding`.
+macro_rules! number_property_value {
+    ($($from:ty => $($to:ty),+;)*) => {$($(
+        impl<'a, Get, Set> IntoPropertyValue<'a, $to, Get, Set> for $from
+        where
+            $to: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set>,
+        {
+            fn into_property_value(self) -> PropertyValue<'a, $to, Get, Set> {
+                PropertyValue::new(<$to>::try_from(self).unwrap_or_else(|_| {
+                    panic!(
+                        "{} is out of range for a property of type {}",
+                        self,
+                        stringify!($to)
+                    )
+                }))
+            }
+        }
+    )+)*};
+}
+
+number_property_value! {
+    i8 => i32, u32, i64, u64, f32, f64;
+    u8 => i32, u32, i64, u64, f32, f64;
+    i16 => i32, u32, i64, u64, f32, f64;
+    u16 => i32, u32, i64, u64, f32, f64;
+    i32 => u32, i64, u64, f64;
+    u32 => i32, i64, u64, f64;
+    i64 => i32, u32, u64;
+    u64 => i32, u32, i64;
+    isize => i32, u32, i64, u64;
+    usize => i32, u32, i64, u64;
+    f32 => f64;
+}
+
+// Larger integers are converted to floating point numbers as long as they can
+// be represented exactly, so `xalign=1` works too.
+macro_rules! float_property_value {
+    ($($from:ty => $($to:ty),+;)*) => {$($(
+        impl<'a, Get, Set> IntoPropertyValue<'a, $to, Get, Set> for $from
+        where
+            $to: PropertyValueCompare<'a, Get> + PropertyValueCoerce<'a, Set>,
+        {
+            fn into_property_value(self) -> PropertyValue<'a, $to, Get, Set> {
+                let value = self as $to;
+                if value as $from != self {
+                    panic!(
+                        "{} can't be represented exactly by a property of type {}",
+                        self,
+                        stringify!($to)
+                    );
+                }
+                PropertyValue::new(value)
+            }
+        }
+    )+)*};
+}
+
+float_property_value! {
+    i32 => f32;
+    u32 => f32;
+    i64 => f32, f64;
+    u64 => f32, f64;
+    isize => f32, f64;
+    usize => f32, f64;
+}
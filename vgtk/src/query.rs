@@ -0,0 +1,176 @@
+//! Find widgets in a component's widget tree by type and attributes.
+//!
+//! [`find()`][find] starts a [`Query`][Query] for widgets of a given type,
+//! which you can narrow down with methods like
+//! [`with_label()`][with_label] and [`with_name()`][with_name], and then run
+//! against any widget, window or application to search everything inside it.
+//! This saves you walking the widget tree by hand when you need to get at a
+//! widget you didn't give a [`WidgetRef`][WidgetRef], whether that's in a test
+//! or in an imperative escape hatch inside your component.
+//!
+//! A query is also a [`Selector`][Selector], so it can be passed to the
+//! methods of [`testing::Mounted`][Mounted].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::lib::gtk::*;
+//! use vgtk::query::find;
+//!
+//! fn clear_completed(window: &Window) {
+//!     if let Some(button) = find::<Button>().with_label("Clear completed").first(window) {
+//!         button.clicked();
+//!     }
+//! }
+//! ```
+//!
+//! [find]: fn.find.html
+//! [Query]: struct.Query.html
+//! [with_label]: struct.Query.html#method.with_label
+//! [with_name]: struct.Query.html#method.with_name
+//! [WidgetRef]: ../struct.WidgetRef.html
+//! [Selector]: ../testing/trait.Selector.html
+//! [Mounted]: ../testing/struct.Mounted.html
+
+use glib::{Cast, IsA, Object, ObjectExt, ObjectType, StaticType};
+use gtk::prelude::*;
+use gtk::{Application, Container, Widget};
+
+use std::marker::PhantomData;
+
+use crate::testing::Selector;
+
+type Filter = Box<dyn Fn(&Widget) -> bool>;
+
+/// Start a query for widgets of type `W`, or of any subtype.
+///
+/// `W` can also be an interface, like [`Editable`][Editable], to find every
+/// widget which implements it.
+///
+/// [Editable]: ../../gtk/struct.Editable.html
+pub fn find<W: ObjectType>() -> Query<W> {
+    Query {
+        description: W::static_type().to_string(),
+        filters: Vec::new(),
+        widget_type: PhantomData,
+    }
+}
+
+/// A search for widgets, built by [`find()`][find].
+///
+/// [find]: fn.find.html
+pub struct Query<W> {
+    description: String,
+    filters: Vec<Filter>,
+    widget_type: PhantomData<W>,
+}
+
+impl<W: ObjectType> Query<W> {
+    /// Only match widgets whose `label` property is `label`, like a
+    /// [`Button`][Button] or a [`Label`][Label].
+    ///
+    /// [Button]: ../../gtk/struct.Button.html
+    /// [Label]: ../../gtk/struct.Label.html
+    pub fn with_label(self, label: &str) -> Self {
+        let description = format!("with label {:?}", label);
+        let label = label.to_string();
+        self.with(description, move |widget| {
+            widget
+                .has_property("label", Some(String::static_type()))
+                .is_ok()
+                && widget
+                    .get_property("label")
+                    .ok()
+                    .and_then(|value| value.get::<String>().ok().flatten())
+                    .as_ref()
+                    == Some(&label)
+        })
+    }
+
+    /// Only match widgets whose widget name is `name`, as given by a
+    /// `widget_name` attribute.
+    pub fn with_name(self, name: &str) -> Self {
+        let description = format!("named {:?}", name);
+        let name = name.to_string();
+        self.with(description, move |widget| {
+            widget.get_widget_name().as_deref() == Some(name.as_str())
+        })
+    }
+
+    /// Only match widgets with the style class `class`.
+    pub fn with_class(self, class: &str) -> Self {
+        let description = format!("with class {:?}", class);
+        let class = class.to_string();
+        self.with(description, move |widget| {
+            widget.get_style_context().has_class(&class)
+        })
+    }
+
+    /// Only match widgets for which `filter` returns `true`.
+    pub fn filter(self, filter: impl Fn(&W) -> bool + 'static) -> Self {
+        self.with("matching a filter".to_string(), move |widget| match widget
+            .dynamic_cast_ref::<W>()
+        {
+            Some(widget) => filter(widget),
+            None => false,
+        })
+    }
+
+    fn with(mut self, description: String, filter: impl Fn(&Widget) -> bool + 'static) -> Self {
+        self.description.push(' ');
+        self.description.push_str(&description);
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Find every matching widget inside `root`, depth first, including `root`
+    /// itself.
+    ///
+    /// If `root` is an [`Application`][Application], every one of its windows
+    /// is searched. Internal children, like the label inside a button, are
+    /// searched too.
+    ///
+    /// [Application]: ../../gtk/struct.Application.html
+    pub fn all(&self, root: &impl IsA<Object>) -> Vec<W> {
+        let mut found = Vec::new();
+        walk_object(root.upcast_ref(), &mut |widget| {
+            if self.matches(widget) {
+                found.extend(widget.clone().dynamic_cast::<W>().ok());
+            }
+        });
+        found
+    }
+
+    /// Find the first matching widget inside `root`.
+    pub fn first(&self, root: &impl IsA<Object>) -> Option<W> {
+        self.all(root).into_iter().next()
+    }
+}
+
+impl<W: ObjectType> Selector for Query<W> {
+    fn matches(&self, widget: &Widget) -> bool {
+        widget.is::<W>() && self.filters.iter().all(|filter| filter(widget))
+    }
+
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+}
+
+/// Visit every widget inside `root`, depth first.
+pub(crate) fn walk_object(root: &Object, visit: &mut impl FnMut(&Widget)) {
+    if let Some(widget) = root.downcast_ref::<Widget>() {
+        walk(widget, visit);
+    } else if let Some(application) = root.downcast_ref::<Application>() {
+        for window in application.get_windows() {
+            walk(window.upcast_ref(), visit);
+        }
+    }
+}
+
+fn walk(widget: &Widget, visit: &mut impl FnMut(&Widget)) {
+    visit(widget);
+    if let Some(container) = widget.downcast_ref::<Container>() {
+        container.forall(|child| walk(child, visit));
+    }
+}
@@ -0,0 +1,166 @@
+use glib::{ObjectExt, SignalHandlerId, WeakRef};
+use gtk::{OrientableExt, Orientation, PositionType, Scale, ScaleExt, WidgetExt};
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+thread_local! {
+    static SCALES: RefCell<Vec<(WeakRef<Scale>, Rc<RefCell<ScaleState>>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+// GTK doesn't let us read back a scale's marks, or find out how it formats
+// its value, so we keep track of them ourselves.
+#[derive(Default)]
+struct ScaleState {
+    marks: Vec<(f64, String)>,
+    format: Option<(ValueFormat, SignalHandlerId)>,
+}
+
+fn state(scale: &Scale) -> Rc<RefCell<ScaleState>> {
+    SCALES.with(|scales| {
+        let mut scales = scales.borrow_mut();
+        scales.retain(|(scale, _)| scale.upgrade().is_some());
+        if let Some((_, state)) = scales
+            .iter()
+            .find(|(existing, _)| existing.upgrade().as_ref() == Some(scale))
+        {
+            return state.clone();
+        }
+        let state = Rc::new(RefCell::new(ScaleState::default()));
+        scales.push((scale.downgrade(), state.clone()));
+        state
+    })
+}
+
+/// Get the marks `scale` was last given.
+pub(crate) fn marks(scale: &Scale) -> Vec<(f64, String)> {
+    state(scale).borrow().marks.clone()
+}
+
+/// Replace the marks on `scale`, placing them below a horizontal scale or to
+/// the right of a vertical one.
+pub(crate) fn set_marks(scale: &Scale, marks: &[(f64, &str)]) {
+    let position = match scale.get_orientation() {
+        Orientation::Vertical => PositionType::Right,
+        _ => PositionType::Bottom,
+    };
+    scale.clear_marks();
+    for (value, markup) in marks {
+        let markup = Some(*markup).filter(|markup| !markup.is_empty());
+        scale.add_mark(*value, position, markup);
+    }
+    state(scale).borrow_mut().marks = marks
+        .iter()
+        .map(|(value, markup)| (*value, markup.to_string()))
+        .collect();
+}
+
+/// Get the value format `scale` was last given.
+pub(crate) fn value_format(scale: &Scale) -> Option<ValueFormat> {
+    state(scale)
+        .borrow()
+        .format
+        .as_ref()
+        .map(|(format, _)| format.clone())
+}
+
+/// Have `scale` display its value the way `format` says.
+pub(crate) fn set_value_format(scale: &Scale, format: ValueFormat) {
+    let state = state(scale);
+    let mut state = state.borrow_mut();
+    if let Some((_, handler)) = state.format.take() {
+        scale.disconnect(handler);
+    }
+    let formatter = format.clone();
+    let handler =
+        scale.connect_format_value(move |_, value| (formatter.format)(&*formatter.state, value));
+    state.format = Some((format, handler));
+    // The value's displayed straight away, so it needs formatting again.
+    scale.queue_resize();
+}
+
+struct FormatState<T> {
+    state: T,
+    format: fn(&T, f64) -> String,
+}
+
+fn same_state<T: 'static + PartialEq>(left: &dyn Any, right: &dyn Any) -> bool {
+    match (
+        left.downcast_ref::<FormatState<T>>(),
+        right.downcast_ref::<FormatState<T>>(),
+    ) {
+        (Some(left), Some(right)) => {
+            left.format as usize == right.format as usize && left.state == right.state
+        }
+        _ => false,
+    }
+}
+
+fn format_state<T: 'static>(state: &dyn Any, value: f64) -> String {
+    let state = state
+        .downcast_ref::<FormatState<T>>()
+        .expect("value format holds state of the wrong type");
+    (state.format)(&state.state, value)
+}
+
+/// A function which formats the value a [`Scale`][Scale] displays, along
+/// with the state it formats it with.
+///
+/// Give a `Scale` a value format through its `value_format` property,
+/// provided by [`ScaleExtHelpers`][ScaleExtHelpers]. Like a
+/// [`Painter`][Painter], the state is a snapshot of whatever your view passed
+/// in, and the scale only changes how it formats its value when the state or
+/// the function changes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, ValueFormat, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::gtk::*;
+/// fn percent(_: &(), value: f64) -> String {
+///     format!("{:.0}%", value * 100.0)
+/// }
+///
+/// # fn view() -> VNode<()> {
+/// gtk! {
+///     <Scale value_format=ValueFormat::new((), percent) />
+/// }
+/// # }
+/// ```
+///
+/// [Scale]: ../gtk/struct.Scale.html
+/// [ScaleExtHelpers]: ext/trait.ScaleExtHelpers.html
+/// [Painter]: struct.Painter.html
+#[derive(Clone)]
+pub struct ValueFormat {
+    state: Rc<dyn Any>,
+    same: fn(&dyn Any, &dyn Any) -> bool,
+    format: fn(&dyn Any, f64) -> String,
+}
+
+impl ValueFormat {
+    /// Construct a value format which formats values with `format`, given
+    /// `state`.
+    pub fn new<T: 'static + PartialEq>(state: T, format: fn(&T, f64) -> String) -> Self {
+        ValueFormat {
+            state: Rc::new(FormatState { state, format }),
+            same: same_state::<T>,
+            format: format_state::<T>,
+        }
+    }
+
+    /// Test whether two value formats would format values the same way.
+    pub(crate) fn is_same(&self, other: &ValueFormat) -> bool {
+        (self.same)(&*self.state, &*other.state)
+    }
+}
+
+impl Debug for ValueFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "ValueFormat")
+    }
+}
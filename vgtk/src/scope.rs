@@ -1,12 +1,18 @@
 use std::any::TypeId;
+use std::cell::RefCell;
 use std::fmt::{Debug, Error, Formatter};
+use std::future::Future;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 use std::sync::{
     atomic::{AtomicPtr, AtomicUsize, Ordering},
     Arc,
 };
+use std::time::Duration;
 
 use colored::Colorize;
+use glib::{Continue, SourceId};
+use gtk::Inhibit;
 use log::debug;
 
 use futures::channel::mpsc::{TrySendError, UnboundedSender};
@@ -15,7 +21,48 @@ use crate::component::{Component, ComponentTask};
 
 /// A channel for sending messages to a [`Component`][Component].
 ///
+/// A `Scope` is cheap to clone, and as long as your component's
+/// [`Message`][Message] type is `Send`, which it has to be anyway, so is the
+/// `Scope`. This means you can hand it to code which knows nothing about
+/// `vgtk`, like a server running on another thread or a callback from a C
+/// library, and have it [`send()`][send] messages to your component from
+/// wherever it likes. They're delivered to your component's
+/// [`update`][update] method on the GTK main loop.
+///
+/// You can get the `Scope` of a top level component from
+/// [`vgtk::start()`][start], or from inside any component with
+/// [`Scope::current()`][current].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, Scope, UpdateAction, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # use std::time::Duration;
+/// # #[derive(Clone, Debug)] enum Message { Tick }
+/// # #[derive(Default)] struct Clock;
+/// # impl Component for Clock {
+/// # type Message = Message; type Properties = ();
+/// # fn view(&self) -> VNode<Self> { todo!() }
+/// fn mounted(&mut self) {
+///     let scope = Scope::<Self>::current();
+///     std::thread::spawn(move || loop {
+///         std::thread::sleep(Duration::from_secs(1));
+///         if scope.try_send(Message::Tick).is_err() {
+///             // The component has unmounted.
+///             break;
+///         }
+///     });
+/// }
+/// # }
+/// ```
+///
 /// [Component]: trait.Component.html
+/// [Message]: trait.Component.html#associatedtype.Message
+/// [send]: #method.send
+/// [update]: trait.Component.html#method.update
+/// [start]: fn.start.html
+/// [current]: #method.current
 pub struct Scope<C: Component> {
     name: &'static str,
     muted: Arc<AtomicUsize>,
@@ -95,6 +142,25 @@ impl<C: 'static + Component> Scope<C> {
         ComponentTask::<_, C>::current_parent_scope()
     }
 
+    /// Get the `Scope` of the component which is currently running.
+    ///
+    /// This works from inside any of the methods of your
+    /// [`Component`][Component] implementation, including
+    /// [`create`][create] and [`view`][view], which is where to get your
+    /// component's scope if you need to pass it on to code outside `vgtk`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it's called from outside a component, or from a component
+    /// other than `C`.
+    ///
+    /// [Component]: trait.Component.html
+    /// [create]: trait.Component.html#method.create
+    /// [view]: trait.Component.html#tymethod.view
+    pub fn current() -> Self {
+        ComponentTask::<C, C>::current_scope()
+    }
+
     #[inline(always)]
     fn log(&self, message: &C::Message) {
         debug!(
@@ -119,6 +185,23 @@ impl<C: 'static + Component> Scope<C> {
         }
     }
 
+    #[doc(hidden)]
+    pub fn send_messages(&self, messages: impl IntoMessages<C::Message>) {
+        for message in messages.into_messages() {
+            self.send_message(message);
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn send_result<R, Ret>(&self, result: R) -> Ret
+    where
+        R: HandlerResult<C::Message, Ret>,
+    {
+        let (ret, messages) = result.split();
+        self.send_messages(messages);
+        ret
+    }
+
     /// Attempt to send a message to the component this `Scope` belongs to.
     ///
     /// This should always succeed if the component is running.
@@ -140,12 +223,230 @@ impl<C: 'static + Component> Scope<C> {
         self.channel.unbounded_send(message)
     }
 
+    /// Send a message to the component this `Scope` belongs to, from any
+    /// thread.
+    ///
+    /// If the component has unmounted, the message is quietly dropped. Use
+    /// [`try_send()`][try_send] instead if you need to know about it.
+    ///
+    /// The same caveat as for [`try_send()`][try_send] applies: never call
+    /// this from inside a signal handler.
+    ///
+    /// [try_send]: #method.try_send
+    pub fn send(&self, message: C::Message) {
+        let _ = self.try_send(message);
+    }
+
+    /// Run a [`Future`][Future] on the GTK main loop and send the message it
+    /// resolves to to the component this `Scope` belongs to.
+    ///
+    /// This is what [`UpdateAction::defer()`][defer] does for you, but you can
+    /// call it from anywhere on the main thread, like a signal handler of a
+    /// widget you're managing yourself. If the component has unmounted by the
+    /// time the future resolves, its message is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it's called from a thread other than the one running the GTK
+    /// main loop.
+    ///
+    /// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [defer]: ../enum.UpdateAction.html#method.defer
+    pub fn spawn(&self, job: impl Future<Output = C::Message> + 'static) {
+        let scope = self.clone();
+        crate::spawn_local(async move {
+            let message = job.await;
+            // The component may have gone away while the job was running,
+            // in which case there's nobody left to receive the result.
+            if scope.try_send(message).is_err() {
+                debug!(
+                    "{} {}",
+                    scope.name().magenta().bold(),
+                    "discarding result of deferred job after unmount".bright_red()
+                );
+            }
+        })
+    }
+
     /// Get the name of the component this `Scope` belongs to.
     pub fn name(&self) -> &'static str {
         &self.name
     }
 }
 
+/// Values which a signal handler can return in place of a single message.
+///
+/// A handler can return a message, which is sent to the component as usual,
+/// an [`Option`][Option] of a message, in which case returning `None` sends
+/// nothing, or a [`Vec`][Vec] of messages, which are sent in order. The
+/// [`msgs!`][msgs] macro builds a [`Vec`][Vec] out of any mix of these.
+///
+/// However many messages a handler sends, the component only renders once
+/// after they've all been processed.
+///
+/// [Option]: https://doc.rust-lang.org/std/option/enum.Option.html
+/// [Vec]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+/// [msgs]: ../macro.msgs.html
+pub trait IntoMessages<M> {
+    /// The iterator over the messages to send.
+    type Iter: Iterator<Item = M>;
+
+    /// Turn this value into the messages it represents.
+    fn into_messages(self) -> Self::Iter;
+}
+
+impl<M> IntoMessages<M> for M {
+    type Iter = std::iter::Once<M>;
+
+    fn into_messages(self) -> Self::Iter {
+        std::iter::once(self)
+    }
+}
+
+impl<M> IntoMessages<M> for Option<M> {
+    type Iter = std::option::IntoIter<M>;
+
+    fn into_messages(self) -> Self::Iter {
+        self.into_iter()
+    }
+}
+
+impl<M> IntoMessages<M> for Vec<M> {
+    type Iter = std::vec::IntoIter<M>;
+
+    fn into_messages(self) -> Self::Iter {
+        self.into_iter()
+    }
+}
+
+/// The result of a signal handler: the messages to send, along with the value
+/// the signal itself expects its handlers to return.
+#[doc(hidden)]
+pub trait HandlerResult<M, Ret> {
+    type Messages: IntoMessages<M>;
+
+    fn split(self) -> (Ret, Self::Messages);
+}
+
+impl<M, R: IntoMessages<M>> HandlerResult<M, ()> for R {
+    type Messages = R;
+
+    fn split(self) -> ((), R) {
+        ((), self)
+    }
+}
+
+impl<M, R: IntoMessages<M>> HandlerResult<M, Inhibit> for (Inhibit, R) {
+    type Messages = R;
+
+    fn split(self) -> (Inhibit, R) {
+        self
+    }
+}
+
+/// Paces the messages of a signal handler declared with
+/// `on signal(debounce=...)` or `on signal(throttle=...)`.
+#[doc(hidden)]
+pub struct RateLimit<C: Component> {
+    scope: Scope<C>,
+    throttle: bool,
+    period: u32,
+    state: Rc<RefCell<RateLimitState<C::Message>>>,
+}
+
+struct RateLimitState<M> {
+    pending: Option<Vec<M>>,
+    timer: Option<SourceId>,
+}
+
+impl<C: 'static + Component> RateLimit<C> {
+    /// Only send the messages from the last signal emission, once `period`
+    /// has passed without another one.
+    pub fn debounce(scope: &Scope<C>, period: Duration) -> Self {
+        Self::new(scope, false, period)
+    }
+
+    /// Send the messages from the first signal emission straight away, then
+    /// at most the messages from the latest emission once every `period`.
+    pub fn throttle(scope: &Scope<C>, period: Duration) -> Self {
+        Self::new(scope, true, period)
+    }
+
+    fn new(scope: &Scope<C>, throttle: bool, period: Duration) -> Self {
+        RateLimit {
+            scope: scope.clone(),
+            throttle,
+            period: period.as_millis().min(u32::MAX as u128) as u32,
+            state: Rc::new(RefCell::new(RateLimitState {
+                pending: None,
+                timer: None,
+            })),
+        }
+    }
+
+    pub fn send_result<R, Ret>(&self, result: R) -> Ret
+    where
+        R: HandlerResult<C::Message, Ret>,
+    {
+        let (ret, messages) = result.split();
+        let messages: Vec<_> = messages.into_messages().collect();
+        if messages.is_empty() {
+            return ret;
+        }
+        let mut state = self.state.borrow_mut();
+        if self.throttle && state.timer.is_none() {
+            self.scope.send_messages(messages);
+        } else {
+            state.pending = Some(messages);
+        }
+        if !self.throttle {
+            if let Some(timer) = state.timer.take() {
+                glib::source_remove(timer);
+            }
+        }
+        if state.timer.is_none() {
+            state.timer = Some(self.start_timer());
+        }
+        ret
+    }
+
+    fn start_timer(&self) -> SourceId {
+        let scope = self.scope.clone();
+        let state = self.state.clone();
+        let throttle = self.throttle;
+        glib::timeout_add_local(self.period, move || {
+            let mut state = state.borrow_mut();
+            let pending = state.pending.take();
+            // A throttled handler keeps pacing its messages for as long as
+            // there are more of them.
+            let more = throttle && pending.is_some();
+            if !more {
+                state.timer = None;
+            }
+            for message in pending.into_iter().flatten() {
+                // The component may have gone away while we were waiting.
+                if scope.try_send(message).is_err() {
+                    break;
+                }
+            }
+            Continue(more)
+        })
+    }
+}
+
+impl<C: Component> Drop for RateLimit<C> {
+    fn drop(&mut self) {
+        if let Some(timer) = self.state.borrow_mut().timer.take() {
+            glib::source_remove(timer);
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn extend_messages<M>(messages: &mut Vec<M>, more: impl IntoMessages<M>) {
+    messages.extend(more.into_messages());
+}
+
 pub(crate) struct AnyScope {
     type_id: TypeId,
     ptr: AtomicPtr<()>,
@@ -164,7 +465,7 @@ impl<C: 'static + Component> From<Scope<C>> for AnyScope {
             }
         };
         AnyScope {
-            type_id: TypeId::of::<C::Properties>(),
+            type_id: TypeId::of::<C>(),
             ptr,
             drop: Box::new(drop),
         }
@@ -179,7 +480,7 @@ impl Drop for AnyScope {
 
 impl AnyScope {
     pub(crate) fn try_get<C: 'static + Component>(&self) -> Option<&'static Scope<C>> {
-        if TypeId::of::<C::Properties>() == self.type_id {
+        if TypeId::of::<C>() == self.type_id {
             #[allow(unsafe_code)]
             unsafe {
                 (self.ptr.load(Ordering::Relaxed) as *const Scope<C>).as_ref()
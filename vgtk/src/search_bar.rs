@@ -0,0 +1,94 @@
+use glib::{Cast, ObjectExt, SignalHandlerId, WeakRef};
+use gtk::{Editable, GtkWindowExt, Inhibit, SearchBar, SearchBarExt, TextView, WidgetExt, Window};
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+thread_local! {
+    static CAPTURES: RefCell<Vec<(WeakRef<SearchBar>, Rc<Capture>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Default)]
+struct Capture {
+    enabled: Cell<bool>,
+    window: RefCell<Option<(WeakRef<Window>, SignalHandlerId)>>,
+}
+
+fn capture(bar: &SearchBar) -> Option<Rc<Capture>> {
+    CAPTURES.with(|captures| {
+        let mut captures = captures.borrow_mut();
+        captures.retain(|(bar, _)| bar.upgrade().is_some());
+        captures
+            .iter()
+            .find(|(capturing, _)| capturing.upgrade().as_ref() == Some(bar))
+            .map(|(_, capture)| capture.clone())
+    })
+}
+
+/// Test whether `bar` is capturing key events from its window.
+pub(crate) fn captures_key_events(bar: &SearchBar) -> bool {
+    capture(bar)
+        .map(|capture| capture.enabled.get())
+        .unwrap_or(false)
+}
+
+/// Start or stop passing the key events of the window `bar` is in to it.
+pub(crate) fn set_capture_key_events(bar: &SearchBar, enabled: bool) {
+    if let Some(capture) = capture(bar) {
+        capture.enabled.set(enabled);
+        return;
+    }
+    if !enabled {
+        return;
+    }
+    let capture = Rc::new(Capture::default());
+    capture.enabled.set(true);
+    CAPTURES.with(|captures| {
+        captures
+            .borrow_mut()
+            .push((bar.downgrade(), capture.clone()))
+    });
+    attach(bar, &capture);
+    // The bar usually isn't in a window yet when its attributes are first
+    // set, and it can be moved to another one later.
+    bar.connect_hierarchy_changed(move |bar, _| attach(bar, &capture));
+}
+
+// Listen for key presses on the window `bar` is in, if it's not the one
+// we're listening to already.
+fn attach(bar: &SearchBar, capture: &Rc<Capture>) {
+    let window = bar
+        .get_toplevel()
+        .and_then(|toplevel| toplevel.downcast::<Window>().ok());
+    let mut attached = capture.window.borrow_mut();
+    let current = attached.as_ref().and_then(|(window, _)| window.upgrade());
+    if current == window {
+        return;
+    }
+    if let Some((window, handler)) = attached.take() {
+        if let Some(window) = window.upgrade() {
+            window.disconnect(handler);
+        }
+    }
+    if let Some(window) = window {
+        let bar = bar.downgrade();
+        let capture = capture.clone();
+        let handler = window.connect_key_press_event(move |window, event| {
+            let bar = match bar.upgrade() {
+                Some(bar) if capture.enabled.get() && !typing(window) => bar,
+                _ => return Inhibit(false),
+            };
+            Inhibit(bar.handle_event(event))
+        });
+        *attached = Some((window.downgrade(), handler));
+    }
+}
+
+// Keys typed into another text field belong to it, not the search bar.
+fn typing(window: &Window) -> bool {
+    window
+        .get_focus()
+        .map(|focus| focus.is::<Editable>() || focus.is::<TextView>())
+        .unwrap_or(false)
+}
@@ -0,0 +1,227 @@
+//! Two way bindings between component state and [`GSettings`][Settings].
+//!
+//! A [`SettingsBinding`][SettingsBinding] connects keys in a GSettings schema
+//! to your component's messages. Keys you [`read()`][read] are sent to your
+//! component as messages, once when it subscribes and again whenever they
+//! change, whether your application changed them or something else did, like
+//! `dconf-editor`. Keys you [`write()`][write] are set from the messages your
+//! component receives, when you pass them to [`store()`][store] from your
+//! [`update`][update] method. Together they give you preferences which
+//! persist between runs without any glue code of your own.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, Component, Subscription, UpdateAction, VNode};
+//! # use vgtk::lib::gtk::*;
+//! use vgtk::lib::gio::Settings;
+//! use vgtk::settings::SettingsBinding;
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     SetDarkTheme(bool),
+//!     SetFontSize(i32),
+//! }
+//!
+//! struct Preferences {
+//!     dark_theme: bool,
+//!     font_size: i32,
+//!     settings: SettingsBinding<Message>,
+//! }
+//!
+//! impl Default for Preferences {
+//!     fn default() -> Self {
+//!         let settings = SettingsBinding::new(Settings::new("org.example.Preferences"))
+//!             .read("dark-theme", Message::SetDarkTheme)
+//!             .write("dark-theme", |message| match message {
+//!                 Message::SetDarkTheme(dark_theme) => Some(*dark_theme),
+//!                 _ => None,
+//!             })
+//!             .read("font-size", Message::SetFontSize)
+//!             .write("font-size", |message| match message {
+//!                 Message::SetFontSize(font_size) => Some(*font_size),
+//!                 _ => None,
+//!             });
+//!         Preferences {
+//!             dark_theme: false,
+//!             font_size: 12,
+//!             settings,
+//!         }
+//!     }
+//! }
+//!
+//! impl Component for Preferences {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         self.settings.store(&message);
+//!         match message {
+//!             Message::SetDarkTheme(dark_theme) => self.dark_theme = dark_theme,
+//!             Message::SetFontSize(font_size) => self.font_size = font_size,
+//!         }
+//!         UpdateAction::Render
+//!     }
+//!
+//!     fn subscriptions(&self) -> Vec<Subscription<Message>> {
+//!         vec![self.settings.subscription()]
+//!     }
+//! #   fn view(&self) -> VNode<Self> { todo!() }
+//! }
+//! ```
+//!
+//! [Settings]: ../../gio/struct.Settings.html
+//! [SettingsBinding]: struct.SettingsBinding.html
+//! [read]: struct.SettingsBinding.html#method.read
+//! [write]: struct.SettingsBinding.html#method.write
+//! [store]: struct.SettingsBinding.html#method.store
+//! [update]: ../trait.Component.html#method.update
+
+use futures::channel::mpsc::unbounded;
+use gio::prelude::SettingsExtManual;
+use gio::{Settings, SettingsExt};
+use glib::variant::FromVariant;
+use glib::{Cast, ToVariant};
+
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use crate::subscription::{SignalStream, Subscription};
+
+/// A set of bindings between the keys of a [`Settings`][Settings] object and
+/// a component's messages.
+///
+/// See the [module documentation](index.html) for an example.
+///
+/// [Settings]: ../../gio/struct.Settings.html
+pub struct SettingsBinding<Msg> {
+    settings: Settings,
+    readers: Vec<(String, Rc<dyn Fn(&Settings) -> Msg>)>,
+    writers: Vec<Rc<dyn Fn(&Settings, &Msg)>>,
+}
+
+impl<Msg: 'static> SettingsBinding<Msg> {
+    /// Construct a binding to `settings` without any keys.
+    pub fn new(settings: Settings) -> Self {
+        SettingsBinding {
+            settings,
+            readers: Vec::new(),
+            writers: Vec::new(),
+        }
+    }
+
+    /// Get the settings object this binding is bound to.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Send the value of `key` to the component as the message returned by
+    /// `f`.
+    ///
+    /// The value is converted from the key's [`Variant`][Variant] into a `T`,
+    /// which must match the type in the schema: a `bool` for a `b` key, an
+    /// `i32` for an `i` key, a `String` for an `s` key and so on. If it doesn't
+    /// match, reading the key panics.
+    ///
+    /// [Variant]: ../../glib/variant/struct.Variant.html
+    pub fn read<T, F>(mut self, key: &str, f: F) -> Self
+    where
+        T: FromVariant,
+        F: Fn(T) -> Msg + 'static,
+    {
+        let name = key.to_string();
+        self.readers.push((
+            key.to_string(),
+            Rc::new(move |settings: &Settings| f(settings.get(&name))),
+        ));
+        self
+    }
+
+    /// Set `key` to the value `f` returns for a message passed to
+    /// [`store()`][store], unless it returns `None`.
+    ///
+    /// [store]: #method.store
+    pub fn write<T, F>(mut self, key: &str, f: F) -> Self
+    where
+        T: ToVariant,
+        F: Fn(&Msg) -> Option<T> + 'static,
+    {
+        let key = key.to_string();
+        self.writers
+            .push(Rc::new(move |settings: &Settings, message: &Msg| {
+                if let Some(value) = f(message) {
+                    let value = value.to_variant();
+                    // Writing a key notifies our own subscription, which would
+                    // send the message straight back to be written again.
+                    if settings.get_value(&key) != value {
+                        if let Err(error) = settings.set_value(&key, &value) {
+                            panic!("unable to write settings key {:?}: {}", key, error);
+                        }
+                    }
+                }
+            }));
+        self
+    }
+
+    /// Write every key bound with [`write()`][write] for which `message` has a
+    /// value.
+    ///
+    /// Call this from your component's [`update`][update] method with each
+    /// message it receives.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key can't be written, either because it isn't writable or
+    /// because its value doesn't match the type in the schema.
+    ///
+    /// [write]: #method.write
+    /// [update]: ../trait.Component.html#method.update
+    pub fn store(&self, message: &Msg) {
+        for writer in &self.writers {
+            writer(&self.settings, message);
+        }
+    }
+
+    /// Construct a subscription which sends the messages for every key bound
+    /// with [`read()`][read], once straight away and again whenever a key
+    /// changes.
+    ///
+    /// [read]: #method.read
+    pub fn subscription(&self) -> Subscription<Msg> {
+        let (sender, receiver) = unbounded();
+        for (_, reader) in &self.readers {
+            let _ = sender.unbounded_send(reader(&self.settings));
+        }
+        let readers = self.readers.clone();
+        let handler = self.settings.connect_changed(move |settings, changed| {
+            for (key, reader) in &readers {
+                if key == changed {
+                    // The receiver only goes away along with the signal handler.
+                    let _ = sender.unbounded_send(reader(settings));
+                }
+            }
+        });
+        Subscription::new(SignalStream {
+            object: self.settings.clone().upcast(),
+            handlers: vec![handler],
+            receiver,
+        })
+    }
+}
+
+impl<Msg> Clone for SettingsBinding<Msg> {
+    fn clone(&self) -> Self {
+        SettingsBinding {
+            settings: self.settings.clone(),
+            readers: self.readers.clone(),
+            writers: self.writers.clone(),
+        }
+    }
+}
+
+impl<Msg> Debug for SettingsBinding<Msg> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let keys: Vec<&str> = self.readers.iter().map(|(key, _)| key.as_str()).collect();
+        write!(f, "SettingsBinding{:?}", keys)
+    }
+}
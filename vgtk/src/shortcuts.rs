@@ -0,0 +1,116 @@
+use glib::markup_escape_text;
+use gtk::prelude::BuilderExtManual;
+use gtk::{Builder, GtkWindowExt, ShortcutsWindow, WidgetExt};
+
+use crate::action::{shortcuts, Shortcut};
+use crate::component::current_window;
+
+fn property(name: &str, value: &str) -> String {
+    format!(
+        "<property name=\"{}\">{}</property>",
+        name,
+        markup_escape_text(value)
+    )
+}
+
+/// Construct a [`ShortcutsWindow`][ShortcutsWindow] listing the keyboard
+/// shortcuts of every action declared with [`ActionDecl`][ActionDecl].
+///
+/// Actions are listed in the order they were declared, under the heading of
+/// their [`group()`][group], and the window reflects the actions as they are
+/// when it's constructed, so construct a new one each time you show it. Most
+/// of the time, [`show_shortcuts()`][show_shortcuts] will do that for you.
+///
+/// [ShortcutsWindow]: ../gtk/struct.ShortcutsWindow.html
+/// [ActionDecl]: struct.ActionDecl.html
+/// [group]: struct.ActionDecl.html#method.group
+/// [show_shortcuts]: fn.show_shortcuts.html
+pub fn shortcuts_window() -> ShortcutsWindow {
+    let mut groups: Vec<(Option<String>, Vec<Shortcut>)> = Vec::new();
+    for shortcut in shortcuts() {
+        match groups
+            .iter_mut()
+            .find(|(group, _)| *group == shortcut.group)
+        {
+            Some((_, shortcuts)) => shortcuts.push(shortcut),
+            None => groups.push((shortcut.group.clone(), vec![shortcut])),
+        }
+    }
+    // The shortcuts widgets don't have Rust bindings, so we build them the
+    // way GTK would from a UI file.
+    let mut ui = String::new();
+    ui += "<interface><object class=\"GtkShortcutsWindow\" id=\"window\">";
+    ui += &property("modal", "True");
+    ui += "<child><object class=\"GtkShortcutsSection\">";
+    ui += &property("visible", "True");
+    ui += &property("section-name", "shortcuts");
+    for (group, shortcuts) in groups {
+        ui += "<child><object class=\"GtkShortcutsGroup\">";
+        ui += &property("visible", "True");
+        if let Some(group) = group {
+            ui += &property("title", &group);
+        }
+        for shortcut in shortcuts {
+            ui += "<child><object class=\"GtkShortcutsShortcut\">";
+            ui += &property("visible", "True");
+            ui += &property("title", &shortcut.title);
+            ui += &property("accelerator", &shortcut.accelerators.join(" "));
+            ui += "</object></child>";
+        }
+        ui += "</object></child>";
+    }
+    ui += "</object></child></object></interface>";
+    Builder::new_from_string(&ui)
+        .get_object("window")
+        .expect("unable to build ShortcutsWindow")
+}
+
+/// Show a [`ShortcutsWindow`][ShortcutsWindow] listing the keyboard
+/// shortcuts of every action declared with [`ActionDecl`][ActionDecl].
+///
+/// Give your actions a [`title()`][title] and a [`group()`][group] to have
+/// them listed nicely. The window belongs to the
+/// [current window][current_window], and is destroyed when it's closed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{ActionDecl, UpdateAction};
+/// # #[derive(Clone, Debug)] enum Message { Save, Shortcuts }
+/// # #[derive(Default)] struct Editor;
+/// # impl vgtk::Component for Editor { type Message = Message; type Properties = ();
+/// # fn view(&self) -> vgtk::VNode<Editor> { unimplemented!() }
+/// fn actions(&self) -> Vec<ActionDecl<Message>> {
+///     vec![
+///         ActionDecl::new("save", Message::Save)
+///             .accelerator("<Ctrl>s")
+///             .title("Save the document")
+///             .group("Documents"),
+///         ActionDecl::new("shortcuts", Message::Shortcuts)
+///             .accelerator("<Ctrl>question")
+///             .title("Show keyboard shortcuts")
+///             .group("General"),
+///     ]
+/// }
+///
+/// fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///     match message {
+///         Message::Shortcuts => vgtk::show_shortcuts(),
+///         // ...
+/// #       _ => {}
+///     }
+///     UpdateAction::None
+/// }
+/// # }
+/// ```
+///
+/// [ShortcutsWindow]: ../gtk/struct.ShortcutsWindow.html
+/// [ActionDecl]: struct.ActionDecl.html
+/// [title]: struct.ActionDecl.html#method.title
+/// [group]: struct.ActionDecl.html#method.group
+/// [current_window]: fn.current_window.html
+pub fn show_shortcuts() {
+    let window = shortcuts_window();
+    window.set_transient_for(current_window().as_ref());
+    window.show_all();
+}
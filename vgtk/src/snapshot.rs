@@ -0,0 +1,85 @@
+//! Render views as text, for snapshot tests.
+//!
+//! [`view()`][view] calls a component's [`view`][Component::view] method and
+//! writes out the result in something like the syntax of the `gtk!` macro,
+//! one widget per line, with each property's value as it was written. It
+//! doesn't build any widgets, so you don't need a display, and you can compare
+//! the result against a saved copy, with a crate like `insta` or just an
+//! `assert_eq!`, to catch changes to a view you didn't mean to make.
+//!
+//! The output is meant to be stable as long as the view is: signal handlers
+//! are left out, subcomponents are written as a single line with their
+//! properties instead of being rendered, and values which don't implement
+//! `Debug` are written as `_`.
+//!
+//! This is only available with the `snapshot` feature enabled. You'll usually
+//! only want it in your tests, so enable it in your `[dev-dependencies]`:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! vgtk = { version = "0.2", features = ["snapshot"] }
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, Component, VNode};
+//! # use vgtk::lib::gtk::*;
+//! #[derive(Default)]
+//! struct Counter {
+//!     count: usize,
+//! }
+//!
+//! impl Component for Counter {
+//!     type Message = ();
+//!     type Properties = ();
+//!
+//!     fn view(&self) -> VNode<Self> {
+//!         gtk! {
+//!             <Box spacing=6>
+//!                 <Label label=format!("Clicked {} times", self.count) />
+//!                 <Button label="Click me" on clicked=|_| () />
+//!             </Box>
+//!         }
+//!     }
+//! }
+//!
+//! #[test]
+//! fn counter_view() {
+//!     let counter = Counter { count: 3 };
+//!     assert_eq!(
+//!         vgtk::snapshot::view(&counter),
+//!         r#"<GtkBox spacing=6>
+//!     <GtkLabel label="Clicked 3 times" />
+//!     <GtkButton label="Click me" />
+//! </GtkBox>
+//! "#
+//!     );
+//! }
+//! ```
+//!
+//! [view]: fn.view.html
+//! [Component::view]: ../trait.Component.html#tymethod.view
+
+use crate::component::{detached_view, Component};
+use crate::vnode::describe::outline;
+use crate::vnode::VNode;
+
+/// Render a component's view as text.
+///
+/// The component doesn't need to be running: its view is rendered with a
+/// scope of its own, and any messages sent to it while rendering are dropped.
+/// Its stylesheet isn't loaded, so its scoped style class doesn't appear.
+pub fn view<C: 'static + Component>(component: &C) -> String {
+    node(&detached_view(component))
+}
+
+/// Render a [`VNode`][VNode] as text.
+///
+/// Lazy nodes are rendered as they would be if they'd never been rendered
+/// before.
+///
+/// [VNode]: ../enum.VNode.html
+pub fn node<C: 'static + Component>(node: &VNode<C>) -> String {
+    outline(node, true)
+}
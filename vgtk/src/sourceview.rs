@@ -0,0 +1,304 @@
+//! Code editor widgets from GtkSourceView.
+//!
+//! A [`View`][View] is a [`TextView`][TextView] which highlights the syntax
+//! of the code in its [`Buffer`][Buffer], using a language definition and a
+//! style scheme from the ones GtkSourceView ships with. vgtk binds just enough
+//! of GtkSourceView to use them in the [`gtk!`][gtk!] macro: with
+//! [`SourceViewExtHelpers`][SourceViewExtHelpers] in scope, a view takes
+//! `language`, `scheme` and `text` attributes, and its `text_changed` signal
+//! sends your component the user's edits. Everything else a view inherits
+//! from [`TextView`][TextView], like `monospace`, works as usual.
+//!
+//! Languages and style schemes are named by their GtkSourceView IDs, like
+//! `"rust"` or `"solarized-dark"`, and an empty ID means none. An ID which
+//! isn't installed logs a warning and leaves the view without one.
+//!
+//! This module is only available with the `sourceview` feature enabled, which
+//! links against GtkSourceView 3.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use vgtk::ext::*;
+//! use vgtk::sourceview::View;
+//! # use vgtk::{gtk, Component, VNode};
+//! # use vgtk::lib::gtk::*;
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Edited(String),
+//! }
+//!
+//! #[derive(Default)]
+//! struct Editor {
+//!     source: String,
+//! }
+//!
+//! # impl Component for Editor { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Editor> {
+//! gtk! {
+//!     <ScrolledWindow>
+//!         <View language="rust" scheme="classic" monospace=true text=self.source.clone()
+//!               on text_changed=|_, text| Message::Edited(text) />
+//!     </ScrolledWindow>
+//! }
+//! # }}
+//! ```
+//!
+//! [View]: struct.View.html
+//! [Buffer]: struct.Buffer.html
+//! [TextView]: ../../gtk/struct.TextView.html
+//! [SourceViewExtHelpers]: ../ext/trait.SourceViewExtHelpers.html
+//! [gtk!]: ../macro.gtk.html
+
+// There's no gtk-rs binding for GtkSourceView 3 that builds against gtk 0.8,
+// so we bind what we need ourselves. `glib_wrapper!` also declares the
+// undocumented class structs.
+#![allow(unsafe_code, missing_docs)]
+
+use glib::translate::{from_glib_none, FromGlibPtrFull, ToGlibPtr};
+use glib::{
+    glib_object_wrapper, glib_wrapper, Cast, GString, IsA, ObjectExt, SignalHandlerId, WeakRef,
+};
+use gtk::{TextBuffer, TextBufferExt, TextViewExt, WidgetExt};
+
+use std::cell::Cell;
+
+use log::warn;
+
+glib_wrapper! {
+    /// A [`TextView`][TextView] which highlights the syntax of its code.
+    ///
+    /// [TextView]: ../../gtk/struct.TextView.html
+    pub struct View(Object<gtk_source_sys::GtkSourceView, gtk_source_sys::GtkSourceViewClass, ViewClass>)
+        @extends gtk::TextView, gtk::Container, gtk::Widget,
+        @implements gtk::Buildable, gtk::Scrollable;
+
+    match fn {
+        get_type => || gtk_source_sys::gtk_source_view_get_type(),
+    }
+}
+
+glib_wrapper! {
+    /// The [`TextBuffer`][TextBuffer] behind a [`View`][View], which knows
+    /// the language of its text and the style scheme to highlight it with.
+    ///
+    /// [TextBuffer]: ../../gtk/struct.TextBuffer.html
+    /// [View]: struct.View.html
+    pub struct Buffer(Object<gtk_source_sys::GtkSourceBuffer, gtk_source_sys::GtkSourceBufferClass, BufferClass>)
+        @extends gtk::TextBuffer;
+
+    match fn {
+        get_type => || gtk_source_sys::gtk_source_buffer_get_type(),
+    }
+}
+
+impl View {
+    /// Create a view with a new, empty [`Buffer`][Buffer].
+    ///
+    /// [Buffer]: struct.Buffer.html
+    pub fn new() -> Self {
+        assert!(gtk::is_initialized_main_thread());
+        unsafe {
+            from_glib_none(
+                gtk_source_sys::gtk_source_view_new() as *mut gtk_source_sys::GtkSourceView
+            )
+        }
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Buffer {
+    /// Create a new, empty buffer.
+    pub fn new() -> Self {
+        assert!(gtk::is_initialized_main_thread());
+        unsafe { from_glib_none(gtk_source_sys::gtk_source_buffer_new(std::ptr::null_mut())) }
+    }
+
+    /// Get the ID of the language the buffer's highlighted as, or an empty
+    /// string if it has none.
+    pub fn get_language(&self) -> String {
+        unsafe {
+            let language = gtk_source_sys::gtk_source_buffer_get_language(self.to_glib_none().0);
+            if language.is_null() {
+                return String::new();
+            }
+            let id: Option<GString> =
+                from_glib_none(gtk_source_sys::gtk_source_language_get_id(language));
+            id.map(Into::into).unwrap_or_default()
+        }
+    }
+
+    /// Highlight the buffer as the language with the given ID, or stop
+    /// highlighting it if the ID is empty.
+    pub fn set_language(&self, id: &str) {
+        unsafe {
+            let language = if id.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                let manager = gtk_source_sys::gtk_source_language_manager_get_default();
+                let language = gtk_source_sys::gtk_source_language_manager_get_language(
+                    manager,
+                    id.to_glib_none().0,
+                );
+                if language.is_null() {
+                    warn!("GtkSourceView has no language {:?}", id);
+                }
+                language
+            };
+            gtk_source_sys::gtk_source_buffer_set_language(self.to_glib_none().0, language);
+        }
+    }
+
+    /// Get the ID of the style scheme the buffer's highlighted with, or an
+    /// empty string if it has none.
+    pub fn get_scheme(&self) -> String {
+        unsafe {
+            let scheme = gtk_source_sys::gtk_source_buffer_get_style_scheme(self.to_glib_none().0);
+            if scheme.is_null() {
+                return String::new();
+            }
+            let id: Option<GString> =
+                from_glib_none(gtk_source_sys::gtk_source_style_scheme_get_id(scheme));
+            id.map(Into::into).unwrap_or_default()
+        }
+    }
+
+    /// Highlight the buffer with the style scheme with the given ID, or leave
+    /// it unstyled if the ID is empty.
+    pub fn set_scheme(&self, id: &str) {
+        unsafe {
+            let scheme = if id.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                let manager = gtk_source_sys::gtk_source_style_scheme_manager_get_default();
+                let scheme = gtk_source_sys::gtk_source_style_scheme_manager_get_scheme(
+                    manager,
+                    id.to_glib_none().0,
+                );
+                if scheme.is_null() {
+                    warn!("GtkSourceView has no style scheme {:?}", id);
+                }
+                scheme
+            };
+            gtk_source_sys::gtk_source_buffer_set_style_scheme(self.to_glib_none().0, scheme);
+        }
+    }
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+thread_local! {
+    // Set while we're replacing a view's text, so `text_changed` only
+    // reports the user's edits and not the ones we make while rendering.
+    static SETTING_TEXT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Get the source buffer behind `view`, if it hasn't been given a plain
+/// `TextBuffer` instead.
+pub(crate) fn buffer(view: &View) -> Option<Buffer> {
+    view.get_buffer()
+        .and_then(|buffer| buffer.downcast::<Buffer>().ok())
+}
+
+/// Get the ID of the language `view` highlights its text as.
+pub(crate) fn language(view: &View) -> String {
+    buffer(view)
+        .map(|buffer| buffer.get_language())
+        .unwrap_or_default()
+}
+
+/// Highlight the text in `view` as the language with the given ID.
+pub(crate) fn set_language(view: &View, id: &str) {
+    if let Some(buffer) = buffer(view) {
+        buffer.set_language(id);
+    }
+}
+
+/// Get the ID of the style scheme `view` highlights its text with.
+pub(crate) fn scheme(view: &View) -> String {
+    buffer(view)
+        .map(|buffer| buffer.get_scheme())
+        .unwrap_or_default()
+}
+
+/// Highlight the text in `view` with the style scheme with the given ID.
+pub(crate) fn set_scheme(view: &View, id: &str) {
+    if let Some(buffer) = buffer(view) {
+        buffer.set_scheme(id);
+    }
+}
+
+/// Get all of the text in `view`.
+pub(crate) fn text(view: &View) -> String {
+    view.get_buffer()
+        .and_then(|buffer| {
+            let (start, end) = buffer.get_bounds();
+            buffer.get_text(&start, &end, true)
+        })
+        .map(Into::into)
+        .unwrap_or_default()
+}
+
+/// Replace the text in `view`, without reporting it as an edit.
+pub(crate) fn set_text(view: &View, text: &str) {
+    if let Some(buffer) = view.get_buffer() {
+        SETTING_TEXT.with(|setting| setting.set(true));
+        buffer.set_text(text);
+        SETTING_TEXT.with(|setting| setting.set(false));
+    }
+}
+
+// Disconnects a handler from a view's buffer when it's dropped.
+struct BufferHandler {
+    buffer: WeakRef<TextBuffer>,
+    id: Option<SignalHandlerId>,
+}
+
+impl Drop for BufferHandler {
+    fn drop(&mut self) {
+        if let (Some(buffer), Some(id)) = (self.buffer.upgrade(), self.id.take()) {
+            buffer.disconnect(id);
+        }
+    }
+}
+
+/// Call `f` with the text in `view` whenever the user edits it.
+pub(crate) fn connect_text_changed<V, F>(view: &V, f: F) -> SignalHandlerId
+where
+    V: IsA<View>,
+    F: Fn(&V, String) + 'static,
+{
+    let buffer = view
+        .as_ref()
+        .get_buffer()
+        .expect("GtkSourceView has no buffer");
+    let weak_view = view.downgrade();
+    let id = buffer.connect_changed(move |_| {
+        if SETTING_TEXT.with(Cell::get) {
+            return;
+        }
+        if let Some(view) = weak_view.upgrade() {
+            f(&view, text(view.as_ref()));
+        }
+    });
+    // vgtk disconnects handlers from the widget they were set on, so the
+    // buffer's handler is owned by a do-nothing handler on the view, and goes
+    // away along with it.
+    let handler = BufferHandler {
+        buffer: buffer.downgrade(),
+        id: Some(id),
+    };
+    view.as_ref().connect_destroy(move |_| {
+        let _ = &handler;
+    })
+}
@@ -0,0 +1,365 @@
+//! A central store for application state, in the style of Redux.
+//!
+//! Per-component state is usually all you need, but once several parts of
+//! your application need to read and change the same state, it can help to
+//! keep it all in one place. A [`Store`][Store] holds a single state value,
+//! which can only be changed by dispatching actions to it. Each action is
+//! passed through the store's [`Middleware`][Middleware], which can log,
+//! rewrite or swallow it, and then to its reducers, which modify the state to
+//! match.
+//!
+//! Components connect to a store either by reading it from their
+//! [`view`][view] method, in which case they're rendered again whenever the
+//! state changes, or by subscribing to it with
+//! [`Store::subscription()`][subscription], which turns state changes into
+//! messages for their [`update`][update] method.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, Component, UpdateAction, VNode};
+//! # use vgtk::lib::gtk::*;
+//! use vgtk::store::{self, Logger, Store};
+//!
+//! #[derive(Clone, Debug, Default)]
+//! struct AppState {
+//!     todos: Vec<String>,
+//!     filter: String,
+//! }
+//!
+//! #[derive(Debug)]
+//! enum Action {
+//!     AddTodo(String),
+//!     SetFilter(String),
+//! }
+//!
+//! fn todos(todos: &mut Vec<String>, action: &Action) {
+//!     if let Action::AddTodo(todo) = action {
+//!         todos.push(todo.clone());
+//!     }
+//! }
+//!
+//! fn filter(filter: &mut String, action: &Action) {
+//!     if let Action::SetFilter(new_filter) = action {
+//!         *filter = new_filter.clone();
+//!     }
+//! }
+//!
+//! fn create_store() -> Store<AppState, Action> {
+//!     Store::new(AppState::default())
+//!         .reducer(store::slice(|state: &mut AppState| &mut state.todos, todos))
+//!         .reducer(store::slice(|state: &mut AppState| &mut state.filter, filter))
+//!         .middleware(Logger)
+//! }
+//!
+//! # #[derive(Clone, Debug)] enum Message { Add }
+//! # struct TodoList { store: Store<AppState, Action> }
+//! # impl Default for TodoList { fn default() -> Self { TodoList { store: create_store() } } }
+//! # impl Component for TodoList { type Message = Message; type Properties = ();
+//! fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!     match message {
+//!         Message::Add => self.store.dispatch(Action::AddTodo("Write docs".into())),
+//!     }
+//!     UpdateAction::None
+//! }
+//!
+//! fn view(&self) -> VNode<Self> {
+//!     let count = self.store.with(|state| state.todos.len());
+//!     gtk! {
+//!         <Label label=format!("{} things to do", count) />
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! [Store]: struct.Store.html
+//! [Middleware]: trait.Middleware.html
+//! [subscription]: struct.Store.html#method.subscription
+//! [view]: ../trait.Component.html#tymethod.view
+//! [update]: ../trait.Component.html#method.update
+
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::stream::StreamExt;
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use colored::Colorize;
+use log::{debug, trace};
+
+use crate::context::Consumers;
+use crate::subscription::Subscription;
+
+type Reducer<S, A> = Box<dyn Fn(&mut S, &A)>;
+
+/// A hook into a [`Store`][Store]'s dispatch process.
+///
+/// Every action dispatched to a store passes through the
+/// [`before()`][before] method of each of its middlewares in the order they
+/// were added, then through the store's reducers, and finally through the
+/// [`after()`][after] method of each middleware in reverse order.
+///
+/// Both methods do nothing by default, so you only need to implement the ones
+/// you need.
+///
+/// # Examples
+///
+/// A middleware which keeps a history of previous states, and restores the
+/// last one when it sees an `Undo` action:
+///
+/// ```rust,no_run
+/// # use std::cell::RefCell;
+/// use vgtk::store::Middleware;
+///
+/// enum Action {
+///     Undo,
+///     Increment,
+/// }
+///
+/// #[derive(Default)]
+/// struct History {
+///     states: RefCell<Vec<i32>>,
+/// }
+///
+/// impl Middleware<i32, Action> for History {
+///     fn before(&self, state: &mut i32, action: Action) -> Option<Action> {
+///         match action {
+///             Action::Undo => {
+///                 if let Some(previous) = self.states.borrow_mut().pop() {
+///                     *state = previous;
+///                 }
+///                 None
+///             }
+///             action => {
+///                 self.states.borrow_mut().push(*state);
+///                 Some(action)
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// [Store]: struct.Store.html
+/// [before]: #method.before
+/// [after]: #method.after
+pub trait Middleware<S, A> {
+    /// Called before an action reaches the reducers.
+    ///
+    /// Return the action, or a different action, to pass it on, or `None` to
+    /// stop it from going any further. You may also modify the state here.
+    fn before(&self, _state: &mut S, action: A) -> Option<A> {
+        Some(action)
+    }
+
+    /// Called after the reducers have processed an action.
+    ///
+    /// This is a good place to save the new state, for instance.
+    fn after(&self, _state: &S, _action: &A) {}
+}
+
+/// A [`Middleware`][Middleware] which logs every action dispatched to a
+/// store at log level `debug`, and the resulting state at log level `trace`.
+///
+/// [Middleware]: trait.Middleware.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Logger;
+
+impl<S: Debug, A: Debug> Middleware<S, A> for Logger {
+    fn before(&self, _state: &mut S, action: A) -> Option<A> {
+        debug!(
+            "{} {}",
+            "Store::dispatch".green(),
+            format!("{:?}", action).bright_white().bold()
+        );
+        Some(action)
+    }
+
+    fn after(&self, state: &S, _action: &A) {
+        trace!(
+            "{} {}",
+            "Store state:".bright_black(),
+            format!("{:?}", state).bright_black().bold()
+        );
+    }
+}
+
+/// Turn a reducer for one part of a state into a reducer for the whole state.
+///
+/// `lens` picks out the part of the state that `reducer` should modify. See
+/// the [module documentation][store] for an example.
+///
+/// [store]: index.html
+pub fn slice<S, T, A, L, R>(lens: L, reducer: R) -> impl Fn(&mut S, &A)
+where
+    L: Fn(&mut S) -> &mut T,
+    R: Fn(&mut T, &A),
+{
+    move |state, action| reducer(lens(state), action)
+}
+
+struct StoreState<S, A> {
+    state: RefCell<S>,
+    reducers: RefCell<Vec<Reducer<S, A>>>,
+    middleware: RefCell<Vec<Box<dyn Middleware<S, A>>>>,
+    queue: RefCell<VecDeque<A>>,
+    dispatching: Cell<bool>,
+    consumers: Consumers,
+    listeners: RefCell<Vec<UnboundedSender<()>>>,
+}
+
+/// A single source of truth for application state, changed only by
+/// dispatching actions to it.
+///
+/// A `Store` is a shared handle, so cloning it gives you another handle to
+/// the same store, which makes it easy to pass around, or to put in a
+/// [`Context`][Context] so every component can find it.
+///
+/// See the [module documentation][store] for an example.
+///
+/// [Context]: ../struct.Context.html
+/// [store]: index.html
+pub struct Store<S, A> {
+    store: Rc<StoreState<S, A>>,
+}
+
+impl<S: 'static, A: 'static> Store<S, A> {
+    /// Construct a store with the given initial state, and no reducers or
+    /// middleware.
+    pub fn new(state: S) -> Self {
+        Store {
+            store: Rc::new(StoreState {
+                state: RefCell::new(state),
+                reducers: Default::default(),
+                middleware: Default::default(),
+                queue: Default::default(),
+                dispatching: Default::default(),
+                consumers: Default::default(),
+                listeners: Default::default(),
+            }),
+        }
+    }
+
+    /// Add a reducer, which updates the state in response to an action.
+    ///
+    /// Reducers are called in the order they were added. Use
+    /// [`slice()`][slice] to build a reducer for a part of the state.
+    ///
+    /// [slice]: fn.slice.html
+    pub fn reducer(self, reducer: impl Fn(&mut S, &A) + 'static) -> Self {
+        self.store.reducers.borrow_mut().push(Box::new(reducer));
+        self
+    }
+
+    /// Add a [`Middleware`][Middleware].
+    ///
+    /// [Middleware]: trait.Middleware.html
+    pub fn middleware(self, middleware: impl Middleware<S, A> + 'static) -> Self {
+        self.store
+            .middleware
+            .borrow_mut()
+            .push(Box::new(middleware));
+        self
+    }
+
+    /// Dispatch an action to the store.
+    ///
+    /// If you dispatch an action from inside a reducer or a middleware, it's
+    /// processed once the current action is done. Components which depend on
+    /// the store are notified once, after every pending action has been
+    /// processed.
+    pub fn dispatch(&self, action: A) {
+        self.store.queue.borrow_mut().push_back(action);
+        if self.store.dispatching.replace(true) {
+            return;
+        }
+        loop {
+            let next = self.store.queue.borrow_mut().pop_front();
+            match next {
+                Some(action) => self.process(action),
+                None => break,
+            }
+        }
+        self.store.dispatching.set(false);
+        self.notify();
+    }
+
+    fn process(&self, action: A) {
+        let middleware = self.store.middleware.borrow();
+        let mut action = Some(action);
+        {
+            let mut state = self.store.state.borrow_mut();
+            for middleware in middleware.iter() {
+                action = match action {
+                    Some(action) => middleware.before(&mut state, action),
+                    None => break,
+                };
+            }
+            if let Some(ref action) = action {
+                for reducer in self.store.reducers.borrow().iter() {
+                    reducer(&mut state, action);
+                }
+            }
+        }
+        if let Some(action) = action {
+            let state = self.store.state.borrow();
+            for middleware in middleware.iter().rev() {
+                middleware.after(&state, &action);
+            }
+        }
+    }
+
+    fn notify(&self) {
+        self.store.consumers.notify();
+        self.store
+            .listeners
+            .borrow_mut()
+            .retain(|listener| listener.unbounded_send(()).is_ok());
+    }
+
+    /// Call a function with a reference to the current state.
+    ///
+    /// If you call this from a component's view, the component will render
+    /// again when the state changes.
+    pub fn with<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        self.store.consumers.subscribe();
+        f(&self.store.state.borrow())
+    }
+
+    /// Get a copy of the current state.
+    ///
+    /// If you call this from a component's view, the component will render
+    /// again when the state changes.
+    pub fn get(&self) -> S
+    where
+        S: Clone,
+    {
+        self.with(S::clone)
+    }
+
+    /// Construct a [`Subscription`][Subscription] which sends a message built
+    /// from the new state every time the state changes.
+    ///
+    /// [Subscription]: ../struct.Subscription.html
+    pub fn subscription<Msg: 'static>(&self, f: impl Fn(&S) -> Msg + 'static) -> Subscription<Msg> {
+        let (sender, receiver) = unbounded();
+        self.store.listeners.borrow_mut().push(sender);
+        let store = self.clone();
+        Subscription::new(receiver.map(move |()| f(&store.store.state.borrow())))
+    }
+}
+
+impl<S, A> Clone for Store<S, A> {
+    fn clone(&self) -> Self {
+        Store {
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl<S: Debug, A> Debug for Store<S, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Store({:?})", self.store.state.borrow())
+    }
+}
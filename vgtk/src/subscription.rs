@@ -0,0 +1,353 @@
+use futures::{
+    channel::mpsc::unbounded,
+    future::FutureExt,
+    stream::{Stream, StreamExt},
+    task::{Context, Poll},
+};
+use gio::{
+    Cancellable, File, FileExt, FileMonitor, FileMonitorEvent, FileMonitorExt, FileMonitorFlags,
+    IOErrorEnum,
+};
+use glib::{Cast, Object, ObjectExt, SignalHandlerId};
+use gtk::{Settings, SettingsExt};
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Error, Formatter};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::timer;
+
+/// A long running source of messages for a [`Component`][Component].
+///
+/// A `Subscription` wraps a [`Stream`][Stream] of messages. You declare your
+/// component's subscriptions by returning them from
+/// [`Component::subscriptions()`][subscriptions], and the runtime takes care of
+/// the rest: every item the stream produces is passed to your component's
+/// [`update`][update] method, and the stream is dropped when the component
+/// unmounts.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use vgtk::{gtk, Component, Subscription, UpdateAction, VNode};
+/// # use vgtk::lib::gtk::Box;
+/// # #[derive(Clone, Debug)]
+/// enum Message {
+///     Tick,
+/// }
+///
+/// # #[derive(Default)]
+/// # struct Clock;
+/// # impl Component for Clock {
+/// #     type Message = Message; type Properties = ();
+/// #     fn view(&self) -> VNode<Self> { gtk!{ <Box/> } }
+/// fn subscriptions(&self) -> Vec<Subscription<Message>> {
+///     vec![Subscription::interval(Duration::from_secs(1), Message::Tick)]
+/// }
+/// # }
+/// ```
+///
+/// [Component]: trait.Component.html
+/// [subscriptions]: trait.Component.html#method.subscriptions
+/// [update]: trait.Component.html#method.update
+/// [Stream]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+pub struct Subscription<Msg> {
+    stream: Pin<Box<dyn Stream<Item = Msg>>>,
+}
+
+impl<Msg: 'static> Subscription<Msg> {
+    /// Construct a subscription from any [`Stream`][Stream] of messages.
+    ///
+    /// This is also how you subscribe to a channel: the receiving end of a
+    /// [`futures::channel::mpsc`][mpsc] channel is a [`Stream`][Stream].
+    ///
+    /// [Stream]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+    /// [mpsc]: https://docs.rs/futures/latest/futures/channel/mpsc/index.html
+    pub fn new(stream: impl Stream<Item = Msg> + 'static) -> Self {
+        Subscription {
+            stream: stream.boxed_local(),
+        }
+    }
+
+    /// Construct a subscription which sends a clone of `message` every `period`.
+    ///
+    /// See the [`timer`][timer] module for the stream this is built on.
+    ///
+    /// [timer]: timer/index.html
+    pub fn interval(period: Duration, message: Msg) -> Self
+    where
+        Msg: Clone,
+    {
+        Self::new(timer::interval(period).map(move |_| message.clone()))
+    }
+
+    /// Construct a subscription which sends `message` once, after `delay`.
+    pub fn timeout(delay: Duration, message: Msg) -> Self {
+        Self::new(timer::timeout(delay).map(|()| message).into_stream())
+    }
+
+    /// Construct a subscription which watches a [`File`][File] for changes.
+    ///
+    /// The callback receives the arguments of the [`FileMonitor`][FileMonitor]'s
+    /// `changed` signal, and can return `None` to ignore an event. The monitor
+    /// is cancelled when the subscription is dropped.
+    ///
+    /// This returns an error if GIO is unable to monitor the file.
+    ///
+    /// [File]: ../gio/struct.File.html
+    /// [FileMonitor]: ../gio/struct.FileMonitor.html
+    pub fn file_monitor<F>(file: &File, f: F) -> Result<Self, glib::Error>
+    where
+        F: Fn(&File, Option<&File>, FileMonitorEvent) -> Option<Msg> + 'static,
+    {
+        let monitor = file.monitor(FileMonitorFlags::NONE, None::<&Cancellable>)?;
+        let (sender, receiver) = unbounded();
+        monitor.connect_changed(move |_, file, other_file, event| {
+            if let Some(message) = f(file, other_file, event) {
+                // The receiver only goes away along with the monitor.
+                let _ = sender.unbounded_send(message);
+            }
+        });
+        Ok(Self::new(MonitorStream { monitor, receiver }))
+    }
+
+    /// Construct a subscription which sends a clone of `message` whenever a
+    /// [`File`][File] has been written, created or deleted.
+    ///
+    /// This is the easy way to reload a configuration file when it changes:
+    /// rather than every write, you only hear about it once the writer is
+    /// done. The file doesn't need to exist yet.
+    ///
+    /// This returns an error if GIO is unable to monitor the file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{gtk, Component, Subscription, VNode};
+    /// # use vgtk::lib::gio::File;
+    /// # use vgtk::lib::gtk::Box;
+    /// # #[derive(Clone, Debug)]
+    /// enum Message {
+    ///     ReloadConfig,
+    /// }
+    ///
+    /// # #[derive(Default)]
+    /// # struct Editor;
+    /// # impl Component for Editor {
+    /// #     type Message = Message; type Properties = ();
+    /// #     fn view(&self) -> VNode<Self> { gtk!{ <Box/> } }
+    /// fn subscriptions(&self) -> Vec<Subscription<Message>> {
+    ///     let config = File::new_for_path("config.toml");
+    ///     Subscription::file_changed(&config, Message::ReloadConfig)
+    ///         .into_iter()
+    ///         .collect()
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [File]: ../gio/struct.File.html
+    pub fn file_changed(file: &File, message: Msg) -> Result<Self, glib::Error>
+    where
+        Msg: Clone,
+    {
+        Self::file_monitor(file, move |_, _, event| match event {
+            FileMonitorEvent::ChangesDoneHint
+            | FileMonitorEvent::Created
+            | FileMonitorEvent::Deleted
+            | FileMonitorEvent::MovedIn
+            | FileMonitorEvent::MovedOut => Some(message.clone()),
+            _ => None,
+        })
+    }
+
+    /// Construct a subscription which follows the lines appended to a
+    /// [`File`][File], like `tail -f`.
+    ///
+    /// The callback is called with each complete line written after the
+    /// subscription was made, without its line ending. If the file is
+    /// truncated or replaced, as happens when logs are rotated, it's followed
+    /// again from the start.
+    ///
+    /// This returns an error if the file isn't on the local file system or GIO
+    /// is unable to monitor it.
+    ///
+    /// [File]: ../gio/struct.File.html
+    pub fn tail<F>(file: &File, f: F) -> Result<Self, glib::Error>
+    where
+        F: Fn(String) -> Msg + 'static,
+    {
+        let path = file.get_path().ok_or_else(|| {
+            glib::Error::new(
+                IOErrorEnum::NotSupported,
+                "only local files can be followed",
+            )
+        })?;
+        let offset = Cell::new(fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0));
+        let partial = RefCell::new(Vec::new());
+        let monitor = file.monitor(FileMonitorFlags::NONE, None::<&Cancellable>)?;
+        let (sender, receiver) = unbounded();
+        monitor.connect_changed(move |_, _, _, event| {
+            match event {
+                FileMonitorEvent::Created => offset.set(0),
+                FileMonitorEvent::Changed | FileMonitorEvent::ChangesDoneHint => {}
+                _ => return,
+            }
+            let mut partial = partial.borrow_mut();
+            if !read_appended(&path, &offset, &mut partial) {
+                partial.clear();
+                return;
+            }
+            while let Some(end) = partial.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<u8> = partial.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end_matches(&['\n', '\r'][..]).to_string();
+                // The receiver only goes away along with the monitor.
+                let _ = sender.unbounded_send(f(line));
+            }
+        });
+        Ok(Self::new(MonitorStream { monitor, receiver }))
+    }
+
+    /// Construct a subscription which follows whether GTK is using a dark
+    /// theme.
+    ///
+    /// The callback is called with `true` if the theme is dark and `false` if
+    /// it's light, once straight away and again whenever the theme changes,
+    /// either because the `gtk-application-prefer-dark-theme` setting changed
+    /// or because a different theme was chosen. A theme counts as dark if its
+    /// name ends in `-dark`, like `Adwaita-dark`.
+    ///
+    /// If there are no GTK settings to follow, the callback is never called.
+    pub fn dark_theme<F>(f: F) -> Self
+    where
+        F: Fn(bool) -> Msg + 'static,
+    {
+        let settings = match Settings::get_default() {
+            Some(settings) => settings,
+            None => return Self::new(futures::stream::empty()),
+        };
+        let (sender, receiver) = unbounded();
+        let send = Rc::new(move |settings: &Settings| {
+            // The receiver only goes away along with the signal handlers.
+            let _ = sender.unbounded_send(f(is_dark_theme(settings)));
+        });
+        send(&settings);
+        let prefer_dark = send.clone();
+        let handlers = vec![
+            settings.connect_property_gtk_application_prefer_dark_theme_notify(move |settings| {
+                prefer_dark(settings)
+            }),
+            settings.connect_property_gtk_theme_name_notify(move |settings| send(settings)),
+        ];
+        Self::new(SignalStream {
+            object: settings.upcast(),
+            handlers,
+            receiver,
+        })
+    }
+
+    /// Transform the messages produced by this subscription.
+    ///
+    /// This is useful for lifting a subscription provided by some other module
+    /// into your component's message type.
+    pub fn map<B: 'static, F>(self, f: F) -> Subscription<B>
+    where
+        F: FnMut(Msg) -> B + 'static,
+    {
+        Subscription::new(self.stream.map(f))
+    }
+
+    pub(crate) fn into_stream(self) -> Pin<Box<dyn Stream<Item = Msg>>> {
+        self.stream
+    }
+}
+
+impl<Msg> Debug for Subscription<Msg> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Subscription")
+    }
+}
+
+struct MonitorStream<S> {
+    monitor: FileMonitor,
+    receiver: S,
+}
+
+impl<S: Stream + Unpin> Stream for MonitorStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(ctx)
+    }
+}
+
+impl<S> Drop for MonitorStream<S> {
+    fn drop(&mut self) {
+        self.monitor.cancel();
+    }
+}
+
+// Read whatever was written to the file at `path` since `offset` onto the end
+// of `buffer`, starting over if the file has shrunk.
+fn read_appended(path: &Path, offset: &Cell<u64>, buffer: &mut Vec<u8>) -> bool {
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+    let len = match file.metadata() {
+        Ok(meta) => meta.len(),
+        Err(_) => return false,
+    };
+    if len < offset.get() {
+        offset.set(0);
+        buffer.clear();
+    }
+    if file.seek(SeekFrom::Start(offset.get())).is_err() {
+        return false;
+    }
+    match file.read_to_end(buffer) {
+        Ok(read) => {
+            offset.set(offset.get() + read as u64);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn is_dark_theme(settings: &Settings) -> bool {
+    settings.get_property_gtk_application_prefer_dark_theme()
+        || settings
+            .get_property_gtk_theme_name()
+            .map(|name| name.ends_with("-dark"))
+            .unwrap_or(false)
+}
+
+/// A stream of messages sent from signal handlers, which disconnects the
+/// handlers when it's dropped.
+pub(crate) struct SignalStream<S> {
+    pub(crate) object: Object,
+    pub(crate) handlers: Vec<SignalHandlerId>,
+    pub(crate) receiver: S,
+}
+
+impl<S: Stream + Unpin> Stream for SignalStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(ctx)
+    }
+}
+
+impl<S> Drop for SignalStream<S> {
+    fn drop(&mut self) {
+        for handler in self.handlers.drain(..) {
+            self.object.disconnect(handler);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Drive a component through its widgets in your tests.
+//!
+//! [`mount()`][mount] builds a component on its own, outside of any
+//! application, and returns a [`Mounted`][Mounted] handle which can find its
+//! widgets with a [`Selector`][Selector] and interact with them the way a user
+//! would: [`click()`][click] a button, [`type_text()`][type_text] into an
+//! entry, or [`activate()`][activate] a widget. These emit the same GTK
+//! signals a user's actions would, so your signal handlers, `update` method
+//! and view are all exercised, and each one runs the main loop until the
+//! component has processed the resulting messages and rendered again.
+//!
+//! GTK only runs on one thread, and needs a display to open windows on, so run
+//! your tests with `cargo test -- --test-threads=1`, under something like
+//! `xvfb-run` if you don't have a display to hand.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, Component, UpdateAction, VNode};
+//! # use vgtk::lib::gtk::*;
+//! # use vgtk::ext::*;
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Add,
+//!     Edit(String),
+//! }
+//!
+//! #[derive(Default)]
+//! struct Todo {
+//!     items: Vec<String>,
+//!     text: String,
+//! }
+//!
+//! impl Component for Todo {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         match message {
+//!             Message::Add => self.items.push(std::mem::take(&mut self.text)),
+//!             Message::Edit(text) => self.text = text,
+//!         }
+//!         UpdateAction::Render
+//!     }
+//!
+//!     fn view(&self) -> VNode<Self> {
+//!         gtk! {
+//!             <Window>
+//!                 <Box>
+//!                     <Entry widget_name="new-item" text=self.text.clone()
+//!                            on changed=|entry| {
+//!                                let text = entry.get_text().map(|text| text.to_string());
+//!                                Message::Edit(text.unwrap_or_default())
+//!                            }
+//!                            on activate=|_| Message::Add />
+//!                     <Button label="Add" on clicked=|_| Message::Add />
+//!                     {
+//!                         self.items.iter().map(|item| gtk! {
+//!                             <Label class="item" label=item.clone() />
+//!                         })
+//!                     }
+//!                 </Box>
+//!             </Window>
+//!         }
+//!     }
+//! }
+//!
+//! #[test]
+//! fn adds_items() {
+//!     let todo = vgtk::testing::mount::<Todo>(());
+//!     todo.type_text("#new-item", "Buy milk");
+//!     todo.activate("#new-item");
+//!     todo.type_text("#new-item", "Walk the dog");
+//!     todo.click("GtkButton");
+//!     assert_eq!(2, todo.find_all("GtkLabel.item").len());
+//! }
+//! ```
+//!
+//! [mount]: fn.mount.html
+//! [Mounted]: struct.Mounted.html
+//! [Selector]: trait.Selector.html
+//! [click]: struct.Mounted.html#method.click
+//! [type_text]: struct.Mounted.html#method.type_text
+//! [activate]: struct.Mounted.html#method.activate
+
+use futures::channel::mpsc::UnboundedSender;
+use glib::{Cast, MainContext, Object, Type};
+use gtk::prelude::*;
+use gtk::{Button, Editable, Widget};
+
+use crate::component::{Component, ComponentMessage, ComponentTask};
+use crate::query::walk_object;
+use crate::scope::Scope;
+
+/// A way to pick out widgets.
+///
+/// A string selector is written like a CSS selector for a single widget, made
+/// up of any combination of a GType name, which matches widgets of that type
+/// or any subtype, a widget name prefixed with `#`, and any number of style
+/// classes prefixed with `.`. For instance, `"GtkButton"` matches every
+/// button, `"#save"` matches the widget with `widget_name="save"`, and
+/// `"GtkButton.destructive-action"` matches buttons with the
+/// `destructive-action` class.
+///
+/// Any function from a [`Widget`][Widget] to a `bool` is also a selector, and
+/// so is a [`Query`][Query], like `find::<Button>().with_label("Save")`.
+///
+/// [Widget]: ../../gtk/struct.Widget.html
+/// [Query]: ../query/struct.Query.html
+pub trait Selector {
+    /// Test whether a widget matches this selector.
+    fn matches(&self, widget: &Widget) -> bool;
+
+    /// Describe this selector in error messages.
+    fn describe(&self) -> String {
+        "the selector".to_string()
+    }
+}
+
+impl Selector for str {
+    fn matches(&self, widget: &Widget) -> bool {
+        let mut rest = self;
+        while !rest.is_empty() {
+            let end = rest[1..]
+                .find(['#', '.'])
+                .map(|end| end + 1)
+                .unwrap_or_else(|| rest.len());
+            let (part, next) = rest.split_at(end);
+            let matched = if let Some(name) = part.strip_prefix('#') {
+                widget.get_widget_name().as_deref() == Some(name)
+            } else if let Some(class) = part.strip_prefix('.') {
+                widget.get_style_context().has_class(class)
+            } else {
+                // GTK only registers a type once it's first used, so an
+                // unknown type can't have any widgets yet.
+                match Type::from_name(part) {
+                    Some(ty) => widget.get_type().is_a(&ty),
+                    None => false,
+                }
+            };
+            if !matched {
+                return false;
+            }
+            rest = next;
+        }
+        true
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl<F: Fn(&Widget) -> bool> Selector for F {
+    fn matches(&self, widget: &Widget) -> bool {
+        self(widget)
+    }
+}
+
+/// Run the main loop until there's nothing left for it to do, so any messages
+/// sent to components have been processed and their views rendered.
+pub fn settle() {
+    let context = MainContext::default();
+    while context.iteration(false) {}
+}
+
+/// Build a component with the given properties, outside of any application.
+///
+/// GTK is initialised first if it hasn't been already. The component is
+/// mounted, and unmounted again when the returned handle is dropped.
+///
+/// # Panics
+///
+/// Panics if GTK can't be initialised.
+pub fn mount<C: 'static + Component>(props: C::Properties) -> Mounted<C> {
+    if !gtk::is_initialized() {
+        gtk::init().expect("GTK failed to initialise");
+    }
+    let (channel, task) = ComponentTask::<C, ()>::new(props, None, None);
+    let object = task
+        .object()
+        .expect("a mounted component must have a root object");
+    let scope = task.scope();
+    MainContext::ref_thread_default().spawn_local(task);
+    channel.unbounded_send(ComponentMessage::Mounted).unwrap();
+    settle();
+    Mounted {
+        object,
+        scope,
+        channel,
+    }
+}
+
+/// A component built by [`mount()`][mount].
+///
+/// [mount]: fn.mount.html
+pub struct Mounted<C: Component> {
+    object: Object,
+    scope: Scope<C>,
+    channel: UnboundedSender<ComponentMessage<C>>,
+}
+
+impl<C: 'static + Component> Mounted<C> {
+    /// Get the component's root object.
+    pub fn object(&self) -> &Object {
+        &self.object
+    }
+
+    /// Get the component's scope, for sending it messages directly.
+    ///
+    /// Call [`settle()`][settle] after sending a message to let the component
+    /// process it.
+    ///
+    /// [settle]: fn.settle.html
+    pub fn scope(&self) -> &Scope<C> {
+        &self.scope
+    }
+
+    /// Find every widget matching `selector`, searching the component's
+    /// widgets depth first.
+    ///
+    /// Internal children, like the label inside a button, are searched too.
+    pub fn find_all<S: Selector + ?Sized>(&self, selector: &S) -> Vec<Widget> {
+        let mut found = Vec::new();
+        walk_object(&self.object, &mut |widget| {
+            if selector.matches(widget) {
+                found.push(widget.clone());
+            }
+        });
+        found
+    }
+
+    /// Find the first widget matching `selector`.
+    pub fn find<S: Selector + ?Sized>(&self, selector: &S) -> Option<Widget> {
+        self.find_all(selector).into_iter().next()
+    }
+
+    /// Click the button matching `selector`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no matching widget, if it isn't a
+    /// [`Button`][Button], or if it can't be clicked because it's insensitive
+    /// or hidden.
+    ///
+    /// [Button]: ../../gtk/struct.Button.html
+    pub fn click<S: Selector + ?Sized>(&self, selector: &S) {
+        let widget = self.interactive(selector, "click");
+        let button = widget
+            .downcast_ref::<Button>()
+            .unwrap_or_else(|| panic!("can't click a {}", widget.get_type()));
+        button.clicked();
+        settle();
+    }
+
+    /// Type `text` into the editable widget matching `selector`, like an
+    /// [`Entry`][Entry], at its cursor position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no matching widget, if it isn't an
+    /// [`Editable`][Editable], or if it can't be typed into because it's
+    /// insensitive or hidden.
+    ///
+    /// [Entry]: ../../gtk/struct.Entry.html
+    /// [Editable]: ../../gtk/struct.Editable.html
+    pub fn type_text<S: Selector + ?Sized>(&self, selector: &S, text: &str) {
+        let widget = self.interactive(selector, "type into");
+        let editable = widget
+            .dynamic_cast_ref::<Editable>()
+            .unwrap_or_else(|| panic!("can't type into a {}", widget.get_type()));
+        if widget.get_can_focus() {
+            widget.grab_focus();
+        }
+        let mut position = editable.get_position();
+        editable.insert_text(text, &mut position);
+        editable.set_position(position);
+        settle();
+    }
+
+    /// Activate the widget matching `selector`, like pressing Enter in an
+    /// [`Entry`][Entry].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no matching widget, or if it can't be activated
+    /// because it's insensitive, hidden or not an activatable widget.
+    ///
+    /// [Entry]: ../../gtk/struct.Entry.html
+    pub fn activate<S: Selector + ?Sized>(&self, selector: &S) {
+        let widget = self.interactive(selector, "activate");
+        if !widget.activate() {
+            panic!("can't activate a {}", widget.get_type());
+        }
+        settle();
+    }
+
+    /// Find a widget a user could interact with.
+    fn interactive<S: Selector + ?Sized>(&self, selector: &S, action: &str) -> Widget {
+        let widget = self
+            .find(selector)
+            .unwrap_or_else(|| panic!("no widget matches {}", selector.describe()));
+        if !widget.is_visible() {
+            panic!("can't {} a hidden {}", action, widget.get_type());
+        }
+        if !widget.is_sensitive() {
+            panic!("can't {} an insensitive {}", action, widget.get_type());
+        }
+        widget
+    }
+}
+
+impl<C: Component> Drop for Mounted<C> {
+    fn drop(&mut self) {
+        // The component may have stopped already, if its window was closed.
+        let _ = self.channel.unbounded_send(ComponentMessage::Unmounted);
+        settle();
+    }
+}
@@ -0,0 +1,93 @@
+//! Timers running on the GTK main loop.
+//!
+//! [`interval()`][interval] ticks at a regular pace, which suits clocks,
+//! autosaving and polling, and [`timeout()`][timeout] waits once. They're
+//! driven by GLib timeouts, so they only make progress while the main loop
+//! is running, and stop as soon as they're dropped.
+//!
+//! To have a component receive the ticks as messages, wrap the stream in a
+//! [`Subscription`][Subscription], which is dropped, and so cancelled, when the
+//! component unmounts. [`Subscription::interval()`][Subscription::interval]
+//! and [`Subscription::timeout()`][Subscription::timeout] do this for you.
+//! To wait inside an [`UpdateAction::defer()`][defer] block, `await` a
+//! [`timeout()`][timeout].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use std::time::{Duration, Instant};
+//! use vgtk::{timer, Subscription};
+//! # use vgtk::{Component, UpdateAction, VNode};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Tick(Instant),
+//!     Flash(String),
+//!     ClearFlash,
+//! }
+//!
+//! #[derive(Default)]
+//! struct Clock {
+//!     now: Option<Instant>,
+//!     flash: Option<String>,
+//! }
+//!
+//! impl Component for Clock {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn subscriptions(&self) -> Vec<Subscription<Message>> {
+//!         vec![Subscription::new(timer::interval(Duration::from_secs(1))).map(Message::Tick)]
+//!     }
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         match message {
+//!             Message::Tick(now) => self.now = Some(now),
+//!             Message::Flash(text) => {
+//!                 self.flash = Some(text);
+//!                 return UpdateAction::defer(async {
+//!                     timer::timeout(Duration::from_secs(3)).await;
+//!                     Message::ClearFlash
+//!                 });
+//!             }
+//!             Message::ClearFlash => self.flash = None,
+//!         }
+//!         UpdateAction::Render
+//!     }
+//! #   fn view(&self) -> VNode<Self> { todo!() }
+//! }
+//! ```
+//!
+//! [interval]: fn.interval.html
+//! [timeout]: fn.timeout.html
+//! [Subscription]: ../struct.Subscription.html
+//! [Subscription::interval]: ../struct.Subscription.html#method.interval
+//! [Subscription::timeout]: ../struct.Subscription.html#method.timeout
+//! [defer]: ../enum.UpdateAction.html#method.defer
+
+use futures::stream::{Stream, StreamExt};
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+fn millis(duration: Duration) -> u32 {
+    duration.as_millis().min(u32::MAX as u128) as u32
+}
+
+/// Construct a [`Stream`][Stream] which produces the current time every
+/// `period`, starting one `period` from now.
+///
+/// If the main loop is too busy to keep up, ticks are delayed rather than
+/// piling up.
+///
+/// [Stream]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+pub fn interval(period: Duration) -> impl Stream<Item = Instant> {
+    glib::interval_stream(millis(period)).map(|()| Instant::now())
+}
+
+/// Construct a [`Future`][Future] which resolves once `delay` has passed.
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+pub fn timeout(delay: Duration) -> impl Future<Output = ()> {
+    glib::timeout_future(millis(delay))
+}
@@ -0,0 +1,201 @@
+//! Running [Tokio] futures and streams alongside the GTK main loop.
+//!
+//! Much of the async ecosystem, like `reqwest`, `tonic` and `sqlx`, expects to
+//! be running inside a Tokio runtime, and the GTK main loop isn't one. Awaiting
+//! one of those futures in an [`UpdateAction::defer()`][defer] block will
+//! either panic for want of a runtime or, worse, block the main loop and freeze
+//! your user interface.
+//!
+//! This module keeps a Tokio runtime running on background threads, started
+//! the first time it's needed, and lets you hand work to it:
+//! [`spawn()`][spawn] runs a future there and gives you a future for its
+//! result which you can await on the main loop, and [`stream()`][stream] does
+//! the same for a stream, which you can wrap in a
+//! [`Subscription`][Subscription]. If you'd rather not go through your
+//! component's `update` method to get there,
+//! [`send_to_component()`][send_to_component] and
+//! [`forward_to_component()`][forward_to_component] deliver the results
+//! straight to a [`Scope`][Scope].
+//!
+//! The futures and streams you hand over run on another thread, so they have
+//! to be `Send`, and can't touch any GTK objects. Send the data you need back
+//! as a message and update your widgets from your view.
+//!
+//! This module is only available with the `tokio` feature enabled.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use vgtk::UpdateAction;
+//! # use vgtk::{Component, VNode};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Fetch,
+//!     Fetched(String),
+//! }
+//!
+//! # async fn fetch(url: &str) -> String { todo!() }
+//! #[derive(Default)]
+//! struct Weather {
+//!     forecast: Option<String>,
+//! }
+//!
+//! impl Component for Weather {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         match message {
+//!             Message::Fetch => UpdateAction::defer(async {
+//!                 // `fetch` could be using any Tokio based HTTP client.
+//!                 let forecast = vgtk::tokio::spawn(fetch("https://example.com/forecast")).await;
+//!                 Message::Fetched(forecast)
+//!             }),
+//!             Message::Fetched(forecast) => {
+//!                 self.forecast = Some(forecast);
+//!                 UpdateAction::Render
+//!             }
+//!         }
+//!     }
+//! #   fn view(&self) -> VNode<Self> { todo!() }
+//! }
+//! ```
+//!
+//! [Tokio]: https://tokio.rs/
+//! [defer]: ../enum.UpdateAction.html#method.defer
+//! [spawn]: fn.spawn.html
+//! [stream]: fn.stream.html
+//! [Subscription]: ../struct.Subscription.html
+//! [send_to_component]: fn.send_to_component.html
+//! [forward_to_component]: fn.forward_to_component.html
+//! [Scope]: ../struct.Scope.html
+
+use ::tokio::runtime::{Builder, Runtime};
+use futures::channel::mpsc::unbounded;
+use futures::stream::{Stream, StreamExt};
+
+use std::future::Future;
+use std::panic::resume_unwind;
+use std::sync::OnceLock;
+
+use crate::component::Component;
+use crate::scope::Scope;
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// Get the Tokio [`Runtime`][Runtime] this module runs things on, starting it
+/// if it isn't running yet.
+///
+/// You'll need this for anything which has to be called from inside a
+/// runtime but isn't a future, like constructing some clients: call it
+/// inside a [`Runtime::enter()`][enter] guard.
+///
+/// # Panics
+///
+/// Panics if the runtime couldn't be started.
+///
+/// [Runtime]: https://docs.rs/tokio/1/tokio/runtime/struct.Runtime.html
+/// [enter]: https://docs.rs/tokio/1/tokio/runtime/struct.Runtime.html#method.enter
+pub fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .thread_name("vgtk-tokio")
+            .enable_all()
+            .build()
+            .expect("unable to start the Tokio runtime")
+    })
+}
+
+/// Run a [`Future`][Future] on the Tokio runtime, and get a future for its
+/// result.
+///
+/// The returned future can be awaited anywhere, including inside an
+/// [`UpdateAction::defer()`][defer] block on the GTK main loop. The work
+/// starts straight away, whether or not you await the result.
+///
+/// If the future panics, so does awaiting its result.
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [defer]: ../enum.UpdateAction.html#method.defer
+pub fn spawn<F>(future: F) -> impl Future<Output = F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let handle = runtime().spawn(future);
+    async move {
+        match handle.await {
+            Ok(output) => output,
+            Err(error) if error.is_panic() => resume_unwind(error.into_panic()),
+            Err(error) => panic!("Tokio task failed: {}", error),
+        }
+    }
+}
+
+/// Run a [`Stream`][Stream] on the Tokio runtime, and get a stream of its
+/// items.
+///
+/// Wrap the result in a [`Subscription`][Subscription] to have your
+/// component receive the items as messages. Once the returned stream has
+/// been dropped, the original stream is dropped too, the next time it
+/// produces an item.
+///
+/// [Stream]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+/// [Subscription]: ../struct.Subscription.html
+pub fn stream<S>(stream: S) -> impl Stream<Item = S::Item>
+where
+    S: Stream + Send + 'static,
+    S::Item: Send + 'static,
+{
+    let (sender, receiver) = unbounded();
+    runtime().spawn(async move {
+        let mut stream = Box::pin(stream);
+        while let Some(item) = stream.next().await {
+            if sender.unbounded_send(item).is_err() {
+                // Nobody's listening any more.
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Run a [`Future`][Future] on the Tokio runtime, and send the message it
+/// resolves to to the component `scope` belongs to.
+///
+/// If the component has unmounted by the time the future resolves, its
+/// message is dropped.
+///
+/// [Future]: https://doc.rust-lang.org/std/future/trait.Future.html
+pub fn send_to_component<C, F>(scope: &Scope<C>, future: F)
+where
+    C: Component + 'static,
+    F: Future<Output = C::Message> + Send + 'static,
+{
+    let scope = scope.clone();
+    runtime().spawn(async move { scope.send(future.await) });
+}
+
+/// Run a [`Stream`][Stream] of messages on the Tokio runtime, and send each
+/// of them to the component `scope` belongs to.
+///
+/// The stream is dropped once it ends or the component unmounts, whichever
+/// comes first.
+///
+/// [Stream]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+pub fn forward_to_component<C, S>(scope: &Scope<C>, stream: S)
+where
+    C: Component + 'static,
+    S: Stream<Item = C::Message> + Send + 'static,
+{
+    let scope = scope.clone();
+    runtime().spawn(async move {
+        let mut stream = Box::pin(stream);
+        while let Some(message) = stream.next().await {
+            if scope.try_send(message).is_err() {
+                break;
+            }
+        }
+    });
+}
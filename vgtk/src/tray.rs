@@ -0,0 +1,528 @@
+//! Status icons in the system tray.
+//!
+//! A [`StatusIcon`][StatusIcon] puts an icon for your application in the
+//! system tray, using the StatusNotifierItem D-Bus protocol, also known as
+//! AppIndicator, which KDE Plasma, most other desktops and GNOME with the
+//! AppIndicator extension display. Clicking the icon sends your component a
+//! message, and right clicking it shows a menu built with
+//! [`menu()`][menu], whose items activate the `app.*` and `win.*` actions
+//! they name, just like the items of a menu bar.
+//!
+//! The icon is a [`Subscription`][Subscription], so it appears when your
+//! component subscribes to it and goes away when the component unmounts.
+//! Together with [`Application::hold()`][hold], this lets an application hide
+//! its window to the tray instead of quitting, and bring it back when the
+//! icon's clicked.
+//!
+//! If there's no session bus, or nothing on it displays status icons, a
+//! warning is logged and the icon never sends anything.
+//!
+//! This module is only available with the `tray` feature enabled, which also
+//! enables the `dbus` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use vgtk::tray::StatusIcon;
+//! use vgtk::{menu, Subscription, UpdateAction};
+//! # use vgtk::{Component, VNode};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     ToggleWindow,
+//! }
+//!
+//! # #[derive(Default)] struct Chat;
+//! # impl Component for Chat { type Message = Message; type Properties = ();
+//! # fn view(&self) -> VNode<Self> { todo!() }
+//! fn subscriptions(&self) -> Vec<Subscription<Message>> {
+//!     vec![StatusIcon::new("chat")
+//!         .title("Chat")
+//!         .icon_name("mail-unread")
+//!         .menu(menu().item("Show", "app.show").item("Quit", "app.quit"))
+//!         .subscription(Message::ToggleWindow)]
+//! }
+//! # }
+//! ```
+//!
+//! [StatusIcon]: struct.StatusIcon.html
+//! [menu]: ../fn.menu.html
+//! [Subscription]: ../struct.Subscription.html
+//! [hold]: ../../gio/trait.ApplicationExt.html#tymethod.hold
+
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::future::FutureExt;
+use futures::stream::{self, StreamExt};
+use gio::{ActionGroup, ActionGroupExt, Application, MenuModel, MenuModelExt};
+use glib::Cast;
+use gtk::GtkApplicationExt;
+use log::warn;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::Connection;
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::menu_builder::MenuBuilder;
+use crate::subscription::Subscription;
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/StatusNotifierItem/Menu";
+
+type Pixmaps = Vec<(i32, i32, Vec<u8>)>;
+
+// A menu item's ID, properties and the layouts of its children.
+type Layout = (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>);
+
+// What the user did to the icon, passed from the D-Bus interfaces, which run
+// on zbus's own thread, to the subscription on the main loop.
+enum Event {
+    Activate,
+    Action(String),
+}
+
+/// Builds a status icon for the system tray.
+///
+/// Give it an icon name from the icon theme, and a title and tooltip for the
+/// desktop to describe it with, then turn it into a
+/// [`Subscription`][Subscription] with [`subscription()`][subscription]. See
+/// the [module documentation][tray] for an example.
+///
+/// The icon doesn't change once it's shown, as a component only subscribes
+/// once.
+///
+/// [Subscription]: ../struct.Subscription.html
+/// [subscription]: #method.subscription
+/// [tray]: index.html
+pub struct StatusIcon {
+    id: String,
+    title: String,
+    icon_name: String,
+    tooltip: String,
+    menu: Vec<MenuNode>,
+}
+
+impl StatusIcon {
+    /// Construct a status icon with an ID, which should stay the same between
+    /// runs of your application, so the desktop can remember the user's
+    /// settings for it.
+    pub fn new(id: &str) -> Self {
+        StatusIcon {
+            id: id.to_string(),
+            title: String::new(),
+            icon_name: String::new(),
+            tooltip: String::new(),
+            menu: Vec::new(),
+        }
+    }
+
+    /// Set the name the desktop gives the icon.
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Set the name of the icon, from the icon theme.
+    pub fn icon_name(mut self, icon_name: &str) -> Self {
+        self.icon_name = icon_name.to_string();
+        self
+    }
+
+    /// Set the text shown when the pointer rests on the icon.
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.tooltip = tooltip.to_string();
+        self
+    }
+
+    /// Set the menu shown when the icon's right clicked.
+    ///
+    /// Each item activates the action it names when it's picked. Items are
+    /// always sensitive, and actions with a parameter are activated without
+    /// one.
+    pub fn menu(mut self, menu: MenuBuilder) -> Self {
+        let mut next_id = 0;
+        self.menu = menu_nodes(menu.build().upcast_ref(), &mut next_id);
+        self
+    }
+
+    /// Show the icon, and construct a subscription which sends `activate`
+    /// whenever the icon's clicked.
+    ///
+    /// The icon goes away when the subscription is dropped.
+    pub fn subscription<Msg: Clone + 'static>(self, activate: Msg) -> Subscription<Msg> {
+        let (sender, receiver) = unbounded();
+        let item = Item {
+            id: self.id,
+            title: self.title,
+            icon_name: self.icon_name,
+            tooltip: self.tooltip,
+            events: sender.clone(),
+        };
+        let menu = Menu {
+            root: MenuNode {
+                children: self.menu,
+                ..MenuNode::default()
+            },
+            events: sender,
+        };
+        let events = async move {
+            match register(item, menu).await {
+                // The icon stays on the bus for as long as its connection's
+                // open, so the stream holds on to it.
+                Ok(connection) => receiver
+                    .map(move |event| {
+                        let _ = &connection;
+                        event
+                    })
+                    .left_stream(),
+                Err(err) => {
+                    warn!("unable to show status icon: {}", err);
+                    stream::empty().right_stream()
+                }
+            }
+        }
+        .into_stream()
+        .flatten()
+        .filter_map(move |event| {
+            let message = match event {
+                Event::Activate => Some(activate.clone()),
+                Event::Action(name) => {
+                    activate_action(&name);
+                    None
+                }
+            };
+            async move { message }
+        });
+        Subscription::new(events)
+    }
+}
+
+static NEXT_ICON: AtomicUsize = AtomicUsize::new(0);
+
+// Each icon gets a connection of its own, as the StatusNotifierItem protocol
+// expects it at a fixed object path, and the desktop removes the icon when the
+// connection closes.
+async fn register(item: Item, menu: Menu) -> zbus::Result<Connection> {
+    let connection = Connection::session().await?;
+    connection.object_server().at(ITEM_PATH, item).await?;
+    connection.object_server().at(MENU_PATH, menu).await?;
+    let name = format!(
+        "org.kde.StatusNotifierItem-{}-{}",
+        std::process::id(),
+        NEXT_ICON.fetch_add(1, Ordering::SeqCst) + 1
+    );
+    connection.request_name(name.as_str()).await?;
+    connection
+        .call_method(
+            Some("org.kde.StatusNotifierWatcher"),
+            "/StatusNotifierWatcher",
+            Some("org.kde.StatusNotifierWatcher"),
+            "RegisterStatusNotifierItem",
+            &(name.as_str(),),
+        )
+        .await?;
+    Ok(connection)
+}
+
+// Activate a menu item's action, on the application for `app.*` actions and
+// on its active window for `win.*` actions.
+fn activate_action(name: &str) {
+    let application = Application::get_default();
+    let (group, action): (Option<ActionGroup>, _) = if let Some(action) = name.strip_prefix("app.")
+    {
+        (application.map(|app| app.upcast()), action)
+    } else if let Some(action) = name.strip_prefix("win.") {
+        let window = application
+            .and_then(|app| app.downcast::<gtk::Application>().ok())
+            .and_then(|app| app.get_active_window());
+        (window.and_then(|window| window.dynamic_cast().ok()), action)
+    } else {
+        (None, name)
+    };
+    match group {
+        Some(group) if group.has_action(action) => group.activate_action(action, None),
+        _ => warn!("status icon menu item refers to unknown action {}", name),
+    }
+}
+
+struct Item {
+    id: String,
+    title: String,
+    icon_name: String,
+    tooltip: String,
+    events: UnboundedSender<Event>,
+}
+
+#[zbus::interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+    fn activate(&self, _x: i32, _y: i32) {
+        let _ = self.events.unbounded_send(Event::Activate);
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {}
+
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: &str) {}
+
+    #[zbus(property)]
+    fn category(&self) -> String {
+        "ApplicationStatus".to_string()
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> String {
+        "Active".to_string()
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> String {
+        self.icon_name.clone()
+    }
+
+    #[zbus(property)]
+    fn icon_pixmap(&self) -> Pixmaps {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn tool_tip(&self) -> (String, Pixmaps, String, String) {
+        (
+            String::new(),
+            Vec::new(),
+            self.tooltip.clone(),
+            String::new(),
+        )
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn menu(&self) -> OwnedObjectPath {
+        ObjectPath::from_static_str_unchecked(MENU_PATH).into()
+    }
+
+    #[zbus(property)]
+    fn window_id(&self) -> i32 {
+        0
+    }
+}
+
+// A menu item, as the `com.canonical.dbusmenu` protocol sees it. Sections are
+// flattened into their parent menu, with separators between them, and the
+// menu itself is the item with ID 0.
+#[derive(Default)]
+struct MenuNode {
+    id: i32,
+    label: String,
+    action: Option<String>,
+    separator: bool,
+    children: Vec<MenuNode>,
+}
+
+fn string_attribute(model: &MenuModel, index: i32, name: &str) -> Option<String> {
+    model
+        .get_item_attribute_value(index, name, None)
+        .and_then(|value| value.get_str().map(str::to_string))
+}
+
+fn separator(next_id: &mut i32) -> MenuNode {
+    *next_id += 1;
+    MenuNode {
+        id: *next_id,
+        separator: true,
+        ..MenuNode::default()
+    }
+}
+
+fn menu_nodes(model: &MenuModel, next_id: &mut i32) -> Vec<MenuNode> {
+    let mut nodes = Vec::new();
+    let mut after_section = false;
+    for index in 0..model.get_n_items() {
+        if let Some(section) = model.get_item_link(index, "section") {
+            let items = menu_nodes(&section, next_id);
+            if items.is_empty() {
+                continue;
+            }
+            if !nodes.is_empty() {
+                nodes.push(separator(next_id));
+            }
+            nodes.extend(items);
+            after_section = true;
+            continue;
+        }
+        if after_section {
+            nodes.push(separator(next_id));
+            after_section = false;
+        }
+        *next_id += 1;
+        let id = *next_id;
+        let children = match model.get_item_link(index, "submenu") {
+            Some(submenu) => menu_nodes(&submenu, next_id),
+            None => Vec::new(),
+        };
+        nodes.push(MenuNode {
+            id,
+            label: string_attribute(model, index, "label").unwrap_or_default(),
+            action: string_attribute(model, index, "action"),
+            separator: false,
+            children,
+        });
+    }
+    nodes
+}
+
+fn owned(value: Value<'_>) -> OwnedValue {
+    value
+        .try_into()
+        .expect("menu properties don't hold file descriptors")
+}
+
+impl MenuNode {
+    fn find(&self, id: i32) -> Option<&MenuNode> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    fn properties(&self, names: &[String]) -> HashMap<String, OwnedValue> {
+        let mut properties = HashMap::new();
+        if self.separator {
+            properties.insert("type".to_string(), owned(Value::from("separator")));
+        } else if self.id != 0 {
+            properties.insert("label".to_string(), owned(Value::from(self.label.as_str())));
+        }
+        if !self.children.is_empty() {
+            properties.insert(
+                "children-display".to_string(),
+                owned(Value::from("submenu")),
+            );
+        }
+        if !names.is_empty() {
+            properties.retain(|name, _| names.contains(name));
+        }
+        properties
+    }
+
+    // A recursion depth of -1 asks for every level below this one.
+    fn layout(&self, depth: i32, names: &[String]) -> Layout {
+        let children = if depth == 0 {
+            Vec::new()
+        } else {
+            self.children
+                .iter()
+                .map(|child| owned(Value::from(child.layout(depth - 1, names))))
+                .collect()
+        };
+        (self.id, self.properties(names), children)
+    }
+}
+
+struct Menu {
+    root: MenuNode,
+    events: UnboundedSender<Event>,
+}
+
+impl Menu {
+    // Send a menu item's action off to be activated if it was clicked, and
+    // tell whether there's an item with that ID.
+    fn handle_event(&self, id: i32, event_id: &str) -> bool {
+        match self.root.find(id) {
+            Some(node) => {
+                if let (Some(action), "clicked") = (&node.action, event_id) {
+                    let _ = self.events.unbounded_send(Event::Action(action.clone()));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[zbus::interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        recursion_depth: i32,
+        property_names: Vec<String>,
+    ) -> zbus::fdo::Result<(u32, Layout)> {
+        self.root
+            .find(parent_id)
+            .map(|node| (1, node.layout(recursion_depth, &property_names)))
+            .ok_or_else(|| zbus::fdo::Error::InvalidArgs(format!("no menu item {}", parent_id)))
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, OwnedValue>)> {
+        ids.into_iter()
+            .filter_map(|id| self.root.find(id))
+            .map(|node| (node.id, node.properties(&property_names)))
+            .collect()
+    }
+
+    fn get_property(&self, id: i32, name: String) -> zbus::fdo::Result<OwnedValue> {
+        self.root
+            .find(id)
+            .and_then(|node| node.properties(std::slice::from_ref(&name)).remove(&name))
+            .ok_or_else(|| zbus::fdo::Error::InvalidArgs(format!("no property {}", name)))
+    }
+
+    fn event(&self, id: i32, event_id: &str, _data: Value<'_>, _timestamp: u32) {
+        self.handle_event(id, event_id);
+    }
+
+    fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        events
+            .into_iter()
+            .filter(|(id, event_id, _, _)| !self.handle_event(*id, event_id))
+            .map(|(id, _, _, _)| id)
+            .collect()
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    fn text_direction(&self) -> String {
+        "ltr".to_string()
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> String {
+        "normal".to_string()
+    }
+
+    #[zbus(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,312 @@
+use glib::{Cast, StaticType, ToValue, Type};
+use gtk::prelude::*;
+use gtk::{ListStore, TreeIter, TreeModel, TreeStore};
+
+use std::cell::RefCell;
+use std::fmt::{Debug, Error, Formatter};
+
+/// A type which can be displayed as a row of a [`TreeView`][TreeView].
+///
+/// Each field you want to show becomes a column of the model, which a cell
+/// renderer can then display with a child attribute such as
+/// `TreeViewColumn::text=0`.
+///
+/// ```rust,no_run
+/// # use vgtk::TreeRow;
+/// # use vgtk::lib::glib::{StaticType, ToValue, Type};
+/// #[derive(Clone, PartialEq)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// impl TreeRow for Person {
+///     fn types() -> Vec<Type> {
+///         vec![String::static_type(), u32::static_type()]
+///     }
+///
+///     fn values(&self) -> Vec<&dyn ToValue> {
+///         vec![&self.name, &self.age]
+///     }
+/// }
+/// ```
+///
+/// Plain values such as `String`s, `bool`s and numbers are already rows with a
+/// single column.
+///
+/// [TreeView]: ../gtk/struct.TreeView.html
+pub trait TreeRow: Clone + PartialEq {
+    /// The types of the model's columns.
+    fn types() -> Vec<Type>;
+
+    /// The values of this row's columns, in the same order as their types.
+    fn values(&self) -> Vec<&dyn ToValue>;
+}
+
+// Plain values make single column rows.
+macro_rules! impl_tree_row {
+    ($($type:ty),*) => {
+        $(
+            impl TreeRow for $type {
+                fn types() -> Vec<Type> {
+                    vec![<$type>::static_type()]
+                }
+
+                fn values(&self) -> Vec<&dyn ToValue> {
+                    vec![self]
+                }
+            }
+        )*
+    };
+}
+
+impl_tree_row!(String, bool, i32, u32, i64, u64, f64);
+
+fn columns<T: TreeRow>() -> Vec<u32> {
+    (0..T::types().len() as u32).collect()
+}
+
+/// A [`ListStore`][ListStore] kept in step with a list of
+/// [`TreeRow`][TreeRow]s.
+///
+/// Keep a `ListRows` in your component, and call [`sync()`][sync] with your
+/// rows as the `model` of a [`TreeView`][TreeView] in your view. Only rows
+/// which have changed since the last render are written to the store, so the
+/// tree view keeps its selection and scroll position.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, ListRows, TreeRow, UpdateAction, VNode};
+/// # use vgtk::ext::*;
+/// # use vgtk::lib::glib::{StaticType, ToValue, Type};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, PartialEq)] struct Person { name: String, age: u32 }
+/// # impl TreeRow for Person {
+/// #     fn types() -> Vec<Type> { vec![String::static_type(), u32::static_type()] }
+/// #     fn values(&self) -> Vec<&dyn ToValue> { vec![&self.name, &self.age] }
+/// # }
+/// #[derive(Clone, Debug)]
+/// enum Message {
+///     Open(usize),
+///     Rename(usize, String),
+/// }
+///
+/// #[derive(Default)]
+/// struct People {
+///     people: Vec<Person>,
+///     rows: ListRows<Person>,
+/// }
+///
+/// impl Component for People {
+///     type Message = Message;
+///     type Properties = ();
+///
+///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///         match message {
+///             Message::Open(index) => UpdateAction::None,
+///             Message::Rename(index, name) => {
+///                 self.people[index].name = name;
+///                 UpdateAction::Render
+///             }
+///         }
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         gtk! {
+///             <TreeView model=self.rows.sync(&self.people)
+///                       on row_activated=|_, path, _| Message::Open(path.get_indices()[0] as usize)>
+///                 <TreeViewColumn title="Name" expand=true>
+///                     <CellRendererText property_editable=true TreeViewColumn::text=0
+///                                       on edited=|_, path, name| {
+///                                           Message::Rename(path.get_indices()[0] as usize, name.to_string())
+///                                       } />
+///                 </TreeViewColumn>
+///                 <TreeViewColumn title="Age">
+///                     <CellRendererText TreeViewColumn::text=1 />
+///                 </TreeViewColumn>
+///             </TreeView>
+///         }
+///     }
+/// }
+/// ```
+///
+/// [ListStore]: ../gtk/struct.ListStore.html
+/// [TreeView]: ../gtk/struct.TreeView.html
+/// [TreeRow]: trait.TreeRow.html
+/// [sync]: #method.sync
+pub struct ListRows<T: TreeRow> {
+    store: ListStore,
+    rows: RefCell<Vec<T>>,
+}
+
+impl<T: TreeRow> ListRows<T> {
+    /// Construct an empty `ListRows`.
+    pub fn new() -> Self {
+        ListRows {
+            store: ListStore::new(&T::types()),
+            rows: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Bring the store up to date with `rows`, and return it as a
+    /// [`TreeModel`][TreeModel].
+    ///
+    /// Rows are matched up by position: rows which differ from the row in the
+    /// same position last time are overwritten, and rows are added or removed
+    /// at the end to make up the difference.
+    ///
+    /// [TreeModel]: ../gtk/struct.TreeModel.html
+    pub fn sync(&self, rows: &[T]) -> TreeModel {
+        let columns = columns::<T>();
+        let mut current = self.rows.borrow_mut();
+        for (index, row) in rows.iter().enumerate() {
+            match current.get(index) {
+                Some(old) if old == row => {}
+                Some(_) => {
+                    let iter = nth_child(&self.store, None, index);
+                    self.store.set(&iter, &columns, &row.values());
+                }
+                None => {
+                    self.store.insert_with_values(None, &columns, &row.values());
+                }
+            }
+        }
+        for index in (rows.len()..current.len()).rev() {
+            self.store.remove(&nth_child(&self.store, None, index));
+        }
+        *current = rows.to_vec();
+        self.store.clone().upcast()
+    }
+}
+
+impl<T: TreeRow> Default for ListRows<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: TreeRow> Debug for ListRows<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "ListRows[{}]", self.rows.borrow().len())
+    }
+}
+
+/// A row of a tree, with the rows nested underneath it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode<T> {
+    /// The row itself.
+    pub row: T,
+    /// The rows underneath it.
+    pub children: Vec<TreeNode<T>>,
+}
+
+impl<T> TreeNode<T> {
+    /// Construct a node without any children.
+    pub fn new(row: T) -> Self {
+        TreeNode {
+            row,
+            children: Vec::new(),
+        }
+    }
+
+    /// Construct a node with children.
+    pub fn with_children(row: T, children: Vec<TreeNode<T>>) -> Self {
+        TreeNode { row, children }
+    }
+}
+
+/// A [`TreeStore`][TreeStore] kept in step with a tree of
+/// [`TreeRow`][TreeRow]s.
+///
+/// This works just like [`ListRows`][ListRows], except you give
+/// [`sync()`][sync] a list of [`TreeNode`][TreeNode]s, and their children are
+/// synchronised too.
+///
+/// [TreeStore]: ../gtk/struct.TreeStore.html
+/// [TreeRow]: trait.TreeRow.html
+/// [ListRows]: struct.ListRows.html
+/// [TreeNode]: struct.TreeNode.html
+/// [sync]: #method.sync
+pub struct TreeRows<T: TreeRow> {
+    store: TreeStore,
+    nodes: RefCell<Vec<TreeNode<T>>>,
+}
+
+impl<T: TreeRow> TreeRows<T> {
+    /// Construct an empty `TreeRows`.
+    pub fn new() -> Self {
+        TreeRows {
+            store: TreeStore::new(&T::types()),
+            nodes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Bring the store up to date with `nodes`, and return it as a
+    /// [`TreeModel`][TreeModel].
+    ///
+    /// Like [`ListRows::sync()`][ListRows::sync], the children of each node
+    /// are matched up by position.
+    ///
+    /// [TreeModel]: ../gtk/struct.TreeModel.html
+    /// [ListRows::sync]: struct.ListRows.html#method.sync
+    pub fn sync(&self, nodes: &[TreeNode<T>]) -> TreeModel {
+        let columns = columns::<T>();
+        let mut current = self.nodes.borrow_mut();
+        sync_level(&self.store, &columns, None, &current, nodes);
+        *current = nodes.to_vec();
+        self.store.clone().upcast()
+    }
+}
+
+impl<T: TreeRow> Default for TreeRows<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: TreeRow> Debug for TreeRows<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "TreeRows[{}]", self.nodes.borrow().len())
+    }
+}
+
+fn nth_child<M: IsA<TreeModel>>(model: &M, parent: Option<&TreeIter>, index: usize) -> TreeIter {
+    model
+        .iter_nth_child(parent, index as i32)
+        .unwrap_or_else(|| panic!("tree model is missing row {}", index))
+}
+
+fn sync_level<T: TreeRow>(
+    store: &TreeStore,
+    columns: &[u32],
+    parent: Option<&TreeIter>,
+    old: &[TreeNode<T>],
+    new: &[TreeNode<T>],
+) {
+    for (index, node) in new.iter().enumerate() {
+        match old.get(index) {
+            Some(old_node) if old_node == node => {}
+            Some(old_node) => {
+                let iter = nth_child(store, parent, index);
+                if old_node.row != node.row {
+                    store.set(&iter, columns, &node.row.values());
+                }
+                sync_level(
+                    store,
+                    columns,
+                    Some(&iter),
+                    &old_node.children,
+                    &node.children,
+                );
+            }
+            None => {
+                let iter = store.insert_with_values(parent, None, columns, &node.row.values());
+                sync_level(store, columns, Some(&iter), &[], &node.children);
+            }
+        }
+    }
+    for index in (new.len()..old.len()).rev() {
+        store.remove(&nth_child(store, parent, index));
+    }
+}
@@ -73,3 +73,52 @@ impl Default for GridPosition {
         }
     }
 }
+
+/// A date on a [`Calendar`][Calendar].
+///
+/// Unlike GTK, which counts months from zero, `month` goes from 1 for
+/// January to 12 for December, and `day` goes from 1 to the length of the
+/// month. Dates order by year, then month, then day.
+///
+/// The primary use of this struct is to select a date on a calendar and
+/// receive the date the user selects via
+/// [`CalendarExtHelpers`][CalendarExtHelpers].
+///
+/// [Calendar]: ../../gtk/struct.Calendar.html
+/// [CalendarExtHelpers]: ../ext/trait.CalendarExtHelpers.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalendarDate {
+    /// The year.
+    pub year: i32,
+
+    /// The month, from 1 to 12.
+    pub month: u32,
+
+    /// The day of the month, from 1 to 31.
+    pub day: u32,
+}
+
+impl CalendarDate {
+    /// Construct a date out of a year, a month from 1 to 12 and a day of the
+    /// month.
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        Self { year, month, day }
+    }
+
+    /// Get today's date in the local time zone.
+    pub fn today() -> Self {
+        let now = glib::DateTime::new_now_local();
+        Self::new(
+            now.get_year(),
+            now.get_month() as u32,
+            now.get_day_of_month() as u32,
+        )
+    }
+}
+
+/// A calendar starts out showing today's date, and so does a `CalendarDate`.
+impl Default for CalendarDate {
+    fn default() -> Self {
+        Self::today()
+    }
+}
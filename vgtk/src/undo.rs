@@ -0,0 +1,249 @@
+//! Undo and redo for component state.
+//!
+//! Wrap the part of your component's state you want to be able to undo in an
+//! [`Undoable`][Undoable], and pass your messages through
+//! [`Undoable::dispatch()`][dispatch] in your [`update`][update] method. Every
+//! message which changes the state records a snapshot of it, and the messages
+//! you've marked as `Undo` and `Redo` through the
+//! [`UndoMessage`][UndoMessage] trait step back and forth through them.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use vgtk::{gtk, Component, UpdateAction, VNode};
+//! # use vgtk::ext::*;
+//! # use vgtk::lib::gtk::*;
+//! use vgtk::undo::{History, UndoMessage, Undoable};
+//!
+//! #[derive(Clone, Debug)]
+//! enum Message {
+//!     Undo,
+//!     Redo,
+//!     Increment,
+//! }
+//!
+//! impl UndoMessage for Message {
+//!     fn history(&self) -> Option<History> {
+//!         match self {
+//!             Message::Undo => Some(History::Undo),
+//!             Message::Redo => Some(History::Redo),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Default)]
+//! struct Counter {
+//!     count: Undoable<i32>,
+//! }
+//!
+//! impl Component for Counter {
+//!     type Message = Message;
+//!     type Properties = ();
+//!
+//!     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+//!         self.count.dispatch(message, |count, message| match message {
+//!             Message::Increment => {
+//!                 *count += 1;
+//!                 UpdateAction::Render
+//!             }
+//!             _ => UpdateAction::None,
+//!         })
+//!     }
+//!
+//!     fn view(&self) -> VNode<Self> {
+//!         gtk! {
+//!             <Box>
+//!                 <Button label=format!("{}", *self.count) on clicked=|_| Message::Increment />
+//!                 <Button label="Undo" sensitive=self.count.can_undo()
+//!                         on clicked=|_| Message::Undo />
+//!                 <Button label="Redo" sensitive=self.count.can_redo()
+//!                         on clicked=|_| Message::Redo />
+//!             </Box>
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [Undoable]: struct.Undoable.html
+//! [UndoMessage]: trait.UndoMessage.html
+//! [dispatch]: struct.Undoable.html#method.dispatch
+//! [update]: ../trait.Component.html#method.update
+
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+use crate::component::{Component, UpdateAction};
+
+/// A step through an [`Undoable`][Undoable]'s history.
+///
+/// [Undoable]: struct.Undoable.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum History {
+    /// Go back to the state before the last change.
+    Undo,
+    /// Go forward to the state the last undo went back from.
+    Redo,
+}
+
+/// A message type which can ask an [`Undoable`][Undoable] to undo or redo.
+///
+/// [Undoable]: struct.Undoable.html
+pub trait UndoMessage {
+    /// Get the step through the history this message asks for, or `None` if
+    /// it's an ordinary message.
+    fn history(&self) -> Option<History>;
+}
+
+/// A value which remembers its previous states, so changes to it can be undone
+/// and redone.
+///
+/// An `Undoable` dereferences to its current state, which can only be changed
+/// through [`update()`][update] or [`dispatch()`][dispatch]. Each change
+/// records a snapshot of the state as it was before, up to a limit of 100 by
+/// default, after which the oldest snapshots are forgotten. Making a change
+/// after undoing forgets the states which could have been redone.
+///
+/// [update]: #method.update
+/// [dispatch]: #method.dispatch
+#[derive(Clone, Debug)]
+pub struct Undoable<T> {
+    present: T,
+    past: VecDeque<T>,
+    future: Vec<T>,
+    limit: usize,
+}
+
+impl<T: Clone + PartialEq> Undoable<T> {
+    /// Construct an `Undoable` with no history.
+    pub fn new(state: T) -> Self {
+        Undoable {
+            present: state,
+            past: VecDeque::new(),
+            future: Vec::new(),
+            limit: 100,
+        }
+    }
+
+    /// Set the number of previous states to remember.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.set_limit(limit);
+        self
+    }
+
+    /// Get the number of previous states to remember.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Set the number of previous states to remember, forgetting the oldest
+    /// ones if there are already more than that.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        while self.past.len() > limit {
+            self.past.pop_front();
+        }
+    }
+
+    /// Change the state with `f`, recording the previous state if `f` changed
+    /// it.
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        let previous = self.present.clone();
+        let result = f(&mut self.present);
+        if self.present != previous {
+            self.future.clear();
+            if self.limit > 0 {
+                if self.past.len() == self.limit {
+                    self.past.pop_front();
+                }
+                self.past.push_back(previous);
+            }
+        }
+        result
+    }
+
+    /// Handle a message in a component's [`update`][update] method.
+    ///
+    /// If the message asks to undo or redo, that's done here, and the
+    /// component is rendered again if the state changed. Any other message is
+    /// passed to `f` along with the state to change, as with
+    /// [`update()`][update_state].
+    ///
+    /// [update]: ../trait.Component.html#method.update
+    /// [update_state]: #method.update
+    pub fn dispatch<M, C, F>(&mut self, message: M, f: F) -> UpdateAction<C>
+    where
+        M: UndoMessage,
+        C: Component,
+        F: FnOnce(&mut T, M) -> UpdateAction<C>,
+    {
+        let changed = match message.history() {
+            Some(History::Undo) => self.undo(),
+            Some(History::Redo) => self.redo(),
+            None => return self.update(|state| f(state, message)),
+        };
+        if changed {
+            UpdateAction::Render
+        } else {
+            UpdateAction::None
+        }
+    }
+
+    /// Go back to the state before the last change.
+    ///
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.past.pop_back() {
+            Some(previous) => {
+                let present = std::mem::replace(&mut self.present, previous);
+                self.future.push(present);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Go forward to the state the last undo went back from.
+    ///
+    /// Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.future.pop() {
+            Some(next) => {
+                let present = std::mem::replace(&mut self.present, next);
+                self.past.push_back(present);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Test whether there's a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    /// Test whether there's an undone change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// Forget every previous and undone state, keeping only the current one.
+    pub fn clear_history(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+}
+
+impl<T: Clone + PartialEq + Default> Default for Undoable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Deref for Undoable<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.present
+    }
+}
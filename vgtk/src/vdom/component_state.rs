@@ -3,10 +3,13 @@ use glib::prelude::*;
 use glib::{MainContext, Object};
 use gtk::{Widget, WidgetExt};
 
-use std::any::TypeId;
+use std::any::{Any, TypeId};
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 use crate::component::{Component, ComponentMessage, ComponentTask};
+#[cfg(feature = "debug")]
+use crate::inspector;
 use crate::scope::Scope;
 use crate::vnode::component::AnyProps;
 use crate::vnode::{VComponent, VProperty};
@@ -14,13 +17,17 @@ use crate::vnode::{VComponent, VProperty};
 trait PropertiesReceiver {
     fn update(&mut self, props: &AnyProps);
     fn unmounting(&self);
+    fn scope(&self) -> &dyn Any;
 }
 
 pub struct ComponentState<Model: Component> {
     parent: PhantomData<Model>,
     pub(crate) object: Object,
+    pub(crate) key: Option<String>,
+    pub(crate) memo: Option<Rc<dyn Any>>,
     model_type: TypeId,
     state: Box<dyn PropertiesReceiver>,
+    component_ref: Option<Rc<dyn Fn(Option<&dyn Any>)>>,
 }
 
 impl<Model: 'static + Component> ComponentState<Model> {
@@ -35,8 +42,11 @@ impl<Model: 'static + Component> ComponentState<Model> {
         ComponentState {
             parent: PhantomData,
             object,
+            key: None,
+            memo: None,
             model_type: TypeId::of::<Child>(),
             state: Box::new(sub_state),
+            component_ref: None,
         }
     }
 
@@ -51,16 +61,52 @@ impl<Model: 'static + Component> ComponentState<Model> {
             for prop in &spec.child_props {
                 (prop.set)(self.object.upcast_ref(), parent, false);
             }
+            self.key = spec.key.clone();
+            self.memo = None;
             self.state.update(&spec.props);
+            self.set_component_ref(spec.component_ref.clone());
             true
         } else {
             // Component type changed; need to rebuild
+            self.set_component_ref(None);
             self.state.unmounting();
             false
         }
     }
 
-    pub fn unmount(self) {
+    /// Point a `ComponentRef` at this component, letting go of any previous
+    /// one in case the ref itself has changed.
+    pub(crate) fn set_component_ref(
+        &mut self,
+        component_ref: Option<Rc<dyn Fn(Option<&dyn Any>)>>,
+    ) {
+        if let Some(old_ref) = self.component_ref.take() {
+            old_ref(None);
+        }
+        if let Some(ref component_ref) = component_ref {
+            component_ref(Some(self.state.scope()));
+        }
+        self.component_ref = component_ref;
+    }
+
+    /// Hand this state over to a tree belonging to a different component.
+    ///
+    /// The model type is only a marker: the subcomponent keeps talking to the
+    /// scope it was built with.
+    pub(crate) fn retype<Other: Component>(self) -> ComponentState<Other> {
+        ComponentState {
+            parent: PhantomData,
+            object: self.object,
+            key: self.key,
+            memo: self.memo,
+            model_type: self.model_type,
+            state: self.state,
+            component_ref: self.component_ref,
+        }
+    }
+
+    pub fn unmount(mut self) {
+        self.set_component_ref(None);
         self.state.unmounting();
         if let Ok(widget) = self.object.downcast::<Widget>() {
             widget.destroy();
@@ -70,6 +116,9 @@ impl<Model: 'static + Component> ComponentState<Model> {
 
 pub(crate) struct SubcomponentState<Model: Component> {
     channel: UnboundedSender<ComponentMessage<Model>>,
+    scope: Scope<Model>,
+    #[cfg(feature = "debug")]
+    inspected: Option<usize>,
 }
 
 impl<Model: 'static + Component> SubcomponentState<Model> {
@@ -79,19 +128,37 @@ impl<Model: 'static + Component> SubcomponentState<Model> {
         child_props: &[VProperty],
         parent_scope: &Scope<P>,
     ) -> (Self, Object) {
+        #[cfg(feature = "debug")]
+        let description = props.description();
         let props: Model::Properties = props.unwrap();
         let (channel, task) = ComponentTask::new(props, parent, Some(parent_scope));
         let object = task.object().unwrap();
+        let scope = task.scope();
+        #[cfg(feature = "debug")]
+        let inspected = task.inspected_id();
+        #[cfg(feature = "debug")]
+        inspector::set_props(inspected, description);
         for prop in child_props {
             (prop.set)(object.upcast_ref(), parent, true);
         }
         MainContext::ref_thread_default().spawn_local(task);
-        (SubcomponentState { channel }, object)
+        channel
+            .unbounded_send(ComponentMessage::Mounted)
+            .expect("failed to send mount message over system channel");
+        let state = SubcomponentState {
+            channel,
+            scope,
+            #[cfg(feature = "debug")]
+            inspected,
+        };
+        (state, object)
     }
 }
 
 impl<Model: 'static + Component> PropertiesReceiver for SubcomponentState<Model> {
     fn update(&mut self, raw_props: &AnyProps) {
+        #[cfg(feature = "debug")]
+        inspector::set_props(self.inspected, raw_props.description());
         let props = raw_props.unwrap();
         self.channel
             .unbounded_send(ComponentMessage::Props(props))
@@ -103,4 +170,8 @@ impl<Model: 'static + Component> PropertiesReceiver for SubcomponentState<Model>
             .unbounded_send(ComponentMessage::Unmounted)
             .expect("failed to send unmount message over system channel")
     }
+
+    fn scope(&self) -> &dyn Any {
+        &self.scope
+    }
 }
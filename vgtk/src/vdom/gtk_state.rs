@@ -1,27 +1,50 @@
+use std::any::Any;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
+use gdk::EventMask;
 use gio::{Action, ActionExt, ActionMapExt};
-use glib::{prelude::*, Object, SignalHandlerId};
+use glib::{prelude::*, Continue, Object, SignalHandlerId, Type, WeakRef};
 use gtk::{
-    self, prelude::*, Application, ApplicationWindow, Bin, Box as GtkBox, Builder, Container,
-    Dialog, Grid, GridExt, Menu, MenuButton, MenuItem, ShortcutsWindow, Widget, Window,
+    self, prelude::*, Application, ApplicationWindow, Assistant, Bin, Box as GtkBox, Builder,
+    CellLayout, CellRenderer, Container, Dialog, EventController, Grid, GridExt, HeaderBar,
+    InfoBar, Inhibit, Menu, MenuButton, MenuItem, Notebook, Overlay, Paned, Popover,
+    PropagationPhase, Revealer, ShortcutsWindow, ToolItem, Toolbar, TreeView, TreeViewColumn,
+    Widget, Window,
 };
 
 use super::State;
 use crate::component::Component;
+#[cfg(feature = "profile")]
+use crate::profile;
 use crate::scope::Scope;
 use crate::vnode::{VHandler, VNode, VObject, VProperty};
 
 pub(crate) struct GtkState<Model: Component> {
     pub(crate) object: Object,
+    pub(crate) key: Option<String>,
+    pub(crate) memo: Option<Rc<dyn Any>>,
     handlers: HashMap<(&'static str, &'static str), SignalHandlerId>,
     children: Vec<State<Model>>,
+    widget_ref: Option<Rc<dyn Fn(Option<&Object>)>>,
 }
 
-fn build_obj<A: IsA<Object>, Model: Component>(spec: &VObject<Model>) -> A {
+fn build_obj<A: IsA<Object>, Model: Component>(
+    spec: &VObject<Model>,
+    parent: Option<&Object>,
+) -> A {
     let class = spec.object_type;
     let obj = if let Some(ref cons) = spec.constructor {
         cons()
+    } else if class.is_a(&EventController::static_type()) {
+        // An event controller belongs to the widget it's declared inside, which
+        // can only be given to it when it's constructed.
+        let widget = parent
+            .and_then(|parent| parent.downcast_ref::<Widget>())
+            .unwrap_or_else(|| panic!("{} must be declared inside a Widget", class));
+        Object::new(class, &[("widget", widget)])
+            .unwrap_or_else(|error| panic!("can't construct {}: {}", class, error))
     } else {
         let mut ui = String::new();
         ui += &format!("<interface><object class=\"{}\"", class);
@@ -38,14 +61,118 @@ fn build_obj<A: IsA<Object>, Model: Component>(spec: &VObject<Model>) -> A {
         .unwrap_or_else(|_| panic!("build_obj: cannot cast {} to {}", class, A::static_type()))
 }
 
+// Event controllers and context menus are attached to the widget they're
+// declared inside rather than laid out in it, so they don't count among its
+// children.
+fn is_attachment<Model: 'static + Component>(parent: &Object, spec: &VNode<Model>) -> bool {
+    match spec {
+        VNode::Object(object) => is_attached_type(parent, object.object_type),
+        VNode::Component(_) => false,
+        VNode::Lazy(lazy) => is_attachment(parent, lazy.node()),
+    }
+}
+
+// A `Menu` is the context menu of any widget it's declared inside, except for
+// the ones which take a menu as their child.
+// A `Popover` points at any widget it's declared inside, except for a
+// `MenuButton`, which takes it as its child.
+fn is_attached_type(parent: &Object, child_type: Type) -> bool {
+    child_type.is_a(&EventController::static_type())
+        || (child_type.is_a(&Menu::static_type())
+            && !parent.is::<MenuItem>()
+            && !parent.is::<MenuButton>())
+        || (child_type.is_a(&Popover::static_type()) && !parent.is::<MenuButton>())
+}
+
+fn attach_popover(parent: &Object, popover: &Popover) {
+    let widget = parent.downcast_ref::<Widget>().unwrap_or_else(|| {
+        panic!(
+            "a Popover must be declared inside a Widget, but {} was found.",
+            parent.get_type()
+        )
+    });
+    // Leave it pointing where `relative_to_ref` pointed it, if it has.
+    if popover.get_relative_to().is_none() {
+        popover.set_relative_to(Some(widget));
+    }
+}
+
+// Every widget is shown once it's been built, except for popovers, which pop
+// up when they're shown, so they're left to their `visible` property.
+fn show(widget: &Widget) {
+    if !widget.is::<Popover>() {
+        widget.show();
+    }
+}
+
+fn attach_context_menu(parent: &Object, menu: &Menu) {
+    let widget = parent.downcast_ref::<Widget>().unwrap_or_else(|| {
+        panic!(
+            "a context Menu must be declared inside a Widget, but {} was found.",
+            parent.get_type()
+        )
+    });
+    menu.set_property("attach-widget", widget)
+        .expect("can't attach Menu to its Widget");
+    widget.add_events(EventMask::BUTTON_PRESS_MASK);
+    let pressed_menu = menu.downgrade();
+    let pressed = widget.connect_button_press_event(move |_, event| match pressed_menu.upgrade() {
+        Some(menu) if event.triggers_context_menu() => {
+            menu.popup_easy(event.get_button(), event.get_time());
+            Inhibit(true)
+        }
+        _ => Inhibit(false),
+    });
+    // The menu key, or Shift+F10, asks the focused widget for its context menu.
+    let keyed_menu = menu.downgrade();
+    let keyed = widget.connect_popup_menu(move |_| match keyed_menu.upgrade() {
+        Some(menu) => {
+            menu.popup_easy(0, gtk::get_current_event_time());
+            true
+        }
+        None => false,
+    });
+    // Once the menu's been detached, its widget stops popping it up.
+    let widget_ref = widget.downgrade();
+    let handlers = RefCell::new(Some((pressed, keyed)));
+    menu.connect_property_attach_widget_notify(move |menu| {
+        if let Some(widget) = widget_ref.upgrade() {
+            if menu.get_attach_widget().as_ref() != Some(&widget) {
+                if let Some((pressed, keyed)) = handlers.borrow_mut().take() {
+                    widget.disconnect(pressed);
+                    widget.disconnect(keyed);
+                }
+            }
+        }
+    });
+}
+
 // Gtk has many strange ways of adding children to a parent.
-fn add_child<Model: Component>(
+fn add_child<Model: 'static + Component>(
     parent: &Object,
+    children: &[VNode<Model>],
     index: usize,
-    total: usize,
-    child_spec: &VNode<Model>,
     child: &Object,
 ) {
+    let child_spec = &children[index];
+    if is_attached_type(parent, child.get_type()) {
+        // Event controllers are attached to their widget when they're built,
+        // and context menus when they're added.
+        if let Some(menu) = child.downcast_ref::<Menu>() {
+            attach_context_menu(parent, menu);
+        } else if let Some(popover) = child.downcast_ref::<Popover>() {
+            attach_popover(parent, popover);
+        }
+        return;
+    }
+    let total = children
+        .iter()
+        .filter(|spec| !is_attachment(parent, spec))
+        .count();
+    let index = children[..index]
+        .iter()
+        .filter(|spec| !is_attachment(parent, spec))
+        .count();
     if let Some(application) = parent.downcast_ref::<Application>() {
         if let Some(window) = child.downcast_ref::<Window>() {
             application.add_window(window);
@@ -125,6 +252,18 @@ fn add_child<Model: Component>(
                 child.get_type()
             );
         }
+    } else if let Some(assistant) = parent.downcast_ref::<Assistant>() {
+        // Assistant: children are pages, inserted at their index. Their
+        // titles, types and whether they're complete are set afterwards
+        // through child properties.
+        if let Some(widget) = child.downcast_ref::<Widget>() {
+            assistant.insert_page(widget, index as i32);
+        } else {
+            panic!(
+                "Assistant's children must be Widgets, but {} was found.",
+                child.get_type()
+            );
+        }
     } else if let Some(window) = parent.downcast_ref::<Window>() {
         // Window: takes only 1 or 2 Widgets. If 1 widget child, it's the
         // window's main widget. If 2, the first is the title bar and the second
@@ -141,6 +280,56 @@ fn add_child<Model: Component>(
                 child.get_type()
             );
         }
+    } else if let Some(layout) = parent.downcast_ref::<CellLayout>() {
+        // CellLayout, such as a TreeViewColumn or a ComboBox: children are
+        // cell renderers, packed in order. Which model columns they display is
+        // set through child properties.
+        if let Some(renderer) = child.downcast_ref::<CellRenderer>() {
+            layout.pack_start(renderer, false);
+        } else {
+            panic!(
+                "{}'s children must be CellRenderers, but {} was found.",
+                parent.get_type(),
+                child.get_type()
+            );
+        }
+    } else if let Some(overlay) = parent.downcast_ref::<Overlay>() {
+        // Overlay: the first child is the main widget, and the rest are
+        // overlays on top of it, stacked in order.
+        if let Some(widget) = child.downcast_ref::<Widget>() {
+            if index == 0 {
+                overlay.add(widget);
+            } else {
+                overlay.add_overlay(widget);
+            }
+        } else {
+            panic!(
+                "Overlay's children must be Widgets, but {} was found.",
+                child.get_type()
+            );
+        }
+    } else if let Some(revealer) = parent.downcast_ref::<Revealer>() {
+        // Revealer: can only have a single child, but the one it had before
+        // may still be sliding out of view. It's done for now.
+        if total > 1 {
+            panic!("Revealer can only have 1 child, but {} were found.", total);
+        }
+        if let Some(widget) = child.downcast_ref::<Widget>() {
+            match revealer.get_child() {
+                Some(ref current) if current == widget => stay(widget),
+                current => {
+                    if let Some(current) = current {
+                        finish_leaving(&current);
+                    }
+                    revealer.add(widget);
+                }
+            }
+        } else {
+            panic!(
+                "Revealer's child must be a Widget, but {} was found.",
+                child.get_type()
+            );
+        }
     } else if let Some(parent) = parent.downcast_ref::<Bin>() {
         // Bin: can only have a single child.
         if total > 1 {
@@ -154,6 +343,39 @@ fn add_child<Model: Component>(
                 child.get_type()
             );
         }
+    } else if let Some(info_bar) = parent.downcast_ref::<InfoBar>() {
+        // InfoBar: children go in the content area. The ones with a response
+        // child property get moved to the action area when it's applied.
+        if let Some(widget) = child.downcast_ref::<Widget>() {
+            let content_area = info_bar
+                .get_content_area()
+                .and_then(|area| area.downcast::<Container>().ok())
+                .expect("InfoBar has no content area");
+            content_area.add(widget);
+        } else {
+            panic!(
+                "InfoBar's children must be Widgets, but {} was found.",
+                child.get_type()
+            );
+        }
+    } else if let Some(paned) = parent.downcast_ref::<Paned>() {
+        // Paned: takes 1 or 2 children, the first in the first pane and the
+        // second in the second.
+        if total > 2 {
+            panic!("Paned can only have 2 children, but {} were found.", total);
+        }
+        if let Some(widget) = child.downcast_ref::<Widget>() {
+            if index == 0 {
+                paned.pack1(widget, false, true);
+            } else {
+                paned.pack2(widget, true, true);
+            }
+        } else {
+            panic!(
+                "Paned's children must be Widgets, but {} was found.",
+                child.get_type()
+            );
+        }
     } else if let Some(parent) = parent.downcast_ref::<GtkBox>() {
         // Box: added normally, except one widget can be added using
         // set_center_widget() if it has the center_widget=true child property
@@ -171,6 +393,33 @@ fn add_child<Model: Component>(
                 child.get_type()
             );
         }
+    } else if let Some(parent) = parent.downcast_ref::<HeaderBar>() {
+        // HeaderBar: packed at the start, except one widget can replace the
+        // title using set_custom_title() if it has the custom_title=true child
+        // property (which is faked in ext.rs). Packing at the end is done by
+        // the real pack_type child property.
+        if let Some(widget) = child.downcast_ref::<Widget>() {
+            if child_spec.get_child_prop("custom_title").is_some() {
+                parent.set_custom_title(Some(widget));
+            } else {
+                parent.pack_start(widget);
+            }
+        } else {
+            panic!(
+                "HeaderBar's children must be Widgets, but {} was found.",
+                child.get_type()
+            );
+        }
+    } else if let Some(toolbar) = parent.downcast_ref::<Toolbar>() {
+        // Toolbar: children are tool items, inserted at their index.
+        if let Some(item) = child.downcast_ref::<ToolItem>() {
+            toolbar.insert(item, index as i32);
+        } else {
+            panic!(
+                "Toolbar's children must be ToolItems, but {} was found. Wrap it in a ToolItem.",
+                child.get_type()
+            );
+        }
     } else if let Some(parent) = parent.downcast_ref::<Grid>() {
         if let Some(widget) = child.downcast_ref::<Widget>() {
             // by default we put widgets in the top left corner of the grid
@@ -184,6 +433,40 @@ fn add_child<Model: Component>(
                 child.get_type()
             );
         }
+    } else if let Some(notebook) = parent.downcast_ref::<Notebook>() {
+        // Notebook: pages are inserted at their index rather than appended, so
+        // a page added in the middle of the list ends up in the right place.
+        // Tab labels are set afterwards through child properties.
+        if let Some(widget) = child.downcast_ref::<Widget>() {
+            notebook.insert_page(widget, None::<&Widget>, Some(index as u32));
+        } else {
+            panic!(
+                "Notebook's children must be Widgets, but {} was found.",
+                child.get_type()
+            );
+        }
+    } else if let Some(tree_view) = parent.downcast_ref::<TreeView>() {
+        // TreeView: children are columns rather than widgets, inserted at
+        // their index.
+        if let Some(column) = child.downcast_ref::<TreeViewColumn>() {
+            tree_view.insert_column(column, index as i32);
+        } else {
+            panic!(
+                "TreeView's children must be TreeViewColumns, but {} was found.",
+                child.get_type()
+            );
+        }
+    } else if let Some(column) = parent.downcast_ref::<TreeViewColumn>() {
+        // TreeViewColumn: children are cell renderers, packed in order. Which
+        // model columns they display is set through child properties.
+        if let Some(renderer) = child.downcast_ref::<CellRenderer>() {
+            column.pack_start(renderer, false);
+        } else {
+            panic!(
+                "TreeViewColumn's children must be CellRenderers, but {} was found.",
+                child.get_type()
+            );
+        }
     } else if let Some(container) = parent.downcast_ref::<Container>() {
         if let Some(widget) = child.downcast_ref::<Widget>() {
             container.add(widget);
@@ -204,7 +487,18 @@ fn add_child<Model: Component>(
 
 fn remove_child(parent: &Object, child: &Object) {
     // There are also special cases for removing children.
-    if let Some(application) = parent.downcast_ref::<Application>() {
+    if let Some(controller) = child.downcast_ref::<EventController>() {
+        // An event controller can't be detached from its widget, but it stops
+        // handling events, and goes away with the last reference to it.
+        controller.reset();
+        controller.set_propagation_phase(PropagationPhase::None);
+    } else if is_attached_type(parent, child.get_type()) {
+        if let Some(menu) = child.downcast_ref::<Menu>() {
+            menu.detach();
+        } else if let Some(popover) = child.downcast_ref::<Popover>() {
+            popover.set_relative_to(None::<&Widget>);
+        }
+    } else if let Some(application) = parent.downcast_ref::<Application>() {
         if let Some(window) = child.downcast_ref::<Window>() {
             application.remove_window(window);
         } else if let Some(action) = child.downcast_ref::<Action>() {
@@ -215,6 +509,42 @@ fn remove_child(parent: &Object, child: &Object) {
                 child.get_type()
             );
         }
+    } else if let (Some(window), Some(action)) = (
+        parent.downcast_ref::<ApplicationWindow>(),
+        child.downcast_ref::<Action>(),
+    ) {
+        // ApplicationWindow: Actions aren't Widgets, so they need removing
+        // from the window's action map rather than the container.
+        window.remove_action(&action.get_name().expect("Action unexpectedly has no name"));
+    } else if let (Some(tree_view), Some(column)) = (
+        parent.downcast_ref::<TreeView>(),
+        child.downcast_ref::<TreeViewColumn>(),
+    ) {
+        tree_view.remove_column(column);
+    } else if let (Some(layout), Some(renderer)) = (
+        parent.downcast_ref::<CellLayout>(),
+        child.downcast_ref::<CellRenderer>(),
+    ) {
+        if let Some(area) = layout.get_area() {
+            area.remove(renderer);
+        }
+    } else if let (Some(_), Some(widget)) = (
+        parent.downcast_ref::<InfoBar>(),
+        child.downcast_ref::<Widget>(),
+    ) {
+        // InfoBar: children live in its content or action area, which is
+        // where they need removing from.
+        if let Some(area) = widget
+            .get_parent()
+            .and_then(|area| area.downcast::<Container>().ok())
+        {
+            area.remove(widget);
+        }
+    } else if let (Some(revealer), Some(widget)) = (
+        parent.downcast_ref::<Revealer>(),
+        child.downcast_ref::<Widget>(),
+    ) {
+        remove_revealed_child(revealer, widget);
     } else if let Some(container) = parent.downcast_ref::<Container>() {
         // For a Container and a Widget child, we should always be able to call
         // `Container::remove`.
@@ -234,6 +564,108 @@ fn remove_child(parent: &Object, child: &Object) {
     }
 }
 
+thread_local! {
+    // Revealer children which have been removed, but are still sliding out of
+    // view.
+    static LEAVING: RefCell<Vec<WeakRef<Widget>>> = const { RefCell::new(Vec::new()) };
+}
+
+fn is_leaving(widget: &Widget) -> bool {
+    LEAVING.with(|leaving| {
+        let mut leaving = leaving.borrow_mut();
+        leaving.retain(|leaving| leaving.upgrade().is_some());
+        leaving
+            .iter()
+            .any(|leaving| leaving.upgrade().as_ref() == Some(widget))
+    })
+}
+
+// Test whether `widget` is, or is inside, a Revealer child which is on its
+// way out.
+fn is_inside_leaving(widget: &Widget) -> bool {
+    LEAVING.with(|leaving| {
+        leaving.borrow().iter().any(|leaving| {
+            leaving
+                .upgrade()
+                .map(|leaving| &leaving == widget || widget.is_ancestor(&leaving))
+                .unwrap_or(false)
+        })
+    })
+}
+
+// Keep a Revealer child which was on its way out, because it's been added
+// back.
+fn stay(widget: &Widget) {
+    LEAVING.with(|leaving| {
+        leaving
+            .borrow_mut()
+            .retain(|leaving| leaving.upgrade().as_ref() != Some(widget))
+    });
+}
+
+// Get rid of a Revealer child which was on its way out, if it still is. It
+// was unmounted when it was removed, but not destroyed.
+fn finish_leaving(widget: &Widget) {
+    if is_leaving(widget) {
+        stay(widget);
+        widget.destroy();
+    }
+}
+
+// A Revealer's child is often only there while it's revealed, so when it's
+// removed while the revealer is showing it, it's left in place until the
+// revealer has finished hiding it, instead of vanishing before it can slide
+// out of view. Properties are patched after children, so we don't know yet
+// whether the revealer's going to hide it: if it isn't, the child goes once
+// the patch is done.
+fn remove_revealed_child(revealer: &Revealer, widget: &Widget) {
+    if !revealer.get_mapped() || !revealer.get_child_revealed() {
+        revealer.remove(widget);
+        return;
+    }
+    LEAVING.with(|leaving| leaving.borrow_mut().push(widget.downgrade()));
+    let handler = Rc::new(RefCell::new(None));
+    let hidden = {
+        let widget = widget.clone();
+        let handler = handler.clone();
+        revealer.connect_property_child_revealed_notify(move |revealer| {
+            if !revealer.get_child_revealed() {
+                finish_leaving(&widget);
+                if let Some(handler) = handler.borrow_mut().take() {
+                    revealer.disconnect(handler);
+                }
+            }
+        })
+    };
+    *handler.borrow_mut() = Some(hidden);
+    let revealer = revealer.downgrade();
+    let widget = widget.downgrade();
+    glib::idle_add_local(move || {
+        if let (Some(revealer), Some(widget)) = (revealer.upgrade(), widget.upgrade()) {
+            if revealer.get_reveal_child() {
+                finish_leaving(&widget);
+            }
+        }
+        Continue(false)
+    });
+}
+
+// The properties which pick out one of a widget's children, and so only take
+// effect once its children have been built.
+const CHILD_SELECTING_PROPERTIES: &[&str] = &[
+    "current_page",
+    "page",
+    "visible_child",
+    "visible_child_full",
+    "visible_child_name",
+];
+
+// Windows take their title bar as their first child, except for an
+// Assistant, whose children are all pages.
+fn has_titlebar_child(object: &Object) -> bool {
+    object.is::<Window>() && !object.is::<Assistant>()
+}
+
 impl<Model: 'static + Component> GtkState<Model> {
     // This function build the root object, but not its children. You must call
     // `build_children()` to finalise construction.
@@ -243,7 +675,11 @@ impl<Model: 'static + Component> GtkState<Model> {
         scope: &Scope<Model>,
     ) -> Self {
         // Build this object
-        let object: Object = build_obj(&vobj);
+        let object: Object = build_obj(&vobj, parent);
+        #[cfg(feature = "profile")]
+        profile::count(|counts| counts.built += 1);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(object = %vobj.object_type, "built object");
 
         // Apply properties
         for prop in &vobj.properties {
@@ -259,26 +695,42 @@ impl<Model: 'static + Component> GtkState<Model> {
 
         GtkState {
             object: object.upcast(),
+            key: vobj.key.clone(),
+            memo: None,
             handlers,
             children: Vec::new(),
+            widget_ref: None,
         }
     }
 
     pub(crate) fn build_children(&mut self, vobj: &VObject<Model>, scope: &Scope<Model>) {
         let object = &self.object;
         // Build children
-        let total_children = vobj.children.len();
         for (index, child_spec) in vobj.children.iter().enumerate() {
             let child = State::build(child_spec, Some(&object), &scope);
             let child_object = child.object().clone();
-            add_child(&object, index, total_children, child_spec, &child_object);
+            add_child(&object, &vobj.children, index, &child_object);
             self.children.push(child);
         }
 
+        // Properties which select a child, such as a Stack's
+        // `visible_child_name`, referred to children which didn't exist yet
+        // when they were first applied in `build_root()`, so give them another
+        // go now that they do.
+        if !vobj.children.is_empty() {
+            for prop in &vobj.properties {
+                if CHILD_SELECTING_PROPERTIES.contains(&prop.name) {
+                    (prop.set)(object.upcast_ref(), None, false);
+                }
+            }
+        }
+
         // Show this object, if it's a widget
         if let Some(widget) = self.object.downcast_ref::<Widget>() {
-            widget.show();
+            show(widget);
         }
+
+        self.set_widget_ref(vobj);
     }
 
     pub(crate) fn build(
@@ -297,12 +749,147 @@ impl<Model: 'static + Component> GtkState<Model> {
         parent: Option<&Object>,
         scope: &Scope<Model>,
     ) -> bool {
+        self.key = vobj.key.clone();
+        self.memo = None;
+
+        if self.can_patch_keyed(vobj) {
+            self.patch_keyed_children(vobj, scope);
+        } else {
+            self.patch_children(vobj, scope);
+        }
+
+        // Patch properties
+        self.patch_properties(&vobj.properties, parent);
+
+        // Patch child properties
+        self.patch_properties(&vobj.child_props, parent);
+
+        // Patch handlers
+        self.patch_handlers(&vobj.handlers, scope);
+
+        self.set_widget_ref(vobj);
+
+        true
+    }
+
+    // Point the object's `ref` at it, letting go of any previous one in case
+    // the ref itself has changed.
+    fn set_widget_ref(&mut self, vobj: &VObject<Model>) {
+        if let Some(old_ref) = self.widget_ref.take() {
+            old_ref(None);
+        }
+        if let Some(ref widget_ref) = vobj.widget_ref {
+            widget_ref(Some(&self.object));
+            self.widget_ref = Some(widget_ref.clone());
+        }
+    }
+
+    // Children can be matched up by key if every child, old and new, has a key,
+    // and the parent is a plain container where we're free to remove and re-add
+    // children in whatever order we like.
+    fn can_patch_keyed(&self, vobj: &VObject<Model>) -> bool {
+        self.object.is::<Container>()
+            && !has_titlebar_child(&self.object)
+            && !self.object.is::<MenuButton>()
+            && !self.object.is::<MenuItem>()
+            && !vobj.children.is_empty()
+            && vobj.children.iter().all(|child| child.get_key().is_some())
+            && self.children.iter().all(|child| child.key().is_some())
+    }
+
+    fn patch_keyed_children(&mut self, vobj: &VObject<Model>, scope: &Scope<Model>) {
+        // Moving pages around in a Notebook changes its current page, so
+        // remember which one it was and switch back to it afterwards.
+        let current_page = self
+            .object
+            .downcast_ref::<Notebook>()
+            .and_then(|notebook| notebook.get_nth_page(notebook.get_current_page()));
+        let old_order: Vec<Object> = self
+            .children
+            .iter()
+            .map(|child| child.object().clone())
+            .collect();
+        let mut old_children = HashMap::new();
+        for child in self.children.drain(..) {
+            let key = child.key().unwrap().to_string();
+            if let Some(duplicate) = old_children.insert(key, child) {
+                remove_child(&self.object, duplicate.object());
+                duplicate.unmount();
+            }
+        }
+
+        // Match new specs with old children by key, patching the ones we find
+        // and building the ones we don't.
+        let mut children = Vec::with_capacity(vobj.children.len());
+        let mut is_new = Vec::with_capacity(vobj.children.len());
+        for spec in &vobj.children {
+            let key = spec.get_key().unwrap();
+            let mut reused = None;
+            if let Some(mut child) = old_children.remove(key) {
+                if child.patch(spec, Some(&self.object), scope) {
+                    reused = Some(child);
+                } else {
+                    remove_child(&self.object, child.object());
+                    child.unmount();
+                }
+            }
+            is_new.push(reused.is_none());
+            children.push(reused.unwrap_or_else(|| State::build(spec, Some(&self.object), scope)));
+        }
+
+        // Anything left over has gone away.
+        for (_, child) in old_children {
+            remove_child(&self.object, child.object());
+            child.unmount();
+        }
+
+        // Children before the first new or moved child are already where they
+        // should be. Everything after that gets detached and added back in the
+        // new order; detaching doesn't destroy a widget, so it keeps its state.
+        let mut survivors = old_order
+            .iter()
+            .filter(|object| children.iter().any(|child| child.object() == *object));
+        let first_moved = children
+            .iter()
+            .zip(is_new.iter())
+            .position(|(child, new)| *new || Some(child.object()) != survivors.next())
+            .unwrap_or(children.len());
+        for (child, new) in children.iter().zip(is_new.iter()).skip(first_moved) {
+            // Event controllers and context menus have no place among the
+            // children to move from.
+            if !new && !is_attached_type(&self.object, child.object().get_type()) {
+                remove_child(&self.object, child.object());
+            }
+        }
+        for (index, child) in children.iter().enumerate().skip(first_moved) {
+            add_child(&self.object, &vobj.children, index, child.object());
+            if let Some(widget) = child.widget() {
+                show(widget);
+            }
+        }
+        self.children = children;
+        if let (Some(notebook), Some(page)) = (self.object.downcast_ref::<Notebook>(), current_page)
+        {
+            if let Some(page_num) = notebook.page_num(&page) {
+                notebook.set_current_page(Some(page_num));
+            }
+        }
+    }
+
+    fn patch_children(&mut self, vobj: &VObject<Model>, scope: &Scope<Model>) {
         // Patch children
         let mut to_remove = None;
         let mut to_append = Vec::new();
         let mut reconstruct_from = None;
         for index in 0..(self.children.len().max(vobj.children.len())) {
             match (self.children.get_mut(index), vobj.children.get(index)) {
+                (Some(target), Some(spec_item @ VNode::Lazy(_))) => {
+                    // Lazy nodes work out for themselves whether they've changed
+                    if !target.patch(spec_item, Some(&self.object), scope) {
+                        reconstruct_from = Some(index);
+                        break;
+                    }
+                }
                 (Some(State::Component(target)), Some(spec_item)) => {
                     match spec_item {
                         VNode::Object(_) => {
@@ -316,6 +903,7 @@ impl<Model: 'static + Component> GtkState<Model> {
                                 break;
                             }
                         }
+                        VNode::Lazy(_) => unreachable!(),
                     }
                 }
                 (Some(State::Gtk(target)), Some(spec_item)) => {
@@ -335,6 +923,7 @@ impl<Model: 'static + Component> GtkState<Model> {
                             reconstruct_from = Some(index);
                             break;
                         }
+                        VNode::Lazy(_) => unreachable!(),
                     }
                 }
                 (Some(_), None) => {
@@ -347,13 +936,7 @@ impl<Model: 'static + Component> GtkState<Model> {
                 (None, Some(spec)) => {
                     // New spec; construct
                     let state = State::build(spec, Some(&self.object), scope);
-                    add_child(
-                        &self.object,
-                        index,
-                        vobj.children.len(),
-                        spec,
-                        state.object(),
-                    );
+                    add_child(&self.object, &vobj.children, index, state.object());
                     to_append.push(state);
                 }
                 (None, None) => break,
@@ -361,7 +944,7 @@ impl<Model: 'static + Component> GtkState<Model> {
         }
         if let Some(index) = reconstruct_from {
             // Remove all previous children from here onwards
-            if self.object.is::<Window>() && index == 0 && self.children.len() == 2 {
+            if has_titlebar_child(&self.object) && index == 0 && self.children.len() == 2 {
                 panic!("Can't remove a title bar widget from an existing Window!");
             }
             for child in self.children.drain(index..) {
@@ -371,22 +954,17 @@ impl<Model: 'static + Component> GtkState<Model> {
             // Rebuild children from new specs
             for (index, child_spec) in vobj.children.iter().enumerate().skip(index) {
                 let state = State::build(child_spec, Some(&self.object), scope);
-                add_child(
-                    &self.object,
-                    index,
-                    vobj.children.len(),
-                    child_spec,
-                    state.object(),
-                );
+                add_child(&self.object, &vobj.children, index, state.object());
                 if let Some(w) = state.widget() {
-                    w.show()
+                    show(w)
                 }
                 self.children.push(state);
             }
         } else {
             // Remove children flagged as extraneous
             if let Some(remove_from) = to_remove {
-                if self.object.is::<Window>() && remove_from == 1 && self.children.len() == 2 {
+                if has_titlebar_child(&self.object) && remove_from == 1 && self.children.len() == 2
+                {
                     panic!("Can't remove a title bar widget from an existing Window!");
                 }
                 for child in self.children.drain(remove_from..) {
@@ -395,27 +973,17 @@ impl<Model: 'static + Component> GtkState<Model> {
                 }
             }
             // Or append newly constructed children
-            if self.object.is::<Window>() && !to_append.is_empty() && self.children.len() == 1 {
+            if has_titlebar_child(&self.object) && !to_append.is_empty() && self.children.len() == 1
+            {
                 panic!("Can't add a title bar widget to an existing Window!");
             }
             for child in to_append {
                 if let Some(w) = child.widget() {
-                    w.show()
+                    show(w)
                 }
                 self.children.push(child);
             }
         }
-
-        // Patch properties
-        self.patch_properties(&vobj.properties, parent);
-
-        // Patch child properties
-        self.patch_properties(&vobj.child_props, parent);
-
-        // Patch handlers
-        self.patch_handlers(&vobj.handlers, scope);
-
-        true
     }
 
     fn patch_properties(&mut self, properties: &[VProperty], parent: Option<&Object>) {
@@ -434,6 +1002,8 @@ impl<Model: 'static + Component> GtkState<Model> {
             if let std::collections::hash_map::Entry::Vacant(entry) = self.handlers.entry(key) {
                 let handle = (handler.set)(self.object.upcast_ref(), scope);
                 entry.insert(handle);
+                #[cfg(feature = "profile")]
+                profile::count(|counts| counts.handlers += 1);
             }
         }
         for key in self.handlers.keys() {
@@ -444,15 +1014,28 @@ impl<Model: 'static + Component> GtkState<Model> {
         for key in remove {
             let obj: &Object = self.object.upcast_ref();
             obj.disconnect(self.handlers.remove(&key).unwrap());
+            #[cfg(feature = "profile")]
+            profile::count(|counts| counts.handlers += 1);
         }
     }
 
     pub(crate) fn unmount(self) {
+        if let Some(widget_ref) = self.widget_ref {
+            widget_ref(None);
+        }
         for child in self.children {
             child.unmount();
         }
+        #[cfg(feature = "profile")]
+        profile::count(|counts| counts.removed += 1);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(object = %self.object.get_type(), "removed object");
         if let Ok(widget) = self.object.downcast::<Widget>() {
-            widget.destroy();
+            // A Revealer child on its way out, along with everything inside it,
+            // is destroyed once it's gone.
+            if !is_inside_leaving(&widget) {
+                widget.destroy();
+            }
         }
     }
 }
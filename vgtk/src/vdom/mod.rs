@@ -1,9 +1,9 @@
-use glib::{Cast, Object};
+use glib::{Cast, Object, ObjectExt};
 use gtk::{self, Widget};
 
 use crate::component::Component;
 use crate::scope::Scope;
-use crate::vnode::VNode;
+use crate::vnode::{VLazy, VNode};
 
 mod component_state;
 pub(crate) use component_state::ComponentState;
@@ -26,9 +26,16 @@ impl<Model: 'static + Component> State<Model> {
         match vnode {
             VNode::Object(object) => State::Gtk(GtkState::build(object, parent, scope)),
             VNode::Component(vcomp) => {
-                let comp = (vcomp.constructor)(&vcomp.props, parent, &vcomp.child_props, scope);
+                let mut comp = (vcomp.constructor)(&vcomp.props, parent, &vcomp.child_props, scope);
+                comp.key = vcomp.key.clone();
+                comp.set_component_ref(vcomp.component_ref.clone());
                 State::Component(comp)
             }
+            VNode::Lazy(lazy) => {
+                let mut state = State::build(lazy.node(), parent, scope);
+                state.remember(lazy);
+                state
+            }
         }
     }
 
@@ -45,6 +52,11 @@ impl<Model: 'static + Component> State<Model> {
                 // State::Component(comp)
                 unimplemented!()
             }
+            VNode::Lazy(lazy) => {
+                let mut state = State::build_root(lazy.node(), parent, scope);
+                state.remember(lazy);
+                state
+            }
         }
     }
 
@@ -54,6 +66,7 @@ impl<Model: 'static + Component> State<Model> {
                 State::Gtk(gtk_state) => gtk_state.build_children(vobject, scope),
                 _ => unimplemented!(),
             },
+            VNode::Lazy(lazy) => self.build_children(lazy.node(), scope),
             _ => unimplemented!(),
         }
     }
@@ -70,16 +83,48 @@ impl<Model: 'static + Component> State<Model> {
     ) -> bool {
         match vnode {
             VNode::Object(object) => match self {
-                State::Gtk(state) => state.patch(object, parent, scope),
-                State::Component(_) => false,
+                State::Gtk(state) if state.object.get_type() == object.object_type => {
+                    state.patch(object, parent, scope)
+                }
+                _ => false,
             },
             VNode::Component(vcomp) => match self {
                 State::Component(state) => state.patch(vcomp, parent, scope),
                 State::Gtk(_) => false,
             },
+            VNode::Lazy(lazy) => {
+                // Skip the subtree entirely if it was built from the same value.
+                if !self.is_memo_of(lazy) && !self.patch(lazy.node(), parent, scope) {
+                    return false;
+                }
+                self.remember(lazy);
+                true
+            }
+        }
+    }
+
+    /// Test whether this state was built from a lazy node with the same value.
+    fn is_memo_of(&self, lazy: &VLazy<Model>) -> bool {
+        let memo = match self {
+            State::Gtk(state) => &state.memo,
+            State::Component(state) => &state.memo,
+        };
+        match memo {
+            Some(memo) => lazy.is_same(&**memo),
+            None => false,
         }
     }
 
+    /// Note that this state was built from a lazy node, taking on its key.
+    fn remember(&mut self, lazy: &VLazy<Model>) {
+        let (key, memo) = match self {
+            State::Gtk(state) => (&mut state.key, &mut state.memo),
+            State::Component(state) => (&mut state.key, &mut state.memo),
+        };
+        *key = lazy.key.clone();
+        *memo = Some(lazy.memo());
+    }
+
     pub(crate) fn unmount(self) {
         match self {
             State::Gtk(state) => state.unmount(),
@@ -87,6 +132,14 @@ impl<Model: 'static + Component> State<Model> {
         }
     }
 
+    /// Get the key of the `VNode` this state was built from, if it had one.
+    pub(crate) fn key(&self) -> Option<&str> {
+        match self {
+            State::Gtk(state) => state.key.as_deref(),
+            State::Component(state) => state.key.as_deref(),
+        }
+    }
+
     /// Get the Glib `Object` represented by this state.
     pub(crate) fn object(&self) -> &Object {
         match self {
@@ -0,0 +1,235 @@
+use gtk::prelude::*;
+use gtk::{Adjustment, Box, Orientation, ScrolledWindow, Viewport};
+
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use crate::children::Children;
+use crate::component::{current_object, Component, UpdateAction};
+use crate::gtk;
+use crate::scope::Scope;
+use crate::vnode::VNode;
+
+/// A function which renders the row at a given index, for the `render_row`
+/// property of a [`VirtualList`][VirtualList].
+///
+/// In the [`gtk!`][gtk!] macro, give the property a closure which takes the
+/// index of a row and returns a [`VNode`][VNode] built with the `gtk!`
+/// macro. The closure can't work out the type of its argument by itself, so
+/// write it in a code block as `{ move |index: usize| ... }`. The row's signal
+/// handlers send their messages to the component which rendered it.
+///
+/// [VirtualList]: struct.VirtualList.html
+/// [VNode]: enum.VNode.html
+/// [gtk!]: macro.gtk.html
+#[derive(Clone, Default)]
+pub struct RowRenderer(pub(crate) Option<Rc<dyn Fn(usize) -> Children>>);
+
+impl RowRenderer {
+    /// Render the row at `index`.
+    ///
+    /// If the renderer is empty, this renders nothing.
+    pub fn render<C: 'static + Component>(&self, index: usize) -> Vec<VNode<C>> {
+        match &self.0 {
+            Some(render) => render(index).render(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Debug for RowRenderer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "RowRenderer()")
+    }
+}
+
+/// A scrolling list which only builds the rows that are actually on screen.
+///
+/// Give it the number of rows in `row_count`, and a closure which renders the
+/// row at a given index in `render_row`. As the list is scrolled, rows coming
+/// into view are rendered and rows leaving it are destroyed, so a list of a
+/// hundred thousand items costs about as much as one of a screenful, and
+/// rendering it again only renders the visible rows.
+///
+/// To know which rows are visible without building them, every row gets the
+/// same height, `row_height` pixels, which defaults to 32. Rows should fit
+/// inside it. A few rows either side of the visible ones are rendered too, so
+/// scrolling doesn't reveal empty space before the list catches up; set
+/// `overscan` to change how many.
+///
+/// The list puts itself inside a [`ScrolledWindow`][ScrolledWindow], so
+/// don't wrap it in another one. The `render_row` closure is kept around to
+/// render rows as they scroll into view, so it can't borrow from your
+/// component; move anything it needs into it, behind an `Rc` if it's large.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, UpdateAction, VNode, VirtualList};
+/// # use vgtk::lib::gtk::*;
+/// # use std::rc::Rc;
+/// # #[derive(Clone, Debug)] enum Message { Open(usize) }
+/// #[derive(Default)]
+/// struct Log {
+///     lines: Rc<Vec<String>>,
+/// }
+///
+/// impl Component for Log {
+///     type Message = Message;
+///     type Properties = ();
+///
+///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///         UpdateAction::None
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         let lines = self.lines.clone();
+///         gtk! {
+///             <Window>
+///                 <@VirtualList row_count=lines.len() row_height=24
+///                               render_row={
+///                                   move |index: usize| gtk! {
+///                                       <Button label=lines[index].clone()
+///                                               on clicked=|_| Message::Open(index) />
+///                                   }
+///                               } />
+///             </Window>
+///         }
+///     }
+/// }
+/// ```
+///
+/// [ScrolledWindow]: ../gtk/struct.ScrolledWindow.html
+#[derive(Clone, Debug)]
+pub struct VirtualList {
+    /// The number of rows in the list.
+    pub row_count: usize,
+    /// The height of every row, in pixels.
+    pub row_height: i32,
+    /// The number of rows to render above and below the visible ones.
+    pub overscan: usize,
+    /// The function which renders each row.
+    pub render_row: RowRenderer,
+    offset: f64,
+    height: f64,
+}
+
+/// The messages a [`VirtualList`][VirtualList] sends itself.
+///
+/// [VirtualList]: struct.VirtualList.html
+#[derive(Clone, Debug)]
+pub enum VirtualListMessage {
+    /// The list was scrolled or resized, and now shows `height` pixels
+    /// starting `offset` pixels from the top.
+    Scrolled {
+        /// The distance from the top of the list to the top of the visible
+        /// area.
+        offset: f64,
+        /// The height of the visible area.
+        height: f64,
+    },
+}
+
+impl Default for VirtualList {
+    fn default() -> Self {
+        VirtualList {
+            row_count: 0,
+            row_height: 32,
+            overscan: 4,
+            render_row: RowRenderer::default(),
+            offset: 0.0,
+            height: 0.0,
+        }
+    }
+}
+
+impl VirtualList {
+    /// Get the range of rows which should currently be built.
+    fn visible(&self) -> (usize, usize) {
+        let row_height = f64::from(self.row_height.max(1));
+        let first = (self.offset / row_height).floor().max(0.0) as usize;
+        let last = ((self.offset + self.height) / row_height).ceil().max(0.0) as usize;
+        (
+            first.saturating_sub(self.overscan).min(self.row_count),
+            last.saturating_add(self.overscan).min(self.row_count),
+        )
+    }
+
+    fn spacer(&self, rows: usize) -> i32 {
+        (rows as i32).saturating_mul(self.row_height.max(1))
+    }
+}
+
+impl Component for VirtualList {
+    type Message = VirtualListMessage;
+    type Properties = Self;
+
+    fn create(props: Self) -> Self {
+        props
+    }
+
+    fn change(&mut self, props: Self) -> UpdateAction<Self> {
+        self.row_count = props.row_count;
+        self.row_height = props.row_height;
+        self.overscan = props.overscan;
+        self.render_row = props.render_row;
+        UpdateAction::Render
+    }
+
+    fn update(&mut self, message: VirtualListMessage) -> UpdateAction<Self> {
+        match message {
+            VirtualListMessage::Scrolled { offset, height } => {
+                let visible = self.visible();
+                self.offset = offset;
+                self.height = height;
+                if self.visible() != visible {
+                    UpdateAction::Render
+                } else {
+                    UpdateAction::None
+                }
+            }
+        }
+    }
+
+    fn mounted(&mut self) {
+        let adjustment = current_object()
+            .and_then(|object| object.downcast::<ScrolledWindow>().ok())
+            .and_then(|window| window.get_vadjustment())
+            .expect("VirtualList has lost its ScrolledWindow");
+        // The adjustment isn't part of our view, so its signals can't return
+        // messages the usual way. If one fires after the list has unmounted,
+        // the message is simply dropped.
+        let scope = Scope::<Self>::current();
+        let scrolled = move |adjustment: &Adjustment| {
+            scope.send(VirtualListMessage::Scrolled {
+                offset: adjustment.get_value(),
+                height: adjustment.get_page_size(),
+            })
+        };
+        scrolled(&adjustment);
+        adjustment.connect_value_changed(scrolled.clone());
+        adjustment.connect_changed(scrolled);
+    }
+
+    fn view(&self) -> VNode<Self> {
+        let (first, last) = self.visible();
+        gtk! {
+            <ScrolledWindow>
+                <Viewport>
+                    <Box orientation=Orientation::Vertical>
+                        <Box key="above" property_height_request=self.spacer(first) />
+                        {
+                            for index in first..last {
+                                <Box key=index orientation=Orientation::Vertical
+                                     property_height_request=self.row_height>
+                                    { self.render_row.render(index) }
+                                </Box>
+                            }
+                        }
+                        <Box key="below" property_height_request=self.spacer(self.row_count - last) />
+                    </Box>
+                </Viewport>
+            </ScrolledWindow>
+        }
+    }
+}
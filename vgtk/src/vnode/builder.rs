@@ -0,0 +1,323 @@
+use glib::{Cast, IsA, Object, ObjectExt, ToValue, Value};
+use gtk::Inhibit;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+use std::panic::Location;
+use std::rc::Rc;
+
+use super::describe::{DescribeDebug, Description};
+use super::{VHandler, VNode, VObject, VProperty};
+use crate::component::Component;
+use crate::properties::same_value;
+use crate::scope::{HandlerResult, Scope};
+use crate::widget_ref::WidgetRef;
+
+/// A builder for a [`VNode`][VNode] representing a Gtk object, for building
+/// views in plain Rust rather than with the [`gtk!`][gtk!] macro.
+///
+/// Use [`VNode::widget()`][widget] to start one, and
+/// [`build()`][build] to turn it into a [`VNode`][VNode].
+///
+/// Where the macro looks up each property's setter at compile time, the
+/// builder sets properties by name through GObject, so a misspelt property
+/// name or a value of the wrong type is only caught when the property is set,
+/// with a panic.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{Component, VNode};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Inc }
+/// # #[derive(Default)] struct Counter { count: usize }
+/// # impl Component for Counter { type Message = Message; type Properties = ();
+/// fn view(&self) -> VNode<Counter> {
+///     VNode::widget::<Box>()
+///         .prop("orientation", Orientation::Vertical)
+///         .prop("spacing", 6)
+///         .child(
+///             VNode::widget::<Label>()
+///                 .prop("label", format!("Clicked {} times", self.count))
+///                 .child_prop("expand", true, Box::get_child_expand, Box::set_child_expand)
+///                 .build(),
+///         )
+///         .child(
+///             VNode::widget::<Button>()
+///                 .prop("label", "Click me")
+///                 .on("clicked", |_| Message::Inc)
+///                 .build(),
+///         )
+///         .build()
+/// }
+/// # }
+/// ```
+///
+/// [VNode]: enum.VNode.html
+/// [gtk!]: macro.gtk.html
+/// [widget]: enum.VNode.html#method.widget
+/// [build]: #method.build
+pub struct VObjectBuilder<Model: Component, W> {
+    object: VObject<Model>,
+    object_type: PhantomData<W>,
+}
+
+impl<Model: 'static + Component> VNode<Model> {
+    /// Start building a node for a Gtk object of type `W`, without the
+    /// [`gtk!`][gtk!] macro.
+    ///
+    /// See [`VObjectBuilder`][VObjectBuilder] for an example.
+    ///
+    /// [gtk!]: macro.gtk.html
+    /// [VObjectBuilder]: struct.VObjectBuilder.html
+    pub fn widget<W: IsA<Object>>() -> VObjectBuilder<Model, W> {
+        VObjectBuilder {
+            object: VObject {
+                object_type: W::static_type(),
+                key: None,
+                constructor: None,
+                widget_ref: None,
+                properties: Vec::new(),
+                child_props: Vec::new(),
+                handlers: Vec::new(),
+                children: Vec::new(),
+            },
+            object_type: PhantomData,
+        }
+    }
+
+    /// Make a node for a subcomponent, like `<@Child />` in the
+    /// [`gtk!`][gtk!] macro.
+    ///
+    /// [gtk!]: macro.gtk.html
+    pub fn component<Child: 'static + Component>(props: Child::Properties) -> Self {
+        let mut comp = super::VComponent::new::<Child>();
+        comp.set_props::<Child>(props);
+        VNode::Component(comp)
+    }
+}
+
+impl<Model: 'static + Component, W: IsA<Object>> VObjectBuilder<Model, W> {
+    /// Build the object with `constructor` instead of with its default
+    /// property values, like `<Box::new(Orientation::Vertical, 6)>` in the
+    /// [`gtk!`][gtk!] macro.
+    ///
+    /// [gtk!]: macro.gtk.html
+    pub fn constructor(mut self, constructor: impl Fn() -> W + 'static) -> Self {
+        self.object.constructor = Some(Box::new(move || constructor().upcast()));
+        self
+    }
+
+    /// Give the node a key, like the `key` attribute in the [`gtk!`][gtk!]
+    /// macro.
+    ///
+    /// [gtk!]: macro.gtk.html
+    pub fn key(mut self, key: impl Display) -> Self {
+        self.object.key = Some(key.to_string());
+        self
+    }
+
+    /// Point `widget_ref` at the object once it's been built, like the `ref`
+    /// attribute in the [`gtk!`][gtk!] macro.
+    ///
+    /// [gtk!]: macro.gtk.html
+    pub fn widget_ref(mut self, widget_ref: &WidgetRef<W>) -> Self {
+        self.object.widget_ref = Some(widget_ref.setter());
+        self
+    }
+
+    /// Set the GObject property `name`, as it's spelt in the Gtk
+    /// documentation, to `value`.
+    ///
+    /// The property is only set again on later renders if its current value
+    /// differs from `value`.
+    ///
+    /// # Panics
+    ///
+    /// Setting the property panics if the object has no property `name`, or
+    /// if `value` is of the wrong type for it.
+    pub fn prop(mut self, name: &'static str, value: impl ToValue + Debug) -> Self {
+        let description = (&Description(&value)).describe();
+        let value = value.to_value();
+        self.object.properties.push(VProperty {
+            name,
+            value: description,
+            set: Box::new(move |object, _parent, force| {
+                let current = object.get_property(name);
+                if force || !matches!(current, Ok(current) if same_value(&current, &value)) {
+                    object.set_property(name, &value).unwrap_or_else(|error| {
+                        panic!(
+                            "can't set property {:?} on {}: {}",
+                            name,
+                            object.get_type(),
+                            error
+                        )
+                    });
+                    VProperty::changed();
+                }
+            }),
+        });
+        self
+    }
+
+    /// Set a child property, which the parent container `P` keeps for this
+    /// object, to `value`, using the parent's `get` and `set` accessors for
+    /// it.
+    ///
+    /// This is like the `P::name` attribute in the [`gtk!`][gtk!] macro. Gtk
+    /// has accessors for the child properties of each container, like
+    /// [`Box::set_child_expand()`][set_child_expand], and vgtk adds some of its
+    /// own in [`vgtk::ext`][ext].
+    ///
+    /// [gtk!]: macro.gtk.html
+    /// [set_child_expand]: ../gtk/trait.BoxExt.html#tymethod.set_child_expand
+    /// [ext]: ext/index.html
+    pub fn child_prop<P, T>(
+        mut self,
+        name: &'static str,
+        value: T,
+        get: impl Fn(&P, &W) -> T + 'static,
+        set: impl Fn(&P, &W, T) + 'static,
+    ) -> Self
+    where
+        P: IsA<Object>,
+        T: Clone + PartialEq + Debug + 'static,
+    {
+        let description = (&Description(&value)).describe();
+        self.object.child_props.push(VProperty {
+            name,
+            value: description,
+            set: Box::new(move |object, parent, force| {
+                let object: &W = object.downcast_ref().unwrap_or_else(|| {
+                    panic!(
+                        "downcast to {:?} failed in property setter",
+                        W::static_type()
+                    )
+                });
+                let parent: &P = parent
+                    .expect("child attribute without a reachable parent")
+                    .downcast_ref()
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "downcast to {:?} failed on parent in property setter",
+                            P::static_type()
+                        )
+                    });
+                if force || get(parent, object) != value {
+                    set(parent, object, value.clone());
+                    VProperty::changed();
+                }
+            }),
+        });
+        self
+    }
+
+    /// Connect a handler to the object's signal `name`, like
+    /// `on name=|...| ...` in the [`gtk!`][gtk!] macro.
+    ///
+    /// The handler is called with the signal's arguments, starting with the
+    /// object itself. It can return anything a handler in the macro can: a
+    /// message, an `Option` or a `Vec` of messages, or, for signals which
+    /// expect an [`Inhibit`][Inhibit], an `(Inhibit, message)` pair.
+    ///
+    /// Like in the macro, the handler is connected once, when the object is
+    /// built, and is matched up with the same call to `on()` on later renders.
+    ///
+    /// # Panics
+    ///
+    /// Connecting the handler panics if the object has no signal `name`.
+    ///
+    /// [gtk!]: macro.gtk.html
+    /// [Inhibit]: ../gtk/struct.Inhibit.html
+    #[track_caller]
+    pub fn on<R, Ret>(
+        mut self,
+        name: &'static str,
+        handler: impl Fn(&[Value]) -> R + 'static,
+    ) -> Self
+    where
+        R: HandlerResult<Model::Message, Ret>,
+        Ret: SignalReturn,
+    {
+        let handler = Rc::new(handler);
+        self.object.handlers.push(VHandler {
+            name,
+            id: handler_id(Location::caller()),
+            set: Box::new(move |object, scope| {
+                let scope: Scope<Model> = scope.clone();
+                let handler = handler.clone();
+                object
+                    .connect_local(name, false, move |args| {
+                        scope.send_result(handler(args)).into_value()
+                    })
+                    .unwrap_or_else(|error| {
+                        panic!(
+                            "can't connect to signal {:?} on {}: {}",
+                            name,
+                            object.get_type(),
+                            error
+                        )
+                    })
+            }),
+        });
+        self
+    }
+
+    /// Add a child node.
+    pub fn child(mut self, child: VNode<Model>) -> Self {
+        self.object.children.push(child);
+        self
+    }
+
+    /// Add a list of child nodes.
+    pub fn children(mut self, children: impl IntoIterator<Item = VNode<Model>>) -> Self {
+        self.object.children.extend(children);
+        self
+    }
+
+    /// Finish building the node.
+    pub fn build(self) -> VNode<Model> {
+        VNode::Object(self.object)
+    }
+}
+
+impl<Model: 'static + Component, W: IsA<Object>> From<VObjectBuilder<Model, W>> for VNode<Model> {
+    fn from(builder: VObjectBuilder<Model, W>) -> Self {
+        builder.build()
+    }
+}
+
+/// The value a signal handler returns to the signal, as a `Value`.
+#[doc(hidden)]
+pub trait SignalReturn {
+    fn into_value(self) -> Option<Value>;
+}
+
+impl SignalReturn for () {
+    fn into_value(self) -> Option<Value> {
+        None
+    }
+}
+
+impl SignalReturn for Inhibit {
+    fn into_value(self) -> Option<Value> {
+        Some(self.0.to_value())
+    }
+}
+
+thread_local! {
+    static HANDLER_IDS: RefCell<HashMap<(&'static str, u32, u32), &'static str>> =
+        RefCell::new(HashMap::new());
+}
+
+// Handlers are told apart by where they're written, like the macro does, so
+// each call site gets an ID which lives as long as the program does.
+fn handler_id(location: &'static Location<'static>) -> &'static str {
+    HANDLER_IDS.with(|ids| {
+        *ids.borrow_mut()
+            .entry((location.file(), location.line(), location.column()))
+            .or_insert_with(|| Box::leak(location.to_string().into_boxed_str()))
+    })
+}
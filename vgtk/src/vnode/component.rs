@@ -1,43 +1,58 @@
 use glib::Object;
 
 use std::any::{Any, TypeId};
+use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
-use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::callback::Callback;
+use crate::children::Children;
 use crate::component::Component;
-use crate::scope::Scope;
+use crate::scope::{IntoMessages, Scope};
 use crate::vdom::ComponentState;
-use crate::vnode::VProperty;
+use crate::virtual_list::RowRenderer;
+use crate::vnode::{VNode, VProperty};
 
+/// A component's properties with their type erased.
+///
+/// Cloning `AnyProps` shares the underlying value, which is moved out when it's
+/// unwrapped if nothing else holds on to it, and cloned otherwise.
 pub struct AnyProps {
-    valid: AtomicBool,
     type_id: TypeId,
-    data: *mut (),
+    data: RefCell<Option<Rc<dyn Any>>>,
+    description: Option<Rc<str>>,
 }
 
 impl AnyProps {
     fn null() -> Self {
         AnyProps {
-            valid: AtomicBool::new(false),
             type_id: TypeId::of::<()>(),
-            data: std::ptr::null_mut(),
+            data: RefCell::new(None),
+            description: None,
         }
     }
 
     pub fn new<Props: Any>(props: Props) -> Self {
         AnyProps {
-            valid: AtomicBool::new(true),
             type_id: TypeId::of::<Props>(),
-            data: Box::into_raw(Box::new(props)) as *mut (),
+            data: RefCell::new(Some(Rc::new(props))),
+            description: None,
         }
     }
 
-    pub fn unwrap<Props: Any>(&self) -> Props {
-        if !self.valid.swap(false, Ordering::SeqCst) {
-            panic!("tried to unwrap AnyProps of type {:?} twice", self.type_id)
-        }
+    /// Attach a description of the properties, for the inspector and for
+    /// snapshots.
+    #[doc(hidden)]
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description.map(Rc::from);
+    }
+
+    #[cfg(any(feature = "debug", feature = "snapshot"))]
+    pub(crate) fn description(&self) -> Option<Rc<str>> {
+        self.description.clone()
+    }
+
+    pub fn unwrap<Props: Any + Clone>(&self) -> Props {
         if self.type_id != TypeId::of::<Props>() {
             panic!(
                 "passed type {:?} to constructor expecting type {:?}",
@@ -45,9 +60,23 @@ impl AnyProps {
                 TypeId::of::<Props>()
             )
         }
-        #[allow(unsafe_code)]
-        unsafe {
-            *Box::from_raw(self.data as *mut Props)
+        let data = match self.data.borrow_mut().take() {
+            None => panic!("tried to unwrap AnyProps of type {:?} twice", self.type_id),
+            Some(data) => data,
+        };
+        match data.downcast::<Props>() {
+            Ok(props) => Rc::try_unwrap(props).unwrap_or_else(|props| (*props).clone()),
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+impl Clone for AnyProps {
+    fn clone(&self) -> Self {
+        AnyProps {
+            type_id: self.type_id,
+            data: RefCell::new(self.data.borrow().clone()),
+            description: self.description.clone(),
         }
     }
 }
@@ -58,8 +87,11 @@ type Constructor<Model> =
 pub struct VComponent<Model: Component> {
     parent: PhantomData<Model>,
     pub model_type: TypeId,
+    pub name: &'static str,
+    pub key: Option<String>,
     pub props: AnyProps,
     pub constructor: Box<Constructor<Model>>,
+    pub component_ref: Option<Rc<dyn Fn(Option<&dyn Any>)>>,
     pub child_props: Vec<VProperty>,
 }
 
@@ -69,12 +101,36 @@ impl<Model: 'static + Component> VComponent<Model> {
         VComponent {
             parent: PhantomData,
             model_type: TypeId::of::<Child>(),
+            name: std::any::type_name::<Child>(),
+            key: None,
             props: AnyProps::null(),
             constructor,
+            component_ref: None,
             child_props: Vec::new(),
         }
     }
 
+    pub(crate) fn with_constructor(
+        model_type: TypeId,
+        name: &'static str,
+        key: Option<String>,
+        props: AnyProps,
+        constructor: Box<Constructor<Model>>,
+        component_ref: Option<Rc<dyn Fn(Option<&dyn Any>)>>,
+        child_props: Vec<VProperty>,
+    ) -> Self {
+        VComponent {
+            parent: PhantomData,
+            model_type,
+            name,
+            key,
+            props,
+            constructor,
+            component_ref,
+            child_props,
+        }
+    }
+
     pub fn set_props<Child: 'static + Component>(&mut self, props: Child::Properties) {
         assert_eq!(self.model_type, TypeId::of::<Child>());
         self.props = AnyProps::new(props);
@@ -103,17 +159,39 @@ impl<'a, Model: Component> PropTransform<Model, &'a str, String> for VComponent<
     }
 }
 
-impl<Model, F, A> PropTransform<Model, F, Callback<A>> for VComponent<Model>
+impl<Model, F, A, R> PropTransform<Model, F, Callback<A>> for VComponent<Model>
 where
     Model: Component + 'static,
-    F: Fn(A) -> Model::Message + 'static,
+    F: Fn(A) -> R + 'static,
+    R: IntoMessages<Model::Message>,
 {
     fn transform(&self, from: F) -> Callback<A> {
         let callback: Rc<dyn Fn(A)> = Rc::new(move |arg| {
             let msg = from(arg);
             let scope = Scope::<Model>::current_parent();
-            scope.send_message(msg);
+            scope.send_messages(msg);
         });
         Callback(Some(callback))
     }
 }
+
+impl<Model: 'static + Component> PropTransform<Model, Vec<VNode<Model>>, Children>
+    for VComponent<Model>
+{
+    fn transform(&self, from: Vec<VNode<Model>>) -> Children {
+        Children::new(from, Scope::current())
+    }
+}
+
+impl<Model, F> PropTransform<Model, F, RowRenderer> for VComponent<Model>
+where
+    Model: 'static + Component,
+    F: Fn(usize) -> VNode<Model> + 'static,
+{
+    fn transform(&self, from: F) -> RowRenderer {
+        let scope = Scope::<Model>::current();
+        RowRenderer(Some(Rc::new(move |index| {
+            Children::new(vec![from(index)], scope.clone())
+        })))
+    }
+}
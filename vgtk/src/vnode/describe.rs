@@ -0,0 +1,135 @@
+use std::fmt::Debug;
+#[cfg(any(feature = "debug", feature = "snapshot"))]
+use std::fmt::Write;
+
+#[cfg(any(feature = "debug", feature = "snapshot"))]
+use super::VNode;
+#[cfg(any(feature = "debug", feature = "snapshot"))]
+use crate::Component;
+
+/// A property value or a component's properties, as seen by the `gtk!` macro
+/// when it describes them for the inspector and for snapshots.
+///
+/// Calling `describe()` on a `&&Description` resolves to
+/// [`DescribeDebug`][DescribeDebug] when the value implements `Debug`, and
+/// falls back to [`DescribeOther`][DescribeOther] when it doesn't.
+///
+/// [DescribeDebug]: trait.DescribeDebug.html
+/// [DescribeOther]: trait.DescribeOther.html
+#[doc(hidden)]
+pub struct Description<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait DescribeDebug {
+    fn describe(&self) -> Option<String>;
+}
+
+impl<T: Debug> DescribeDebug for &Description<'_, T> {
+    fn describe(&self) -> Option<String> {
+        // Nothing looks at descriptions unless one of these is enabled.
+        if cfg!(any(feature = "debug", feature = "snapshot")) {
+            Some(format!("{:?}", self.0))
+        } else {
+            None
+        }
+    }
+}
+
+#[doc(hidden)]
+pub trait DescribeOther {
+    fn describe(&self) -> Option<String>;
+}
+
+impl<T> DescribeOther for Description<'_, T> {
+    fn describe(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Write out a node and its children in something like the syntax of the
+/// `gtk!` macro, leaving out signal handlers.
+///
+/// Values which don't implement `Debug` are written as `_`. Lazy nodes which
+/// haven't been rendered are only rendered if `render_lazy` is set.
+#[cfg(any(feature = "debug", feature = "snapshot"))]
+pub(crate) fn outline<Model: 'static + Component>(
+    node: &VNode<Model>,
+    render_lazy: bool,
+) -> String {
+    let mut out = String::new();
+    write_node(&mut out, node, None, 0, render_lazy);
+    out
+}
+
+#[cfg(any(feature = "debug", feature = "snapshot"))]
+fn write_node<Model: 'static + Component>(
+    out: &mut String,
+    node: &VNode<Model>,
+    parent: Option<&str>,
+    depth: usize,
+    render_lazy: bool,
+) {
+    let indent = "    ".repeat(depth);
+    match node {
+        VNode::Object(object) => {
+            let name = object.object_type.name();
+            let _ = write!(out, "{}<{}", indent, name);
+            write_key(out, object.key.as_deref());
+            write_properties(out, &object.properties, None);
+            write_properties(out, &object.child_props, parent);
+            if object.children.is_empty() {
+                out.push_str(" />\n");
+            } else {
+                out.push_str(">\n");
+                for child in &object.children {
+                    write_node(out, child, Some(&name), depth + 1, render_lazy);
+                }
+                let _ = writeln!(out, "{}</{}>", indent, name);
+            }
+        }
+        VNode::Component(comp) => {
+            let _ = write!(out, "{}<@{}", indent, comp.name);
+            write_key(out, comp.key.as_deref());
+            if let Some(props) = comp.props.description() {
+                let _ = write!(out, " props={}", props);
+            }
+            write_properties(out, &comp.child_props, parent);
+            out.push_str(" />\n");
+        }
+        VNode::Lazy(lazy) => match lazy.rendered_node() {
+            Some(node) => write_node(out, node, parent, depth, render_lazy),
+            None if render_lazy => write_node(out, lazy.node(), parent, depth, render_lazy),
+            None => {
+                let _ = writeln!(out, "{}(lazy node, not rendered)", indent);
+            }
+        },
+    }
+}
+
+#[cfg(any(feature = "debug", feature = "snapshot"))]
+fn write_key(out: &mut String, key: Option<&str>) {
+    if let Some(key) = key {
+        let _ = write!(out, " key={:?}", key);
+    }
+}
+
+#[cfg(any(feature = "debug", feature = "snapshot"))]
+fn write_properties(out: &mut String, properties: &[super::VProperty], parent: Option<&str>) {
+    for prop in properties {
+        // Properties vgtk adds itself, like scoped style classes, aren't part
+        // of the view as written.
+        if prop.name.starts_with("vgtk-") {
+            continue;
+        }
+        out.push(' ');
+        if let Some(parent) = parent {
+            let _ = write!(out, "{}::", parent);
+        }
+        let _ = write!(
+            out,
+            "{}={}",
+            prop.name,
+            prop.value.as_deref().unwrap_or("_")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::borrow::Borrow;
+use std::rc::Rc;
 
 use glib::{Object, Type};
 
@@ -7,7 +8,9 @@ use crate::Component;
 
 pub struct VObject<Model: Component> {
     pub object_type: Type,
+    pub key: Option<String>,
     pub constructor: Option<Box<dyn Fn() -> Object>>,
+    pub widget_ref: Option<Rc<dyn Fn(Option<&Object>)>>,
     pub properties: Vec<VProperty>,
     pub child_props: Vec<VProperty>,
     pub handlers: Vec<VHandler<Model>>,
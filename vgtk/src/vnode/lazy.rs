@@ -0,0 +1,98 @@
+use std::any::Any;
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+use super::VNode;
+use crate::{css, Component};
+
+struct LazyMemo<Model: Component, T> {
+    value: T,
+    render: fn(&T) -> VNode<Model>,
+}
+
+fn same_memo<Model: 'static + Component, T: 'static + PartialEq>(
+    left: &dyn Any,
+    right: &dyn Any,
+) -> bool {
+    match (
+        left.downcast_ref::<LazyMemo<Model, T>>(),
+        right.downcast_ref::<LazyMemo<Model, T>>(),
+    ) {
+        (Some(left), Some(right)) => {
+            left.render as usize == right.render as usize && left.value == right.value
+        }
+        _ => false,
+    }
+}
+
+fn render_memo<Model: 'static + Component, T: 'static>(memo: &dyn Any) -> VNode<Model> {
+    let memo = memo
+        .downcast_ref::<LazyMemo<Model, T>>()
+        .expect("lazy node holds a memo of the wrong type");
+    (memo.render)(&memo.value)
+}
+
+/// A node whose subtree is only rendered when the value it's rendered from
+/// has changed.
+///
+/// Use [`VNode::lazy()`][lazy] to construct one.
+///
+/// [lazy]: enum.VNode.html#method.lazy
+pub struct VLazy<Model: Component> {
+    pub(crate) key: Option<String>,
+    memo: Rc<dyn Any>,
+    same: fn(&dyn Any, &dyn Any) -> bool,
+    render: fn(&dyn Any) -> VNode<Model>,
+    node: OnceCell<Box<VNode<Model>>>,
+}
+
+impl<Model: 'static + Component> VLazy<Model> {
+    pub(crate) fn new<T: 'static + PartialEq>(value: T, render: fn(&T) -> VNode<Model>) -> Self {
+        VLazy {
+            key: None,
+            memo: Rc::new(LazyMemo { value, render }),
+            same: same_memo::<Model, T>,
+            render: render_memo::<Model, T>,
+            node: OnceCell::new(),
+        }
+    }
+
+    /// Get the value this node is rendered from, to remember alongside the
+    /// state it builds.
+    pub(crate) fn memo(&self) -> Rc<dyn Any> {
+        self.memo.clone()
+    }
+
+    /// Test whether this node would render the same subtree as the node which
+    /// left behind `memo`.
+    pub(crate) fn is_same(&self, memo: &dyn Any) -> bool {
+        (self.same)(&*self.memo, memo)
+    }
+
+    /// Get the subtree, rendering it the first time it's asked for.
+    pub(crate) fn node(&self) -> &VNode<Model> {
+        self.node.get_or_init(|| {
+            let mut node = (self.render)(&*self.memo);
+            css::scope_lazy(&mut node);
+            Box::new(node)
+        })
+    }
+
+    /// Get the subtree if it's been rendered already.
+    #[cfg(any(feature = "debug", feature = "snapshot"))]
+    pub(crate) fn rendered_node(&self) -> Option<&VNode<Model>> {
+        self.node.get().map(|node| &**node)
+    }
+
+    /// Turn this into the subtree it renders, carrying over its key.
+    pub(crate) fn into_node(self) -> VNode<Model> {
+        let node = match self.node.into_inner() {
+            Some(node) => *node,
+            None => (self.render)(&*self.memo),
+        };
+        match self.key {
+            Some(key) => node.with_key(key),
+            None => node,
+        }
+    }
+}
@@ -1,31 +1,58 @@
+use std::fmt::Display;
+
 use crate::Component;
 
+mod builder;
 pub(crate) mod component;
+pub(crate) mod describe;
 mod gobject;
 mod handler;
+mod lazy;
 mod property;
+mod props;
 
+#[doc(hidden)]
+pub use builder::SignalReturn;
+pub use builder::VObjectBuilder;
+#[doc(hidden)]
 pub use component::{PropTransform, VComponent};
+#[doc(hidden)]
+pub use describe::{DescribeDebug, DescribeOther, Description};
 pub use gobject::VObject;
 pub use handler::VHandler;
+pub use lazy::VLazy;
 pub use property::VProperty;
+pub use props::Props;
 
 /// A node in the virtual component tree representing a [`Component`][Component] or a Gtk widget.
 ///
-/// Don't attempt to construct these directly. Use the [`gtk!`][gtk!] macro instead.
+/// Don't attempt to construct these directly. Use the [`gtk!`][gtk!] macro instead, or, to build
+/// one in plain Rust, [`VNode::widget()`][widget] and [`VNode::component()`][component].
 ///
 /// [gtk!]: macro.gtk.html
+/// [widget]: #method.widget
+/// [component]: #method.component
 /// [Component]: trait.Component.html
 pub enum VNode<Model: Component> {
     Object(VObject<Model>),
     Component(VComponent<Model>),
+    Lazy(VLazy<Model>),
 }
 
-impl<Model: Component> VNode<Model> {
+impl<Model: 'static + Component> VNode<Model> {
+    pub(crate) fn get_key(&self) -> Option<&str> {
+        match self {
+            VNode::Object(object) => object.key.as_deref(),
+            VNode::Component(comp) => comp.key.as_deref(),
+            VNode::Lazy(lazy) => lazy.key.as_deref(),
+        }
+    }
+
     pub(crate) fn get_child_props(&self) -> &[VProperty] {
         match self {
             VNode::Object(object) => &object.child_props,
             VNode::Component(comp) => &comp.child_props,
+            VNode::Lazy(lazy) => lazy.node().get_child_props(),
         }
     }
 
@@ -112,3 +139,81 @@ impl<Model: Component> VNode<Model> {
         VNodeIterator { node: None }
     }
 }
+
+impl<Model: 'static + Component> VNode<Model> {
+    /// Make a node which is only rendered again when the value it's rendered
+    /// from changes.
+    ///
+    /// `render` is called with `value` to build the node, but only if the
+    /// node at the same position in the previous render was made from a
+    /// different value, or with a different function. Otherwise, the existing
+    /// widgets are left alone without rendering or diffing the node at all,
+    /// which can save a lot of work in a long list where only a few items
+    /// change at a time.
+    ///
+    /// Because `render` has to be a plain function rather than a closure, the
+    /// node can only depend on `value`, so put everything it needs in there.
+    ///
+    /// If the node's siblings have keys, give it one too with
+    /// [`with_key()`][with_key]. Keys inside the rendered node aren't seen
+    /// until it's rendered, so they don't count.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use vgtk::{gtk, Component, VNode};
+    /// # use vgtk::lib::gtk::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Item {
+    ///     id: usize,
+    ///     text: String,
+    /// }
+    ///
+    /// # #[derive(Clone, Debug)] enum Message { Remove(usize) }
+    /// # #[derive(Default)] struct TodoList { items: Vec<Item> }
+    /// fn render_item(item: &Item) -> VNode<TodoList> {
+    ///     let id = item.id;
+    ///     gtk! {
+    ///         <ListBoxRow>
+    ///             <Box>
+    ///                 <Label label=item.text.clone() />
+    ///                 <Button label="Remove" on clicked=|_| Message::Remove(id) />
+    ///             </Box>
+    ///         </ListBoxRow>
+    ///     }
+    /// }
+    ///
+    /// # impl Component for TodoList { type Message = Message; type Properties = ();
+    /// fn view(&self) -> VNode<TodoList> {
+    ///     gtk! {
+    ///         <ListBox>
+    ///             {
+    ///                 self.items.iter().map(|item| {
+    ///                     VNode::lazy(item.clone(), render_item).with_key(item.id)
+    ///                 })
+    ///             }
+    ///         </ListBox>
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [with_key]: #method.with_key
+    pub fn lazy<T: 'static + PartialEq>(value: T, render: fn(&T) -> VNode<Model>) -> Self {
+        VNode::Lazy(VLazy::new(value, render))
+    }
+
+    /// Give a node a key, like the `key` attribute in the [`gtk!`][gtk!]
+    /// macro, for nodes built in code.
+    ///
+    /// [gtk!]: macro.gtk.html
+    pub fn with_key(mut self, key: impl Display) -> Self {
+        let key = Some(key.to_string());
+        match self {
+            VNode::Object(ref mut object) => object.key = key,
+            VNode::Component(ref mut comp) => comp.key = key,
+            VNode::Lazy(ref mut lazy) => lazy.key = key,
+        }
+        self
+    }
+}
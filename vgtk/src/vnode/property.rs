@@ -2,5 +2,18 @@ use glib::Object;
 
 pub struct VProperty {
     pub name: &'static str,
+    /// The property's value as written, for the inspector and for snapshots,
+    /// if it implements `Debug`.
+    pub value: Option<String>,
     pub set: Box<dyn Fn(&Object, Option<&Object>, bool) + 'static>,
 }
+
+impl VProperty {
+    /// Count a property whose value a setter has just changed.
+    #[doc(hidden)]
+    #[inline]
+    pub fn changed() {
+        #[cfg(feature = "profile")]
+        crate::profile::count(|counts| counts.properties += 1);
+    }
+}
@@ -0,0 +1,87 @@
+use glib::{IsA, Object};
+
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use super::VProperty;
+
+/// A bundle of properties for an object of type `W`, which can be spread over
+/// any element of type `W`, or of a type descended from it, in the
+/// [`gtk!`][gtk!] macro.
+///
+/// Bundles are made with the [`props!`][props!] macro, and are cheap to clone,
+/// so you can keep the attributes many elements have in common, like margins,
+/// sizing or tooltips, in one place.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, props, Props, VNode};
+/// # use vgtk::lib::gtk::*;
+/// fn form_field() -> Props<Widget> {
+///     props! { <Widget margin_start=12 margin_end=12 hexpand=true /> }
+/// }
+///
+/// # fn view() -> VNode<()> {
+/// gtk! {
+///     <Box orientation=Orientation::Vertical>
+///         <Entry {..form_field()} placeholder_text="Name" />
+///         <Entry {..form_field()} placeholder_text="Email" margin_end=0 />
+///     </Box>
+/// }
+/// # }
+/// ```
+///
+/// [gtk!]: macro.gtk.html
+/// [props!]: macro.props.html
+pub struct Props<W> {
+    properties: Vec<Rc<VProperty>>,
+    child_props: Vec<Rc<VProperty>>,
+    object_type: PhantomData<W>,
+}
+
+impl<W> Clone for Props<W> {
+    fn clone(&self) -> Self {
+        Props {
+            properties: self.properties.clone(),
+            child_props: self.child_props.clone(),
+            object_type: PhantomData,
+        }
+    }
+}
+
+impl<W: IsA<Object>> Props<W> {
+    #[doc(hidden)]
+    pub fn new(properties: Vec<VProperty>, child_props: Vec<VProperty>) -> Self {
+        Props {
+            properties: properties.into_iter().map(Rc::new).collect(),
+            child_props: child_props.into_iter().map(Rc::new).collect(),
+            object_type: PhantomData,
+        }
+    }
+
+    /// Add the bundle's properties to those of an element of type `T`,
+    /// except for the ones it sets already.
+    #[doc(hidden)]
+    pub fn spread<T: IsA<W>>(
+        &self,
+        properties: &mut Vec<VProperty>,
+        child_props: &mut Vec<VProperty>,
+    ) {
+        spread_into(&self.properties, properties);
+        spread_into(&self.child_props, child_props);
+    }
+}
+
+fn spread_into(bundle: &[Rc<VProperty>], properties: &mut Vec<VProperty>) {
+    for prop in bundle {
+        if properties.iter().all(|existing| existing.name != prop.name) {
+            let prop = prop.clone();
+            properties.push(VProperty {
+                name: prop.name,
+                value: prop.value.clone(),
+                set: Box::new(move |object, parent, force| (prop.set)(object, parent, force)),
+            });
+        }
+    }
+}
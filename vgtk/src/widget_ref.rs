@@ -0,0 +1,174 @@
+use glib::{Cast, IsA, Object, ObjectExt};
+use gtk::Widget;
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Error, Formatter};
+use std::rc::Rc;
+
+use crate::ext::grab_focus_when_mapped;
+
+/// A reference to a GTK object built by the [`gtk!`][gtk!] macro.
+///
+/// Sometimes you need to get at a widget directly after it's been built, to
+/// grab the keyboard focus, scroll a window to the bottom or start a spinner.
+/// Keep a `WidgetRef` in your component and pass it to the widget's `ref`
+/// attribute, and it will hold on to the widget once it's been built or
+/// patched, and let go of it again when the widget is unmounted.
+///
+/// A `WidgetRef` is a shared handle, so cloning it gives you another
+/// reference to the same widget.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Component, UpdateAction, VNode, WidgetRef};
+/// # use vgtk::lib::gtk::*;
+/// # #[derive(Clone, Debug)] enum Message { Edit }
+/// #[derive(Default)]
+/// struct Editor {
+///     entry: WidgetRef<Entry>,
+/// }
+///
+/// impl Component for Editor {
+///     type Message = Message;
+///     type Properties = ();
+///
+///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///         match message {
+///             Message::Edit => {
+///                 if let Some(entry) = self.entry.get() {
+///                     entry.grab_focus();
+///                 }
+///                 UpdateAction::None
+///             }
+///         }
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         gtk! {
+///             <Box>
+///                 <Entry ref=self.entry />
+///                 <Button label="Edit" on clicked=|_| Message::Edit />
+///             </Box>
+///         }
+///     }
+/// }
+/// ```
+///
+/// [gtk!]: macro.gtk.html
+pub struct WidgetRef<T> {
+    object: Rc<RefCell<Option<T>>>,
+    focus_pending: Rc<Cell<bool>>,
+    mount_pending: Rc<RefCell<Vec<Box<dyn FnOnce(&T)>>>>,
+}
+
+impl<T> WidgetRef<T> {
+    /// Construct an empty `WidgetRef`.
+    pub fn new() -> Self {
+        WidgetRef {
+            object: Rc::new(RefCell::new(None)),
+            focus_pending: Default::default(),
+            mount_pending: Default::default(),
+        }
+    }
+
+    /// Get the widget this `WidgetRef` refers to, if it's currently mounted.
+    pub fn get(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.object.borrow().clone()
+    }
+
+    /// Test whether the widget this `WidgetRef` refers to is currently mounted.
+    pub fn is_mounted(&self) -> bool {
+        self.object.borrow().is_some()
+    }
+
+    /// Call `f` with the widget, now if it's mounted, or else as soon as it
+    /// is.
+    pub(crate) fn when_mounted(&self, f: impl FnOnce(&T) + 'static) {
+        match &*self.object.borrow() {
+            Some(widget) => f(widget),
+            None => self.mount_pending.borrow_mut().push(Box::new(f)),
+        }
+    }
+}
+
+impl<T: IsA<Object>> WidgetRef<T> {
+    #[doc(hidden)]
+    pub fn setter(&self) -> Rc<dyn Fn(Option<&Object>)> {
+        let cell = self.object.clone();
+        let focus_pending = self.focus_pending.clone();
+        let mount_pending = self.mount_pending.clone();
+        Rc::new(move |object| {
+            if let Some(widget) = object.and_then(|object| object.downcast_ref::<Widget>()) {
+                if focus_pending.replace(false) {
+                    grab_focus_when_mapped(widget);
+                }
+            }
+            *cell.borrow_mut() = object.map(|object| {
+                object.clone().downcast::<T>().unwrap_or_else(|object| {
+                    panic!(
+                        "a WidgetRef<{}> can't refer to a {}",
+                        T::static_type(),
+                        object.get_type()
+                    )
+                })
+            });
+            if let Some(widget) = &*cell.borrow() {
+                let pending = mount_pending.replace(Vec::new());
+                for f in pending {
+                    f(widget);
+                }
+            }
+        })
+    }
+}
+
+impl<T: IsA<Widget>> WidgetRef<T> {
+    /// Give the widget this `WidgetRef` refers to the keyboard focus.
+    ///
+    /// You can call this from your [`update`][update] method even if the
+    /// widget hasn't been built yet, because the component is about to render
+    /// it: the widget will be focused as soon as it's mounted and on screen.
+    ///
+    /// [update]: trait.Component.html#method.update
+    pub fn grab_focus(&self) {
+        match &*self.object.borrow() {
+            Some(widget) => grab_focus_when_mapped(widget),
+            None => self.focus_pending.set(true),
+        }
+    }
+}
+
+impl<T> Default for WidgetRef<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for WidgetRef<T> {
+    fn clone(&self) -> Self {
+        WidgetRef {
+            object: self.object.clone(),
+            focus_pending: self.focus_pending.clone(),
+            mount_pending: self.mount_pending.clone(),
+        }
+    }
+}
+
+impl<T> PartialEq for WidgetRef<T> {
+    /// Test whether two `WidgetRef`s are equal.
+    ///
+    /// Two `WidgetRef`s are equal if one is a clone of the other.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.object, &other.object)
+    }
+}
+
+impl<T: Debug> Debug for WidgetRef<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "WidgetRef({:?})", self.object.borrow())
+    }
+}
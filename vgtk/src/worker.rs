@@ -0,0 +1,226 @@
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::stream::StreamExt;
+
+use std::cell::RefCell;
+use std::fmt::{Debug, Error, Formatter};
+use std::sync::mpsc;
+use std::thread;
+
+use log::debug;
+
+use crate::subscription::Subscription;
+
+/// A long running job which runs on its own thread, and exchanges messages
+/// with your components.
+///
+/// Anything which takes long enough to be noticeable, like indexing files or
+/// decoding images, shouldn't happen inside [`update`][update] or
+/// [`view`][view], because nothing else on the main loop can run while it
+/// does. Move it into a `Worker` instead, start it with
+/// [`Bridge::spawn()`][spawn], and talk to it through the [`Bridge`][Bridge].
+///
+/// The worker handles one input at a time, and can send back any number of
+/// outputs for each through its [`WorkerLink`][WorkerLink].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use vgtk::{gtk, Bridge, Component, Subscription, UpdateAction, VNode, Worker, WorkerLink};
+/// # use vgtk::lib::gtk::*;
+/// struct Primes;
+///
+/// impl Worker for Primes {
+///     type Input = u64;
+///     type Output = Vec<u64>;
+///
+///     fn handle(&mut self, limit: u64, link: &WorkerLink<Self>) {
+///         let primes = (2..limit)
+///             .filter(|n| (2..*n).take_while(|d| d * d <= *n).all(|d| n % d != 0))
+///             .collect();
+///         link.send(primes);
+///     }
+/// }
+///
+/// # #[derive(Clone, Debug)]
+/// enum Message {
+///     Calculate(u64),
+///     Calculated(Vec<u64>),
+/// }
+///
+/// struct App {
+///     primes: Bridge<Primes>,
+///     result: Vec<u64>,
+/// }
+///
+/// impl Default for App {
+///     fn default() -> Self {
+///         App {
+///             primes: Bridge::spawn(Primes),
+///             result: Vec::new(),
+///         }
+///     }
+/// }
+///
+/// impl Component for App {
+///     type Message = Message;
+///     type Properties = ();
+///
+///     fn subscriptions(&self) -> Vec<Subscription<Message>> {
+///         vec![self.primes.subscription(Message::Calculated)]
+///     }
+///
+///     fn update(&mut self, message: Message) -> UpdateAction<Self> {
+///         match message {
+///             Message::Calculate(limit) => {
+///                 self.primes.send(limit);
+///                 UpdateAction::None
+///             }
+///             Message::Calculated(result) => {
+///                 self.result = result;
+///                 UpdateAction::Render
+///             }
+///         }
+///     }
+///
+///     fn view(&self) -> VNode<Self> {
+///         gtk! {
+///             <Button label=format!("{} primes", self.result.len())
+///                     on clicked=|_| Message::Calculate(1_000_000) />
+///         }
+///     }
+/// }
+/// ```
+///
+/// [update]: trait.Component.html#method.update
+/// [view]: trait.Component.html#tymethod.view
+/// [spawn]: struct.Bridge.html#method.spawn
+/// [Bridge]: struct.Bridge.html
+/// [WorkerLink]: struct.WorkerLink.html
+pub trait Worker: Send + Sized + 'static {
+    /// The type of the messages the worker receives.
+    type Input: Send + 'static;
+    /// The type of the messages the worker sends back.
+    type Output: Send + 'static;
+
+    /// Handle an input, sending any results back through `link`.
+    ///
+    /// This runs on the worker's own thread, so it's fine for it to take a
+    /// while.
+    fn handle(&mut self, input: Self::Input, link: &WorkerLink<Self>);
+}
+
+/// A handle for a [`Worker`][Worker] to send its outputs back to the main
+/// thread.
+///
+/// A `WorkerLink` can be cloned and moved to other threads, if the worker
+/// wants to spread its work out further.
+///
+/// [Worker]: trait.Worker.html
+pub struct WorkerLink<W: Worker> {
+    sender: UnboundedSender<W::Output>,
+}
+
+impl<W: Worker> WorkerLink<W> {
+    /// Send an output back to the main thread.
+    ///
+    /// If nobody is listening any more, the output is quietly dropped.
+    pub fn send(&self, output: W::Output) {
+        let _ = self.sender.unbounded_send(output);
+    }
+}
+
+impl<W: Worker> Clone for WorkerLink<W> {
+    fn clone(&self) -> Self {
+        WorkerLink {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<W: Worker> Debug for WorkerLink<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "WorkerLink[{}]", std::any::type_name::<W>())
+    }
+}
+
+/// A connection between a component and a [`Worker`][Worker] running on its
+/// own thread.
+///
+/// Send inputs to the worker with [`send()`][send], and receive its outputs
+/// as messages by returning [`subscription()`][subscription] from your
+/// component's [`Component::subscriptions()`][subscriptions] method.
+///
+/// The worker's thread stops once the `Bridge` is dropped and the worker has
+/// finished handling the inputs it's already been sent.
+///
+/// [Worker]: trait.Worker.html
+/// [send]: #method.send
+/// [subscription]: #method.subscription
+/// [subscriptions]: trait.Component.html#method.subscriptions
+pub struct Bridge<W: Worker> {
+    input: mpsc::Sender<W::Input>,
+    output: RefCell<Option<UnboundedReceiver<W::Output>>>,
+}
+
+impl<W: Worker> Bridge<W> {
+    /// Start a worker on a new thread.
+    pub fn spawn(mut worker: W) -> Self {
+        let (input, inputs) = mpsc::channel();
+        let (sender, output) = unbounded();
+        let name = std::any::type_name::<W>();
+        thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let link = WorkerLink { sender };
+                for input in inputs {
+                    worker.handle(input, &link);
+                }
+                debug!("Worker stopped: {}", name);
+            })
+            .unwrap_or_else(|error| panic!("failed to start worker {}: {}", name, error));
+        Bridge {
+            input,
+            output: RefCell::new(Some(output)),
+        }
+    }
+
+    /// Send an input to the worker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the worker's thread has stopped, which only happens if the
+    /// worker itself has panicked.
+    pub fn send(&self, input: W::Input) {
+        self.input
+            .send(input)
+            .unwrap_or_else(|_| panic!("worker {} has stopped", std::any::type_name::<W>()))
+    }
+
+    /// Construct a [`Subscription`][Subscription] which turns each of the
+    /// worker's outputs into a message.
+    ///
+    /// # Panics
+    ///
+    /// A worker's outputs can only go to one place, so this panics if it's
+    /// called more than once for the same `Bridge`.
+    ///
+    /// [Subscription]: struct.Subscription.html
+    pub fn subscription<Msg: 'static>(
+        &self,
+        f: impl Fn(W::Output) -> Msg + 'static,
+    ) -> Subscription<Msg> {
+        let output = self.output.borrow_mut().take().unwrap_or_else(|| {
+            panic!(
+                "the outputs of worker {} have already been subscribed to",
+                std::any::type_name::<W>()
+            )
+        });
+        Subscription::new(output.map(f))
+    }
+}
+
+impl<W: Worker> Debug for Bridge<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Bridge[{}]", std::any::type_name::<W>())
+    }
+}